@@ -1,4 +1,46 @@
+use std::path::{Path, PathBuf};
+
+/// The nearest `node_modules/xterm` above the crate. Bun and npm workspaces usually
+/// hoist it to the repo root rather than the frontend's own node_modules.
+fn find_xterm_package(manifest_dir: &Path) -> Option<PathBuf> {
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join("node_modules").join("xterm"))
+        .find(|dir| dir.join("package.json").is_file())
+}
+
+/// The session share viewer (share.rs) serves xterm.js from the binary instead of a
+/// CDN; take it from the frontend's copy. Without it the build embeds empty files and
+/// share links answer 503.
+fn copy_share_viewer_assets() {
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR not set");
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set"));
+    let package = find_xterm_package(&manifest_dir);
+    if package.is_none() {
+        // Pick the package up once it's installed.
+        for dir in manifest_dir.ancestors().take(4) {
+            let package_json = dir.join("node_modules").join("xterm").join("package.json");
+            println!("cargo:rerun-if-changed={}", package_json.display());
+        }
+    }
+
+    for (from, to) in [("lib/xterm.js", "xterm.js"), ("css/xterm.css", "xterm.css")] {
+        let contents = match package.as_ref().map(|dir| dir.join(from)) {
+            Some(src) => {
+                println!("cargo:rerun-if-changed={}", src.display());
+                std::fs::read(&src).ok()
+            }
+            None => None,
+        };
+        let contents = contents.unwrap_or_else(|| {
+            println!("cargo:warning=xterm/{from} not found in any node_modules; run bun install for the share viewer");
+            Vec::new()
+        });
+        std::fs::write(Path::new(&out_dir).join(to), contents).expect("write share viewer asset");
+    }
+}
+
 fn main() {
+    copy_share_viewer_assets();
     tauri_build::build()
 }
-
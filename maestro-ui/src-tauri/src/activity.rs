@@ -0,0 +1,295 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Once, OnceLock};
+use std::time::Duration;
+use tauri::{Emitter, Manager, WebviewWindow};
+
+use crate::tray::StatusTrayState;
+use crate::util::now_epoch_ms;
+
+pub(crate) const EVENT_PTY_ACTIVITY: &str = "pty-activity";
+/// A working session with no output for this long goes idle.
+const IDLE_AFTER_MS: u64 = 3000;
+const TICK_INTERVAL: Duration = Duration::from_millis(500);
+/// Output with fewer visible characters than this (cursor moves, a blinking
+/// caret, title updates) doesn't count as work on its own.
+const MIN_VISIBLE_CHARS: usize = 8;
+
+/// Braille and block spinners drawn by agent CLIs while they run.
+const SPINNER_CHARS: &[char] = &[
+    '⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏', '✻', '✽', '✶', '✳', '✢', '◐', '◓', '◑', '◒',
+];
+/// Status lines agent CLIs show only while busy (lowercase).
+const BUSY_PHRASES: &[&str] = &["esc to interrupt", "ctrl+c to interrupt", "thinking…", "working…"];
+/// Printed by the shell integration before every prompt (see pty.rs).
+const PROMPT_MARKER: &str = "\x1b]1337;CurrentDir=";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityState {
+    Working,
+    Idle,
+    WaitingForInput,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyActivity {
+    pub id: String,
+    pub state: ActivityState,
+    pub previous: ActivityState,
+    pub changed_at: u64,
+}
+
+/// What one chunk of output says about the session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Signal {
+    Work,
+    Prompt,
+    Noise,
+}
+
+fn classify_chunk(data: &str) -> Signal {
+    // A shell prompt ends whatever ran before it, even if output came first.
+    if data.contains(PROMPT_MARKER) {
+        return Signal::Prompt;
+    }
+    let text = crate::throttle::strip_ansi(data);
+    if text.contains(SPINNER_CHARS) {
+        return Signal::Work;
+    }
+    let lower = text.to_lowercase();
+    if BUSY_PHRASES.iter().any(|p| lower.contains(p)) {
+        return Signal::Work;
+    }
+    if text.chars().filter(|c| !c.is_whitespace() && !c.is_control()).count() >= MIN_VISIBLE_CHARS {
+        Signal::Work
+    } else {
+        Signal::Noise
+    }
+}
+
+/// Per-session classifier. Output drives it into `Working`; the ticker moves it back
+/// to `Idle` after a quiet period; the needs-input detector's flag wins over both.
+struct Tracker {
+    state: ActivityState,
+    last_work_at: u64,
+}
+
+impl Tracker {
+    fn new() -> Self {
+        Self {
+            state: ActivityState::Idle,
+            last_work_at: 0,
+        }
+    }
+
+    fn on_output(&mut self, signal: Signal, awaiting_input: bool, now: u64) -> ActivityState {
+        match signal {
+            Signal::Work => self.last_work_at = now,
+            Signal::Prompt => self.last_work_at = 0,
+            Signal::Noise => {}
+        }
+        self.state = if awaiting_input {
+            ActivityState::WaitingForInput
+        } else if signal == Signal::Prompt {
+            ActivityState::Idle
+        } else if signal == Signal::Work {
+            ActivityState::Working
+        } else {
+            self.state
+        };
+        self.state
+    }
+
+    fn on_tick(&mut self, awaiting_input: bool, now: u64) -> ActivityState {
+        self.state = match self.state {
+            _ if awaiting_input => ActivityState::WaitingForInput,
+            // Answered; stays idle until the agent picks up again.
+            ActivityState::WaitingForInput => ActivityState::Idle,
+            ActivityState::Working if now.saturating_sub(self.last_work_at) > IDLE_AFTER_MS => ActivityState::Idle,
+            state => state,
+        };
+        self.state
+    }
+}
+
+struct TrackedSession {
+    window: WebviewWindow,
+    is_shell: bool,
+    awaiting_input: Arc<AtomicBool>,
+    tracker: Tracker,
+}
+
+fn sessions() -> &'static Mutex<HashMap<String, TrackedSession>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, TrackedSession>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+struct Transition {
+    window: WebviewWindow,
+    event: PtyActivity,
+}
+
+/// Emit state changes and keep the tray's "Agents working" count in step. Called
+/// with the session lock released.
+fn publish(transitions: Vec<Transition>, working_agents: Option<u32>) {
+    let window = transitions.first().map(|t| t.window.clone());
+    for t in transitions {
+        let _ = t.window.emit(EVENT_PTY_ACTIVITY, t.event);
+    }
+    if let (Some(window), Some(count)) = (window, working_agents) {
+        if let Some(tray) = window.app_handle().try_state::<StatusTrayState>() {
+            let _ = tray.set_working_count(count);
+        }
+    }
+}
+
+fn working_agents(sessions: &HashMap<String, TrackedSession>) -> u32 {
+    sessions
+        .values()
+        .filter(|s| !s.is_shell && s.tracker.state == ActivityState::Working)
+        .count() as u32
+}
+
+fn update(
+    sessions: &mut HashMap<String, TrackedSession>,
+    step: impl Fn(&mut TrackedSession, u64) -> (ActivityState, ActivityState),
+    only: Option<&str>,
+) -> (Vec<Transition>, Option<u32>) {
+    let now = now_epoch_ms();
+    let mut transitions = Vec::new();
+    let mut agents_changed = false;
+    for (id, s) in sessions.iter_mut() {
+        if only.is_some_and(|only| only != id) {
+            continue;
+        }
+        let (previous, state) = step(s, now);
+        if previous != state {
+            agents_changed |= !s.is_shell;
+            transitions.push(Transition {
+                window: s.window.clone(),
+                event: PtyActivity {
+                    id: id.clone(),
+                    state,
+                    previous,
+                    changed_at: now,
+                },
+            });
+        }
+    }
+    let working = agents_changed.then(|| working_agents(sessions));
+    (transitions, working)
+}
+
+fn start_ticker() {
+    static TICKER: Once = Once::new();
+    TICKER.call_once(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(TICK_INTERVAL);
+            let Ok(mut sessions) = sessions().lock() else {
+                return;
+            };
+            let (transitions, working) = update(
+                &mut sessions,
+                |s, now| {
+                    let previous = s.tracker.state;
+                    (previous, s.tracker.on_tick(s.awaiting_input.load(Ordering::Relaxed), now))
+                },
+                None,
+            );
+            drop(sessions);
+            publish(transitions, working);
+        });
+    });
+}
+
+/// Start classifying a new session; `awaiting_input` is the flag its
+/// `NeedsInputDetector` sets.
+pub(crate) fn register(window: &WebviewWindow, id: &str, is_shell: bool, awaiting_input: Arc<AtomicBool>) {
+    start_ticker();
+    if let Ok(mut sessions) = sessions().lock() {
+        sessions.insert(
+            id.to_string(),
+            TrackedSession {
+                window: window.clone(),
+                is_shell,
+                awaiting_input,
+                tracker: Tracker::new(),
+            },
+        );
+    }
+}
+
+/// Feed decoded output from the reader thread, after the needs-input detector.
+pub(crate) fn feed(id: &str, data: &str) {
+    if data.is_empty() {
+        return;
+    }
+    let signal = classify_chunk(data);
+    let Ok(mut sessions) = sessions().lock() else {
+        return;
+    };
+    let (transitions, working) = update(
+        &mut sessions,
+        |s, now| {
+            let previous = s.tracker.state;
+            (previous, s.tracker.on_output(signal, s.awaiting_input.load(Ordering::Relaxed), now))
+        },
+        Some(id),
+    );
+    drop(sessions);
+    publish(transitions, working);
+}
+
+pub(crate) fn state_of(id: &str) -> Option<ActivityState> {
+    sessions().lock().ok()?.get(id).map(|s| s.tracker.state)
+}
+
+pub(crate) fn session_closed(id: &str) {
+    let Ok(mut sessions) = sessions().lock() else {
+        return;
+    };
+    let Some(closed) = sessions.remove(id) else {
+        return;
+    };
+    let working = (!closed.is_shell && closed.tracker.state == ActivityState::Working)
+        .then(|| working_agents(&sessions));
+    drop(sessions);
+    if let Some(count) = working {
+        if let Some(tray) = closed.window.app_handle().try_state::<StatusTrayState>() {
+            let _ = tray.set_working_count(count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_chunk, ActivityState, Signal, Tracker, IDLE_AFTER_MS};
+
+    #[test]
+    fn classifies_output_chunks() {
+        assert_eq!(classify_chunk("\x1b[2K\r⠹ Compiling"), Signal::Work);
+        assert_eq!(classify_chunk("\x1b[2m(esc to interrupt)\x1b[0m"), Signal::Work);
+        assert_eq!(classify_chunk("Running tests for crate foo\r\n"), Signal::Work);
+        assert_eq!(classify_chunk("\x1b[?25l\x1b[3;1H \x1b[?25h"), Signal::Noise);
+        assert_eq!(classify_chunk("done\r\n\x1b]1337;CurrentDir=/a\x07$ "), Signal::Prompt);
+    }
+
+    #[test]
+    fn tracks_working_idle_and_waiting() {
+        let mut t = Tracker::new();
+        assert_eq!(t.on_output(Signal::Work, false, 1000), ActivityState::Working);
+        assert_eq!(t.on_output(Signal::Noise, false, 2000), ActivityState::Working);
+        assert_eq!(t.on_tick(false, 1000 + IDLE_AFTER_MS), ActivityState::Working);
+        assert_eq!(t.on_tick(false, 1001 + IDLE_AFTER_MS), ActivityState::Idle);
+
+        assert_eq!(t.on_output(Signal::Work, true, 9000), ActivityState::WaitingForInput);
+        assert_eq!(t.on_tick(true, 20_000), ActivityState::WaitingForInput);
+        assert_eq!(t.on_tick(false, 20_500), ActivityState::Idle);
+
+        t.on_output(Signal::Work, false, 21_000);
+        assert_eq!(t.on_output(Signal::Prompt, false, 21_100), ActivityState::Idle);
+    }
+}
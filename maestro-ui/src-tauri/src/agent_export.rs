@@ -0,0 +1,320 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::io::BufReader;
+
+use crate::agent_logs::{read_log_line, resolve_agent_log_path, AgentLogProvider};
+
+const MAX_TOOL_SUMMARY_CHARS: usize = 200;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentExportFormat {
+    Markdown,
+    Html,
+}
+
+/// One rendered unit of a transcript. Tool results and reasoning are left out;
+/// tool calls are reduced to a one-line summary.
+enum TranscriptItem {
+    Message { role: String, text: String },
+    Tool { name: String, summary: String },
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    let mut out: String = text.chars().take(max).collect();
+    if text.chars().count() > max {
+        out.push('…');
+    }
+    out
+}
+
+/// The most telling argument of a tool call (path, command, pattern, ...).
+fn tool_summary(input: &Value) -> String {
+    const KEYS: [&str; 6] = ["file_path", "command", "pattern", "path", "url", "description"];
+    let value = KEYS.iter().find_map(|k| input.get(*k));
+    let text = match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|p| p.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => String::new(),
+    };
+    truncate_chars(text.lines().next().unwrap_or(""), MAX_TOOL_SUMMARY_CHARS)
+}
+
+fn push_message(items: &mut Vec<TranscriptItem>, role: &str, text: &str) {
+    let text = text.trim();
+    if !text.is_empty() {
+        items.push(TranscriptItem::Message {
+            role: role.to_string(),
+            text: text.to_string(),
+        });
+    }
+}
+
+/// Claude lines: `{"type":"user"|"assistant","message":{"content": string | [blocks]}}`.
+fn claude_items(entry: &Value, items: &mut Vec<TranscriptItem>) {
+    let role = match entry.get("type").and_then(|v| v.as_str()) {
+        Some(r @ ("user" | "assistant")) => r,
+        _ => return,
+    };
+    if entry.get("isMeta").and_then(|v| v.as_bool()) == Some(true) {
+        return;
+    }
+    match entry.pointer("/message/content") {
+        Some(Value::String(text)) => push_message(items, role, text),
+        Some(Value::Array(blocks)) => {
+            for block in blocks {
+                match block.get("type").and_then(|v| v.as_str()) {
+                    Some("text") => {
+                        if let Some(text) = block.get("text").and_then(|v| v.as_str()) {
+                            push_message(items, role, text);
+                        }
+                    }
+                    Some("tool_use") => items.push(TranscriptItem::Tool {
+                        name: block.get("name").and_then(|v| v.as_str()).unwrap_or("tool").to_string(),
+                        summary: tool_summary(block.get("input").unwrap_or(&Value::Null)),
+                    }),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Codex lines wrap response items in `{"type":"response_item","payload":{..}}`;
+/// older logs write the items bare.
+fn codex_items(entry: &Value, items: &mut Vec<TranscriptItem>) {
+    let item = match entry.get("type").and_then(|v| v.as_str()) {
+        Some("response_item") => match entry.get("payload") {
+            Some(payload) => payload,
+            None => return,
+        },
+        _ => entry,
+    };
+    match item.get("type").and_then(|v| v.as_str()) {
+        Some("message") => {
+            let role = item.get("role").and_then(|v| v.as_str()).unwrap_or("assistant");
+            if role != "user" && role != "assistant" {
+                return;
+            }
+            let blocks = item.get("content").and_then(|v| v.as_array());
+            for block in blocks.into_iter().flatten() {
+                if let Some(text) = block.get("text").and_then(|v| v.as_str()) {
+                    push_message(items, role, text);
+                }
+            }
+        }
+        Some("function_call") | Some("custom_tool_call") => {
+            let input = item
+                .get("arguments")
+                .and_then(|v| v.as_str())
+                .and_then(|s| serde_json::from_str::<Value>(s).ok())
+                .or_else(|| item.get("input").cloned())
+                .unwrap_or(Value::Null);
+            items.push(TranscriptItem::Tool {
+                name: item.get("name").and_then(|v| v.as_str()).unwrap_or("tool").to_string(),
+                summary: tool_summary(&input),
+            });
+        }
+        _ => {}
+    }
+}
+
+fn role_heading(role: &str) -> &'static str {
+    if role == "user" {
+        "User"
+    } else {
+        "Assistant"
+    }
+}
+
+fn render_markdown(items: &[TranscriptItem]) -> String {
+    let mut out = String::from("# Agent conversation\n");
+    let mut last_role: Option<&str> = None;
+    for item in items {
+        match item {
+            TranscriptItem::Message { role, text } => {
+                if last_role != Some(role.as_str()) {
+                    out.push_str(&format!("\n## {}\n", role_heading(role)));
+                    last_role = Some(role.as_str());
+                }
+                out.push('\n');
+                out.push_str(text);
+                out.push('\n');
+            }
+            TranscriptItem::Tool { name, summary } => {
+                if summary.is_empty() {
+                    out.push_str(&format!("\n> **{name}**\n"));
+                } else {
+                    out.push_str(&format!("\n> **{name}** `{}`\n", summary.replace('`', "'")));
+                }
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render message text as HTML: fenced code blocks become `<pre><code>`,
+/// everything else is escaped into paragraphs.
+fn text_to_html(text: &str) -> String {
+    let mut out = String::new();
+    for (i, part) in text.split("```").enumerate() {
+        if i % 2 == 1 {
+            let (lang, code) = part.split_once('\n').unwrap_or(("", part));
+            let lang = lang.trim();
+            let class = if lang.is_empty() {
+                String::new()
+            } else {
+                format!(" class=\"language-{}\"", escape_html(lang))
+            };
+            out.push_str(&format!("<pre><code{class}>{}</code></pre>\n", escape_html(code)));
+            continue;
+        }
+        for para in part.split("\n\n") {
+            let para = para.trim();
+            if !para.is_empty() {
+                out.push_str(&format!("<p>{}</p>\n", escape_html(para).replace('\n', "<br>\n")));
+            }
+        }
+    }
+    out
+}
+
+fn render_html(items: &[TranscriptItem]) -> String {
+    let mut out = String::from(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+        "<title>Agent conversation</title>\n<style>\n",
+        "body{font-family:-apple-system,system-ui,sans-serif;max-width:860px;margin:2em auto;padding:0 1em;line-height:1.5}\n",
+        ".msg{margin:1em 0;padding:.5em 1em;border-radius:6px}\n",
+        ".user{background:#eef4ff}.assistant{background:#f6f6f6}\n",
+        ".role{font-weight:600;font-size:.85em;text-transform:uppercase;color:#555}\n",
+        ".tool{font-family:monospace;font-size:.85em;color:#666;margin:.25em 1em}\n",
+        "pre{background:#1e1e1e;color:#ddd;padding:.75em;overflow-x:auto;border-radius:4px}\n",
+        "</style>\n</head>\n<body>\n<h1>Agent conversation</h1>\n",
+    ));
+    for item in items {
+        match item {
+            TranscriptItem::Message { role, text } => {
+                let class = if role == "user" { "user" } else { "assistant" };
+                out.push_str(&format!(
+                    "<div class=\"msg {class}\">\n<div class=\"role\">{}</div>\n{}</div>\n",
+                    role_heading(role),
+                    text_to_html(text)
+                ));
+            }
+            TranscriptItem::Tool { name, summary } => {
+                out.push_str(&format!(
+                    "<div class=\"tool\">&#9656; <b>{}</b> {}</div>\n",
+                    escape_html(name),
+                    escape_html(summary)
+                ));
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Convert a Claude or Codex session log into a shareable Markdown or HTML
+/// transcript of the messages and tool calls.
+#[tauri::command]
+pub async fn export_agent_conversation(
+    provider: AgentLogProvider,
+    cwd: String,
+    filename: String,
+    format: AgentExportFormat,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = resolve_agent_log_path(provider, &cwd, &filename)?;
+        let file = fs::File::open(&path).map_err(|e| format!("open failed: {e}"))?;
+        let mut reader = BufReader::new(file);
+        let mut buf = Vec::new();
+
+        let mut items = Vec::new();
+        while let Some(line) = read_log_line(&mut reader, &mut buf)? {
+            let Ok(entry) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            match provider {
+                AgentLogProvider::Claude => claude_items(&entry, &mut items),
+                AgentLogProvider::Codex => codex_items(&entry, &mut items),
+            }
+        }
+
+        Ok(match format {
+            AgentExportFormat::Markdown => render_markdown(&items),
+            AgentExportFormat::Html => render_html(&items),
+        })
+    })
+    .await
+    .map_err(|e| format!("export task join failed: {e:?}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{claude_items, codex_items, escape_html, render_html, render_markdown, TranscriptItem};
+    use serde_json::json;
+
+    #[test]
+    fn escapes_html_special_chars() {
+        assert_eq!(
+            escape_html(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+        assert_eq!(escape_html("plain – text"), "plain – text");
+    }
+
+    #[test]
+    fn renders_tool_calls_in_markdown_and_html() {
+        let mut items = Vec::new();
+        claude_items(
+            &json!({"type": "assistant", "message": {"content": [
+                {"type": "text", "text": "Checking the build."},
+                {"type": "tool_use", "name": "Bash", "input": {"command": "cargo `test` <all>\nsecond line"}},
+            ]}}),
+            &mut items,
+        );
+        codex_items(
+            &json!({"type": "response_item", "payload": {
+                "type": "function_call", "name": "shell", "arguments": "{\"command\": [\"ls\", \"-la\"]}"
+            }}),
+            &mut items,
+        );
+        items.push(TranscriptItem::Tool {
+            name: "<Read>".to_string(),
+            summary: String::new(),
+        });
+        assert_eq!(items.len(), 4);
+
+        let markdown = render_markdown(&items);
+        assert!(markdown.contains("## Assistant\n\nChecking the build.\n"));
+        assert!(markdown.contains("\n> **Bash** `cargo 'test' <all>`\n"));
+        assert!(markdown.contains("\n> **shell** `ls -la`\n"));
+        assert!(markdown.ends_with("\n> **<Read>**\n"));
+
+        let html = render_html(&items);
+        assert!(html.contains("<div class=\"tool\">&#9656; <b>Bash</b> cargo `test` &lt;all&gt;</div>\n"));
+        assert!(html.contains("<div class=\"tool\">&#9656; <b>shell</b> ls -la</div>\n"));
+        assert!(html.contains("<div class=\"tool\">&#9656; <b>&lt;Read&gt;</b> </div>\n"));
+    }
+}
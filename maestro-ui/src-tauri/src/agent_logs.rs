@@ -0,0 +1,535 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::claude_logs::{
+    claude_project_dir, claude_projects_dir, extract_maestro_session_id, resolve_claude_log_path,
+};
+use crate::codex_logs::{
+    codex_sessions_dir, list_codex_session_logs, list_jsonl_files_recursive, resolve_codex_log_for_cwd,
+    session_cwd,
+};
+use crate::util::now_epoch_ms;
+
+// Never touch logs written to recently: the agent may still be appending to them.
+const CLEANUP_MIN_AGE_MS: u64 = 10 * 60 * 1000; // 10 minutes
+const MAX_RANGE_LINES: usize = 2000;
+const MAX_RANGE_BYTES: usize = 10 * 1024 * 1024; // 10MB
+const DEFAULT_ACTIVE_WINDOW_SECS: u64 = 30;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentLogProvider {
+    Claude,
+    Codex,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentLogCleanupOptions {
+    /// Only remove logs last modified more than this many milliseconds ago.
+    pub older_than: Option<u64>,
+    /// Always keep the N most recent logs of each project.
+    pub keep_last: Option<usize>,
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentLogCleanupEntry {
+    pub path: String,
+    pub size: u64,
+    pub modified_at: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentLogCleanupResult {
+    pub dry_run: bool,
+    pub files: Vec<AgentLogCleanupEntry>,
+    pub removed_count: usize,
+    pub freed_bytes: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentLogRange {
+    pub lines: Vec<String>,
+    pub from_line: usize,
+    /// Line to request next; equal to `from_line + lines.len()`.
+    pub next_line: usize,
+    pub eof: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentLogLineCount {
+    pub line_count: usize,
+    pub file_size: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveAgentSession {
+    pub provider: AgentLogProvider,
+    /// Claude: log filename; Codex: path relative to the sessions dir.
+    pub filename: String,
+    pub modified_at: u64,
+    pub maestro_session_id: Option<String>,
+}
+
+struct LogCandidate {
+    path: PathBuf,
+    group: String,
+    size: u64,
+    modified_at: u64,
+}
+
+pub(crate) fn modified_ms(meta: &fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub(crate) fn provider_root(provider: AgentLogProvider) -> Result<PathBuf, String> {
+    match provider {
+        AgentLogProvider::Claude => claude_projects_dir(),
+        AgentLogProvider::Codex => codex_sessions_dir(),
+    }
+}
+
+/// Resolve a log file for either provider. Claude logs are addressed by filename
+/// within the project dir for `cwd`; Codex logs by path relative to the sessions dir.
+pub(crate) fn resolve_agent_log_path(
+    provider: AgentLogProvider,
+    cwd: &str,
+    filename: &str,
+) -> Result<PathBuf, String> {
+    match provider {
+        AgentLogProvider::Claude => resolve_claude_log_path(cwd, filename),
+        AgentLogProvider::Codex => resolve_codex_log_for_cwd(cwd, filename),
+    }
+}
+
+/// Read the next line (without its terminator) as lossy UTF-8. Returns `None` at EOF.
+pub(crate) fn read_log_line(reader: &mut impl BufRead, buf: &mut Vec<u8>) -> Result<Option<String>, String> {
+    buf.clear();
+    let n = reader
+        .read_until(b'\n', buf)
+        .map_err(|e| format!("read failed: {e}"))?;
+    if n == 0 {
+        return Ok(None);
+    }
+    while matches!(buf.last(), Some(b'\n') | Some(b'\r')) {
+        buf.pop();
+    }
+    Ok(Some(String::from_utf8_lossy(buf).to_string()))
+}
+
+/// Read `count` lines starting at zero-based `from_line`, streaming the file so
+/// logs larger than the whole-file read limit stay viewable.
+#[tauri::command]
+pub async fn read_agent_log_range(
+    provider: AgentLogProvider,
+    cwd: String,
+    filename: String,
+    from_line: usize,
+    count: usize,
+) -> Result<AgentLogRange, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = resolve_agent_log_path(provider, &cwd, &filename)?;
+        let file = fs::File::open(&path).map_err(|e| format!("open failed: {e}"))?;
+        let mut reader = BufReader::new(file);
+        let count = count.min(MAX_RANGE_LINES);
+
+        let mut buf: Vec<u8> = Vec::new();
+        let mut skipped = 0usize;
+        while skipped < from_line {
+            buf.clear();
+            let n = reader
+                .read_until(b'\n', &mut buf)
+                .map_err(|e| format!("read failed: {e}"))?;
+            if n == 0 {
+                return Ok(AgentLogRange {
+                    lines: Vec::new(),
+                    from_line,
+                    next_line: from_line,
+                    eof: true,
+                });
+            }
+            skipped += 1;
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut bytes = 0usize;
+        let mut eof = false;
+        while lines.len() < count && bytes < MAX_RANGE_BYTES {
+            match read_log_line(&mut reader, &mut buf)? {
+                Some(line) => {
+                    bytes += line.len();
+                    lines.push(line);
+                }
+                None => {
+                    eof = true;
+                    break;
+                }
+            }
+        }
+        if !eof {
+            eof = reader
+                .fill_buf()
+                .map(|rest| rest.is_empty())
+                .map_err(|e| format!("read failed: {e}"))?;
+        }
+
+        Ok(AgentLogRange {
+            next_line: from_line + lines.len(),
+            lines,
+            from_line,
+            eof,
+        })
+    })
+    .await
+    .map_err(|e| format!("log task join failed: {e:?}"))?
+}
+
+/// Count the lines of a log file without loading it into memory.
+#[tauri::command]
+pub async fn count_agent_log_lines(
+    provider: AgentLogProvider,
+    cwd: String,
+    filename: String,
+) -> Result<AgentLogLineCount, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = resolve_agent_log_path(provider, &cwd, &filename)?;
+        let file = fs::File::open(&path).map_err(|e| format!("open failed: {e}"))?;
+        let file_size = file
+            .metadata()
+            .map_err(|e| format!("metadata failed: {e}"))?
+            .len();
+        let mut reader = BufReader::with_capacity(256 * 1024, file);
+
+        let mut line_count = 0usize;
+        let mut last_byte: Option<u8> = None;
+        loop {
+            let chunk = reader.fill_buf().map_err(|e| format!("read failed: {e}"))?;
+            if chunk.is_empty() {
+                break;
+            }
+            line_count += chunk.iter().filter(|b| **b == b'\n').count();
+            last_byte = chunk.last().copied();
+            let n = chunk.len();
+            reader.consume(n);
+        }
+        // A trailing line without a newline still counts.
+        if last_byte.is_some() && last_byte != Some(b'\n') {
+            line_count += 1;
+        }
+
+        Ok(AgentLogLineCount {
+            line_count,
+            file_size,
+        })
+    })
+    .await
+    .map_err(|e| format!("log task join failed: {e:?}"))?
+}
+
+fn active_claude_sessions(cwd: &str, since: u64) -> Result<Vec<ActiveAgentSession>, String> {
+    let project_dir = claude_project_dir(cwd)?;
+    let Ok(read_dir) = fs::read_dir(&project_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut out = Vec::new();
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let Ok(meta) = fs::metadata(&path) else {
+            continue;
+        };
+        let modified_at = modified_ms(&meta);
+        if !meta.is_file() || modified_at < since {
+            continue;
+        }
+        // Only pay for the prefix scan on files that are actually live.
+        out.push(ActiveAgentSession {
+            provider: AgentLogProvider::Claude,
+            filename: entry.file_name().to_string_lossy().to_string(),
+            modified_at,
+            maestro_session_id: extract_maestro_session_id(&path),
+        });
+    }
+    Ok(out)
+}
+
+fn active_codex_sessions(cwd: &str, since: u64) -> Result<Vec<ActiveAgentSession>, String> {
+    Ok(list_codex_session_logs(cwd.to_string())?
+        .into_iter()
+        .filter(|log| log.modified_at >= since)
+        .map(|log| ActiveAgentSession {
+            provider: AgentLogProvider::Codex,
+            filename: log.relative_path,
+            modified_at: log.modified_at,
+            maestro_session_id: log.maestro_session_id,
+        })
+        .collect())
+}
+
+/// Claude/Codex logs for `cwd` written to within the last `withinSecs` seconds
+/// (default 30), i.e. agents that are currently running, most recent first.
+#[tauri::command]
+pub async fn get_active_agent_sessions(
+    cwd: String,
+    within_secs: Option<u64>,
+) -> Result<Vec<ActiveAgentSession>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let window_ms = within_secs.unwrap_or(DEFAULT_ACTIVE_WINDOW_SECS) * 1000;
+        let since = now_epoch_ms().saturating_sub(window_ms);
+
+        let mut out = active_claude_sessions(&cwd, since)?;
+        out.extend(active_codex_sessions(&cwd, since)?);
+        out.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+        Ok(out)
+    })
+    .await
+    .map_err(|e| format!("log task join failed: {e:?}"))?
+}
+
+/// Top-level `*.jsonl` files in each Claude project dir, grouped by project dir.
+fn claude_candidates(root: &Path) -> Vec<LogCandidate> {
+    let mut out = Vec::new();
+    let Ok(projects) = fs::read_dir(root) else {
+        return out;
+    };
+    for project in projects.flatten() {
+        let project_dir = project.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+        let group = project.file_name().to_string_lossy().to_string();
+        let Ok(files) = fs::read_dir(&project_dir) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let path = file.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let Ok(meta) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            out.push(LogCandidate {
+                path,
+                group: group.clone(),
+                size: meta.len(),
+                modified_at: modified_ms(&meta),
+            });
+        }
+    }
+    out
+}
+
+/// Every Codex `*.jsonl` session, grouped by the cwd recorded in its session meta.
+fn codex_candidates(root: &Path) -> Vec<LogCandidate> {
+    list_jsonl_files_recursive(root)
+        .into_iter()
+        .filter_map(|path| {
+            let meta = fs::symlink_metadata(&path).ok()?;
+            if !meta.is_file() {
+                return None;
+            }
+            Some(LogCandidate {
+                group: session_cwd(&path).unwrap_or_default(),
+                size: meta.len(),
+                modified_at: modified_ms(&meta),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Pick the candidates to remove: older than `older_than` (if set) and not among
+/// the `keep_last` newest of their group (if set).
+fn select_for_cleanup(
+    mut candidates: Vec<LogCandidate>,
+    now: u64,
+    older_than: Option<u64>,
+    keep_last: Option<usize>,
+) -> Vec<LogCandidate> {
+    candidates.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+
+    let mut seen_per_group: HashMap<String, usize> = HashMap::new();
+    let mut out = Vec::new();
+    for candidate in candidates {
+        let rank = seen_per_group.entry(candidate.group.clone()).or_insert(0);
+        *rank += 1;
+
+        let age = now.saturating_sub(candidate.modified_at);
+        if age < CLEANUP_MIN_AGE_MS {
+            continue;
+        }
+        if let Some(keep) = keep_last {
+            if *rank <= keep {
+                continue;
+            }
+        }
+        if let Some(older_than) = older_than {
+            if age <= older_than {
+                continue;
+            }
+        }
+        out.push(candidate);
+    }
+    out
+}
+
+/// Remove old session logs from `~/.claude/projects` or `~/.codex/sessions`.
+/// With `dryRun` set, nothing is deleted and the files that would be are returned.
+#[tauri::command]
+pub async fn cleanup_agent_logs(
+    provider: AgentLogProvider,
+    options: AgentLogCleanupOptions,
+) -> Result<AgentLogCleanupResult, String> {
+    tauri::async_runtime::spawn_blocking(move || cleanup_agent_logs_sync(provider, options))
+        .await
+        .map_err(|e| format!("cleanup task join failed: {e:?}"))?
+}
+
+fn cleanup_agent_logs_sync(
+    provider: AgentLogProvider,
+    options: AgentLogCleanupOptions,
+) -> Result<AgentLogCleanupResult, String> {
+    if options.older_than.is_none() && options.keep_last.is_none() {
+        return Err("specify olderThan and/or keepLast".to_string());
+    }
+    let dry_run = options.dry_run.unwrap_or(false);
+
+    let root = provider_root(provider)?;
+    if !root.is_dir() {
+        return Ok(AgentLogCleanupResult {
+            dry_run,
+            files: Vec::new(),
+            removed_count: 0,
+            freed_bytes: 0,
+        });
+    }
+    let canon_root = fs::canonicalize(&root).map_err(|e| format!("root resolve failed: {e}"))?;
+
+    let candidates = match provider {
+        AgentLogProvider::Claude => claude_candidates(&root),
+        AgentLogProvider::Codex => codex_candidates(&root),
+    };
+    let selected = select_for_cleanup(candidates, now_epoch_ms(), options.older_than, options.keep_last);
+
+    let mut files = Vec::new();
+    let mut removed_count = 0usize;
+    let mut freed_bytes = 0u64;
+    for candidate in selected {
+        let within_root = fs::canonicalize(&candidate.path)
+            .map(|p| p.starts_with(&canon_root))
+            .unwrap_or(false);
+        if !within_root {
+            continue;
+        }
+        if !dry_run {
+            if let Err(e) = fs::remove_file(&candidate.path) {
+                eprintln!("Failed to remove agent log {:?}: {e}", candidate.path);
+                continue;
+            }
+        }
+        removed_count += 1;
+        freed_bytes += candidate.size;
+        files.push(AgentLogCleanupEntry {
+            path: candidate.path.to_string_lossy().to_string(),
+            size: candidate.size,
+            modified_at: candidate.modified_at,
+        });
+    }
+
+    Ok(AgentLogCleanupResult {
+        dry_run,
+        files,
+        removed_count,
+        freed_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{select_for_cleanup, LogCandidate, CLEANUP_MIN_AGE_MS};
+    use std::path::PathBuf;
+
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    fn candidate(name: &str, group: &str, modified_at: u64) -> LogCandidate {
+        LogCandidate {
+            path: PathBuf::from(name),
+            group: group.to_string(),
+            size: 1,
+            modified_at,
+        }
+    }
+
+    fn names(selected: &[LogCandidate]) -> Vec<String> {
+        selected
+            .iter()
+            .map(|c| c.path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn keep_last_applies_per_group() {
+        let now = 100 * HOUR;
+        let selected = select_for_cleanup(
+            vec![
+                candidate("a1", "a", now - HOUR),
+                candidate("a2", "a", now - 2 * HOUR),
+                candidate("a3", "a", now - 3 * HOUR),
+                candidate("b1", "b", now - 5 * HOUR),
+            ],
+            now,
+            None,
+            Some(1),
+        );
+        assert_eq!(names(&selected), vec!["a2", "a3"]);
+    }
+
+    #[test]
+    fn older_than_and_keep_last_combine() {
+        let now = 100 * HOUR;
+        let selected = select_for_cleanup(
+            vec![
+                candidate("new", "a", now - HOUR),
+                candidate("mid", "a", now - 10 * HOUR),
+                candidate("old", "a", now - 50 * HOUR),
+            ],
+            now,
+            Some(24 * HOUR),
+            Some(1),
+        );
+        assert_eq!(names(&selected), vec!["old"]);
+    }
+
+    #[test]
+    fn never_selects_recently_modified_logs() {
+        let now = 100 * HOUR;
+        let selected = select_for_cleanup(
+            vec![candidate("live", "a", now - CLEANUP_MIN_AGE_MS / 2)],
+            now,
+            Some(0),
+            Some(0),
+        );
+        assert!(selected.is_empty());
+    }
+}
@@ -1,20 +1,49 @@
-use tauri::menu::{AboutMetadata, Menu, MenuEvent, MenuItemBuilder, MenuItemKind, PredefinedMenuItem, HELP_SUBMENU_ID};
-use tauri::{AppHandle, Emitter, Runtime};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tauri::menu::{
+    AboutMetadata, Menu, MenuEvent, MenuItem, MenuItemBuilder, MenuItemKind, PredefinedMenuItem,
+    SubmenuBuilder, HELP_SUBMENU_ID, WINDOW_SUBMENU_ID,
+};
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+
+use crate::keymap::{
+    KeyBinding, KeyScope, ACTION_CHECK_UPDATES, ACTION_CLOSE_SESSION, ACTION_NEW_TERMINAL,
+};
+use crate::pty::AppState;
 
 pub const MENU_ID_CHECK_UPDATES: &str = "help-check-updates";
 pub const EVENT_APP_MENU: &str = "app-menu";
+const SESSIONS_SUBMENU_ID: &str = "app-sessions";
+const PROJECTS_SUBMENU_ID: &str = "app-projects";
+const MENU_ID_NEW_TERMINAL: &str = "app-new-terminal";
+const MENU_ID_CLOSE_SESSION: &str = "app-close-session";
+/// Dynamic entries are `<prefix><session or project id>`; the bare prefix is the
+/// disabled placeholder shown when there are none.
+const SESSION_ITEM_PREFIX: &str = "app-session-";
+const PROJECT_ITEM_PREFIX: &str = "app-project-";
+const MAX_DYNAMIC_ITEMS: usize = 30;
+/// Keymap actions that are menu items: (action, submenu id, item id).
+const MENU_ACTIONS: &[(&str, &str, &str)] = &[
+    (ACTION_NEW_TERMINAL, SESSIONS_SUBMENU_ID, MENU_ID_NEW_TERMINAL),
+    (ACTION_CLOSE_SESSION, SESSIONS_SUBMENU_ID, MENU_ID_CLOSE_SESSION),
+    (ACTION_CHECK_UPDATES, HELP_SUBMENU_ID, MENU_ID_CHECK_UPDATES),
+];
 
 #[derive(serde::Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct AppMenuEventPayload {
+    /// `check-updates` keeps its menu id; otherwise `new-terminal`,
+    /// `close-session`, `focus-session` or `open-project`.
     id: String,
+    session_id: Option<String>,
+    project_id: Option<String>,
 }
 
 pub fn build_app_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
     let menu = Menu::default(app)?;
 
     let check_updates_item =
-        MenuItemBuilder::with_id(MENU_ID_CHECK_UPDATES, "Check for Updates…").build(app)?;
+        bound_item(app, MENU_ID_CHECK_UPDATES, "Check for Updates…", ACTION_CHECK_UPDATES)?;
     let separator = PredefinedMenuItem::separator(app)?;
 
     if let Some(MenuItemKind::Submenu(help_menu)) = menu.get(HELP_SUBMENU_ID) {
@@ -37,16 +66,192 @@ pub fn build_app_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>>
         }
     }
 
+    // Filled in by `refresh_dynamic_menus` once there's state to show.
+    let sessions_menu = SubmenuBuilder::with_id(app, SESSIONS_SUBMENU_ID, "Sessions")
+        .item(&bound_item(app, MENU_ID_NEW_TERMINAL, "New Terminal", ACTION_NEW_TERMINAL)?)
+        .item(&bound_item(app, MENU_ID_CLOSE_SESSION, "Close Current Session", ACTION_CLOSE_SESSION)?)
+        .separator()
+        .item(&placeholder(app, SESSION_ITEM_PREFIX, "No Open Sessions")?)
+        .build()?;
+    let projects_menu = SubmenuBuilder::with_id(app, PROJECTS_SUBMENU_ID, "Projects")
+        .item(&placeholder(app, PROJECT_ITEM_PREFIX, "No Projects")?)
+        .build()?;
+    // Before Window and Help, which platforms keep last.
+    let position = menu
+        .items()?
+        .iter()
+        .position(|item| item.id().as_ref() == WINDOW_SUBMENU_ID || item.id().as_ref() == HELP_SUBMENU_ID);
+    match position {
+        Some(position) => {
+            menu.insert(&sessions_menu, position)?;
+            menu.insert(&projects_menu, position + 1)?;
+        }
+        None => {
+            menu.append(&sessions_menu)?;
+            menu.append(&projects_menu)?;
+        }
+    }
+
     Ok(menu)
 }
 
+/// A menu item with the accelerator the keymap gives `action`, if any.
+fn bound_item<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    text: &str,
+    action: &str,
+) -> tauri::Result<MenuItem<R>> {
+    let mut builder = MenuItemBuilder::with_id(id, text);
+    if let Some(accelerator) = crate::keymap::accelerator_for(app, action) {
+        builder = builder.accelerator(accelerator);
+    }
+    builder.build(app)
+}
+
+/// Update menu item accelerators after the keymap changes.
+pub(crate) fn apply_keymap(app: &AppHandle, bindings: &[KeyBinding]) {
+    let Some(menu) = app.menu() else {
+        return;
+    };
+    for binding in bindings.iter().filter(|b| b.scope == KeyScope::Menu) {
+        let entry = MENU_ACTIONS.iter().find(|(action, _, _)| *action == binding.action);
+        let Some((_, submenu_id, item_id)) = entry else {
+            continue;
+        };
+        let Some(MenuItemKind::Submenu(submenu)) = menu.get(submenu_id) else {
+            continue;
+        };
+        if let Some(MenuItemKind::MenuItem(item)) = submenu.get(item_id) {
+            if let Err(e) = item.set_accelerator(binding.accelerator.as_deref()) {
+                eprintln!("set accelerator failed: {e}");
+            }
+        }
+    }
+}
+
+fn placeholder<R: Runtime>(
+    app: &AppHandle<R>,
+    prefix: &str,
+    label: &str,
+) -> tauri::Result<MenuItem<R>> {
+    MenuItemBuilder::with_id(prefix, label).enabled(false).build(app)
+}
+
+/// `(id, label)` pairs listed in a dynamic submenu.
+type MenuEntries = Vec<(String, String)>;
+
+/// Entries last put in each dynamic submenu, so unchanged state doesn't rebuild it.
+fn shown_entries() -> &'static Mutex<HashMap<&'static str, MenuEntries>> {
+    static SHOWN: OnceLock<Mutex<HashMap<&'static str, MenuEntries>>> = OnceLock::new();
+    SHOWN.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Replace the dynamic entries of a submenu with `(id, label)` items.
+fn replace_entries(
+    app: &AppHandle,
+    menu: &Menu<tauri::Wry>,
+    submenu_id: &'static str,
+    prefix: &str,
+    entries: MenuEntries,
+    empty_label: &str,
+) -> tauri::Result<()> {
+    if let Ok(mut shown) = shown_entries().lock() {
+        if shown.get(submenu_id) == Some(&entries) {
+            return Ok(());
+        }
+        shown.insert(submenu_id, entries.clone());
+    }
+    let Some(MenuItemKind::Submenu(submenu)) = menu.get(submenu_id) else {
+        return Ok(());
+    };
+    for item in submenu.items()? {
+        if item.id().as_ref().starts_with(prefix) {
+            submenu.remove(&item)?;
+        }
+    }
+    if entries.is_empty() {
+        submenu.append(&placeholder(app, prefix, empty_label)?)?;
+    }
+    for (id, label) in entries.into_iter().take(MAX_DYNAMIC_ITEMS) {
+        submenu.append(&MenuItemBuilder::with_id(format!("{prefix}{id}"), label).build(app)?)?;
+    }
+    Ok(())
+}
+
+/// Rebuild the Sessions and Projects menus from the open sessions and the saved
+/// projects. Called when sessions open, close or are renamed and when the state
+/// is saved.
+pub(crate) fn refresh_dynamic_menus(window: &WebviewWindow) {
+    let app = window.app_handle();
+    let Some(menu) = app.menu() else {
+        return;
+    };
+    let mut sessions: MenuEntries = app
+        .try_state::<AppState>()
+        .and_then(|state| state.session_activity().ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| (s.id, s.name))
+        .collect();
+    sessions.sort_by_key(|(id, _)| id.parse::<u64>().unwrap_or(u64::MAX));
+    let projects = crate::persist::read_project_titles(window);
+
+    let result = replace_entries(
+        app,
+        &menu,
+        SESSIONS_SUBMENU_ID,
+        SESSION_ITEM_PREFIX,
+        sessions,
+        "No Open Sessions",
+    )
+    .and_then(|_| {
+        replace_entries(app, &menu, PROJECTS_SUBMENU_ID, PROJECT_ITEM_PREFIX, projects, "No Projects")
+    });
+    if let Err(e) = result {
+        eprintln!("app menu update failed: {e}");
+    }
+}
+
+fn menu_payload(id: &str) -> Option<AppMenuEventPayload> {
+    let payload = |id: &str, session_id: Option<&str>, project_id: Option<&str>| AppMenuEventPayload {
+        id: id.to_string(),
+        session_id: session_id.map(str::to_string),
+        project_id: project_id.map(str::to_string),
+    };
+    match id {
+        MENU_ID_CHECK_UPDATES => Some(payload(MENU_ID_CHECK_UPDATES, None, None)),
+        MENU_ID_NEW_TERMINAL => Some(payload("new-terminal", None, None)),
+        MENU_ID_CLOSE_SESSION => Some(payload("close-session", None, None)),
+        id => {
+            if let Some(session_id) = id.strip_prefix(SESSION_ITEM_PREFIX).filter(|s| !s.is_empty()) {
+                return Some(payload("focus-session", Some(session_id), None));
+            }
+            id.strip_prefix(PROJECT_ITEM_PREFIX)
+                .filter(|s| !s.is_empty())
+                .map(|project_id| payload("open-project", None, Some(project_id)))
+        }
+    }
+}
+
 pub fn handle_app_menu_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
-    if event.id().as_ref() == MENU_ID_CHECK_UPDATES {
-        let _ = app.emit(
-            EVENT_APP_MENU,
-            AppMenuEventPayload {
-                id: MENU_ID_CHECK_UPDATES.to_string(),
-            },
-        );
+    if let Some(payload) = menu_payload(event.id().as_ref()) {
+        let _ = app.emit(EVENT_APP_MENU, payload);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::menu_payload;
+
+    #[test]
+    fn menu_ids_map_to_typed_payloads() {
+        let session = menu_payload("app-session-12").unwrap();
+        assert_eq!((session.id.as_str(), session.session_id.as_deref()), ("focus-session", Some("12")));
+        let project = menu_payload("app-project-p-1").unwrap();
+        assert_eq!((project.id.as_str(), project.project_id.as_deref()), ("open-project", Some("p-1")));
+        assert_eq!(menu_payload("app-close-session").unwrap().id, "close-session");
+        assert!(menu_payload("app-session-").is_none());
+        assert!(menu_payload("tray-open").is_none());
     }
 }
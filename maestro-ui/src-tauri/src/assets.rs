@@ -1,7 +1,6 @@
 use base64::Engine;
 use serde::Deserialize;
 use std::fs;
-use std::io::Write;
 use std::path::{Component, Path, PathBuf};
 
 #[derive(Deserialize, Clone)]
@@ -53,16 +52,7 @@ fn validate_relative_path(input: &str) -> Result<PathBuf, String> {
 
 fn write_text_file_atomic(path: &Path, content: &str) -> Result<(), String> {
     let parent = path.parent().ok_or("invalid target path")?;
-    fs::create_dir_all(parent).map_err(|e| format!("create dir failed: {e}"))?;
-
-    let tmp = path.with_extension("tmp");
-    let mut file = fs::File::create(&tmp).map_err(|e| format!("write failed: {e}"))?;
-    file.write_all(content.as_bytes())
-        .map_err(|e| format!("write failed: {e}"))?;
-    file.sync_all().ok();
-    drop(file);
-
-    fs::rename(&tmp, path).map_err(|e| format!("rename failed: {e}"))?;
+    crate::util::write_atomic(path, content.as_bytes())?;
 
     // Best-effort: ensure the directory entry for the rename is durable.
     let _ = fs::File::open(parent).and_then(|dir_handle| dir_handle.sync_all());
@@ -131,14 +121,7 @@ pub fn save_session_asset(filename: String, content_base64: String) -> Result<St
         .map_err(|e| format!("base64 decode failed: {e}"))?;
 
     let target = dir.join(&safe);
-    let tmp = target.with_extension("tmp-write");
-    {
-        let mut file = fs::File::create(&tmp).map_err(|e| format!("write failed: {e}"))?;
-        file.write_all(&bytes)
-            .map_err(|e| format!("write failed: {e}"))?;
-        file.sync_all().ok();
-    }
-    fs::rename(&tmp, &target).map_err(|e| format!("rename failed: {e}"))?;
+    crate::util::write_atomic(&target, &bytes)?;
 
     Ok(target.to_string_lossy().to_string())
 }
@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+
+use crate::util::{app_data_file, now_epoch_ms};
+
+const AUDIT_FILE_NAME: &str = "audit-v1.jsonl";
+const AUDIT_SETTINGS_FILE_NAME: &str = "audit-settings-v1.json";
+const DEFAULT_RETENTION_DAYS: u64 = 90;
+const PRUNE_INTERVAL_MS: u64 = 60 * 60 * 1000; // 1 hour
+const DEFAULT_READ_LIMIT: usize = 500;
+/// Retention can't be set low enough to wipe the log by accident.
+const MIN_RETENTION_DAYS: u64 = 1;
+const MIN_RETENTION_ENTRIES: usize = 100;
+
+/// One privileged operation, as written to the audit log.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntryV1 {
+    pub ts: u64,
+    /// e.g. `fs.delete`, `ssh.write`, `secret.read`, `recording.decrypt`.
+    pub action: String,
+    pub target: String,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRetentionV1 {
+    /// Entries older than this are pruned. `None` keeps entries forever.
+    pub max_age_days: Option<u64>,
+    /// Keep at most this many newest entries. `None` means no cap.
+    pub max_entries: Option<usize>,
+}
+
+impl AuditRetentionV1 {
+    fn validate(&self) -> Result<(), String> {
+        if self.max_age_days.is_some_and(|days| days < MIN_RETENTION_DAYS) {
+            return Err(format!("maxAgeDays must be at least {MIN_RETENTION_DAYS}"));
+        }
+        if self.max_entries.is_some_and(|entries| entries < MIN_RETENTION_ENTRIES) {
+            return Err(format!("maxEntries must be at least {MIN_RETENTION_ENTRIES}"));
+        }
+        Ok(())
+    }
+
+    /// e.g. `maxAgeDays=90 maxEntries=none`, the target of an `audit.retention` entry.
+    fn describe(&self) -> String {
+        let or_none = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+        format!(
+            "maxAgeDays={} maxEntries={}",
+            or_none(self.max_age_days.map(|d| d.to_string())),
+            or_none(self.max_entries.map(|n| n.to_string()))
+        )
+    }
+}
+
+impl Default for AuditRetentionV1 {
+    fn default() -> Self {
+        Self {
+            max_age_days: Some(DEFAULT_RETENTION_DAYS),
+            max_entries: None,
+        }
+    }
+}
+
+/// Serializes appends and pruning; holds the last prune time (epoch ms).
+fn audit_lock() -> &'static Mutex<u64> {
+    static LOCK: OnceLock<Mutex<u64>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(0))
+}
+
+fn load_retention(app: &AppHandle) -> AuditRetentionV1 {
+    app_data_file(app, AUDIT_SETTINGS_FILE_NAME)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn read_entries(path: &PathBuf) -> Result<Vec<AuditEntryV1>, String> {
+    let file = match fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("open failed: {e}")),
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("read failed: {e}"))?;
+        if let Ok(entry) = serde_json::from_str::<AuditEntryV1>(line.trim()) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Rewrite the log keeping only entries allowed by `retention`.
+fn prune(path: &PathBuf, retention: &AuditRetentionV1, now: u64) -> Result<(), String> {
+    let mut entries = read_entries(path)?;
+    let before = entries.len();
+    if let Some(days) = retention.max_age_days {
+        let cutoff = now.saturating_sub(days.saturating_mul(24 * 60 * 60 * 1000));
+        entries.retain(|e| e.ts >= cutoff);
+    }
+    if let Some(max) = retention.max_entries {
+        if entries.len() > max {
+            entries.drain(..entries.len() - max);
+        }
+    }
+    if entries.len() == before {
+        return Ok(());
+    }
+
+    let tmp = path.with_extension("jsonl.tmp");
+    let mut file = fs::File::create(&tmp).map_err(|e| format!("write temp failed: {e}"))?;
+    for entry in &entries {
+        let line = serde_json::to_string(entry).map_err(|e| format!("serialize failed: {e}"))?;
+        writeln!(file, "{line}").map_err(|e| format!("write temp failed: {e}"))?;
+    }
+    file.sync_all().ok();
+    drop(file);
+    fs::rename(&tmp, path).map_err(|e| format!("rename failed: {e}"))
+}
+
+fn append(app: &AppHandle, entry: &AuditEntryV1) -> Result<(), String> {
+    let path = app_data_file(app, AUDIT_FILE_NAME)?;
+    let dir = path.parent().ok_or("invalid audit log path")?;
+    fs::create_dir_all(dir).map_err(|e| format!("create dir failed: {e}"))?;
+
+    let mut last_prune = audit_lock().lock().map_err(|_| "audit log lock poisoned")?;
+    let line = serde_json::to_string(entry).map_err(|e| format!("serialize failed: {e}"))?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("open failed: {e}"))?;
+    writeln!(file, "{line}").map_err(|e| format!("write failed: {e}"))?;
+    drop(file);
+
+    if entry.ts.saturating_sub(*last_prune) >= PRUNE_INTERVAL_MS {
+        *last_prune = entry.ts;
+        prune(&path, &load_retention(app), entry.ts)?;
+    }
+    Ok(())
+}
+
+/// Record a privileged operation. Failures to write the log are reported on
+/// stderr but never fail the operation itself.
+pub(crate) fn record(app: &AppHandle, action: &str, target: &str, error: Option<&str>) {
+    let entry = AuditEntryV1 {
+        ts: now_epoch_ms(),
+        action: action.to_string(),
+        target: target.to_string(),
+        ok: error.is_none(),
+        error: error.map(str::to_string),
+    };
+    if let Err(e) = append(app, &entry) {
+        eprintln!("Failed to write audit log entry ({action}): {e}");
+    }
+}
+
+/// `record` for the outcome of an operation.
+pub(crate) fn record_result<T>(app: &AppHandle, action: &str, target: &str, result: &Result<T, String>) {
+    record(app, action, target, result.as_ref().err().map(String::as_str));
+}
+
+/// Audit entries, newest first, optionally only those at or after `since` (epoch ms).
+#[tauri::command]
+pub fn read_audit_log(
+    app: AppHandle,
+    since: Option<u64>,
+    limit: Option<usize>,
+) -> Result<Vec<AuditEntryV1>, String> {
+    let path = app_data_file(&app, AUDIT_FILE_NAME)?;
+    let _guard = audit_lock().lock().map_err(|_| "audit log lock poisoned")?;
+    let mut entries = read_entries(&path)?;
+    if let Some(since) = since {
+        entries.retain(|e| e.ts >= since);
+    }
+    entries.reverse();
+    entries.truncate(limit.unwrap_or(DEFAULT_READ_LIMIT));
+    Ok(entries)
+}
+
+#[tauri::command]
+pub fn get_audit_retention(app: AppHandle) -> AuditRetentionV1 {
+    load_retention(&app)
+}
+
+/// Save retention settings and prune the log to match right away. The change itself
+/// is logged first, so it survives the prune it causes.
+#[tauri::command]
+pub fn set_audit_retention(app: AppHandle, retention: AuditRetentionV1) -> Result<(), String> {
+    retention.validate()?;
+    let path = app_data_file(&app, AUDIT_SETTINGS_FILE_NAME)?;
+    crate::util::write_json_atomic(&path, &retention)?;
+    record(&app, "audit.retention", &retention.describe(), None);
+
+    let log_path = app_data_file(&app, AUDIT_FILE_NAME)?;
+    let mut last_prune = audit_lock().lock().map_err(|_| "audit log lock poisoned")?;
+    let now = now_epoch_ms();
+    *last_prune = now;
+    prune(&log_path, &retention, now)
+}
@@ -0,0 +1,189 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::needs_input::{SessionNeedsInput, EVENT_SESSION_NEEDS_INPUT};
+use crate::pty::AppState;
+
+const EVENT_SESSION_AUTO_APPROVED: &str = "session-auto-approved";
+
+/// If the same prompt comes back this soon after being auto-answered, the
+/// answer didn't take; hand it to the user instead of looping.
+const REPEAT_GUARD: Duration = Duration::from_secs(10);
+
+/// Per-project rule answering a known agent prompt automatically.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoApproveRuleV1 {
+    /// Regex matched against the detected prompt text.
+    pub pattern: String,
+    /// Written to the pty verbatim; include `\r` to press Enter.
+    pub response: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SessionAutoApproved {
+    id: String,
+    prompt: String,
+    rule: String,
+    detected_at: u64,
+}
+
+type CompiledRules = Arc<Vec<(Regex, AutoApproveRuleV1)>>;
+
+/// Compiled rules per project id, filled on first prompt and dropped when the
+/// project's settings are saved.
+fn compiled_cache() -> &'static Mutex<HashMap<String, CompiledRules>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, CompiledRules>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Check rules before they are saved.
+pub(crate) fn validate_rules(rules: &[AutoApproveRuleV1]) -> Result<(), String> {
+    for rule in rules {
+        if rule.pattern.trim().is_empty() {
+            return Err("auto-approve pattern is required".to_string());
+        }
+        if rule.response.is_empty() {
+            return Err(format!("auto-approve rule {} has no response", rule.pattern));
+        }
+        Regex::new(&rule.pattern).map_err(|e| format!("invalid auto-approve pattern {}: {e}", rule.pattern))?;
+    }
+    Ok(())
+}
+
+fn compile(rules: Vec<AutoApproveRuleV1>) -> Vec<(Regex, AutoApproveRuleV1)> {
+    rules
+        .into_iter()
+        .filter_map(|rule| match Regex::new(&rule.pattern) {
+            Ok(re) => Some((re, rule)),
+            Err(e) => {
+                eprintln!("Skipping auto-approve rule {}: {e}", rule.pattern);
+                None
+            }
+        })
+        .collect()
+}
+
+fn rules_for(app: &AppHandle, project_id: &str) -> CompiledRules {
+    let Ok(mut cache) = compiled_cache().lock() else {
+        return CompiledRules::default();
+    };
+    if let Some(rules) = cache.get(project_id) {
+        return rules.clone();
+    }
+    // Held across the settings read so an `invalidate` that follows a save can't
+    // be overtaken by rules compiled from the old settings.
+    let rules = match crate::project_settings::settings_for(app, project_id) {
+        Ok(settings) => Arc::new(compile(settings.auto_approve_rules)),
+        Err(e) => {
+            eprintln!("Failed to read auto-approve rules: {e}");
+            return CompiledRules::default();
+        }
+    };
+    cache.insert(project_id.to_string(), rules.clone());
+    rules
+}
+
+/// Forget `project_id`'s compiled rules; called after its settings are saved.
+pub(crate) fn invalidate(project_id: &str) {
+    if let Ok(mut cache) = compiled_cache().lock() {
+        cache.remove(project_id);
+    }
+}
+
+/// Decides, per session, whether a detected prompt is answered by a project
+/// rule or surfaced as `session-needs-input`.
+pub(crate) struct AutoApprover {
+    project_id: Option<String>,
+    last_answered: Option<(String, Instant)>,
+}
+
+impl AutoApprover {
+    pub(crate) fn new(project_id: Option<String>) -> Self {
+        Self {
+            project_id,
+            last_answered: None,
+        }
+    }
+
+    fn matching_rule(&self, window: &WebviewWindow, prompt: &str) -> Option<AutoApproveRuleV1> {
+        let project_id = self.project_id.as_deref()?;
+        rules_for(window.app_handle(), project_id)
+            .iter()
+            .find(|(re, _)| re.is_match(prompt))
+            .map(|(_, rule)| rule.clone())
+    }
+
+    pub(crate) fn handle(&mut self, window: &WebviewWindow, state: &AppState, event: SessionNeedsInput) {
+        let repeated = matches!(
+            &self.last_answered,
+            Some((prompt, at)) if *prompt == event.prompt && at.elapsed() < REPEAT_GUARD
+        );
+        let rule = if repeated {
+            None
+        } else {
+            self.matching_rule(window, &event.prompt)
+        };
+        let Some(rule) = rule else {
+            let _ = window.emit(EVENT_SESSION_NEEDS_INPUT, event);
+            return;
+        };
+
+        let result = state.write_auto_response(&event.id, &rule.response);
+        let label = rule.description.clone().unwrap_or_else(|| rule.pattern.clone());
+        crate::audit::record_result(
+            window.app_handle(),
+            "session.auto_approve",
+            &format!("session {}: {} -> {}", event.id, event.prompt, label),
+            &result,
+        );
+        if let Err(e) = result {
+            eprintln!("Auto-approve failed for session {}: {e}", event.id);
+            let _ = window.emit(EVENT_SESSION_NEEDS_INPUT, event);
+            return;
+        }
+
+        self.last_answered = Some((event.prompt.clone(), Instant::now()));
+        let _ = window.emit(
+            EVENT_SESSION_AUTO_APPROVED,
+            SessionAutoApproved {
+                id: event.id,
+                prompt: event.prompt,
+                rule: label,
+                detected_at: event.detected_at,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile, validate_rules, AutoApproveRuleV1};
+
+    fn rule(pattern: &str, response: &str) -> AutoApproveRuleV1 {
+        AutoApproveRuleV1 {
+            pattern: pattern.to_string(),
+            response: response.to_string(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn rules_are_checked_and_compiled() {
+        assert!(validate_rules(&[rule(r"Proceed\? \(y/n\)", "y\r")]).is_ok());
+        assert!(validate_rules(&[rule("(unclosed", "y\r")]).is_err());
+        assert!(validate_rules(&[rule(" ", "y\r")]).is_err());
+        assert!(validate_rules(&[rule("Proceed", "")]).is_err());
+
+        let compiled = compile(vec![rule("(unclosed", "y"), rule("Trust this folder", "1\r")]);
+        assert_eq!(compiled.len(), 1);
+        assert!(compiled[0].0.is_match("Do you Trust this folder?"));
+    }
+}
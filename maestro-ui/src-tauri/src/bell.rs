@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{Emitter, Manager, UserAttentionType, WebviewWindow};
+
+use crate::tray::StatusTrayState;
+use crate::util::now_epoch_ms;
+
+pub(crate) const EVENT_SESSION_BELL: &str = "session-bell";
+const SETTINGS_FILE_NAME: &str = "bell-settings-v1.json";
+/// At most one `session-bell` per session in this window; extra bells are counted
+/// into the next event.
+const BELL_MIN_INTERVAL_MS: u64 = 1000;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BellSettingsV1 {
+    /// Ask the OS for attention (dock bounce, taskbar flash) when a session rings
+    /// while the window is unfocused. Off by default; held back in Do Not Disturb.
+    #[serde(default)]
+    pub attention_when_unfocused: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionBell {
+    pub id: String,
+    /// Bells rung since the previous event.
+    pub count: usize,
+    pub at: u64,
+}
+
+fn settings_cache() -> &'static Mutex<Option<BellSettingsV1>> {
+    static CACHE: OnceLock<Mutex<Option<BellSettingsV1>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn settings_file_path(window: &WebviewWindow) -> Result<PathBuf, String> {
+    crate::util::app_data_file(window.app_handle(), SETTINGS_FILE_NAME)
+}
+
+fn load_settings(window: &WebviewWindow) -> Result<BellSettingsV1, String> {
+    let mut cache = settings_cache().lock().map_err(|_| "bell settings cache poisoned")?;
+    if let Some(settings) = cache.as_ref() {
+        return Ok(settings.clone());
+    }
+    let path = settings_file_path(window)?;
+    let settings = match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}"))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => BellSettingsV1::default(),
+        Err(e) => return Err(format!("read failed: {e}")),
+    };
+    *cache = Some(settings.clone());
+    Ok(settings)
+}
+
+/// Rate-limits a session's bells; fed by the reader thread with the count from
+/// `OscScanner::take_bells`.
+pub(crate) struct BellDetector {
+    id: String,
+    last_event_at: u64,
+    pending: usize,
+}
+
+impl BellDetector {
+    pub(crate) fn new(id: String) -> Self {
+        Self {
+            id,
+            last_event_at: 0,
+            pending: 0,
+        }
+    }
+
+    fn feed_at(&mut self, bells: usize, now: u64) -> Option<SessionBell> {
+        if bells == 0 {
+            return None;
+        }
+        self.pending += bells;
+        if now.saturating_sub(self.last_event_at) < BELL_MIN_INTERVAL_MS {
+            return None;
+        }
+        self.last_event_at = now;
+        Some(SessionBell {
+            id: self.id.clone(),
+            count: std::mem::take(&mut self.pending),
+            at: now,
+        })
+    }
+
+    /// Emit `session-bell` when due, and request attention if that's enabled.
+    pub(crate) fn feed(&mut self, window: &WebviewWindow, bells: usize) {
+        let Some(event) = self.feed_at(bells, now_epoch_ms()) else {
+            return;
+        };
+        let _ = window.emit(EVENT_SESSION_BELL, event);
+
+        let wants_attention = load_settings(window).is_ok_and(|s| s.attention_when_unfocused);
+        let dnd = window
+            .app_handle()
+            .try_state::<StatusTrayState>()
+            .is_some_and(|tray| tray.focus_mode().enabled);
+        if wants_attention && !dnd && !window.is_focused().unwrap_or(true) {
+            let _ = window.request_user_attention(Some(UserAttentionType::Informational));
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_bell_settings(window: WebviewWindow) -> Result<BellSettingsV1, String> {
+    load_settings(&window)
+}
+
+#[tauri::command]
+pub fn set_bell_settings(window: WebviewWindow, settings: BellSettingsV1) -> Result<(), String> {
+    let path = settings_file_path(&window)?;
+    crate::util::write_json_atomic(&path, &settings)?;
+
+    *settings_cache().lock().map_err(|_| "bell settings cache poisoned")? = Some(settings);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BellDetector;
+
+    #[test]
+    fn bursts_are_rate_limited_and_counted() {
+        let mut bells = BellDetector::new("1".to_string());
+        assert!(bells.feed_at(0, 10_000).is_none());
+        assert_eq!(bells.feed_at(1, 10_000).map(|e| e.count), Some(1));
+        assert!(bells.feed_at(3, 10_200).is_none());
+        assert!(bells.feed_at(1, 10_900).is_none());
+        assert_eq!(bells.feed_at(1, 11_000).map(|e| e.count), Some(5));
+    }
+}
@@ -0,0 +1,181 @@
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use serde::Serialize;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::WebviewWindow;
+
+use crate::output_stats::LatencyPercentiles;
+
+/// Benchmark output goes out under its own event so it doesn't land in the
+/// frontend's buffer for not-yet-mounted terminals.
+const EVENT_PTY_BENCHMARK_OUTPUT: &str = "pty-benchmark-output";
+const DEFAULT_BENCHMARK_BYTES: u64 = 32 * 1024 * 1024;
+const MAX_BENCHMARK_BYTES: u64 = 1024 * 1024 * 1024;
+const BENCHMARK_TIMEOUT: Duration = Duration::from_secs(120);
+/// Printed over and over, like a build log or a large file being catted.
+const BENCHMARK_LINE: &str = "maestro pty throughput benchmark 0123456789 abcdefghijklmnopqrstuvwxyz";
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyBenchmarkV1 {
+    pub platform: String,
+    pub flush_interval_ms: u64,
+    /// Bytes read from the pty. More than requested, since the terminal turns each
+    /// `\n` into `\r\n` (and on Windows ConPTY rewrites the output).
+    pub bytes: u64,
+    /// From spawning the process to the last event being emitted.
+    pub elapsed_ms: f64,
+    pub bytes_per_sec: f64,
+    /// From spawning the process to the pty reaching end of file.
+    pub read_ms: f64,
+    pub reads: u64,
+    pub events: u64,
+    pub coalesced: u64,
+    pub dropped: u64,
+    pub pipeline_latency: Option<LatencyPercentiles>,
+    /// The process was killed after two minutes.
+    pub timed_out: bool,
+}
+
+#[cfg(target_family = "unix")]
+fn benchmark_command(bytes: u64) -> CommandBuilder {
+    let mut cmd = CommandBuilder::new("/bin/sh");
+    cmd.arg("-c");
+    cmd.arg(format!("yes '{BENCHMARK_LINE}' | head -c {bytes}"));
+    cmd
+}
+
+#[cfg(not(target_family = "unix"))]
+fn benchmark_command(bytes: u64) -> CommandBuilder {
+    let line_len = BENCHMARK_LINE.len() as u64 + 2;
+    let script = format!(
+        "$b=[Text.Encoding]::ASCII.GetBytes(('{BENCHMARK_LINE}'+\"`r`n\")*1024);\
+         $o=[Console]::OpenStandardOutput();\
+         for($i=0;$i -lt {};$i++){{$o.Write($b,0,$b.Length)}};$o.Flush()",
+        bytes.div_ceil(line_len * 1024)
+    );
+    let mut cmd = CommandBuilder::new("powershell.exe");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    cmd
+}
+
+fn run_benchmark(
+    window: WebviewWindow,
+    bytes: u64,
+    flush_interval_ms: u64,
+) -> Result<PtyBenchmarkV1, String> {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    let id = format!("benchmark-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+
+    let pair = native_pty_system()
+        .openpty(PtySize {
+            rows: 40,
+            cols: 120,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| format!("openpty failed: {e}"))?;
+    let started = Instant::now();
+    let mut child = pair
+        .slave
+        .spawn_command(benchmark_command(bytes))
+        .map_err(|e| format!("spawn failed: {e}"))?;
+    // Without the slave end open the reader sees end of file once the child exits.
+    drop(pair.slave);
+    let mut reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| format!("pty reader failed: {e}"))?;
+
+    let done = Arc::new(AtomicBool::new(false));
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let mut killer = child.clone_killer();
+    let watchdog_done = done.clone();
+    let watchdog_timed_out = timed_out.clone();
+    std::thread::spawn(move || {
+        while started.elapsed() < BENCHMARK_TIMEOUT {
+            if watchdog_done.load(Ordering::Relaxed) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        watchdog_timed_out.store(true, Ordering::Relaxed);
+        let _ = killer.kill();
+    });
+
+    let output = crate::output_batch::OutputBatcher::spawn_on(
+        window,
+        id.clone(),
+        EVENT_PTY_BENCHMARK_OUTPUT,
+        flush_interval_ms,
+    );
+    let stats = output.stats();
+    let mut buf = [0u8; 8192];
+    let mut utf8_carry: Vec<u8> = Vec::new();
+    let mut read = 0u64;
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                read += n as u64;
+                let data = crate::pty::decode_utf8_stream(&mut utf8_carry, &buf[..n]);
+                if !data.is_empty() {
+                    output.push(data);
+                }
+            }
+            Err(_) => break,
+        }
+    }
+    let read_ms = started.elapsed().as_secs_f64() * 1000.0;
+    output.finish();
+    let elapsed = started.elapsed().as_secs_f64();
+    done.store(true, Ordering::Relaxed);
+    let _ = child.wait();
+
+    if read == 0 {
+        return Err("benchmark process produced no output".to_string());
+    }
+    let stats = stats.lock().map_err(|_| "output stats poisoned")?.snapshot(&id);
+    Ok(PtyBenchmarkV1 {
+        platform: std::env::consts::OS.to_string(),
+        flush_interval_ms,
+        bytes: read,
+        elapsed_ms: elapsed * 1000.0,
+        bytes_per_sec: read as f64 / elapsed.max(f64::EPSILON),
+        read_ms,
+        reads: stats.chunks,
+        events: stats.events,
+        coalesced: stats.coalesced,
+        dropped: stats.dropped,
+        pipeline_latency: stats.pipeline_latency,
+        timed_out: timed_out.load(Ordering::Relaxed),
+    })
+}
+
+/// Push `bytes` of synthetic output (32 MiB by default) through a pty, the reader's
+/// UTF-8 decoding and the output batcher, and time it end to end. For tuning the
+/// batching on each platform; not exposed in the UI. `flush_interval_ms` defaults to
+/// the saved output batching setting.
+#[tauri::command]
+pub async fn benchmark_pty_throughput(
+    window: WebviewWindow,
+    bytes: Option<u64>,
+    flush_interval_ms: Option<u64>,
+) -> Result<PtyBenchmarkV1, String> {
+    let bytes = bytes.unwrap_or(DEFAULT_BENCHMARK_BYTES);
+    if bytes == 0 || bytes > MAX_BENCHMARK_BYTES {
+        return Err(format!("bytes must be 1-{MAX_BENCHMARK_BYTES}"));
+    }
+    let flush_interval_ms = match flush_interval_ms {
+        Some(ms) => {
+            crate::output_batch::validate(&crate::output_batch::OutputBatchingV1 { flush_interval_ms: ms })?;
+            ms
+        }
+        None => crate::output_batch::flush_interval_ms(),
+    };
+    tauri::async_runtime::spawn_blocking(move || run_benchmark(window, bytes, flush_interval_ms))
+        .await
+        .map_err(|e| format!("benchmark task join failed: {e:?}"))?
+}
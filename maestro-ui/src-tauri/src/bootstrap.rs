@@ -0,0 +1,205 @@
+use serde::Serialize;
+use tauri::{Emitter, WebviewWindow};
+
+use crate::pty::AppState;
+
+pub(crate) const EVENT_SESSION_BOOTSTRAP: &str = "session-bootstrap";
+
+/// OSC sequence printed after each step: `ESC ] 1337 ; MaestroBootstrap=<step>;<exit code> BEL`.
+/// Terminals ignore unknown 1337 keys, so the marker never shows up on screen.
+const MARKER_PREFIX: &str = "\x1b]1337;MaestroBootstrap=";
+const MARKER_END: char = '\x07';
+const MAX_PENDING_CHARS: usize = 64;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionBootstrapProgress {
+    pub id: String,
+    /// 1-based index of the step; equals `total` once status is `done`.
+    pub step: usize,
+    pub total: usize,
+    pub command: Option<String>,
+    /// `running`, `succeeded`, `failed`, or `done`.
+    pub status: &'static str,
+    pub exit_code: Option<i32>,
+}
+
+/// Trims the requested steps and drops empty ones. Steps are single command lines.
+pub(crate) fn normalize_steps(steps: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let steps: Vec<String> = steps
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if steps.iter().any(|s| s.contains('\n') || s.contains('\r')) {
+        return Err("bootstrap commands must be single lines".to_string());
+    }
+    #[cfg(not(target_family = "unix"))]
+    if !steps.is_empty() {
+        return Err("bootstrap commands require a unix shell".to_string());
+    }
+    Ok(steps)
+}
+
+fn marker_command(step: usize, status: &str) -> String {
+    format!("printf '\\033]1337;MaestroBootstrap=%s;%s\\007' {step} \"{status}\"")
+}
+
+/// Prepends the steps to an agent command run through `sh -c`; the first failing
+/// step exits the script with its status, so the agent never starts.
+pub(crate) fn wrap_agent_script(steps: &[String], command: &str) -> String {
+    let mut lines: Vec<String> = Vec::with_capacity(steps.len() + 1);
+    for (i, step) in steps.iter().enumerate() {
+        lines.push(step.clone());
+        lines.push(format!(
+            "__maestro_rc=$?; {}; [ \"$__maestro_rc\" -eq 0 ] || exit \"$__maestro_rc\"",
+            marker_command(i + 1, "$__maestro_rc")
+        ));
+    }
+    lines.push(command.to_string());
+    lines.join("\n")
+}
+
+/// Tracks a session's bootstrap steps from the markers in its output. For
+/// interactive shells the steps are typed into the shell one at a time, each
+/// only after the previous one reported success.
+pub(crate) struct BootstrapRunner {
+    id: String,
+    steps: Vec<String>,
+    /// Status variable of the interactive shell; None when the steps are scripted.
+    shell_status_var: Option<&'static str>,
+    current: usize,
+    pending: String,
+    finished: bool,
+}
+
+impl BootstrapRunner {
+    pub(crate) fn new(id: String, steps: Vec<String>, shell: Option<&str>) -> Option<Self> {
+        if steps.is_empty() {
+            return None;
+        }
+        let shell_status_var = shell.map(|shell| {
+            if shell.ends_with("fish") {
+                "$status"
+            } else {
+                "$?"
+            }
+        });
+        Some(Self {
+            id,
+            steps,
+            shell_status_var,
+            current: 0,
+            pending: String::new(),
+            finished: false,
+        })
+    }
+
+    fn emit(&self, window: &WebviewWindow, step: usize, status: &'static str, exit_code: Option<i32>) {
+        let _ = window.emit(
+            EVENT_SESSION_BOOTSTRAP,
+            SessionBootstrapProgress {
+                id: self.id.clone(),
+                step,
+                total: self.steps.len(),
+                command: self.steps.get(step.wrapping_sub(1)).cloned(),
+                status,
+                exit_code,
+            },
+        );
+    }
+
+    /// Starts the step at `self.current`, typing it into the shell when interactive.
+    fn run_current(&mut self, window: &WebviewWindow, state: &AppState) {
+        self.emit(window, self.current + 1, "running", None);
+        let Some(status_var) = self.shell_status_var else {
+            return;
+        };
+        let line = format!(
+            "{}; {}\r",
+            self.steps[self.current],
+            marker_command(self.current + 1, status_var)
+        );
+        if let Err(e) = state.write_prechecked(&self.id, &line) {
+            self.finished = true;
+            self.emit(window, self.current + 1, "failed", None);
+            eprintln!("[PTY] Bootstrap write failed: id={}, {e}", self.id);
+        }
+    }
+
+    pub(crate) fn start(&mut self, window: &WebviewWindow, state: &AppState) {
+        self.run_current(window, state);
+    }
+
+    pub(crate) fn feed(&mut self, window: &WebviewWindow, state: &AppState, data: &str) {
+        if self.finished {
+            return;
+        }
+        self.pending.push_str(data);
+
+        while let Some((step, code)) = take_marker(&mut self.pending) {
+            if step != self.current + 1 {
+                continue;
+            }
+            if code != 0 {
+                self.finished = true;
+                self.emit(window, step, "failed", Some(code));
+                return;
+            }
+            self.emit(window, step, "succeeded", Some(0));
+            self.current += 1;
+            if self.current == self.steps.len() {
+                self.finished = true;
+                self.emit(window, self.steps.len(), "done", None);
+                return;
+            }
+            self.run_current(window, state);
+        }
+    }
+}
+
+/// Removes the first complete marker from `pending` and returns (step, exit code).
+/// Text that can't be part of a marker is discarded.
+fn take_marker(pending: &mut String) -> Option<(usize, i32)> {
+    let Some(start) = pending.find(MARKER_PREFIX) else {
+        let keep_from = pending
+            .char_indices()
+            .rev()
+            .nth(MAX_PENDING_CHARS - 1)
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        pending.drain(..keep_from);
+        return None;
+    };
+    let body_start = start + MARKER_PREFIX.len();
+    let Some(len) = pending[body_start..].find(MARKER_END) else {
+        pending.drain(..start);
+        if pending.len() > MARKER_PREFIX.len() + MAX_PENDING_CHARS {
+            pending.clear();
+        }
+        return None;
+    };
+    let body = pending[body_start..body_start + len].to_string();
+    pending.drain(..body_start + len + MARKER_END.len_utf8());
+
+    let parsed = body
+        .split_once(';')
+        .and_then(|(step, code)| Some((step.trim().parse().ok()?, code.trim().parse().ok()?)));
+    parsed.or_else(|| take_marker(pending))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_marker_handles_split_chunks() {
+        let mut pending = String::from("npm i\r\nadded 3 packages\x1b]1337;Maestro");
+        assert_eq!(take_marker(&mut pending), None);
+        pending.push_str("Bootstrap=1;0\x07$ \x1b]1337;MaestroBootstrap=2;127\x07");
+        assert_eq!(take_marker(&mut pending), Some((1, 0)));
+        assert_eq!(take_marker(&mut pending), Some((2, 127)));
+        assert_eq!(take_marker(&mut pending), None);
+    }
+}
@@ -0,0 +1,211 @@
+use rand_core::{OsRng, RngCore};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::WebviewWindow;
+
+use crate::claude_logs::{claude_projects_dir, encode_project_path};
+use crate::persist::PersistedProjectV1;
+
+/// Log files (newest first) and lines per file scanned for a recorded `cwd`.
+const MAX_LOGS_SCANNED: usize = 3;
+const MAX_LINES_SCANNED: usize = 200;
+const MAX_WALK_DEPTH: usize = 32;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedClaudeProject {
+    /// Directory name under `~/.claude/projects`.
+    pub encoded: String,
+    pub path: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeProjectImport {
+    pub created: Vec<PersistedProjectV1>,
+    pub skipped: Vec<SkippedClaudeProject>,
+}
+
+fn new_project_id() -> String {
+    let mut b = [0u8; 16];
+    OsRng.fill_bytes(&mut b);
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let hex: String = b.iter().map(|x| format!("{x:02x}")).collect();
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+/// The `cwd` Claude recorded in the project's newest session logs. Only accepted
+/// if it encodes back to `encoded`.
+fn cwd_from_logs(dir: &Path, encoded: &str) -> Option<String> {
+    let mut logs: Vec<(SystemTime, PathBuf)> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .filter_map(|p| Some((fs::metadata(&p).ok()?.modified().ok()?, p)))
+        .collect();
+    logs.sort_by(|a, b| b.0.cmp(&a.0));
+
+    for (_, path) in logs.into_iter().take(MAX_LOGS_SCANNED) {
+        let Ok(file) = fs::File::open(&path) else {
+            continue;
+        };
+        for line in BufReader::new(file).lines().take(MAX_LINES_SCANNED).map_while(Result::ok) {
+            let Ok(value) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            if let Some(cwd) = value.get("cwd").and_then(Value::as_str) {
+                if encode_project_path(cwd) == encoded {
+                    return Some(cwd.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Find the directory under `dir` whose encoded path is `rest`. The encoding maps
+/// every non-alphanumeric char to `-`, so each level matches child names by their
+/// encoded form and backtracks on ambiguity (`my-app` vs `my/app`).
+#[cfg_attr(not(target_family = "unix"), allow(dead_code))]
+fn walk_encoded(dir: &Path, rest: &str, depth: usize) -> Option<PathBuf> {
+    if rest.is_empty() {
+        return Some(dir.to_path_buf());
+    }
+    if depth > MAX_WALK_DEPTH {
+        return None;
+    }
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = encode_project_path(&entry.file_name().to_string_lossy());
+        if name.is_empty() {
+            continue;
+        }
+        let remaining = if rest == name {
+            Some("")
+        } else {
+            rest.strip_prefix(name.as_str()).and_then(|r| r.strip_prefix('-'))
+        };
+        if let Some(found) = remaining.and_then(|r| walk_encoded(&path, r, depth + 1)) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn decode_project_dir(dir: &Path, encoded: &str) -> Option<String> {
+    if let Some(cwd) = cwd_from_logs(dir, encoded) {
+        return Some(cwd);
+    }
+    // Without a log to go on, only absolute unix paths (leading `/` -> `-`) can be walked.
+    #[cfg(target_family = "unix")]
+    {
+        let found = walk_encoded(Path::new("/"), encoded.strip_prefix('-')?, 0)?;
+        Some(found.to_string_lossy().to_string())
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        None
+    }
+}
+
+/// Turn every `~/.claude/projects/<encoded cwd>` whose directory still exists into a
+/// Maestro project saved in the persisted state, skipping paths that already are
+/// projects. The UI should reload persisted state afterwards to pick them up.
+#[tauri::command]
+pub async fn import_claude_projects(window: WebviewWindow) -> Result<ClaudeProjectImport, String> {
+    let projects_dir = claude_projects_dir()?;
+    if !projects_dir.is_dir() {
+        return Ok(ClaudeProjectImport {
+            created: Vec::new(),
+            skipped: Vec::new(),
+        });
+    }
+
+    let (candidates, mut skipped) = tauri::async_runtime::spawn_blocking(move || {
+        let mut candidates: Vec<PersistedProjectV1> = Vec::new();
+        let mut skipped: Vec<SkippedClaudeProject> = Vec::new();
+        let Ok(read_dir) = fs::read_dir(&projects_dir) else {
+            return (candidates, skipped);
+        };
+        let mut dirs: Vec<PathBuf> = read_dir.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+        dirs.sort();
+
+        for dir in dirs {
+            let encoded = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let Some(cwd) = decode_project_dir(&dir, &encoded) else {
+                skipped.push(SkippedClaudeProject {
+                    encoded,
+                    path: None,
+                    reason: "could not resolve the original directory".to_string(),
+                });
+                continue;
+            };
+            if !Path::new(&cwd).is_dir() {
+                skipped.push(SkippedClaudeProject {
+                    encoded,
+                    path: Some(cwd),
+                    reason: "directory no longer exists".to_string(),
+                });
+                continue;
+            }
+            let title = Path::new(&cwd)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| cwd.clone());
+            candidates.push(PersistedProjectV1 {
+                id: new_project_id(),
+                title,
+                base_path: Some(cwd),
+                environment_id: None,
+                assets_enabled: None,
+                sound_instrument: None,
+                sound_config: None,
+            });
+        }
+        (candidates, skipped)
+    })
+    .await
+    .map_err(|e| format!("import claude projects task join failed: {e:?}"))?;
+
+    let created = crate::persist::append_projects(&window, candidates.clone())?;
+    for project in candidates {
+        if !created.iter().any(|p| p.id == project.id) {
+            skipped.push(SkippedClaudeProject {
+                encoded: encode_project_path(project.base_path.as_deref().unwrap_or_default()),
+                path: project.base_path,
+                reason: "already a project".to_string(),
+            });
+        }
+    }
+    Ok(ClaudeProjectImport { created, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::walk_encoded;
+    use std::fs;
+
+    #[test]
+    fn walk_encoded_backtracks_on_ambiguous_names() {
+        let root = std::env::temp_dir().join(format!("maestro-claude-import-{}", std::process::id()));
+        fs::create_dir_all(root.join("my").join("other")).unwrap();
+        fs::create_dir_all(root.join("my-app").join(".config")).unwrap();
+
+        assert_eq!(walk_encoded(&root, "my-app--config", 0), Some(root.join("my-app").join(".config")));
+        assert_eq!(walk_encoded(&root, "my-other", 0), Some(root.join("my").join("other")));
+        assert_eq!(walk_encoded(&root, "missing", 0), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
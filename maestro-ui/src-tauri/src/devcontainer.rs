@@ -0,0 +1,269 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::{State, WebviewWindow};
+
+use crate::pty::{AppState, SessionInfo};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DevcontainerInfo {
+    pub config_path: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub dockerfile: Option<String>,
+    pub workspace_folder: Option<String>,
+    /// Whether the `devcontainer` CLI needed to build/start it is on PATH.
+    pub cli_available: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DevcontainerUpResult {
+    pub container_id: String,
+    pub remote_user: Option<String>,
+    pub remote_workspace_folder: Option<String>,
+}
+
+/// devcontainer.json is JSONC: drop `//` and `/* */` comments and trailing
+/// commas so it parses as plain JSON.
+fn strip_jsonc(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                if let Some(next) = chars.next() {
+                    out.push(next);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match (c, chars.peek().copied()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+            }
+            (',', _) => {
+                // Skip the comma if only whitespace separates it from a closing bracket.
+                let rest: String = chars.clone().take_while(|ch| ch.is_whitespace()).collect();
+                let after = chars.clone().nth(rest.chars().count());
+                if !matches!(after, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn devcontainer_config_path(root: &Path) -> Option<PathBuf> {
+    [
+        root.join(".devcontainer").join("devcontainer.json"),
+        root.join(".devcontainer.json"),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+}
+
+fn devcontainer_cli() -> Result<PathBuf, String> {
+    crate::ssh_fs::find_program_in_path("devcontainer").ok_or_else(|| {
+        "devcontainer CLI not found. Install it with `npm install -g @devcontainers/cli`.".to_string()
+    })
+}
+
+fn quote_arg(value: &str) -> String {
+    #[cfg(target_family = "unix")]
+    {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        format!("\"{}\"", value.replace('"', "\\\""))
+    }
+}
+
+/// Look for `.devcontainer/devcontainer.json` (or `.devcontainer.json`) in `root`.
+#[tauri::command]
+pub fn detect_devcontainer(root: String) -> Result<Option<DevcontainerInfo>, String> {
+    let Some(path) = devcontainer_config_path(Path::new(root.trim())) else {
+        return Ok(None);
+    };
+    let raw = fs::read_to_string(&path).map_err(|e| format!("read failed: {e}"))?;
+    let config: Value =
+        serde_json::from_str(&strip_jsonc(&raw)).map_err(|e| format!("parse failed: {e}"))?;
+    let text = |pointer: &str| config.pointer(pointer).and_then(|v| v.as_str()).map(str::to_string);
+
+    Ok(Some(DevcontainerInfo {
+        config_path: path.to_string_lossy().to_string(),
+        name: text("/name"),
+        image: text("/image"),
+        dockerfile: text("/build/dockerfile").or_else(|| text("/dockerFile")),
+        workspace_folder: text("/workspaceFolder"),
+        cli_available: devcontainer_cli().is_ok(),
+    }))
+}
+
+/// Build (if needed) and start the project's devcontainer via `devcontainer up`.
+#[tauri::command]
+pub async fn devcontainer_up(root: String) -> Result<DevcontainerUpResult, String> {
+    tauri::async_runtime::spawn_blocking(move || devcontainer_up_sync(root))
+        .await
+        .map_err(|e| format!("devcontainer task join failed: {e:?}"))?
+}
+
+fn devcontainer_up_sync(root: String) -> Result<DevcontainerUpResult, String> {
+    let root = root.trim();
+    if devcontainer_config_path(Path::new(root)).is_none() {
+        return Err("no devcontainer.json found".to_string());
+    }
+    let output = Command::new(devcontainer_cli()?)
+        .args(["up", "--workspace-folder", root])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| format!("run devcontainer failed: {e}"))?;
+
+    // The CLI prints progress logs, then a JSON result as its last stdout line.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let result: Option<Value> = stdout
+        .lines()
+        .rev()
+        .find(|l| l.trim_start().starts_with('{'))
+        .and_then(|l| serde_json::from_str(l.trim()).ok());
+    let Some(result) = result else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+        return Err(format!(
+            "devcontainer up failed: {}",
+            tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+        ));
+    };
+    let text = |key: &str| result.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    if text("outcome").as_deref() != Some("success") {
+        return Err(format!(
+            "devcontainer up failed: {}",
+            text("message").unwrap_or_else(|| "unknown error".to_string())
+        ));
+    }
+
+    Ok(DevcontainerUpResult {
+        container_id: text("containerId").ok_or("devcontainer up returned no container id")?,
+        remote_user: text("remoteUser"),
+        remote_workspace_folder: text("remoteWorkspaceFolder"),
+    })
+}
+
+/// Open a session running `command` (or a login shell) inside the project's
+/// devcontainer through `devcontainer exec`. The container must be up.
+#[tauri::command]
+pub async fn create_devcontainer_session(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    root: String,
+    name: Option<String>,
+    command: Option<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    env_vars: Option<HashMap<String, String>>,
+) -> Result<SessionInfo, String> {
+    let root = root.trim().to_string();
+    if devcontainer_config_path(Path::new(&root)).is_none() {
+        return Err("no devcontainer.json found".to_string());
+    }
+    let cli = devcontainer_cli()?;
+
+    let mut exec = format!(
+        "{} exec --workspace-folder {}",
+        quote_arg(&cli.to_string_lossy()),
+        quote_arg(&root)
+    );
+    // Env vars have to be forwarded explicitly; the host env doesn't reach the
+    // container. Reference them by name so values never appear in the command line.
+    let mut keys: Vec<&str> = env_vars
+        .iter()
+        .flat_map(|vars| vars.keys())
+        .map(|k| k.trim())
+        .filter(|k| !k.is_empty() && k.chars().all(|c| c == '_' || c.is_ascii_alphanumeric()))
+        .collect();
+    keys.sort();
+    for key in keys {
+        #[cfg(target_family = "unix")]
+        exec.push_str(&format!(" --remote-env \"{key}=${{{key}}}\""));
+        #[cfg(not(target_family = "unix"))]
+        exec.push_str(&format!(" --remote-env \"{key}=%{key}%\""));
+    }
+    let inner = command
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(|c| format!("sh -lc {}", quote_arg(c)))
+        .unwrap_or_else(|| "sh -c 'exec \"${SHELL:-/bin/sh}\" -l'".to_string());
+    exec.push(' ');
+    exec.push_str(&inner);
+
+    crate::telemetry::record_feature("devcontainer");
+    crate::pty::create_session(
+        window,
+        state,
+        crate::pty::CreateSessionOptions {
+            session: crate::pty::SessionRequest {
+                name: Some(name.unwrap_or_else(|| "devcontainer".to_string())),
+                command: Some(exec),
+                cwd: Some(root),
+                cols,
+                rows,
+                env_vars,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_jsonc;
+
+    #[test]
+    fn strips_comments_and_trailing_commas() {
+        let input = r#"{
+            // line comment
+            "name": "app // not a comment",
+            /* block */ "features": { "a": 1, },
+            "forwardPorts": [3000, 8080,],
+        }"#;
+        let parsed: serde_json::Value = serde_json::from_str(&strip_jsonc(input)).unwrap();
+        assert_eq!(parsed["name"], "app // not a comment");
+        assert_eq!(parsed["forwardPorts"][1], 8080);
+        assert_eq!(parsed["features"]["a"], 1);
+    }
+}
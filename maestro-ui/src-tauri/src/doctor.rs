@@ -0,0 +1,346 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{Manager, WebviewWindow};
+
+use crate::util::now_epoch_ms;
+
+/// Sidecars listed under `bundle.externalBin` that release builds spawn.
+const BUNDLED_BINARIES: &[&str] = &["maestro-server"];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorCheck {
+    pub id: String,
+    pub label: String,
+    /// `ok`, `warning`, `error`, or `skipped`.
+    pub status: String,
+    pub message: String,
+    pub details: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    /// False when any check is an error.
+    pub ok: bool,
+    pub checks: Vec<DoctorCheck>,
+    pub generated_at: u64,
+}
+
+fn check(id: &str, label: &str, status: &str, message: impl Into<String>, details: Vec<String>) -> DoctorCheck {
+    DoctorCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status: status.to_string(),
+        message: message.into(),
+        details,
+    }
+}
+
+fn check_login_path() -> DoctorCheck {
+    let Some(path) = crate::pty::computed_login_path() else {
+        return check("path", "Login PATH", "error", "PATH could not be computed; sessions start without one", Vec::new());
+    };
+    let entries: Vec<PathBuf> = std::env::split_paths(&path).collect();
+    let missing: Vec<String> = entries
+        .iter()
+        .filter(|p| !p.is_dir())
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    let mut details: Vec<String> = entries.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    if missing.is_empty() {
+        check("path", "Login PATH", "ok", format!("{} entries", entries.len()), details)
+    } else {
+        details.retain(|d| missing.contains(d));
+        check(
+            "path",
+            "Login PATH",
+            "warning",
+            format!("{} of {} entries don't exist", missing.len(), entries.len()),
+            details,
+        )
+    }
+}
+
+fn check_ssh_tools() -> DoctorCheck {
+    let mut details: Vec<String> = Vec::new();
+    let mut missing: Vec<&str> = Vec::new();
+    for tool in ["ssh", "scp", "sftp"] {
+        match crate::ssh_fs::program_path(tool) {
+            Ok(path) => details.push(format!("{tool}: {}", path.to_string_lossy())),
+            Err(_) => {
+                details.push(format!("{tool}: not found"));
+                missing.push(tool);
+            }
+        }
+    }
+    if missing.is_empty() {
+        check("ssh", "SSH tools", "ok", "ssh, scp and sftp found", details)
+    } else {
+        check(
+            "ssh",
+            "SSH tools",
+            "error",
+            format!("missing {}; remote hosts and file transfer won't work", missing.join(", ")),
+            details,
+        )
+    }
+}
+
+fn check_bundled_binaries() -> DoctorCheck {
+    if cfg!(not(feature = "custom-protocol")) {
+        return check("bundled", "Bundled binaries", "skipped", "dev builds don't bundle sidecars", Vec::new());
+    }
+    let Some(dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) else {
+        return check("bundled", "Bundled binaries", "error", "app executable location unknown", Vec::new());
+    };
+    let mut details: Vec<String> = Vec::new();
+    let mut missing = 0;
+    for name in BUNDLED_BINARIES {
+        let file = if cfg!(windows) { format!("{name}.exe") } else { name.to_string() };
+        let path = dir.join(file);
+        if path.is_file() {
+            details.push(path.to_string_lossy().to_string());
+        } else {
+            missing += 1;
+            details.push(format!("{} (missing)", path.to_string_lossy()));
+        }
+    }
+    if missing == 0 {
+        check("bundled", "Bundled binaries", "ok", "all sidecars present", details)
+    } else {
+        check("bundled", "Bundled binaries", "error", format!("{missing} sidecar(s) missing"), details)
+    }
+}
+
+fn check_keychain(window: &WebviewWindow) -> DoctorCheck {
+    match crate::secure::probe_master_key(window) {
+        Ok(true) => check("keychain", "Keychain access", "ok", "data key readable", Vec::new()),
+        Ok(false) => check(
+            "keychain",
+            "Keychain access",
+            "warning",
+            "no data key stored yet; it is created when secure storage is first used",
+            Vec::new(),
+        ),
+        Err(e) => check("keychain", "Keychain access", "error", e, Vec::new()),
+    }
+}
+
+/// zellij runs persistent sessions and layouts; it needs a socket dir it owns.
+fn check_zellij() -> DoctorCheck {
+    let info = crate::zellij_layouts::inspect();
+    let Some(path) = info.path else {
+        return check(
+            "zellij",
+            "Zellij",
+            "warning",
+            "zellij not found on the login PATH; persistent zellij sessions and layouts are unavailable",
+            Vec::new(),
+        );
+    };
+    let mut details = vec![format!("zellij: {path}")];
+    let mut problems: Vec<String> = Vec::new();
+    let mut status = "ok";
+    match info.version.as_deref() {
+        Some(version) if info.supported => details.push(format!("version: {version}")),
+        Some(version) => {
+            details.push(format!("version: {version}"));
+            problems.push(format!("{version} is too old; {} or newer is needed", info.minimum_version));
+            status = "warning";
+        }
+        None => {
+            problems.push("zellij --version failed".to_string());
+            status = "warning";
+        }
+    }
+    if !info.stale_sessions.is_empty() {
+        problems.push(format!("{} session(s) from another zellij version", info.stale_sessions.len()));
+        details.extend(info.stale_sessions.iter().map(|s| format!("stale: {} ({})", s.name, s.socket)));
+        status = "warning";
+    }
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let uid = crate::external_sessions::current_uid();
+        for dir in crate::external_sessions::zellij_socket_dirs() {
+            let shown = dir.to_string_lossy();
+            let Ok(meta) = fs::metadata(&dir) else {
+                details.push(format!("socket dir {shown}: not created yet"));
+                continue;
+            };
+            if !meta.is_dir() {
+                details.push(format!("socket dir {shown}: not a directory"));
+                problems.push(format!("{shown} is not a directory"));
+                status = "error";
+            } else if uid.is_some_and(|uid| meta.uid() != uid) {
+                details.push(format!("socket dir {shown}: owned by uid {}", meta.uid()));
+                problems.push(format!("{shown} belongs to another user"));
+                status = "error";
+            } else if meta.mode() & 0o022 != 0 {
+                details.push(format!("socket dir {shown}: mode {:o}", meta.mode() & 0o777));
+                problems.push(format!("{shown} is writable by other users"));
+                if status == "ok" {
+                    status = "warning";
+                }
+            } else {
+                details.push(format!("socket dir {shown}: ok"));
+            }
+        }
+        let live = crate::external_sessions::live_zellij_sockets().len();
+        details.push(format!("running sessions: {live}"));
+    }
+    let message = if problems.is_empty() {
+        "zellij found; socket dirs ok".to_string()
+    } else {
+        problems.join("; ")
+    };
+    check("zellij", "Zellij", status, message, details)
+}
+
+/// Output of a D-Bus call on the session bus, through gdbus or dbus-send.
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn session_bus_call(method: &str, arg: Option<&str>) -> Option<String> {
+    let output = if let Some(gdbus) = crate::ssh_fs::find_program_in_path("gdbus") {
+        Command::new(gdbus)
+            .args(["call", "--session", "--dest", "org.freedesktop.DBus"])
+            .args(["--object-path", "/org/freedesktop/DBus", "--method"])
+            .arg(format!("org.freedesktop.DBus.{method}"))
+            .args(arg)
+            .output()
+    } else {
+        Command::new(crate::ssh_fs::find_program_in_path("dbus-send")?)
+            .args(["--session", "--print-reply", "--dest=org.freedesktop.DBus", "/org/freedesktop/DBus"])
+            .arg(format!("org.freedesktop.DBus.{method}"))
+            .args(arg.map(|a| format!("string:{a}")))
+            .output()
+    };
+    let output = output.ok().filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// A notification daemon owns, or can be started for, `org.freedesktop.Notifications`.
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn check_notifications(_window: &WebviewWindow) -> DoctorCheck {
+    const NAME: &str = "org.freedesktop.Notifications";
+    const LABEL: &str = "Notifications";
+    if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none() {
+        let message = "no D-Bus session bus; notifications can't be shown";
+        return check("notifications", LABEL, "warning", message, Vec::new());
+    }
+    let Some(owned) = session_bus_call("NameHasOwner", Some(NAME)) else {
+        return check("notifications", LABEL, "skipped", "gdbus or dbus-send is needed to check", Vec::new());
+    };
+    if owned.contains("true") {
+        return check("notifications", LABEL, "ok", "notification daemon running", Vec::new());
+    }
+    if session_bus_call("ListActivatableNames", None).is_some_and(|names| names.contains(NAME)) {
+        check("notifications", LABEL, "ok", "notification daemon starts on demand", Vec::new())
+    } else {
+        check("notifications", LABEL, "warning", "no notification daemon on the session bus", Vec::new())
+    }
+}
+
+/// The app's entry in Notification Center's preferences, created once macOS has asked.
+#[cfg(target_os = "macos")]
+fn check_notifications(window: &WebviewWindow) -> DoctorCheck {
+    const LABEL: &str = "Notifications";
+    let identifier = window.app_handle().config().identifier.clone();
+    let Some(home) = dirs::home_dir() else {
+        return check("notifications", LABEL, "skipped", "home directory unknown", Vec::new());
+    };
+    let apps = Command::new("plutil")
+        .args(["-convert", "json", "-o", "-"])
+        .arg(home.join("Library/Preferences/com.apple.ncprefs.plist"))
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| serde_json::from_slice::<serde_json::Value>(&o.stdout).ok())
+        .and_then(|value| value.get("apps").and_then(|apps| apps.as_array()).cloned());
+    let Some(apps) = apps else {
+        let message = "Notification Center preferences unreadable";
+        return check("notifications", LABEL, "skipped", message, Vec::new());
+    };
+    match apps
+        .iter()
+        .find(|app| app.get("bundle-id").and_then(|id| id.as_str()) == Some(identifier.as_str()))
+    {
+        Some(app) => {
+            let flags = app.get("flags").and_then(|f| f.as_u64()).unwrap_or_default();
+            check(
+                "notifications",
+                LABEL,
+                "ok",
+                "registered with Notification Center; allow or mute it in System Settings",
+                vec![format!("{identifier}: flags {flags:#x}")],
+            )
+        }
+        None => check(
+            "notifications",
+            LABEL,
+            "warning",
+            "not registered with Notification Center yet; macOS asks the first time",
+            vec![identifier],
+        ),
+    }
+}
+
+/// Windows' global switch for app notifications (Settings › System › Notifications).
+#[cfg(windows)]
+fn check_notifications(_window: &WebviewWindow) -> DoctorCheck {
+    const LABEL: &str = "Notifications";
+    let output = Command::new("reg")
+        .args(["query", r"HKCU\Software\Microsoft\Windows\CurrentVersion\PushNotifications"])
+        .args(["/v", "ToastEnabled"])
+        .output();
+    match output {
+        Ok(o) if o.status.success() && String::from_utf8_lossy(&o.stdout).contains("0x0") => {
+            let message = "notifications are turned off in Windows settings";
+            check("notifications", LABEL, "warning", message, Vec::new())
+        }
+        Ok(_) => check("notifications", LABEL, "ok", "notifications are on", Vec::new()),
+        Err(e) => check("notifications", LABEL, "skipped", format!("reg failed: {e}"), Vec::new()),
+    }
+}
+
+fn check_app_data(window: &WebviewWindow) -> DoctorCheck {
+    let dir = match window.app_handle().path().app_data_dir() {
+        Ok(dir) => dir,
+        Err(_) => return check("appData", "App data directory", "error", "unknown app data dir", Vec::new()),
+    };
+    let shown = vec![dir.to_string_lossy().to_string()];
+    let probe = dir.join(".doctor-probe");
+    let result = fs::create_dir_all(&dir).and_then(|_| fs::write(&probe, b"ok"));
+    let _ = fs::remove_file(&probe);
+    match result {
+        Ok(()) => check("appData", "App data directory", "ok", "writable", shown),
+        Err(e) => check("appData", "App data directory", "error", format!("not writable: {e}"), shown),
+    }
+}
+
+/// Health report for the settings page: login PATH, ssh/scp/sftp, bundled sidecars,
+/// keychain access, OS notifications, zellij and its socket dirs, and the app data dir.
+#[tauri::command]
+pub async fn run_doctor(window: WebviewWindow) -> Result<DoctorReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let checks = vec![
+            check_login_path(),
+            check_ssh_tools(),
+            check_bundled_binaries(),
+            check_keychain(&window),
+            check_notifications(&window),
+            check_zellij(),
+            check_app_data(&window),
+        ];
+        let ok = !checks.iter().any(|c| c.status == "error");
+        DoctorReport {
+            ok,
+            checks,
+            generated_at: now_epoch_ms(),
+        }
+    })
+    .await
+    .map_err(|e| format!("doctor task join failed: {e:?}"))
+}
@@ -0,0 +1,383 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{Emitter, Manager, State, WebviewWindow};
+
+use crate::pty::AppState;
+use crate::util::now_epoch_ms;
+
+pub(crate) const EVENT_SESSION_EXEC_CONFIRM: &str = "session-exec-confirm";
+const CONFIG_FILE_NAME: &str = "exec-guard-v1.json";
+
+/// Commands destructive enough to ask about before they run.
+const DEFAULT_PATTERNS: &[&str] = &[
+    r"\brm\s+(-\w*[rR]\w*f\w*|-\w*f\w*[rR]\w*|(-\w+\s+)*--recursive\s+--force)\s+(/|~|\$HOME)/?(\s|$)",
+    r"\bgit\s+push\b.*(\s--force|\s-f)(\s|$)",
+    r"(?i)\bdrop\s+(table|database|schema)\b",
+    r"(?i)\btruncate\s+table\b",
+    r"\bmkfs(\.\w+)?\s",
+    r"\bdd\b.*\bof=/dev/",
+];
+
+/// Confirmation before a typed command runs. When enabled, a line matching one of
+/// `patterns` is held back at the Enter key until the UI answers through
+/// `confirm_session_exec`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecGuardV1 {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Also hold commands typed by the user, not only automation and agent writes.
+    #[serde(default)]
+    pub include_user_input: bool,
+    pub patterns: Vec<String>,
+}
+
+impl Default for ExecGuardV1 {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            include_user_input: false,
+            patterns: DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExecConfirm {
+    pub id: String,
+    pub token: String,
+    pub command: String,
+    pub pattern: String,
+    pub requested_at: u64,
+}
+
+/// Input split at the Enter key that completed a guarded command.
+pub(crate) struct HeldExec {
+    /// Written right away, so the command is visible on the prompt line.
+    pub before: String,
+    /// The Enter key and anything after it; written only once confirmed.
+    pub after: String,
+    pub command: String,
+    pub pattern: String,
+}
+
+struct PendingExec {
+    session_id: String,
+    after: String,
+    /// Whether the held input was the user's, which the queued input is taken to be too.
+    is_user: bool,
+}
+
+/// How a held command was answered (see `AppState::answer_held_exec`).
+pub(crate) enum ExecAnswer {
+    /// Write the held Enter, then screen `queued` like fresh input.
+    Approved { enter: String, queued: String, is_user: bool },
+    /// Clear the shell's line; the queued input is dropped.
+    Rejected,
+}
+
+type CompiledGuard = Arc<(ExecGuardV1, Vec<(String, Regex)>)>;
+
+fn guard_cache() -> &'static Mutex<Option<CompiledGuard>> {
+    static CACHE: OnceLock<Mutex<Option<CompiledGuard>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn pending() -> &'static Mutex<HashMap<String, PendingExec>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, PendingExec>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn config_file_path(window: &WebviewWindow) -> Result<PathBuf, String> {
+    crate::util::app_data_file(window.app_handle(), CONFIG_FILE_NAME)
+}
+
+fn compile(config: ExecGuardV1) -> Result<CompiledGuard, String> {
+    let compiled = config
+        .patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            Regex::new(p)
+                .map(|re| (p.to_string(), re))
+                .map_err(|e| format!("invalid pattern {p:?}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Arc::new((config, compiled)))
+}
+
+fn load_config(window: &WebviewWindow) -> Result<ExecGuardV1, String> {
+    let path = config_file_path(window)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ExecGuardV1::default()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn compiled_guard(window: &WebviewWindow) -> Option<CompiledGuard> {
+    let mut cache = guard_cache().lock().ok()?;
+    if let Some(guard) = cache.as_ref() {
+        return Some(guard.clone());
+    }
+    let guard = load_config(window)
+        .and_then(compile)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load exec guard; leaving it off: {e}");
+            Arc::new((
+                ExecGuardV1 {
+                    enabled: false,
+                    ..ExecGuardV1::default()
+                },
+                Vec::new(),
+            ))
+        });
+    *cache = Some(guard.clone());
+    Some(guard)
+}
+
+/// Replay `data` onto `line`, the command line typed so far, the way the shell's line
+/// editor would. At each Enter the finished line is taken out of `line` and passed to
+/// `on_enter` with the Enter's byte offset in `data`; returning false stops there.
+pub(crate) fn replay_input(line: &mut String, data: &str, mut on_enter: impl FnMut(usize, &str) -> bool) {
+    let mut iter = data.char_indices().peekable();
+    while let Some((i, ch)) = iter.next() {
+        match ch {
+            '\r' | '\n' => {
+                let command = std::mem::take(line);
+                if !on_enter(i, command.trim()) {
+                    return;
+                }
+            }
+            '\u{7f}' | '\u{8}' => {
+                line.pop();
+            }
+            // Ctrl-C / Ctrl-U abandon the line.
+            '\u{3}' | '\u{15}' => line.clear(),
+            '\u{1b}' => {
+                // Cursor keys and the like; the tracked line may drift from what the
+                // shell shows.
+                let mut chars = data[i + 1..].chars().peekable();
+                let before = chars.clone().count();
+                crate::pty::skip_escape_sequence(&mut chars);
+                for _ in 0..before - chars.count() {
+                    iter.next();
+                }
+            }
+            '\t' => line.push(' '),
+            c if c.is_control() => {}
+            c => line.push(c),
+        }
+    }
+}
+
+/// Track the line being typed in `line` and stop at the first Enter that would run
+/// a command matching one of `patterns`. A line that drifted from the shell's only
+/// makes the guard miss, never block wrongly.
+fn screen_line(patterns: &[(String, Regex)], line: &mut String, data: &str) -> Option<HeldExec> {
+    let mut held = None;
+    replay_input(line, data, |i, command| {
+        let Some((pattern, _)) = patterns.iter().find(|(_, re)| re.is_match(command)) else {
+            return true;
+        };
+        held = Some(HeldExec {
+            before: data[..i].to_string(),
+            after: data[i..].to_string(),
+            command: command.to_string(),
+            pattern: pattern.clone(),
+        });
+        false
+    });
+    held
+}
+
+/// Check input about to be written to session `id`. `line` is the session's
+/// partially typed command line. Returns the split input when a command must be
+/// confirmed first.
+pub(crate) fn screen(window: &WebviewWindow, line: &mut String, data: &str, is_user: bool) -> Option<HeldExec> {
+    let guard = compiled_guard(window)?;
+    let (config, patterns) = guard.as_ref();
+    if !config.enabled || (is_user && !config.include_user_input) {
+        line.clear();
+        return None;
+    }
+    screen_line(patterns, line, data)
+}
+
+/// Park `after` for session `id` under a new confirmation token.
+fn park(id: &str, after: String, is_user: bool) -> String {
+    static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+    let token = format!("exec-{}", NEXT_TOKEN.fetch_add(1, Ordering::Relaxed));
+    if let Ok(mut pending) = pending().lock() {
+        pending.insert(
+            token.clone(),
+            PendingExec {
+                session_id: id.to_string(),
+                after,
+                is_user,
+            },
+        );
+    }
+    token
+}
+
+/// Park the rest of the input and ask the UI to confirm the command.
+pub(crate) fn hold(window: &WebviewWindow, id: &str, held: HeldExec, is_user: bool) {
+    let token = park(id, held.after, is_user);
+    let _ = window.emit(
+        EVENT_SESSION_EXEC_CONFIRM,
+        SessionExecConfirm {
+            id: id.to_string(),
+            token,
+            command: held.command,
+            pattern: held.pattern,
+            requested_at: now_epoch_ms(),
+        },
+    );
+}
+
+/// While a command waits for confirmation, later input for the session is queued
+/// behind it instead of being typed onto the held line. Returns true if queued.
+pub(crate) fn queue_if_pending(id: &str, data: &str) -> bool {
+    let Ok(mut pending) = pending().lock() else {
+        return false;
+    };
+    match pending.values_mut().find(|p| p.session_id == id) {
+        Some(p) => {
+            p.after.push_str(data);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Whether a command for the session is waiting for confirmation.
+pub(crate) fn has_pending(id: &str) -> bool {
+    pending()
+        .lock()
+        .map(|pending| pending.values().any(|p| p.session_id == id))
+        .unwrap_or(false)
+}
+
+/// Drop any confirmation waiting on a session that has closed.
+pub(crate) fn session_closed(id: &str) {
+    if let Ok(mut pending) = pending().lock() {
+        pending.retain(|_, p| p.session_id != id);
+    }
+}
+
+/// Take the confirmation `token` and the session it belongs to.
+fn take_answer(token: &str, approve: bool) -> Result<(String, ExecAnswer), String> {
+    let held = pending()
+        .lock()
+        .map_err(|_| "exec guard poisoned")?
+        .remove(token)
+        .ok_or("unknown or expired confirmation")?;
+    if !approve {
+        return Ok((held.session_id, ExecAnswer::Rejected));
+    }
+    let enter_len = held.after.chars().next().map_or(0, char::len_utf8);
+    let (enter, queued) = held.after.split_at(enter_len);
+    let answer = ExecAnswer::Approved {
+        enter: enter.to_string(),
+        queued: queued.to_string(),
+        is_user: held.is_user,
+    };
+    Ok((held.session_id, answer))
+}
+
+/// Answer a `session-exec-confirm` event. Approving sends the held Enter; input queued
+/// after it is screened again and may be held at the next guarded command. Rejecting
+/// clears the typed line with Ctrl-U and drops the queued input.
+#[tauri::command]
+pub fn confirm_session_exec(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    token: String,
+    approve: bool,
+) -> Result<(), String> {
+    let (session_id, answer) = take_answer(&token, approve)?;
+    state.answer_held_exec(&window, &session_id, answer)
+}
+
+#[tauri::command]
+pub fn get_exec_guard(window: WebviewWindow) -> Result<ExecGuardV1, String> {
+    load_config(&window)
+}
+
+/// Replace the exec guard settings. Applies to running sessions immediately.
+#[tauri::command]
+pub fn set_exec_guard(window: WebviewWindow, guard: ExecGuardV1) -> Result<(), String> {
+    let compiled = compile(guard.clone())?;
+    crate::util::write_json_atomic(&config_file_path(&window)?, &guard)?;
+
+    *guard_cache().lock().map_err(|_| "exec guard cache poisoned")? = Some(compiled);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile, has_pending, park, queue_if_pending, screen_line, take_answer, ExecAnswer, ExecGuardV1};
+
+    #[test]
+    fn holds_dangerous_commands_at_enter() {
+        let guard = compile(ExecGuardV1::default()).unwrap();
+        let patterns = &guard.1;
+
+        let mut line = String::new();
+        assert!(screen_line(patterns, &mut line, "rm -rf ./build\r").is_none());
+        assert!(screen_line(patterns, &mut line, "git push --force-with-lease\r").is_none());
+
+        assert!(screen_line(patterns, &mut line, "rm -rf /").is_none());
+        let held = screen_line(patterns, &mut line, "\rls\r").unwrap();
+        assert_eq!(held.command, "rm -rf /");
+        assert_eq!((held.before.as_str(), held.after.as_str()), ("", "\rls\r"));
+
+        let held = screen_line(patterns, &mut line, "git push -f origin\x1b[D main\r").unwrap();
+        assert_eq!(held.before, "git push -f origin\x1b[D main");
+        assert!(screen_line(patterns, &mut line, "psql -c 'drop table users'\r").is_some());
+    }
+
+    #[test]
+    fn approving_releases_the_enter_and_rescreens_queued_input() {
+        let guard = compile(ExecGuardV1::default()).unwrap();
+        let id = format!("exec-approve-{}", std::process::id());
+        let token = park(&id, "\rls\r".to_string(), false);
+        assert!(queue_if_pending(&id, "rm -rf ~\recho done\r"));
+
+        let (session_id, answer) = take_answer(&token, true).unwrap();
+        assert_eq!(session_id, id);
+        assert!(!has_pending(&id));
+        let ExecAnswer::Approved { enter, queued, is_user } = answer else {
+            panic!("expected an approval");
+        };
+        assert_eq!((enter.as_str(), queued.as_str(), is_user), ("\r", "ls\rrm -rf ~\recho done\r", false));
+
+        // The queued input starts on a fresh line and holds at the next guarded Enter.
+        let mut line = String::new();
+        let held = screen_line(&guard.1, &mut line, &queued).unwrap();
+        assert_eq!(held.command, "rm -rf ~");
+        assert_eq!((held.before.as_str(), held.after.as_str()), ("ls\rrm -rf ~", "\recho done\r"));
+    }
+
+    #[test]
+    fn rejecting_drops_the_queued_input() {
+        let id = format!("exec-reject-{}", std::process::id());
+        let token = park(&id, "\r".to_string(), true);
+        assert!(queue_if_pending(&id, "ls\r"));
+
+        let (session_id, answer) = take_answer(&token, false).unwrap();
+        assert_eq!(session_id, id);
+        assert!(matches!(answer, ExecAnswer::Rejected));
+        assert!(!has_pending(&id));
+        assert!(!queue_if_pending(&id, "ls\r"));
+        assert!(take_answer(&token, true).is_err());
+    }
+}
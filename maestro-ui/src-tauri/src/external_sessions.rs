@@ -0,0 +1,550 @@
+use serde::Serialize;
+#[cfg(target_family = "unix")]
+use std::fs;
+#[cfg(target_family = "unix")]
+use std::path::{Path, PathBuf};
+#[cfg(target_family = "unix")]
+use std::process::Command;
+#[cfg(target_family = "unix")]
+use std::time::{Duration, UNIX_EPOCH};
+#[cfg(target_family = "unix")]
+use tauri::Emitter;
+use tauri::{State, WebviewWindow};
+
+use crate::pty::{AppState, SessionInfo};
+#[cfg(target_family = "unix")]
+use crate::util::now_epoch_ms;
+
+/// Sessions with this prefix were created by Maestro itself and aren't "external".
+#[cfg(target_family = "unix")]
+const OWN_SESSION_PREFIX: &str = crate::persistent_sessions::SESSION_PREFIX;
+#[cfg(target_family = "unix")]
+pub(crate) const EVENT_SESSION_ATTACHED: &str = "session-attached";
+/// How long a fresh client gets to start drawing before the redraw nudge.
+#[cfg(target_family = "unix")]
+const ATTACH_SETTLE_MS: u64 = 300;
+/// Give up waiting for a first frame after this long; no event is sent.
+#[cfg(target_family = "unix")]
+const ATTACH_TIMEOUT_MS: u64 = 10_000;
+#[cfg(target_family = "unix")]
+const ATTACH_POLL_MS: u64 = 25;
+/// A refused socket younger than this may belong to a server that's still starting.
+#[cfg(target_family = "unix")]
+const DEAD_SOCKET_MIN_AGE_MS: u64 = 10_000;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalSessionInfo {
+    /// `tmux` or `zellij`.
+    pub kind: String,
+    pub name: String,
+    /// tmux server socket the session lives on.
+    pub socket: Option<String>,
+    pub windows: Option<u32>,
+    pub attached: Option<bool>,
+    pub created_at: Option<u64>,
+    pub last_activity_at: Option<u64>,
+    /// Working directory of the session (tmux only).
+    pub cwd: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PersistentBackend {
+    Tmux,
+    Zellij,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentBackendSession {
+    pub backend: PersistentBackend,
+    pub name: String,
+    pub socket: Option<String>,
+    pub attached: Option<bool>,
+    pub created_at: Option<u64>,
+    pub last_activity_at: Option<u64>,
+    pub cwd: Option<String>,
+    /// Project whose base path contains `cwd` (the most specific one wins).
+    pub project_id: Option<String>,
+}
+
+#[cfg(target_family = "unix")]
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionAttached {
+    pub id: String,
+    /// `tmux` or `zellij`.
+    pub kind: String,
+    pub name: String,
+    /// Epoch ms of the first output after attaching (after the redraw, for zellij).
+    pub first_frame_at: u64,
+    /// A resize was sent to force a full redraw.
+    pub nudged: bool,
+}
+
+#[cfg(target_family = "unix")]
+pub(crate) fn current_uid() -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    let home = dirs::home_dir()?;
+    fs::metadata(home).ok().map(|m| m.uid())
+}
+
+/// tmux server sockets: `$TMUX_TMPDIR/tmux-<uid>/*` (default `/tmp`). Each `-L`
+/// name the user has started a server with is its own socket.
+#[cfg(target_family = "unix")]
+fn tmux_sockets() -> Vec<PathBuf> {
+    let Some(uid) = current_uid() else {
+        return Vec::new();
+    };
+    let base = std::env::var("TMUX_TMPDIR")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| "/tmp".to_string());
+    let dir = Path::new(&base).join(format!("tmux-{uid}"));
+    let mut sockets: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| !p.is_dir()).collect())
+        .unwrap_or_default();
+    sockets.sort();
+    sockets
+}
+
+#[cfg(target_family = "unix")]
+fn list_tmux_sessions(tmux: &Path) -> Vec<ExternalSessionInfo> {
+    let mut out: Vec<ExternalSessionInfo> = Vec::new();
+    for socket in tmux_sockets() {
+        let Ok(output) = Command::new(tmux)
+            .arg("-S")
+            .arg(&socket)
+            .args([
+                "list-sessions",
+                "-F",
+                "#{session_name}\t#{session_windows}\t#{session_attached}\t#{session_created}\t#{session_activity}\t#{session_path}",
+            ])
+            .output()
+        else {
+            continue;
+        };
+        if !output.status.success() {
+            continue;
+        }
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let mut fields = line.split('\t');
+            let name = fields.next().unwrap_or_default().to_string();
+            if name.is_empty() || name.starts_with(OWN_SESSION_PREFIX) {
+                continue;
+            }
+            out.push(ExternalSessionInfo {
+                kind: "tmux".to_string(),
+                name,
+                socket: Some(socket.to_string_lossy().to_string()),
+                windows: fields.next().and_then(|s| s.parse().ok()),
+                attached: fields.next().and_then(|s| s.parse::<u32>().ok()).map(|n| n > 0),
+                created_at: fields.next().and_then(|s| s.parse::<u64>().ok()).map(|s| s * 1000),
+                last_activity_at: fields.next().and_then(|s| s.parse::<u64>().ok()).map(|s| s * 1000),
+                cwd: fields.next().filter(|s| !s.is_empty()).map(str::to_string),
+            });
+        }
+    }
+    out
+}
+
+#[derive(Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentSessionsRepair {
+    /// Session names whose server answered.
+    pub alive: Vec<String>,
+    /// Socket files removed because nothing was listening on them.
+    pub removed: Vec<String>,
+    /// Dead sockets that couldn't be removed, with the reason.
+    pub failed: Vec<String>,
+}
+
+#[cfg(target_family = "unix")]
+#[derive(Debug, PartialEq)]
+enum SocketState {
+    Alive,
+    /// The file exists but nothing is listening: the server exited without cleaning up.
+    Dead,
+    /// Couldn't tell (permissions, timeouts); left alone.
+    Unknown,
+}
+
+#[cfg(target_family = "unix")]
+fn probe_socket(path: &Path) -> SocketState {
+    match std::os::unix::net::UnixStream::connect(path) {
+        Ok(_) => SocketState::Alive,
+        Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => SocketState::Dead,
+        Err(_) => SocketState::Unknown,
+    }
+}
+
+#[cfg(target_family = "unix")]
+fn modified_ms(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+}
+
+/// Remove a socket nothing listens on. Fresh sockets are kept in case their server
+/// is still starting up.
+#[cfg(target_family = "unix")]
+fn remove_dead_socket(path: &Path) -> Result<bool, String> {
+    let age = modified_ms(path).map(|at| now_epoch_ms().saturating_sub(at));
+    if !age.is_some_and(|age| age >= DEAD_SOCKET_MIN_AGE_MS) {
+        return Ok(false);
+    }
+    fs::remove_file(path).map_err(|e| format!("remove failed: {e}"))?;
+    Ok(true)
+}
+
+/// Where zellij may keep its sockets: `$ZELLIJ_SOCKET_DIR`, `$XDG_RUNTIME_DIR/zellij`,
+/// or `$TMPDIR/zellij-<uid>`. Not all of them need exist.
+#[cfg(target_family = "unix")]
+pub(crate) fn zellij_socket_dirs() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    if let Ok(dir) = std::env::var("ZELLIJ_SOCKET_DIR") {
+        roots.push(PathBuf::from(dir));
+    }
+    if let Ok(dir) = std::env::var("XDG_RUNTIME_DIR") {
+        roots.push(Path::new(&dir).join("zellij"));
+    }
+    if let Some(uid) = current_uid() {
+        roots.push(std::env::temp_dir().join(format!("zellij-{uid}")));
+        roots.push(Path::new("/tmp").join(format!("zellij-{uid}")));
+    }
+    roots.dedup();
+    roots
+}
+
+/// zellij keeps one socket per session under `<socket dir>/<version>/<name>`.
+#[cfg(target_family = "unix")]
+fn zellij_sockets() -> Vec<PathBuf> {
+    use std::os::unix::fs::FileTypeExt;
+    let mut sockets: Vec<PathBuf> = zellij_socket_dirs()
+        .iter()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| p.is_dir())
+        .filter_map(|version_dir| fs::read_dir(version_dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_socket()))
+        .map(|e| e.path())
+        .collect();
+    sockets.sort();
+    sockets.dedup();
+    sockets
+}
+
+/// Sockets of running zellij servers, of any zellij version.
+#[cfg(target_family = "unix")]
+pub(crate) fn live_zellij_sockets() -> Vec<PathBuf> {
+    zellij_sockets()
+        .into_iter()
+        .filter(|path| probe_socket(path) == SocketState::Alive)
+        .collect()
+}
+
+#[cfg(target_family = "unix")]
+fn socket_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// Sessions with a live server. Sockets left behind by crashed servers are removed
+/// on the way, so they don't show up as sessions that can't be attached.
+#[cfg(target_family = "unix")]
+fn list_zellij_sessions() -> Vec<ExternalSessionInfo> {
+    let mut out: Vec<ExternalSessionInfo> = Vec::new();
+    for path in zellij_sockets() {
+        let name = socket_name(&path);
+        if name.is_empty() || name.starts_with(OWN_SESSION_PREFIX) || out.iter().any(|s| s.name == name) {
+            continue;
+        }
+        if probe_socket(&path) == SocketState::Dead {
+            if let Err(e) = remove_dead_socket(&path) {
+                eprintln!("Failed to remove dead zellij socket {}: {e}", path.display());
+            }
+            continue;
+        }
+        out.push(ExternalSessionInfo {
+            kind: "zellij".to_string(),
+            name,
+            socket: None,
+            windows: None,
+            attached: None,
+            created_at: None,
+            // The socket is touched whenever a client talks to the server.
+            last_activity_at: modified_ms(&path),
+            cwd: None,
+        });
+    }
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
+
+#[cfg(target_family = "unix")]
+fn repair_zellij_sockets() -> PersistentSessionsRepair {
+    let mut report = PersistentSessionsRepair::default();
+    for path in zellij_sockets() {
+        match probe_socket(&path) {
+            SocketState::Alive => report.alive.push(socket_name(&path)),
+            SocketState::Dead => match remove_dead_socket(&path) {
+                Ok(true) => report.removed.push(path.to_string_lossy().to_string()),
+                Ok(false) => {}
+                Err(e) => report.failed.push(format!("{}: {e}", path.display())),
+            },
+            SocketState::Unknown => {}
+        }
+    }
+    report
+}
+
+/// Poll until the session has output newer than `after`. `None` when it closed or
+/// nothing came within `timeout_ms`.
+#[cfg(target_family = "unix")]
+fn wait_for_output(state: &AppState, id: &str, after: u64, timeout_ms: u64) -> Option<u64> {
+    let deadline = now_epoch_ms() + timeout_ms;
+    loop {
+        let at = state.last_output_at(id)?;
+        if at > after {
+            return Some(at);
+        }
+        if now_epoch_ms() >= deadline {
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(ATTACH_POLL_MS));
+    }
+}
+
+/// Watch a freshly attached multiplexer client and emit `session-attached` once it
+/// has drawn. A zellij client often shows nothing until the next redraw, so it gets
+/// one: the pty is shrunk by a row and restored, and each SIGWINCH makes zellij
+/// repaint the whole screen.
+#[cfg(target_family = "unix")]
+fn prime_attach(window: WebviewWindow, state: AppState, id: String, kind: String, name: String) {
+    std::thread::spawn(move || {
+        let attached_at = now_epoch_ms();
+        let mut first_frame = wait_for_output(&state, &id, 0, ATTACH_SETTLE_MS);
+        if state.last_output_at(&id).is_none() {
+            return;
+        }
+        let mut nudged = false;
+        if kind == "zellij" {
+            if let Some(size) = state.pty_size(&id).filter(|s| s.rows > 1) {
+                let nudge_at = now_epoch_ms();
+                let shrunk = portable_pty::PtySize {
+                    rows: size.rows - 1,
+                    ..size
+                };
+                if state.resize(&id, shrunk).is_ok() {
+                    // Let zellij handle the first SIGWINCH so the two sizes aren't merged.
+                    std::thread::sleep(Duration::from_millis(50));
+                    nudged = state.resize(&id, size).is_ok();
+                }
+                if nudged {
+                    first_frame = wait_for_output(&state, &id, nudge_at, ATTACH_TIMEOUT_MS);
+                }
+            }
+        }
+        let remaining = ATTACH_TIMEOUT_MS.saturating_sub(now_epoch_ms().saturating_sub(attached_at));
+        let Some(first_frame_at) = first_frame.or_else(|| wait_for_output(&state, &id, 0, remaining)) else {
+            return;
+        };
+        let _ = window.emit(
+            EVENT_SESSION_ATTACHED,
+            SessionAttached {
+                id,
+                kind,
+                name,
+                first_frame_at,
+                nudged,
+            },
+        );
+    });
+}
+
+/// The user's own tmux/zellij sessions, found through their socket dirs. Opt-in: the UI
+/// only calls this when the user asks to look for sessions started outside Maestro.
+#[tauri::command]
+pub async fn list_external_sessions() -> Result<Vec<ExternalSessionInfo>, String> {
+    #[cfg(target_family = "unix")]
+    {
+        tauri::async_runtime::spawn_blocking(|| {
+            let mut sessions: Vec<ExternalSessionInfo> = Vec::new();
+            if let Some(tmux) = crate::ssh_fs::find_program_in_path("tmux") {
+                sessions.extend(list_tmux_sessions(&tmux));
+            }
+            if crate::ssh_fs::find_program_in_path("zellij").is_some() {
+                sessions.extend(list_zellij_sessions());
+            }
+            sessions
+        })
+        .await
+        .map_err(|e| format!("list external sessions task join failed: {e:?}"))
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        Ok(Vec::new())
+    }
+}
+
+/// Reconcile zellij's socket dirs with the servers that are actually running: sockets
+/// nothing listens on (left by a crash or a killed server) are deleted, so
+/// `list_external_sessions` only reports sessions that can be attached.
+#[tauri::command]
+pub async fn repair_persistent_sessions() -> Result<PersistentSessionsRepair, String> {
+    #[cfg(target_family = "unix")]
+    {
+        tauri::async_runtime::spawn_blocking(repair_zellij_sockets)
+            .await
+            .map_err(|e| format!("repair persistent sessions task join failed: {e:?}"))
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        Ok(PersistentSessionsRepair::default())
+    }
+}
+
+/// The project whose base path is the longest prefix of `cwd`.
+fn project_for_cwd(projects: &[(String, String)], cwd: &str) -> Option<String> {
+    let cwd = std::path::Path::new(cwd);
+    projects
+        .iter()
+        .filter(|(_, base)| cwd.starts_with(base))
+        .max_by_key(|(_, base)| base.len())
+        .map(|(id, _)| id.clone())
+}
+
+/// Every multiplexer session Maestro can see, across backends, in one list: tmux
+/// sessions on all of the user's sockets and zellij sessions, newest activity first.
+/// Maestro no longer keeps persistent sessions of its own, so these are all external.
+#[tauri::command]
+pub async fn list_all_persistent_backends(window: WebviewWindow) -> Result<Vec<PersistentBackendSession>, String> {
+    let sessions = list_external_sessions().await?;
+    let projects = crate::persist::read_project_paths(&window);
+    let mut out: Vec<PersistentBackendSession> = sessions
+        .into_iter()
+        .map(|s| PersistentBackendSession {
+            backend: if s.kind == "tmux" {
+                PersistentBackend::Tmux
+            } else {
+                PersistentBackend::Zellij
+            },
+            project_id: s.cwd.as_deref().and_then(|cwd| project_for_cwd(&projects, cwd)),
+            name: s.name,
+            socket: s.socket,
+            attached: s.attached,
+            created_at: s.created_at,
+            last_activity_at: s.last_activity_at,
+            cwd: s.cwd,
+        })
+        .collect();
+    out.sort_by(|a, b| b.last_activity_at.cmp(&a.last_activity_at));
+    Ok(out)
+}
+
+/// Open a Maestro session attached to an external tmux/zellij session as another client.
+/// Closing the Maestro session detaches; the external session keeps running.
+/// `session-attached` follows once the client has drawn its first frame.
+#[tauri::command]
+pub async fn adopt_external_session(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    kind: String,
+    name: String,
+    socket: Option<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    project_id: Option<String>,
+) -> Result<SessionInfo, String> {
+    #[cfg(target_family = "unix")]
+    {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err("session name is required".to_string());
+        }
+        let kind = kind.trim().to_string();
+        let command = match kind.as_str() {
+            "tmux" => {
+                let socket = socket
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .filter(|s| tmux_sockets().iter().any(|p| p.to_string_lossy() == s.as_str()))
+                    .ok_or("unknown tmux socket")?;
+                format!(
+                    "exec tmux -S {} attach-session -t {}",
+                    crate::ssh_fs::shell_escape_posix(&socket),
+                    crate::ssh_fs::shell_escape_posix(&format!("={name}"))
+                )
+            }
+            "zellij" => format!("exec zellij attach {}", crate::ssh_fs::shell_escape_posix(&name)),
+            other => return Err(format!("unsupported session kind: {other}")),
+        };
+        // zellij output is spooled under this id so a later reattach can show it.
+        let persist_id = (kind == "zellij").then(|| crate::scrollback_spool::zellij_persist_id(&name));
+
+        let app_state = state.inner().clone();
+        let info = crate::pty::create_session(
+            window.clone(),
+            state,
+            crate::pty::CreateSessionOptions {
+                session: crate::pty::SessionRequest {
+                    name: Some(name.clone()),
+                    command: Some(command),
+                    cols,
+                    rows,
+                    persist_id,
+                    project_id,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await?;
+        crate::telemetry::record_feature("external-attach");
+        prime_attach(window, app_state, info.id.clone(), kind, name);
+        Ok(info)
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        let _ = (window, state, kind, name, socket, cols, rows, project_id);
+        Err("external tmux/zellij sessions are only supported on unix".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::project_for_cwd;
+    #[cfg(target_family = "unix")]
+    use super::{probe_socket, SocketState};
+
+    #[test]
+    fn project_for_cwd_prefers_most_specific_base() {
+        let projects = vec![
+            ("outer".to_string(), "/work".to_string()),
+            ("inner".to_string(), "/work/api".to_string()),
+        ];
+        assert_eq!(project_for_cwd(&projects, "/work/api/src").as_deref(), Some("inner"));
+        assert_eq!(project_for_cwd(&projects, "/work/apix").as_deref(), Some("outer"));
+        assert_eq!(project_for_cwd(&projects, "/home"), None);
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn probe_tells_live_from_dead_sockets() {
+        let dir = std::env::temp_dir().join(format!("maestro-zellij-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("work");
+        let _ = std::fs::remove_file(&path);
+
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        assert_eq!(probe_socket(&path), SocketState::Alive);
+        drop(listener);
+        assert_eq!(probe_socket(&path), SocketState::Dead);
+        assert_eq!(probe_socket(&dir.join("missing")), SocketState::Unknown);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
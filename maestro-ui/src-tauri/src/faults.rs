@@ -0,0 +1,159 @@
+use rand_core::{OsRng, RngCore};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// `domain=rate[:max_delay_ms],...`, e.g. `fs=0.2,ssh=0.5:3000,keychain=1`. Read once
+/// at first use, and only in debug builds.
+const FAULTS_ENV: &str = "MAESTRO_FAULTS";
+
+/// Kinds of operation that can be made to fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum FaultDomain {
+    /// Project file commands (files.rs) and state writes (persist.rs).
+    Fs,
+    /// Every `ssh` invocation of the remote file browser (ssh_fs.rs).
+    Ssh,
+    /// Keychain reads and writes for the data key (secure.rs), as if access was denied.
+    Keychain,
+    /// Poisons the secure storage cache lock, as if a thread had panicked holding it.
+    Poison,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct FaultSpec {
+    /// Chance of failing, 0 to 1.
+    rate: f64,
+    /// Each operation first sleeps a random time up to this.
+    max_delay_ms: u64,
+}
+
+fn parse_domain(name: &str) -> Option<FaultDomain> {
+    match name {
+        "fs" => Some(FaultDomain::Fs),
+        "ssh" => Some(FaultDomain::Ssh),
+        "keychain" => Some(FaultDomain::Keychain),
+        "poison" => Some(FaultDomain::Poison),
+        _ => None,
+    }
+}
+
+fn parse_spec(raw: &str) -> Result<HashMap<FaultDomain, FaultSpec>, String> {
+    let mut specs = HashMap::new();
+    for part in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (name, value) = part.split_once('=').ok_or_else(|| format!("expected domain=rate: {part}"))?;
+        let domain = parse_domain(name.trim()).ok_or_else(|| format!("unknown fault domain: {name}"))?;
+        let (rate, delay) = match value.split_once(':') {
+            Some((rate, delay)) => (rate, Some(delay)),
+            None => (value, None),
+        };
+        let rate: f64 = rate.trim().parse().map_err(|_| format!("invalid rate: {part}"))?;
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(format!("rate must be 0-1: {part}"));
+        }
+        let max_delay_ms = match delay {
+            Some(delay) => delay.trim().parse().map_err(|_| format!("invalid delay: {part}"))?,
+            None => 0,
+        };
+        specs.insert(domain, FaultSpec { rate, max_delay_ms });
+    }
+    Ok(specs)
+}
+
+fn specs() -> &'static HashMap<FaultDomain, FaultSpec> {
+    static SPECS: OnceLock<HashMap<FaultDomain, FaultSpec>> = OnceLock::new();
+    SPECS.get_or_init(|| {
+        if !cfg!(debug_assertions) {
+            return HashMap::new();
+        }
+        let Ok(raw) = std::env::var(FAULTS_ENV) else {
+            return HashMap::new();
+        };
+        match parse_spec(&raw) {
+            Ok(specs) => {
+                eprintln!("[faults] injecting faults: {specs:?}");
+                specs
+            }
+            Err(e) => {
+                eprintln!("[faults] ignoring {FAULTS_ENV}: {e}");
+                HashMap::new()
+            }
+        }
+    })
+}
+
+fn random_unit() -> f64 {
+    (OsRng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Sleep as configured, then whether this operation should fail.
+fn roll(domain: FaultDomain) -> bool {
+    let Some(spec) = specs().get(&domain) else {
+        return false;
+    };
+    if spec.max_delay_ms > 0 {
+        let delay = (random_unit() * spec.max_delay_ms as f64) as u64;
+        std::thread::sleep(Duration::from_millis(delay));
+    }
+    random_unit() < spec.rate
+}
+
+/// Fail `op` (named as in the caller's own errors, e.g. `keychain read`) when
+/// `MAESTRO_FAULTS` says so. Always Ok in release builds.
+pub(crate) fn inject(domain: FaultDomain, op: &str) -> Result<(), String> {
+    if roll(domain) {
+        return Err(format!("{op} failed: injected fault"));
+    }
+    Ok(())
+}
+
+/// `write_all`, except that an injected `fs` fault writes only part of `bytes` and
+/// then errors, like a full disk or a crash mid-write.
+pub(crate) fn write_all<W: Write>(out: &mut W, bytes: &[u8]) -> io::Result<()> {
+    if roll(FaultDomain::Fs) {
+        let cut = (random_unit() * bytes.len() as f64) as usize;
+        out.write_all(&bytes[..cut])?;
+        return Err(io::Error::other("injected partial write"));
+    }
+    out.write_all(bytes)
+}
+
+/// Poison `lock` when a `poison` fault is rolled, so the caller's poisoned-lock
+/// path runs. The lock stays poisoned for the rest of the run.
+pub(crate) fn maybe_poison<T: Send>(lock: &Mutex<T>) {
+    if !roll(FaultDomain::Poison) {
+        return;
+    }
+    eprintln!("[faults] poisoning a lock");
+    std::thread::scope(|scope| {
+        let _ = scope
+            .spawn(|| {
+                let _guard = lock.lock();
+                panic!("injected fault: poisoned lock");
+            })
+            .join();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_spec, FaultDomain, FaultSpec};
+
+    #[test]
+    fn parses_rates_and_delays() {
+        let specs = parse_spec("fs=0.25, ssh=1:1500,,keychain=0").unwrap();
+        assert_eq!(
+            specs.get(&FaultDomain::Ssh),
+            Some(&FaultSpec {
+                rate: 1.0,
+                max_delay_ms: 1500
+            })
+        );
+        assert_eq!(specs.get(&FaultDomain::Fs).map(|s| s.rate), Some(0.25));
+        assert_eq!(specs.len(), 3);
+        assert!(parse_spec("fs=2").is_err());
+        assert!(parse_spec("disk=0.5").is_err());
+        assert!(parse_spec("ssh").is_err());
+    }
+}
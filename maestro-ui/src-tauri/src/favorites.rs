@@ -0,0 +1,286 @@
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::ssh_fs::{build_sh_c_command, normalize_posix_path, output_to_error, run_ssh};
+use crate::util::now_epoch_ms;
+
+const FAVORITES_FILE_NAME: &str = "favorite-paths-v1.json";
+const MAX_FAVORITES_PER_PROJECT: usize = 200;
+const MAX_LABEL_CHARS: usize = 120;
+
+/// A pinned file or directory in a project's file panel, local or on an ssh target.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoritePathV1 {
+    pub id: String,
+    pub path: String,
+    /// `file` or `directory`, as found when the favorite was added.
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub added_at: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoritePathStatus {
+    #[serde(flatten)]
+    pub favorite: FavoritePathV1,
+    /// Whether the path still exists; `None` when not checked or the ssh target
+    /// couldn't be reached.
+    pub exists: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct FavoritesV1 {
+    #[serde(default)]
+    projects: HashMap<String, Vec<FavoritePathV1>>,
+}
+
+fn new_favorite_id() -> String {
+    let mut bytes = [0u8; 8];
+    OsRng.fill_bytes(&mut bytes);
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("fav-{hex}")
+}
+
+fn favorites_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, FAVORITES_FILE_NAME)
+}
+
+fn read_favorites(app: &AppHandle) -> Result<HashMap<String, Vec<FavoritePathV1>>, String> {
+    let path = favorites_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str::<FavoritesV1>(&raw)
+            .map(|f| f.projects)
+            .map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn write_favorites(app: &AppHandle, mut projects: HashMap<String, Vec<FavoritePathV1>>) -> Result<(), String> {
+    projects.retain(|_, favorites| !favorites.is_empty());
+    let path = favorites_file_path(app)?;
+    crate::util::write_json_atomic(&path, &FavoritesV1 { projects })
+}
+
+fn clean_project_id(project_id: &str) -> Result<String, String> {
+    let project_id = project_id.trim();
+    if project_id.is_empty() {
+        return Err("missing project id".to_string());
+    }
+    Ok(project_id.to_string())
+}
+
+fn clean_label(label: Option<String>) -> Result<Option<String>, String> {
+    let label = label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    if label.as_ref().is_some_and(|l| l.chars().count() > MAX_LABEL_CHARS) {
+        return Err(format!("label is longer than {MAX_LABEL_CHARS} characters"));
+    }
+    Ok(label)
+}
+
+fn local_kind(path: &Path) -> Option<&'static str> {
+    let meta = fs::metadata(path).ok()?;
+    Some(if meta.is_dir() { "directory" } else { "file" })
+}
+
+/// Kinds of `paths` on an ssh target, in one round trip; `None` for missing paths.
+fn remote_kinds(target: &str, paths: &[String]) -> Result<Vec<Option<&'static str>>, String> {
+    let script = r#"for p in "$@"; do if [ -d "$p" ]; then echo d; elif [ -e "$p" ]; then echo f; else echo -; fi; done"#;
+    let command = build_sh_c_command(script, Some("--"), paths);
+    let output = run_ssh(target, &[command], None)?;
+    if !output.status.success() {
+        return Err(output_to_error("ssh failed", &output));
+    }
+    let kinds: Vec<Option<&'static str>> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| match line.trim() {
+            "d" => Some("directory"),
+            "f" => Some("file"),
+            _ => None,
+        })
+        .collect();
+    if kinds.len() != paths.len() {
+        return Err("ssh returned an unexpected path check".to_string());
+    }
+    Ok(kinds)
+}
+
+/// Validate a new favorite's path and find out what it points to.
+fn resolve_favorite(target: Option<&str>, path: &str) -> Result<(String, &'static str), String> {
+    match target {
+        Some(target) => {
+            let path = normalize_posix_path(path)?;
+            let kind = remote_kinds(target, std::slice::from_ref(&path))?
+                .pop()
+                .flatten()
+                .ok_or_else(|| format!("{path} does not exist on {target}"))?;
+            Ok((path, kind))
+        }
+        None => {
+            let path = Path::new(path.trim());
+            if !path.is_absolute() {
+                return Err("path must be absolute".to_string());
+            }
+            let kind = local_kind(path).ok_or_else(|| format!("{} does not exist", path.display()))?;
+            Ok((path.to_string_lossy().to_string(), kind))
+        }
+    }
+}
+
+/// Which favorites still exist: local paths are checked directly, remote ones with
+/// one ssh call per target.
+fn check_exists(favorites: &[FavoritePathV1]) -> Vec<Option<bool>> {
+    let mut exists: Vec<Option<bool>> = favorites
+        .iter()
+        .map(|f| match f.target {
+            Some(_) => None,
+            None => Some(local_kind(Path::new(&f.path)).is_some()),
+        })
+        .collect();
+
+    let mut by_target: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, f) in favorites.iter().enumerate() {
+        if let Some(target) = &f.target {
+            by_target.entry(target.as_str()).or_default().push(i);
+        }
+    }
+    for (target, indexes) in by_target {
+        let paths: Vec<String> = indexes.iter().map(|&i| favorites[i].path.clone()).collect();
+        let Ok(kinds) = remote_kinds(target, &paths) else {
+            continue;
+        };
+        for (i, kind) in indexes.into_iter().zip(kinds) {
+            exists[i] = Some(kind.is_some());
+        }
+    }
+    exists
+}
+
+/// A project's favorites in the order they were added. With `check_exists`, each
+/// entry says whether its path is still there.
+#[tauri::command]
+pub async fn list_favorite_paths(
+    window: WebviewWindow,
+    project_id: String,
+    check_exists: Option<bool>,
+) -> Result<Vec<FavoritePathStatus>, String> {
+    let favorites = read_favorites(window.app_handle())?
+        .remove(project_id.trim())
+        .unwrap_or_default();
+    let exists = if check_exists.unwrap_or(false) {
+        let favorites = favorites.clone();
+        tauri::async_runtime::spawn_blocking(move || self::check_exists(&favorites))
+            .await
+            .map_err(|e| format!("check favorites task join failed: {e:?}"))?
+    } else {
+        vec![None; favorites.len()]
+    };
+    Ok(favorites
+        .into_iter()
+        .zip(exists)
+        .map(|(favorite, exists)| FavoritePathStatus { favorite, exists })
+        .collect())
+}
+
+/// Pin a path for a project. Local paths must be absolute; remote ones (with
+/// `target`) absolute POSIX paths. The path must exist.
+#[tauri::command]
+pub async fn add_favorite_path(
+    window: WebviewWindow,
+    project_id: String,
+    path: String,
+    target: Option<String>,
+    label: Option<String>,
+) -> Result<FavoritePathV1, String> {
+    let project_id = clean_project_id(&project_id)?;
+    let label = clean_label(label)?;
+    let target = target.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let lookup_target = target.clone();
+    let (path, kind) = tauri::async_runtime::spawn_blocking(move || resolve_favorite(lookup_target.as_deref(), &path))
+        .await
+        .map_err(|e| format!("add favorite task join failed: {e:?}"))??;
+
+    let app = window.app_handle();
+    let mut projects = read_favorites(app)?;
+    let favorites = projects.entry(project_id).or_default();
+    if favorites.iter().any(|f| f.path == path && f.target == target) {
+        return Err("already a favorite".to_string());
+    }
+    if favorites.len() >= MAX_FAVORITES_PER_PROJECT {
+        return Err(format!("at most {MAX_FAVORITES_PER_PROJECT} favorites per project"));
+    }
+    let favorite = FavoritePathV1 {
+        id: new_favorite_id(),
+        path,
+        kind: kind.to_string(),
+        target,
+        label,
+        added_at: now_epoch_ms(),
+    };
+    favorites.push(favorite.clone());
+    write_favorites(app, projects)?;
+    Ok(favorite)
+}
+
+/// Change a favorite's label; `None` or empty shows the path again.
+#[tauri::command]
+pub fn rename_favorite_path(
+    window: WebviewWindow,
+    project_id: String,
+    favorite_id: String,
+    label: Option<String>,
+) -> Result<FavoritePathV1, String> {
+    let project_id = clean_project_id(&project_id)?;
+    let label = clean_label(label)?;
+    let app = window.app_handle();
+    let mut projects = read_favorites(app)?;
+    let favorite = projects
+        .get_mut(&project_id)
+        .and_then(|favorites| favorites.iter_mut().find(|f| f.id == favorite_id))
+        .ok_or("unknown favorite")?;
+    favorite.label = label;
+    let favorite = favorite.clone();
+    write_favorites(app, projects)?;
+    Ok(favorite)
+}
+
+#[tauri::command]
+pub fn remove_favorite_path(window: WebviewWindow, project_id: String, favorite_id: String) -> Result<(), String> {
+    let project_id = clean_project_id(&project_id)?;
+    let app = window.app_handle();
+    let mut projects = read_favorites(app)?;
+    let favorites = projects.get_mut(&project_id).ok_or("unknown favorite")?;
+    let before = favorites.len();
+    favorites.retain(|f| f.id != favorite_id);
+    if favorites.len() == before {
+        return Err("unknown favorite".to_string());
+    }
+    write_favorites(app, projects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clean_label, resolve_favorite};
+
+    #[test]
+    fn local_favorites_must_exist_and_be_absolute() {
+        let dir = std::env::temp_dir();
+        let (path, kind) = resolve_favorite(None, &dir.to_string_lossy()).unwrap();
+        assert_eq!((path.as_str(), kind), (dir.to_string_lossy().as_ref(), "directory"));
+        assert!(resolve_favorite(None, "relative/path").is_err());
+        assert!(resolve_favorite(None, &dir.join("maestro-missing-favorite").to_string_lossy()).is_err());
+
+        assert_eq!(clean_label(Some("  ".to_string())).unwrap(), None);
+        assert!(clean_label(Some("x".repeat(121))).is_err());
+    }
+}
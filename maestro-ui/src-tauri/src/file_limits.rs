@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+const SETTINGS_FILE_NAME: &str = "file-limits-v1.json";
+
+const DEFAULT_MAX_TEXT_FILE_BYTES: u64 = 2 * 1024 * 1024;
+const DEFAULT_BINARY_CHECK_BYTES: u64 = 8 * 1024;
+/// Upper bounds for the settings and per-call overrides; the whole file is held in
+/// memory and sent to the editor in one piece.
+const MAX_TEXT_FILE_BYTES_LIMIT: u64 = 64 * 1024 * 1024;
+const MAX_BINARY_CHECK_BYTES_LIMIT: u64 = 1024 * 1024;
+
+fn default_max_text_file_bytes() -> u64 {
+    DEFAULT_MAX_TEXT_FILE_BYTES
+}
+
+fn default_binary_check_bytes() -> u64 {
+    DEFAULT_BINARY_CHECK_BYTES
+}
+
+/// Size limits for opening files as text (`read_text_file`, `ssh_read_text_file`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileLimitsV1 {
+    /// Larger files are refused.
+    #[serde(default = "default_max_text_file_bytes")]
+    pub max_text_file_bytes: u64,
+    /// How much of the start of a file is checked for NUL bytes; 0 skips the check.
+    #[serde(default = "default_binary_check_bytes")]
+    pub binary_check_bytes: u64,
+}
+
+impl Default for FileLimitsV1 {
+    fn default() -> Self {
+        Self {
+            max_text_file_bytes: DEFAULT_MAX_TEXT_FILE_BYTES,
+            binary_check_bytes: DEFAULT_BINARY_CHECK_BYTES,
+        }
+    }
+}
+
+fn validate(limits: &FileLimitsV1) -> Result<(), String> {
+    if limits.max_text_file_bytes == 0 || limits.max_text_file_bytes > MAX_TEXT_FILE_BYTES_LIMIT {
+        return Err(format!(
+            "maxTextFileBytes must be between 1 and {MAX_TEXT_FILE_BYTES_LIMIT}"
+        ));
+    }
+    if limits.binary_check_bytes > MAX_BINARY_CHECK_BYTES_LIMIT {
+        return Err(format!(
+            "binaryCheckBytes must be at most {MAX_BINARY_CHECK_BYTES_LIMIT}"
+        ));
+    }
+    Ok(())
+}
+
+fn settings_cache() -> &'static Mutex<Option<FileLimitsV1>> {
+    static CACHE: OnceLock<Mutex<Option<FileLimitsV1>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn settings_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, SETTINGS_FILE_NAME)
+}
+
+fn read_limits(app: &AppHandle) -> Result<FileLimitsV1, String> {
+    let path = settings_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(FileLimitsV1::default()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+/// Load the saved limits at startup; the file commands don't take a window handle.
+pub(crate) fn init(app: &AppHandle) {
+    let limits = read_limits(app)
+        .and_then(|l| validate(&l).map(|_| l))
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load file limits: {e}");
+            FileLimitsV1::default()
+        });
+    if let Ok(mut cache) = settings_cache().lock() {
+        *cache = Some(limits);
+    }
+}
+
+/// The saved limits with a per-call `max_bytes` override applied. Overrides are
+/// bounded by the same maximum as the setting.
+pub(crate) fn resolve(max_bytes: Option<u64>) -> Result<FileLimitsV1, String> {
+    let mut limits = settings_cache()
+        .lock()
+        .ok()
+        .and_then(|c| *c)
+        .unwrap_or_default();
+    if let Some(max_bytes) = max_bytes {
+        if max_bytes == 0 || max_bytes > MAX_TEXT_FILE_BYTES_LIMIT {
+            return Err(format!("maxBytes must be between 1 and {MAX_TEXT_FILE_BYTES_LIMIT}"));
+        }
+        limits.max_text_file_bytes = max_bytes;
+    }
+    Ok(limits)
+}
+
+/// Decode file contents as text, refusing files with a NUL byte in the checked prefix.
+pub(crate) fn decode_text(bytes: Vec<u8>, limits: &FileLimitsV1) -> Result<String, String> {
+    let check = bytes.len().min(limits.binary_check_bytes as usize);
+    if bytes[..check].contains(&0) {
+        return Err("binary files are not supported".to_string());
+    }
+    String::from_utf8(bytes).map_err(|_| "file is not valid UTF-8".to_string())
+}
+
+#[tauri::command]
+pub fn get_file_limits(window: WebviewWindow) -> Result<FileLimitsV1, String> {
+    read_limits(window.app_handle())
+}
+
+#[tauri::command]
+pub fn set_file_limits(window: WebviewWindow, limits: FileLimitsV1) -> Result<(), String> {
+    validate(&limits)?;
+    let path = settings_file_path(window.app_handle())?;
+    crate::util::write_json_atomic(&path, &limits)?;
+
+    *settings_cache().lock().map_err(|_| "file limits cache poisoned")? = Some(limits);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_text, validate, FileLimitsV1};
+
+    #[test]
+    fn limits_are_bounded_and_sniff_is_configurable() {
+        let defaults = FileLimitsV1::default();
+        assert!(validate(&defaults).is_ok());
+        assert!(validate(&FileLimitsV1 { max_text_file_bytes: 0, ..defaults }).is_err());
+        assert!(validate(&FileLimitsV1 { max_text_file_bytes: 1 << 40, ..defaults }).is_err());
+
+        let mut bytes = vec![b'a'; 16];
+        bytes.push(0);
+        let short_sniff = FileLimitsV1 { binary_check_bytes: 8, ..defaults };
+        assert!(decode_text(bytes.clone(), &short_sniff).is_ok());
+        assert_eq!(decode_text(bytes, &defaults).unwrap_err(), "binary files are not supported");
+    }
+}
@@ -4,9 +4,10 @@ use std::{
     io,
     path::{Path, PathBuf},
 };
+use tauri::AppHandle;
 
-const MAX_TEXT_FILE_BYTES: u64 = 2 * 1024 * 1024;
-const BINARY_CHECK_BYTES: usize = 8 * 1024;
+use crate::faults::FaultDomain;
+use crate::project_ignore::Visit;
 
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -52,6 +53,7 @@ pub fn list_fs_entries(root: String, path: String) -> Result<Vec<FsEntry>, Strin
         return Err("not a directory".to_string());
     }
 
+    crate::faults::inject(FaultDomain::Fs, "read dir")?;
     let mut entries: Vec<FsEntry> = Vec::new();
     let read_dir = fs::read_dir(&dir).map_err(|e| format!("read dir failed: {e}"))?;
     for item in read_dir {
@@ -98,16 +100,24 @@ pub fn list_fs_entries(root: String, path: String) -> Result<Vec<FsEntry>, Strin
     Ok(entries)
 }
 
+/// Files under `root`, relative to it. With `project_id`, that project's ignore and
+/// include patterns (see project_ignore.rs) apply on top of the built-in skips.
 #[tauri::command]
-pub fn list_project_files(root: String) -> Result<Vec<String>, String> {
+pub fn list_project_files(
+    app: AppHandle,
+    root: String,
+    project_id: Option<String>,
+) -> Result<Vec<String>, String> {
     let root = Path::new(root.trim());
     let canon_root = ensure_root_dir(root)?;
+    let filter = crate::project_ignore::filter_for(&app, project_id.as_deref())?;
 
     let mut files = Vec::new();
-    let mut dirs_to_visit = vec![canon_root.clone()];
+    // Each directory with whether it was only entered for included paths under it.
+    let mut dirs_to_visit = vec![(canon_root.clone(), false)];
     let max_files = 10000;
 
-    while let Some(dir) = dirs_to_visit.pop() {
+    while let Some((dir, hidden)) = dirs_to_visit.pop() {
         if files.len() >= max_files {
             break;
         }
@@ -121,18 +131,21 @@ pub fn list_project_files(root: String) -> Result<Vec<String>, String> {
 
             let path = entry.path();
             let name = entry.file_name().to_string_lossy().to_string();
-
-            // Ignore hidden files and common build directories
-            if name.starts_with('.') || name == "node_modules" || name == "target" || name == "dist" || name == "build" || name == "coverage" {
+            let Ok(rel) = path.strip_prefix(&canon_root) else {
                 continue;
-            }
+            };
+            let rel = rel.to_string_lossy().to_string();
+            let is_dir = path.is_dir();
 
-            if path.is_dir() {
-                dirs_to_visit.push(path);
-            } else {
-                if let Ok(rel) = path.strip_prefix(&canon_root) {
-                    files.push(rel.to_string_lossy().to_string());
-                }
+            // Ignore hidden files and common build directories
+            let skipped = name.starts_with('.')
+                || matches!(name.as_str(), "node_modules" | "target" | "dist" | "build" | "coverage");
+
+            match filter.visit(&rel.replace('\\', "/"), is_dir, hidden || skipped) {
+                Visit::Skip => {}
+                Visit::DescendHidden => dirs_to_visit.push((path, true)),
+                Visit::Show if is_dir => dirs_to_visit.push((path, false)),
+                Visit::Show => files.push(rel),
             }
         }
     }
@@ -141,8 +154,18 @@ pub fn list_project_files(root: String) -> Result<Vec<String>, String> {
     Ok(files)
 }
 
+/// Read a text file within `root`. `max_bytes` raises or lowers the size limit for
+/// this call (see file_limits.rs for the default and maximum). With `project_id`,
+/// the file is added to that project's recent files.
 #[tauri::command]
-pub fn read_text_file(root: String, path: String) -> Result<String, String> {
+pub fn read_text_file(
+    app: AppHandle,
+    root: String,
+    path: String,
+    max_bytes: Option<u64>,
+    project_id: Option<String>,
+) -> Result<String, String> {
+    let limits = crate::file_limits::resolve(max_bytes)?;
     let root = Path::new(root.trim());
     let path = Path::new(path.trim());
     let file = ensure_within_root(root, path)?;
@@ -152,21 +175,22 @@ pub fn read_text_file(root: String, path: String) -> Result<String, String> {
 
     let meta = fs::metadata(&file).map_err(|e| format!("metadata failed: {e}"))?;
     let size = meta.len();
-    if size > MAX_TEXT_FILE_BYTES {
-        return Err(format!(
-            "file too large ({size} bytes, max {MAX_TEXT_FILE_BYTES} bytes)"
-        ));
+    let max = limits.max_text_file_bytes;
+    if size > max {
+        return Err(format!("file too large ({size} bytes, max {max} bytes)"));
     }
 
+    crate::faults::inject(FaultDomain::Fs, "read")?;
     let bytes = fs::read(&file).map_err(|e| format!("read failed: {e}"))?;
-    if bytes[..bytes.len().min(BINARY_CHECK_BYTES)]
-        .iter()
-        .any(|b| *b == 0)
-    {
-        return Err("binary files are not supported".to_string());
-    }
-
-    String::from_utf8(bytes).map_err(|_| "file is not valid UTF-8".to_string())
+    let text = crate::file_limits::decode_text(bytes, &limits)?;
+    crate::recent_files::record(
+        &app,
+        project_id.as_deref(),
+        None,
+        &root.to_string_lossy(),
+        &file.to_string_lossy(),
+    );
+    Ok(text)
 }
 
 #[tauri::command]
@@ -177,7 +201,9 @@ pub fn write_text_file(root: String, path: String, content: String) -> Result<()
     if !file.is_file() {
         return Err("not a file".to_string());
     }
-    fs::write(&file, content.as_bytes()).map_err(|e| format!("write failed: {e}"))?;
+    crate::faults::inject(FaultDomain::Fs, "write")?;
+    let mut out = fs::File::create(&file).map_err(|e| format!("write failed: {e}"))?;
+    crate::faults::write_all(&mut out, content.as_bytes()).map_err(|e| format!("write failed: {e}"))?;
     Ok(())
 }
 
@@ -224,12 +250,20 @@ pub fn rename_fs_entry(root: String, path: String, new_name: String) -> Result<S
     }
     fs::symlink_metadata(&from).map_err(|e| format!("metadata failed: {e}"))?;
 
+    crate::faults::inject(FaultDomain::Fs, "rename")?;
     fs::rename(&from, &to).map_err(|e| format!("rename failed: {e}"))?;
     Ok(to.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-pub fn delete_fs_entry(root: String, path: String) -> Result<(), String> {
+pub fn delete_fs_entry(app: AppHandle, root: String, path: String) -> Result<(), String> {
+    let result = remove_fs_entry(&root, &path);
+    crate::audit::record_result(&app, "fs.delete", path.trim(), &result);
+    result
+}
+
+/// Checks a delete request; returns the path to remove.
+pub(crate) fn prepare_delete(root: &str, path: &str) -> Result<PathBuf, String> {
     let root = Path::new(root.trim());
     let path = Path::new(path.trim());
     let (canon_root, _) = ensure_parent_within_root(root, path)?;
@@ -237,7 +271,13 @@ pub fn delete_fs_entry(root: String, path: String) -> Result<(), String> {
     if target == canon_root {
         return Err("cannot delete root".to_string());
     }
+    fs::symlink_metadata(&target).map_err(|e| format!("metadata failed: {e}"))?;
+    Ok(target)
+}
 
+fn remove_fs_entry(root: &str, path: &str) -> Result<(), String> {
+    let target = prepare_delete(root, path)?;
+    crate::faults::inject(FaultDomain::Fs, "delete")?;
     let meta = fs::symlink_metadata(&target).map_err(|e| format!("metadata failed: {e}"))?;
     if meta.file_type().is_symlink() {
         return fs::remove_file(&target).map_err(|e| format!("delete failed: {e}"));
@@ -265,8 +305,8 @@ fn copy_dir_recursive(src: &Path, dest: &Path) -> io::Result<()> {
     Ok(())
 }
 
-#[tauri::command]
-pub fn copy_fs_entry(root: String, source_path: String, dest_path: String) -> Result<(), String> {
+/// Checks a copy request; returns (source, destination).
+pub(crate) fn prepare_copy(root: &str, source_path: &str, dest_path: &str) -> Result<(PathBuf, PathBuf), String> {
     let root = Path::new(root.trim());
     let source = Path::new(source_path.trim());
     let dest = Path::new(dest_path.trim());
@@ -297,12 +337,20 @@ pub fn copy_fs_entry(root: String, source_path: String, dest_path: String) -> Re
         return Err("destination already exists".to_string());
     }
 
+    Ok((source.to_path_buf(), dest.to_path_buf()))
+}
+
+#[tauri::command]
+pub fn copy_fs_entry(root: String, source_path: String, dest_path: String) -> Result<(), String> {
+    let (source, dest) = prepare_copy(&root, &source_path, &dest_path)?;
+
     // Perform the copy
-    let meta = fs::metadata(source).map_err(|e| format!("metadata failed: {e}"))?;
+    crate::faults::inject(FaultDomain::Fs, "copy")?;
+    let meta = fs::metadata(&source).map_err(|e| format!("metadata failed: {e}"))?;
     if meta.is_dir() {
-        copy_dir_recursive(source, dest).map_err(|e| format!("copy failed: {e}"))?;
+        copy_dir_recursive(&source, &dest).map_err(|e| format!("copy failed: {e}"))?;
     } else {
-        fs::copy(source, dest).map_err(|e| format!("copy failed: {e}"))?;
+        fs::copy(&source, &dest).map_err(|e| format!("copy failed: {e}"))?;
     }
 
     Ok(())
@@ -0,0 +1,321 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+const MAX_DIR_DEPTH: usize = 6;
+const MAX_FONT_FILES: usize = 5000;
+const MAX_FACES_PER_FILE: u32 = 64;
+const MAX_NAME_TABLE_BYTES: u32 = 256 * 1024;
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "otc"];
+const LIGATURE_FEATURES: &[&[u8; 4]] = &[b"liga", b"calt", b"dlig"];
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MonospaceFont {
+    pub family: String,
+    /// Some face of the family has ligature or contextual-alternate features
+    /// (Fira Code, JetBrains Mono, ...). A hint: not every such font draws
+    /// programming ligatures.
+    pub ligatures: bool,
+}
+
+struct FaceInfo {
+    family: String,
+    monospace: bool,
+    ligatures: bool,
+}
+
+fn be_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Offsets of the faces in a font file: several for a `.ttc` collection.
+fn face_offsets<R: Read + Seek>(reader: &mut R) -> io::Result<Vec<u32>> {
+    let header = read_at(reader, 0, 12)?;
+    if &header[..4] != b"ttcf" {
+        return Ok(vec![0]);
+    }
+    let count = be_u32(&header, 8).unwrap_or(0).min(MAX_FACES_PER_FILE);
+    let offsets = read_at(reader, 12, count as usize * 4)?;
+    Ok((0..count as usize).filter_map(|i| be_u32(&offsets, i * 4)).collect())
+}
+
+/// The family name from the `name` table, preferring the typographic family
+/// (ID 16) over the legacy one (ID 1) and English Windows names over the rest.
+fn family_name(table: &[u8]) -> Option<String> {
+    let count = be_u16(table, 2)? as usize;
+    let strings = be_u16(table, 4)? as usize;
+    let mut best: Option<(u8, String)> = None;
+    for i in 0..count {
+        let record = 6 + i * 12;
+        let platform = be_u16(table, record)?;
+        let encoding = be_u16(table, record + 2)?;
+        let language = be_u16(table, record + 4)?;
+        let name_id = be_u16(table, record + 6)?;
+        let len = be_u16(table, record + 8)? as usize;
+        let offset = be_u16(table, record + 10)? as usize;
+        if name_id != 1 && name_id != 16 {
+            continue;
+        }
+        let Some(raw) = table.get(strings + offset..strings + offset + len) else {
+            continue;
+        };
+        let name = match (platform, encoding) {
+            (0, _) | (3, 0) | (3, 1) | (3, 10) => {
+                let units: Vec<u16> = raw.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+                String::from_utf16_lossy(&units)
+            }
+            // Mac Roman; family names are ASCII in practice.
+            (1, 0) => raw.iter().map(|&b| b as char).collect(),
+            _ => continue,
+        };
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            continue;
+        }
+        let rank = u8::from(name_id == 16) * 2 + u8::from(platform == 3 && language == 0x409);
+        if best.as_ref().is_none_or(|(r, _)| rank > *r) {
+            best = Some((rank, name));
+        }
+    }
+    best.map(|(_, name)| name)
+}
+
+fn read_face<R: Read + Seek>(reader: &mut R, offset: u32) -> Option<FaceInfo> {
+    let offset = u64::from(offset);
+    let header = read_at(reader, offset, 12).ok()?;
+    if !matches!(&header[..4], [0, 1, 0, 0] | b"OTTO" | b"true") {
+        return None;
+    }
+    let num_tables = be_u16(&header, 4)? as usize;
+    let directory = read_at(reader, offset + 12, num_tables * 16).ok()?;
+    let tables: HashMap<&[u8], (u32, u32)> = (0..num_tables)
+        .filter_map(|i| {
+            let record = i * 16;
+            let tag = directory.get(record..record + 4)?;
+            Some((tag, (be_u32(&directory, record + 8)?, be_u32(&directory, record + 12)?)))
+        })
+        .collect();
+
+    let (name_offset, name_len) = *tables.get(&b"name"[..])?;
+    let name = read_at(reader, u64::from(name_offset), name_len.min(MAX_NAME_TABLE_BYTES) as usize).ok()?;
+    let family = family_name(&name)?;
+
+    // `post.isFixedPitch`, or a monospaced PANOSE proportion in `OS/2`.
+    let fixed_pitch = tables
+        .get(&b"post"[..])
+        .and_then(|(at, _)| read_at(reader, u64::from(*at), 16).ok())
+        .and_then(|post| be_u32(&post, 12))
+        .is_some_and(|fixed| fixed != 0);
+    let panose_mono = tables
+        .get(&b"OS/2"[..])
+        .and_then(|(at, _)| read_at(reader, u64::from(*at), 42).ok())
+        .is_some_and(|os2| os2[32] == 2 && os2[35] == 9);
+
+    let ligatures = tables
+        .get(&b"GSUB"[..])
+        .and_then(|(at, _)| {
+            let gsub = read_at(reader, u64::from(*at), 10).ok()?;
+            let features_at = u64::from(*at) + u64::from(be_u16(&gsub, 6)?);
+            let count = be_u16(&read_at(reader, features_at, 2).ok()?, 0)? as usize;
+            let records = read_at(reader, features_at + 2, count * 6).ok()?;
+            Some(records.chunks_exact(6).any(|r| LIGATURE_FEATURES.iter().any(|f| &r[..4] == *f)))
+        })
+        .unwrap_or(false);
+
+    Some(FaceInfo {
+        family,
+        monospace: fixed_pitch || panose_mono,
+        ligatures,
+    })
+}
+
+fn font_dirs() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    #[cfg(target_os = "macos")]
+    {
+        roots.push(PathBuf::from("/System/Library/Fonts"));
+        roots.push(PathBuf::from("/Library/Fonts"));
+        if let Some(home) = dirs::home_dir() {
+            roots.push(home.join("Library/Fonts"));
+        }
+    }
+    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
+    {
+        roots.push(PathBuf::from("/usr/share/fonts"));
+        roots.push(PathBuf::from("/usr/local/share/fonts"));
+        if let Some(home) = dirs::home_dir() {
+            roots.push(home.join(".local/share/fonts"));
+            roots.push(home.join(".fonts"));
+        }
+    }
+    #[cfg(windows)]
+    {
+        let windir = std::env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".to_string());
+        roots.push(PathBuf::from(windir).join("Fonts"));
+        if let Ok(local) = std::env::var("LOCALAPPDATA") {
+            roots.push(PathBuf::from(local).join("Microsoft\\Windows\\Fonts"));
+        }
+    }
+    roots
+}
+
+fn collect_font_files(dir: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    if depth > MAX_DIR_DEPTH || out.len() >= MAX_FONT_FILES {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_font_files(&path, depth + 1, out);
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| FONT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        {
+            out.push(path);
+            if out.len() >= MAX_FONT_FILES {
+                return;
+            }
+        }
+    }
+}
+
+/// Read the installed fonts' own tables rather than asking a font service, so the
+/// same code works with CoreText, fontconfig and Windows font directories.
+fn discover() -> Vec<MonospaceFont> {
+    let mut files = Vec::new();
+    for dir in font_dirs() {
+        collect_font_files(&dir, 0, &mut files);
+    }
+    // Keyed case-insensitively so `Menlo` from two directories is listed once.
+    let mut families: BTreeMap<String, MonospaceFont> = BTreeMap::new();
+    for path in files {
+        let Ok(mut file) = fs::File::open(&path) else {
+            continue;
+        };
+        let Ok(offsets) = face_offsets(&mut file) else {
+            continue;
+        };
+        for offset in offsets {
+            let Some(face) = read_face(&mut file, offset) else {
+                continue;
+            };
+            // Dot-prefixed families are private macOS system fonts.
+            if !face.monospace || face.family.starts_with('.') {
+                continue;
+            }
+            let entry = families
+                .entry(face.family.to_lowercase())
+                .or_insert_with(|| MonospaceFont {
+                    family: face.family.clone(),
+                    ligatures: false,
+                });
+            entry.ligatures |= face.ligatures;
+        }
+    }
+    families.into_values().collect()
+}
+
+fn cache() -> &'static Mutex<Option<Vec<MonospaceFont>>> {
+    static CACHE: OnceLock<Mutex<Option<Vec<MonospaceFont>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// Installed monospace font families, sorted by name. The scan is cached; pass
+/// `refresh` after installing fonts.
+#[tauri::command]
+pub async fn list_monospace_fonts(refresh: Option<bool>) -> Result<Vec<MonospaceFont>, String> {
+    let refresh = refresh.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || {
+        if !refresh {
+            if let Some(fonts) = cache().lock().ok().and_then(|c| c.clone()) {
+                return fonts;
+            }
+        }
+        let fonts = discover();
+        if let Ok(mut cache) = cache().lock() {
+            *cache = Some(fonts.clone());
+        }
+        fonts
+    })
+    .await
+    .map_err(|e| format!("list fonts task join failed: {e:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{face_offsets, read_face};
+    use std::io::Cursor;
+
+    /// A minimal sfnt with `name`, `post` and `GSUB` tables.
+    fn font(family: &str, fixed_pitch: bool, feature: &[u8; 4]) -> Vec<u8> {
+        let name_string: Vec<u8> = family.encode_utf16().flat_map(|u| u.to_be_bytes()).collect();
+        let mut name = Vec::new();
+        for v in [0u16, 1, 18, 3, 1, 0x409, 1, name_string.len() as u16, 0] {
+            name.extend_from_slice(&v.to_be_bytes());
+        }
+        name.extend_from_slice(&name_string);
+
+        let mut post = vec![0u8; 16];
+        post[12..16].copy_from_slice(&u32::from(fixed_pitch).to_be_bytes());
+
+        let mut gsub = Vec::new();
+        for v in [1u16, 0, 0, 10, 0] {
+            gsub.extend_from_slice(&v.to_be_bytes());
+        }
+        gsub.extend_from_slice(&1u16.to_be_bytes());
+        gsub.extend_from_slice(feature);
+        gsub.extend_from_slice(&0u16.to_be_bytes());
+
+        let tables: [(&[u8; 4], Vec<u8>); 3] = [(b"GSUB", gsub), (b"name", name), (b"post", post)];
+        let mut out = vec![0, 1, 0, 0];
+        out.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+        out.extend_from_slice(&[0; 6]);
+        let mut offset = 12 + tables.len() * 16;
+        for (tag, data) in &tables {
+            out.extend_from_slice(*tag);
+            out.extend_from_slice(&[0; 4]);
+            out.extend_from_slice(&(offset as u32).to_be_bytes());
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            offset += data.len();
+        }
+        for (_, data) in &tables {
+            out.extend_from_slice(data);
+        }
+        out
+    }
+
+    #[test]
+    fn reads_family_pitch_and_ligature_hints() {
+        let mut mono = Cursor::new(font("Code Mono", true, b"calt"));
+        let offsets = face_offsets(&mut mono).unwrap();
+        assert_eq!(offsets, [0]);
+        let face = read_face(&mut mono, 0).unwrap();
+        assert_eq!((face.family.as_str(), face.monospace, face.ligatures), ("Code Mono", true, true));
+
+        let mut sans = Cursor::new(font("Plain Sans", false, b"kern"));
+        let face = read_face(&mut sans, 0).unwrap();
+        assert_eq!((face.family.as_str(), face.monospace, face.ligatures), ("Plain Sans", false, false));
+    }
+}
@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+pub(crate) const EVENT_FS_OPERATION_PROGRESS: &str = "fs-operation-progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(150);
+const COPY_CHUNK_BYTES: usize = 1024 * 1024;
+const CANCELLED: &str = "cancelled";
+
+/// A long-running local file operation, started with `start_fs_operation`. Same
+/// shape as the steps of `ssh_batch_operations`.
+#[derive(Deserialize, Clone)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum FsOperation {
+    #[serde(rename_all = "camelCase")]
+    Copy {
+        root: String,
+        source_path: String,
+        dest_path: String,
+    },
+    Delete { root: String, path: String },
+}
+
+#[derive(Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FsOperationProgress {
+    pub task_id: String,
+    pub op: String,
+    /// `scanning`, `running`, `done`, `failed` or `cancelled`.
+    pub state: String,
+    pub total_entries: u64,
+    pub done_entries: u64,
+    pub total_bytes: u64,
+    pub done_bytes: u64,
+    pub current_path: Option<String>,
+    pub error: Option<String>,
+}
+
+fn tasks() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static TASKS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    TASKS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+struct Tracker<'a> {
+    progress: FsOperationProgress,
+    cancel: &'a AtomicBool,
+    report: &'a mut dyn FnMut(&FsOperationProgress),
+    last_report: Instant,
+}
+
+impl Tracker<'_> {
+    fn tick(&mut self, path: &Path) -> Result<(), String> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(CANCELLED.to_string());
+        }
+        if self.last_report.elapsed() >= PROGRESS_INTERVAL {
+            self.progress.current_path = Some(path.to_string_lossy().to_string());
+            (self.report)(&self.progress);
+            self.last_report = Instant::now();
+        }
+        Ok(())
+    }
+
+    fn set_state(&mut self, state: &str) {
+        self.progress.state = state.to_string();
+        (self.report)(&self.progress);
+        self.last_report = Instant::now();
+    }
+}
+
+/// Count entries and bytes under `path`. Copies follow symlinks like `copy_fs_entry`;
+/// deletes remove the link itself.
+fn scan(path: &Path, follow_links: bool, tracker: &mut Tracker) -> Result<(), String> {
+    tracker.tick(path)?;
+    let meta = if follow_links {
+        fs::metadata(path)
+    } else {
+        fs::symlink_metadata(path)
+    }
+    .map_err(|e| format!("metadata failed: {e}"))?;
+    tracker.progress.total_entries += 1;
+    if meta.is_dir() {
+        for entry in fs::read_dir(path).map_err(|e| format!("read dir failed: {e}"))? {
+            let entry = entry.map_err(|e| format!("read dir failed: {e}"))?;
+            scan(&entry.path(), follow_links, tracker)?;
+        }
+    } else {
+        tracker.progress.total_bytes += meta.len();
+    }
+    Ok(())
+}
+
+fn copy_file(src: &Path, dest: &Path, tracker: &mut Tracker) -> Result<(), String> {
+    let mut input = fs::File::open(src).map_err(|e| format!("copy failed: {e}"))?;
+    let mut output = fs::File::create(dest).map_err(|e| format!("copy failed: {e}"))?;
+    let mut buf = vec![0u8; COPY_CHUNK_BYTES];
+    loop {
+        let n = input.read(&mut buf).map_err(|e| format!("copy failed: {e}"))?;
+        if n == 0 {
+            break;
+        }
+        output.write_all(&buf[..n]).map_err(|e| format!("copy failed: {e}"))?;
+        tracker.progress.done_bytes += n as u64;
+        tracker.tick(src)?;
+    }
+    if let Ok(meta) = input.metadata() {
+        let _ = fs::set_permissions(dest, meta.permissions());
+    }
+    tracker.progress.done_entries += 1;
+    Ok(())
+}
+
+fn copy_tree(src: &Path, dest: &Path, tracker: &mut Tracker) -> Result<(), String> {
+    tracker.tick(src)?;
+    if !src.is_dir() {
+        return copy_file(src, dest, tracker);
+    }
+    fs::create_dir_all(dest).map_err(|e| format!("copy failed: {e}"))?;
+    tracker.progress.done_entries += 1;
+    for entry in fs::read_dir(src).map_err(|e| format!("copy failed: {e}"))? {
+        let entry = entry.map_err(|e| format!("copy failed: {e}"))?;
+        copy_tree(&entry.path(), &dest.join(entry.file_name()), tracker)?;
+    }
+    Ok(())
+}
+
+fn delete_tree(path: &Path, tracker: &mut Tracker) -> Result<(), String> {
+    tracker.tick(path)?;
+    let meta = fs::symlink_metadata(path).map_err(|e| format!("metadata failed: {e}"))?;
+    if meta.is_dir() {
+        for entry in fs::read_dir(path).map_err(|e| format!("delete failed: {e}"))? {
+            let entry = entry.map_err(|e| format!("delete failed: {e}"))?;
+            delete_tree(&entry.path(), tracker)?;
+        }
+        fs::remove_dir(path).map_err(|e| format!("delete failed: {e}"))?;
+    } else {
+        fs::remove_file(path).map_err(|e| format!("delete failed: {e}"))?;
+        tracker.progress.done_bytes += meta.len();
+    }
+    tracker.progress.done_entries += 1;
+    Ok(())
+}
+
+/// Run a validated operation to completion, reporting progress along the way. A
+/// cancelled or failed copy removes what it created; a cancelled delete stops where
+/// it is, with the remaining entries left in place.
+fn run_operation(
+    task_id: &str,
+    op: &str,
+    source: &Path,
+    dest: Option<&Path>,
+    cancel: &AtomicBool,
+    report: &mut dyn FnMut(&FsOperationProgress),
+) -> FsOperationProgress {
+    let mut tracker = Tracker {
+        progress: FsOperationProgress {
+            task_id: task_id.to_string(),
+            op: op.to_string(),
+            ..FsOperationProgress::default()
+        },
+        cancel,
+        report,
+        last_report: Instant::now(),
+    };
+    tracker.set_state("scanning");
+    let mut result = scan(source, dest.is_some(), &mut tracker);
+    if result.is_ok() {
+        tracker.set_state("running");
+        result = match dest {
+            Some(dest) => copy_tree(source, dest, &mut tracker),
+            None => delete_tree(source, &mut tracker),
+        };
+    }
+
+    if let (Err(_), Some(dest)) = (&result, dest) {
+        let _ = if dest.is_dir() {
+            fs::remove_dir_all(dest)
+        } else {
+            fs::remove_file(dest)
+        };
+    }
+    tracker.progress.current_path = None;
+    match result {
+        Ok(()) => tracker.set_state("done"),
+        Err(e) if e == CANCELLED => tracker.set_state("cancelled"),
+        Err(e) => {
+            tracker.progress.error = Some(e);
+            tracker.set_state("failed");
+        }
+    }
+    tracker.progress
+}
+
+/// Start a copy or delete in the background and return its task id right away.
+/// Progress arrives as `fs-operation-progress` events, ending in state `done`,
+/// `failed` or `cancelled`. Requests are validated before the task starts.
+#[tauri::command]
+pub fn start_fs_operation(app: AppHandle, operation: FsOperation) -> Result<String, String> {
+    let (op, source, dest) = match &operation {
+        FsOperation::Copy {
+            root,
+            source_path,
+            dest_path,
+        } => {
+            let (source, dest) = crate::files::prepare_copy(root, source_path, dest_path)?;
+            ("copy", source, Some(dest))
+        }
+        FsOperation::Delete { root, path } => ("delete", crate::files::prepare_delete(root, path)?, None),
+    };
+
+    static NEXT_TASK: AtomicU64 = AtomicU64::new(1);
+    let task_id = format!("fs-{}", NEXT_TASK.fetch_add(1, Ordering::Relaxed));
+    let cancel = Arc::new(AtomicBool::new(false));
+    tasks()
+        .lock()
+        .map_err(|_| "fs tasks poisoned")?
+        .insert(task_id.clone(), cancel.clone());
+
+    let id = task_id.clone();
+    std::thread::spawn(move || {
+        let mut report = |p: &FsOperationProgress| {
+            let _ = app.emit(EVENT_FS_OPERATION_PROGRESS, p.clone());
+        };
+        let progress = run_operation(&id, op, &source, dest.as_deref(), &cancel, &mut report);
+        if let Ok(mut tasks) = tasks().lock() {
+            tasks.remove(&id);
+        }
+        if op == "delete" {
+            crate::audit::record(
+                &app,
+                "fs.delete",
+                &source.to_string_lossy(),
+                progress.error.as_deref().or((progress.state == "cancelled").then_some(CANCELLED)),
+            );
+        }
+    });
+    Ok(task_id)
+}
+
+/// Ask a running `start_fs_operation` task to stop. It reports `cancelled` once it has.
+#[tauri::command]
+pub fn cancel_fs_operation(task_id: String) -> Result<(), String> {
+    let tasks = tasks().lock().map_err(|_| "fs tasks poisoned")?;
+    let cancel = tasks.get(&task_id).ok_or("unknown or finished task")?;
+    cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_operation;
+    use std::fs;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn copies_and_deletes_trees_with_progress() {
+        let base = std::env::temp_dir().join(format!("maestro-fs-op-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("src/nested")).unwrap();
+        fs::write(base.join("src/a.txt"), "hello").unwrap();
+        fs::write(base.join("src/nested/b.txt"), "world!").unwrap();
+
+        let mut states = Vec::new();
+        let not_cancelled = AtomicBool::new(false);
+        let done = run_operation("t1", "copy", &base.join("src"), Some(&base.join("dst")), &not_cancelled, &mut |p| {
+            states.push(p.state.clone())
+        });
+        assert_eq!(states, ["scanning", "running", "done"]);
+        assert_eq!((done.total_entries, done.done_entries, done.total_bytes, done.done_bytes), (4, 4, 11, 11));
+        assert_eq!(fs::read_to_string(base.join("dst/nested/b.txt")).unwrap(), "world!");
+
+        let cancelled = AtomicBool::new(true);
+        let out = run_operation("t2", "copy", &base.join("src"), Some(&base.join("dst2")), &cancelled, &mut |_| {});
+        assert_eq!(out.state, "cancelled");
+        assert!(!base.join("dst2").exists());
+
+        let out = run_operation("t3", "delete", &base.join("dst"), None, &not_cancelled, &mut |_| {});
+        assert_eq!((out.state.as_str(), out.done_entries), ("done", 4));
+        assert!(!base.join("dst").exists());
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,170 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::Duration;
+use tauri::{Emitter, WebviewWindow};
+
+use crate::util::now_epoch_ms;
+
+pub(crate) const EVENT_SESSION_IDLE_WARNING: &str = "session-idle-warning";
+pub(crate) const EVENT_SESSION_IDLE_CLOSED: &str = "session-idle-closed";
+const MAX_IDLE_TIMEOUT_MINUTES: u32 = 7 * 24 * 60;
+/// The warning goes out this long before the close (or halfway, for short timeouts).
+const WARNING_LEAD_MS: u64 = 60_000;
+const TICK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionIdleWarning {
+    pub id: String,
+    pub idle_ms: u64,
+    /// Epoch ms the session will be closed at unless there's input or output first.
+    pub closes_at: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionIdleClosed {
+    pub id: String,
+    pub idle_ms: u64,
+}
+
+struct IdleSession {
+    window: WebviewWindow,
+    timeout_ms: u64,
+    last_activity_at: u64,
+    warned: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum Step {
+    Wait,
+    Warn,
+    Close,
+}
+
+fn sessions() -> &'static Mutex<HashMap<String, IdleSession>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, IdleSession>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn warning_lead(timeout_ms: u64) -> u64 {
+    WARNING_LEAD_MS.min(timeout_ms / 2)
+}
+
+fn step(timeout_ms: u64, last_activity_at: u64, warned: bool, now: u64) -> Step {
+    let idle = now.saturating_sub(last_activity_at);
+    if idle >= timeout_ms {
+        Step::Close
+    } else if !warned && idle + warning_lead(timeout_ms) >= timeout_ms {
+        Step::Warn
+    } else {
+        Step::Wait
+    }
+}
+
+/// Check the `idleTimeoutMinutes` passed to `create_session`.
+pub(crate) fn validate(minutes: u32) -> Result<(), String> {
+    if minutes == 0 || minutes > MAX_IDLE_TIMEOUT_MINUTES {
+        return Err(format!("idleTimeoutMinutes must be 1-{MAX_IDLE_TIMEOUT_MINUTES}"));
+    }
+    Ok(())
+}
+
+fn start_ticker() {
+    static TICKER: Once = Once::new();
+    TICKER.call_once(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(TICK_INTERVAL);
+            let now = now_epoch_ms();
+            let Ok(mut sessions) = sessions().lock() else {
+                return;
+            };
+            let mut warnings = Vec::new();
+            let mut closes = Vec::new();
+            for (id, s) in sessions.iter_mut() {
+                let idle_ms = now.saturating_sub(s.last_activity_at);
+                match step(s.timeout_ms, s.last_activity_at, s.warned, now) {
+                    Step::Wait => {}
+                    Step::Warn => {
+                        s.warned = true;
+                        let warning = SessionIdleWarning {
+                            id: id.clone(),
+                            idle_ms,
+                            closes_at: s.last_activity_at + s.timeout_ms,
+                        };
+                        warnings.push((s.window.clone(), warning));
+                    }
+                    Step::Close => {
+                        closes.push((s.window.clone(), SessionIdleClosed { id: id.clone(), idle_ms }));
+                    }
+                }
+            }
+            for (_, closed) in &closes {
+                sessions.remove(&closed.id);
+            }
+            drop(sessions);
+
+            for (window, warning) in warnings {
+                let _ = window.emit(EVENT_SESSION_IDLE_WARNING, warning);
+            }
+            for (window, closed) in closes {
+                let id = closed.id.clone();
+                let _ = window.emit(EVENT_SESSION_IDLE_CLOSED, closed);
+                crate::pty::close_idle_session(&window, id);
+            }
+        });
+    });
+}
+
+/// Close the session after `minutes` without input or output. Sessions attached to
+/// a tmux/zellij session (see external_sessions.rs) only close the attach client,
+/// which detaches and leaves the multiplexer session running.
+pub(crate) fn register(window: &WebviewWindow, id: &str, minutes: u32) {
+    start_ticker();
+    if let Ok(mut sessions) = sessions().lock() {
+        sessions.insert(
+            id.to_string(),
+            IdleSession {
+                window: window.clone(),
+                timeout_ms: u64::from(minutes) * 60_000,
+                last_activity_at: now_epoch_ms(),
+                warned: false,
+            },
+        );
+    }
+}
+
+/// Input or output on the session; restarts its idle clock.
+pub(crate) fn touch(id: &str) {
+    let Ok(mut sessions) = sessions().lock() else {
+        return;
+    };
+    if let Some(s) = sessions.get_mut(id) {
+        s.last_activity_at = now_epoch_ms();
+        s.warned = false;
+    }
+}
+
+pub(crate) fn session_closed(id: &str) {
+    if let Ok(mut sessions) = sessions().lock() {
+        sessions.remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{step, Step};
+
+    #[test]
+    fn warns_before_closing_idle_sessions() {
+        let minute = 60_000;
+        let timeout = 10 * minute;
+        assert_eq!(step(timeout, 0, false, 8 * minute), Step::Wait);
+        assert_eq!(step(timeout, 0, false, 9 * minute), Step::Warn);
+        assert_eq!(step(timeout, 0, true, 9 * minute), Step::Wait);
+        assert_eq!(step(timeout, 0, true, timeout), Step::Close);
+        // Short timeouts warn halfway through.
+        assert_eq!(step(minute, 0, false, minute / 2), Step::Warn);
+    }
+}
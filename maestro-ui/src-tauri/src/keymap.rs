@@ -0,0 +1,411 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+const SETTINGS_FILE_NAME: &str = "keymap-v1.json";
+
+pub(crate) const ACTION_NEW_TERMINAL: &str = "new-terminal";
+pub(crate) const ACTION_CLOSE_SESSION: &str = "close-session";
+pub(crate) const ACTION_CHECK_UPDATES: &str = "check-updates";
+pub(crate) const ACTION_SHOW_WINDOW: &str = "show-window";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyScope {
+    /// Accelerator on an app menu item; only fires while the app is focused.
+    Menu,
+    /// Registered with the OS and fires from any app.
+    Global,
+}
+
+struct KeyAction {
+    id: &'static str,
+    label: &'static str,
+    scope: KeyScope,
+    default: Option<&'static str>,
+}
+
+/// Everything that can be bound. Nothing is bound by default: the terminal view
+/// handles its own shortcuts (Cmd+T, Ctrl+Shift+W, ...) and a menu accelerator
+/// would take the key away from it.
+const ACTIONS: &[KeyAction] = &[
+    KeyAction {
+        id: ACTION_NEW_TERMINAL,
+        label: "New Terminal",
+        scope: KeyScope::Menu,
+        default: None,
+    },
+    KeyAction {
+        id: ACTION_CLOSE_SESSION,
+        label: "Close Current Session",
+        scope: KeyScope::Menu,
+        default: None,
+    },
+    KeyAction {
+        id: ACTION_CHECK_UPDATES,
+        label: "Check for Updates",
+        scope: KeyScope::Menu,
+        default: None,
+    },
+    KeyAction {
+        id: ACTION_SHOW_WINDOW,
+        label: "Show Agent Maestro",
+        scope: KeyScope::Global,
+        default: None,
+    },
+];
+
+/// Accelerators of the predefined items in the default menu (Edit, Window, app menu).
+const RESERVED: &[(&str, &str)] = &[
+    ("CmdOrCtrl+Q", "Quit"),
+    ("CmdOrCtrl+W", "Close Window"),
+    ("CmdOrCtrl+H", "Hide"),
+    ("CmdOrCtrl+M", "Minimize"),
+    ("CmdOrCtrl+Z", "Undo"),
+    ("CmdOrCtrl+Shift+Z", "Redo"),
+    ("CmdOrCtrl+X", "Cut"),
+    ("CmdOrCtrl+C", "Copy"),
+    ("CmdOrCtrl+V", "Paste"),
+    ("CmdOrCtrl+A", "Select All"),
+];
+
+const NAMED_KEYS: &[&str] = &[
+    "Space", "Tab", "Enter", "Escape", "Backspace", "Delete", "Insert", "Home", "End", "PageUp",
+    "PageDown", "Up", "Down", "Left", "Right", "Plus",
+];
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeymapV1 {
+    /// Changes from the defaults by action id; `null` unbinds the action.
+    #[serde(default)]
+    pub bindings: BTreeMap<String, Option<String>>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyBinding {
+    pub action: String,
+    pub label: String,
+    pub scope: KeyScope,
+    pub accelerator: Option<String>,
+    pub default_accelerator: Option<String>,
+}
+
+/// A parsed accelerator such as `CmdOrCtrl+Shift+T`.
+#[derive(Clone, Debug, PartialEq)]
+struct Chord {
+    cmd_or_ctrl: bool,
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    super_key: bool,
+    key: String,
+}
+
+impl Chord {
+    fn parse(raw: &str) -> Result<Self, String> {
+        let mut chord = Chord {
+            cmd_or_ctrl: false,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            super_key: false,
+            key: String::new(),
+        };
+        let parts: Vec<&str> = raw.split('+').map(str::trim).collect();
+        let Some((key, modifiers)) = parts.split_last() else {
+            return Err("empty accelerator".to_string());
+        };
+        for modifier in modifiers {
+            match modifier.to_ascii_lowercase().as_str() {
+                "cmdorctrl" | "cmdorcontrol" | "commandorctrl" | "commandorcontrol" => {
+                    chord.cmd_or_ctrl = true
+                }
+                "ctrl" | "control" => chord.ctrl = true,
+                "alt" | "option" => chord.alt = true,
+                "shift" => chord.shift = true,
+                "super" | "cmd" | "command" | "meta" => chord.super_key = true,
+                _ => return Err(format!("unknown modifier in {raw}: {modifier}")),
+            }
+        }
+        chord.key = canonical_key(key).ok_or_else(|| format!("unknown key in {raw}: {key}"))?;
+        Ok(chord)
+    }
+
+    fn has_modifier(&self) -> bool {
+        self.cmd_or_ctrl || self.ctrl || self.alt || self.shift || self.super_key
+    }
+
+    /// The keys actually pressed on this platform, so `CmdOrCtrl+K` and `Ctrl+K`
+    /// are caught as the same shortcut off macOS.
+    fn physical(&self, macos: bool) -> Chord {
+        let mut chord = self.clone();
+        if chord.cmd_or_ctrl {
+            chord.cmd_or_ctrl = false;
+            if macos {
+                chord.super_key = true;
+            } else {
+                chord.ctrl = true;
+            }
+        }
+        chord
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (self.cmd_or_ctrl, "CmdOrCtrl"),
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.super_key, "Super"),
+        ];
+        for (_, name) in modifiers.iter().filter(|(on, _)| *on) {
+            write!(f, "{name}+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+fn canonical_key(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        let valid = c.is_ascii_alphanumeric() || "-=[];',./`\\".contains(c);
+        return valid.then(|| c.to_ascii_uppercase().to_string());
+    }
+    if let Some(n) = key.strip_prefix(['F', 'f']).and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&n).then(|| format!("F{n}"));
+    }
+    let key = match key.to_ascii_lowercase().as_str() {
+        "esc" => "escape".to_string(),
+        "return" => "enter".to_string(),
+        lower => lower.strip_prefix("arrow").unwrap_or(lower).to_string(),
+    };
+    NAMED_KEYS
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(&key))
+        .map(|name| name.to_string())
+}
+
+fn normalize(raw: &str) -> Result<String, String> {
+    Chord::parse(raw).map(|chord| chord.to_string())
+}
+
+/// Every action with its effective accelerator. Saved values that no longer parse
+/// fall back to the default.
+fn bindings(keymap: &KeymapV1) -> Vec<KeyBinding> {
+    ACTIONS
+        .iter()
+        .map(|action| {
+            let accelerator = match keymap.bindings.get(action.id) {
+                Some(Some(saved)) => normalize(saved).ok().or(action.default.map(str::to_string)),
+                Some(None) => None,
+                None => action.default.map(str::to_string),
+            };
+            KeyBinding {
+                action: action.id.to_string(),
+                label: action.label.to_string(),
+                scope: action.scope,
+                accelerator,
+                default_accelerator: action.default.map(str::to_string),
+            }
+        })
+        .collect()
+}
+
+fn check_conflicts(bindings: &[KeyBinding], macos: bool) -> Result<(), String> {
+    let mut taken: Vec<(Chord, &str)> = RESERVED
+        .iter()
+        .filter_map(|(accelerator, label)| Some((Chord::parse(accelerator).ok()?.physical(macos), *label)))
+        .collect();
+    for binding in bindings {
+        let Some(accelerator) = &binding.accelerator else {
+            continue;
+        };
+        let chord = Chord::parse(accelerator)?.physical(macos);
+        if let Some((_, label)) = taken.iter().find(|(other, _)| *other == chord) {
+            return Err(format!("{accelerator} is already used by {label}"));
+        }
+        taken.push((chord, binding.label.as_str()));
+    }
+    Ok(())
+}
+
+fn settings_cache() -> &'static Mutex<Option<KeymapV1>> {
+    static CACHE: OnceLock<Mutex<Option<KeymapV1>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn settings_file_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, SETTINGS_FILE_NAME)
+}
+
+fn read_settings<R: Runtime>(app: &AppHandle<R>) -> Result<KeymapV1, String> {
+    let path = settings_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(KeymapV1::default()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+/// The saved keymap, loaded on first use: the app menu is built before `setup` runs.
+fn current<R: Runtime>(app: &AppHandle<R>) -> KeymapV1 {
+    let Ok(mut cache) = settings_cache().lock() else {
+        return KeymapV1::default();
+    };
+    cache
+        .get_or_insert_with(|| {
+            read_settings(app).unwrap_or_else(|e| {
+                eprintln!("Failed to load keymap: {e}");
+                KeymapV1::default()
+            })
+        })
+        .clone()
+}
+
+pub(crate) fn accelerator_for<R: Runtime>(app: &AppHandle<R>, action: &str) -> Option<String> {
+    bindings(&current(app))
+        .into_iter()
+        .find(|binding| binding.action == action)
+        .and_then(|binding| binding.accelerator)
+}
+
+fn register_global_shortcuts(app: &AppHandle, bindings: &[KeyBinding]) -> Result<(), String> {
+    let shortcuts = app.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|e| format!("unregister shortcuts failed: {e}"))?;
+    for binding in bindings.iter().filter(|b| b.scope == KeyScope::Global) {
+        if let Some(accelerator) = &binding.accelerator {
+            shortcuts
+                .register(accelerator.as_str())
+                .map_err(|e| format!("register {accelerator} failed: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Register the saved global shortcuts at startup. Menu accelerators are applied
+/// when the menu is built.
+pub(crate) fn init(app: &AppHandle) {
+    if let Err(e) = register_global_shortcuts(app, &bindings(&current(app))) {
+        eprintln!("Failed to register global shortcuts: {e}");
+    }
+}
+
+pub(crate) fn handle_global_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = bindings(&current(app))
+        .into_iter()
+        .filter(|binding| binding.scope == KeyScope::Global)
+        .find(|binding| {
+            binding
+                .accelerator
+                .as_deref()
+                .and_then(|accelerator| accelerator.parse::<Shortcut>().ok())
+                .as_ref()
+                == Some(shortcut)
+        });
+    if action.is_some_and(|binding| binding.action == ACTION_SHOW_WINDOW) {
+        crate::tray::show_main_window(app);
+    }
+}
+
+/// Check, apply and save a new keymap. Nothing changes if it conflicts or a
+/// global shortcut can't be registered (e.g. another app holds it).
+fn save_keymap(app: &AppHandle, keymap: KeymapV1) -> Result<Vec<KeyBinding>, String> {
+    let updated = bindings(&keymap);
+    check_conflicts(&updated, cfg!(target_os = "macos"))?;
+    if let Err(e) = register_global_shortcuts(app, &updated) {
+        let _ = register_global_shortcuts(app, &bindings(&current(app)));
+        return Err(e);
+    }
+
+    let path = settings_file_path(app)?;
+    crate::util::write_json_atomic(&path, &keymap)?;
+
+    *settings_cache().lock().map_err(|_| "keymap cache poisoned")? = Some(keymap);
+    crate::app_menu::apply_keymap(app, &updated);
+    Ok(updated)
+}
+
+#[tauri::command]
+pub fn get_keymap(window: WebviewWindow) -> Result<Vec<KeyBinding>, String> {
+    Ok(bindings(&current(window.app_handle())))
+}
+
+/// Bind `action` to `accelerator` (e.g. `CmdOrCtrl+Shift+T`), or unbind it with
+/// `null`. Returns the whole keymap after the change.
+#[tauri::command]
+pub fn update_keybinding(
+    window: WebviewWindow,
+    action: String,
+    accelerator: Option<String>,
+) -> Result<Vec<KeyBinding>, String> {
+    let known = ACTIONS
+        .iter()
+        .find(|a| a.id == action)
+        .ok_or_else(|| format!("unknown action: {action}"))?;
+    let accelerator = match accelerator.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        Some(raw) => Some(Chord::parse(raw)?),
+        None => None,
+    };
+    if known.scope == KeyScope::Global && accelerator.as_ref().is_some_and(|chord| !chord.has_modifier()) {
+        return Err("global shortcuts need a modifier key".to_string());
+    }
+    let accelerator = accelerator.map(|chord| chord.to_string());
+
+    let app = window.app_handle();
+    let mut keymap = current(app);
+    if accelerator.as_deref() == known.default {
+        keymap.bindings.remove(&action);
+    } else {
+        keymap.bindings.insert(action, accelerator);
+    }
+    save_keymap(app, keymap)
+}
+
+#[tauri::command]
+pub fn reset_keybindings(window: WebviewWindow) -> Result<Vec<KeyBinding>, String> {
+    save_keymap(window.app_handle(), KeymapV1::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bindings, check_conflicts, normalize, KeymapV1};
+
+    #[test]
+    fn normalizes_accelerators() {
+        assert_eq!(normalize("shift+cmdorctrl+t").unwrap(), "CmdOrCtrl+Shift+T");
+        assert_eq!(normalize("Option + ArrowUp").unwrap(), "Alt+Up");
+        assert_eq!(normalize("Ctrl+f12").unwrap(), "Ctrl+F12");
+        assert!(normalize("Hyper+K").is_err());
+        assert!(normalize("Ctrl+F25").is_err());
+        assert!(normalize("").is_err());
+    }
+
+    #[test]
+    fn detects_conflicts_per_platform() {
+        let mut keymap = KeymapV1::default();
+        keymap.bindings.insert("new-terminal".to_string(), Some("Ctrl+K".to_string()));
+        keymap.bindings.insert("close-session".to_string(), Some("CmdOrCtrl+K".to_string()));
+        let both = bindings(&keymap);
+        assert!(check_conflicts(&both, true).is_ok());
+        assert!(check_conflicts(&both, false).is_err());
+
+        keymap.bindings.insert("close-session".to_string(), Some("CmdOrCtrl+W".to_string()));
+        let err = check_conflicts(&bindings(&keymap), true).unwrap_err();
+        assert!(err.contains("Close Window"), "{err}");
+    }
+}
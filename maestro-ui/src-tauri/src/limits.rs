@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+const SETTINGS_FILE_NAME: &str = "agent-resource-limits-v1.json";
+
+/// CPU/memory caps for a spawned session and everything it starts. Saved per
+/// agent profile with `set_agent_resource_limits`, or passed to `create_session`.
+/// `cpu_percent` is a share of the whole machine (100 = all cores).
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLimitsV1 {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_percent: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+}
+
+impl ResourceLimitsV1 {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.cpu_percent.is_none() && self.memory_mb.is_none()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(cpu) = self.cpu_percent {
+            if cpu == 0 || cpu > 100 {
+                return Err("cpuPercent must be between 1 and 100".to_string());
+            }
+        }
+        if self.memory_mb == Some(0) {
+            return Err("memoryMb must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// `systemd-run` options for a transient scope with the limits. CPUQuota is
+/// relative to a single core, so the machine share is scaled by `cores`.
+#[cfg(target_os = "linux")]
+fn systemd_run_args(limits: &ResourceLimitsV1, cores: u32) -> Vec<String> {
+    let mut out: Vec<String> = vec!["--user".into(), "--scope".into(), "--quiet".into()];
+    if let Some(cpu) = limits.cpu_percent {
+        out.extend(["-p".to_string(), format!("CPUQuota={}%", cpu * cores)]);
+    }
+    if let Some(mb) = limits.memory_mb {
+        out.extend(["-p".to_string(), format!("MemoryMax={mb}M")]);
+    }
+    out
+}
+
+/// `systemd-run`, if it's installed and the user's systemd instance is up to start a
+/// scope with it; probed once. Without a user session bus (containers, ssh logins
+/// without lingering) the scope would fail at spawn time instead.
+#[cfg(target_os = "linux")]
+fn usable_systemd_run() -> Option<&'static std::path::Path> {
+    static SYSTEMD_RUN: std::sync::OnceLock<Option<PathBuf>> = std::sync::OnceLock::new();
+    SYSTEMD_RUN
+        .get_or_init(|| {
+            let systemd_run = crate::ssh_fs::find_program_in_path("systemd-run")?;
+            let output = std::process::Command::new("systemctl")
+                .args(["--user", "is-system-running"])
+                .stdin(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .output()
+                .ok()?;
+            // `degraded` only means some unit failed; scopes still start.
+            let status = String::from_utf8_lossy(&output.stdout);
+            matches!(status.trim(), "running" | "degraded").then_some(systemd_run)
+        })
+        .as_deref()
+}
+
+/// No hard CPU cap outside cgroups: lower the priority in proportion, from 0 for
+/// the whole machine to 19 for the smallest share.
+#[cfg(target_family = "unix")]
+fn niceness(cpu_percent: u32) -> u32 {
+    19 * (100 - cpu_percent.min(100)) / 100
+}
+
+/// Wrap `program args` so the process starts under the requested limits:
+/// a transient systemd scope (cgroup) on Linux, falling back to nice for CPU
+/// (memory limits need the scope); taskpolicy/nice on macOS. Windows limits are applied after spawn with
+/// `apply_to_process` instead.
+#[cfg(target_family = "unix")]
+pub(crate) fn wrap_command(
+    limits: &ResourceLimitsV1,
+    program: String,
+    args: Vec<String>,
+) -> Result<(String, Vec<String>), String> {
+    limits.validate()?;
+    if limits.is_empty() {
+        return Ok((program, args));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(systemd_run) = usable_systemd_run() {
+            let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1) as u32;
+            let mut out = systemd_run_args(limits, cores);
+            out.push("--".to_string());
+            out.push(program);
+            out.extend(args);
+            return Ok((systemd_run.to_string_lossy().to_string(), out));
+        }
+    }
+
+    let mut wrapped = (program, args);
+    // An address-space cap (prlimit --as) is no substitute for the scope: runtimes
+    // that reserve large virtual ranges fail at startup under it.
+    #[cfg(target_os = "linux")]
+    if limits.memory_mb.is_some() {
+        return Err("memory limits require systemd-run".to_string());
+    }
+    #[cfg(not(target_os = "linux"))]
+    if let Some(mb) = limits.memory_mb {
+        eprintln!("[limits] memory limit of {mb}MB is not enforceable on this platform; ignoring");
+    }
+    if let Some(cpu) = limits.cpu_percent {
+        let mut out = vec!["-n".to_string(), niceness(cpu).to_string()];
+        #[cfg(target_os = "macos")]
+        {
+            // Throttled QoS so the scheduler prefers interactive work.
+            out = vec!["-c".to_string(), "utility".to_string(), "/usr/bin/nice".to_string()]
+                .into_iter()
+                .chain(out)
+                .collect();
+        }
+        out.push(wrapped.0);
+        out.extend(wrapped.1);
+        #[cfg(target_os = "macos")]
+        let launcher = "/usr/sbin/taskpolicy";
+        #[cfg(not(target_os = "macos"))]
+        let launcher = "nice";
+        wrapped = (launcher.to_string(), out);
+    }
+    Ok(wrapped)
+}
+
+fn settings_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, SETTINGS_FILE_NAME)
+}
+
+fn read_agent_limits(app: &AppHandle) -> Result<HashMap<String, ResourceLimitsV1>, String> {
+    let path = settings_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+/// Drop blank agent ids and empty limits, and check the rest.
+fn clean_agent_limits(
+    limits: HashMap<String, ResourceLimitsV1>,
+) -> Result<HashMap<String, ResourceLimitsV1>, String> {
+    let mut cleaned = HashMap::new();
+    for (agent, agent_limits) in limits {
+        let agent = agent.trim();
+        if agent.is_empty() || agent_limits.is_empty() {
+            continue;
+        }
+        agent_limits.validate().map_err(|e| format!("{agent}: {e}"))?;
+        cleaned.insert(agent.to_string(), agent_limits);
+    }
+    Ok(cleaned)
+}
+
+/// The saved limits for an agent profile (its effect id, e.g. `claude`).
+pub(crate) fn for_agent(app: &AppHandle, agent: &str) -> Result<Option<ResourceLimitsV1>, String> {
+    Ok(read_agent_limits(app)?.remove(agent.trim()))
+}
+
+/// Resource limits saved per agent profile, keyed by effect id.
+#[tauri::command]
+pub fn get_agent_resource_limits(window: WebviewWindow) -> Result<HashMap<String, ResourceLimitsV1>, String> {
+    read_agent_limits(window.app_handle())
+}
+
+/// Replace the per-agent limits. They apply to sessions created afterwards.
+#[tauri::command]
+pub fn set_agent_resource_limits(
+    window: WebviewWindow,
+    limits: HashMap<String, ResourceLimitsV1>,
+) -> Result<HashMap<String, ResourceLimitsV1>, String> {
+    let limits = clean_agent_limits(limits)?;
+    let path = settings_file_path(window.app_handle())?;
+    crate::util::write_json_atomic(&path, &limits)?;
+    Ok(limits)
+}
+
+/// A job object holding a session's process tree. Closing it (on drop) kills
+/// the tree, matching the session lifetime.
+#[cfg(windows)]
+pub(crate) struct JobLimit(windows_sys::Win32::Foundation::HANDLE);
+
+#[cfg(windows)]
+unsafe impl Send for JobLimit {}
+
+#[cfg(windows)]
+impl Drop for JobLimit {
+    fn drop(&mut self) {
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
+/// Put an already spawned process into a job object with the requested limits.
+#[cfg(windows)]
+pub(crate) fn apply_to_process(limits: &ResourceLimitsV1, pid: u32) -> Result<Option<JobLimit>, String> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, SetInformationJobObject,
+        JobObjectCpuRateControlInformation, JobObjectExtendedLimitInformation,
+        JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_CPU_RATE_CONTROL_ENABLE, JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+        JOB_OBJECT_LIMIT_JOB_MEMORY, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
+
+    limits.validate()?;
+    if limits.is_empty() {
+        return Ok(None);
+    }
+
+    unsafe {
+        let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if job.is_null() {
+            return Err(format!("create job object failed: {}", std::io::Error::last_os_error()));
+        }
+        let job = JobLimit(job);
+
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        if let Some(mb) = limits.memory_mb {
+            info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_JOB_MEMORY;
+            info.JobMemoryLimit = mb.saturating_mul(1024 * 1024) as usize;
+        }
+        if SetInformationJobObject(
+            job.0,
+            JobObjectExtendedLimitInformation,
+            &info as *const _ as *const _,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        ) == 0
+        {
+            return Err(format!("set job limits failed: {}", std::io::Error::last_os_error()));
+        }
+
+        if let Some(cpu) = limits.cpu_percent {
+            let mut rate: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION = std::mem::zeroed();
+            rate.ControlFlags = JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+            // CpuRate is in 1/100ths of a percent of total machine time.
+            rate.Anonymous.CpuRate = cpu * 100;
+            if SetInformationJobObject(
+                job.0,
+                JobObjectCpuRateControlInformation,
+                &rate as *const _ as *const _,
+                std::mem::size_of::<JOBOBJECT_CPU_RATE_CONTROL_INFORMATION>() as u32,
+            ) == 0
+            {
+                return Err(format!("set cpu rate failed: {}", std::io::Error::last_os_error()));
+            }
+        }
+
+        let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+        if process.is_null() {
+            return Err(format!("open process failed: {}", std::io::Error::last_os_error()));
+        }
+        let assigned = AssignProcessToJobObject(job.0, process);
+        CloseHandle(process);
+        if assigned == 0 {
+            return Err(format!("assign job failed: {}", std::io::Error::last_os_error()));
+        }
+        Ok(Some(job))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clean_agent_limits, ResourceLimitsV1};
+    use std::collections::HashMap;
+
+    #[test]
+    fn cleans_and_validates_agent_limits() {
+        let limits = |cpu_percent, memory_mb| ResourceLimitsV1 { cpu_percent, memory_mb };
+        let saved = HashMap::from([
+            (" claude ".to_string(), limits(Some(50), None)),
+            ("codex".to_string(), limits(None, None)),
+            (" ".to_string(), limits(Some(10), None)),
+        ]);
+        let cleaned = clean_agent_limits(saved).unwrap();
+        assert_eq!(cleaned.len(), 1);
+        assert_eq!(cleaned["claude"].cpu_percent, Some(50));
+
+        let bad = HashMap::from([("gemini".to_string(), limits(Some(150), None))]);
+        assert_eq!(
+            clean_agent_limits(bad).unwrap_err(),
+            "gemini: cpuPercent must be between 1 and 100"
+        );
+        assert!(clean_agent_limits(HashMap::from([("a".to_string(), limits(None, Some(0)))])).is_err());
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn scales_cpu_share_to_nice_levels() {
+        use super::niceness;
+        assert_eq!([100, 50, 25, 1].map(niceness), [0, 9, 14, 18]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn builds_systemd_run_scope_args() {
+        use super::systemd_run_args;
+        let limits = ResourceLimitsV1 { cpu_percent: Some(25), memory_mb: Some(2048) };
+        assert_eq!(
+            systemd_run_args(&limits, 8),
+            ["--user", "--scope", "--quiet", "-p", "CPUQuota=200%", "-p", "MemoryMax=2048M"]
+        );
+        let memory_only = ResourceLimitsV1 { cpu_percent: None, memory_mb: Some(512) };
+        assert_eq!(systemd_run_args(&memory_only, 4)[3..], ["-p", "MemoryMax=512M"]);
+    }
+}
@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+const SETTINGS_FILE_NAME: &str = "locale-settings-v1.json";
+const FALLBACK_LANG: &str = "en_US.UTF-8";
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleSettingsV1 {
+    /// Set LANG/LC_ALL/TZ on new sessions from the detected locale. Apps started
+    /// from Finder or a desktop launcher often get no locale at all, which leaves
+    /// agents rendering Unicode as `?`.
+    #[serde(default = "default_true")]
+    pub propagate: bool,
+}
+
+impl Default for LocaleSettingsV1 {
+    fn default() -> Self {
+        Self { propagate: true }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedLocale {
+    /// Used for both LANG and LC_ALL.
+    pub lang: Option<String>,
+    /// IANA zone name, e.g. `Europe/Berlin`.
+    pub tz: Option<String>,
+}
+
+fn settings_cache() -> &'static Mutex<Option<LocaleSettingsV1>> {
+    static CACHE: OnceLock<Mutex<Option<LocaleSettingsV1>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn settings_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, SETTINGS_FILE_NAME)
+}
+
+fn read_settings(app: &AppHandle) -> Result<LocaleSettingsV1, String> {
+    let path = settings_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(LocaleSettingsV1::default()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+/// `en_US.utf8` and `en_US.UTF-8` name the same locale; compare them loosely.
+fn normalize_locale(name: &str) -> String {
+    name.to_ascii_lowercase().replace("utf-8", "utf8")
+}
+
+/// A UTF-8 locale name from an environment value or the macOS `AppleLocale`
+/// preference (`en_GB`, `de_DE@rg=chzzzz`). `C`/`POSIX` don't count.
+fn utf8_locale(raw: &str) -> Option<String> {
+    let base = raw.trim().split('@').next().unwrap_or_default();
+    let (name, codeset) = match base.split_once('.') {
+        Some((name, codeset)) => (name, Some(codeset)),
+        None => (base, None),
+    };
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    match codeset {
+        Some(codeset) if normalize_locale(codeset) != "utf8" => None,
+        _ => Some(format!("{name}.UTF-8")),
+    }
+}
+
+/// Zone name from the `/etc/localtime` symlink target.
+fn zone_from_localtime(target: &str) -> Option<String> {
+    let (_, zone) = target.split_once("zoneinfo/")?;
+    let zone = zone.trim_matches('/');
+    (!zone.is_empty() && !zone.contains("..")).then(|| zone.to_string())
+}
+
+fn installed_locales() -> Option<Vec<String>> {
+    let output = Command::new("locale").arg("-a").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(normalize_locale)
+            .collect(),
+    )
+}
+
+fn detect() -> DetectedLocale {
+    let mut candidates: Vec<String> = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|k| std::env::var(k).ok())
+        .filter_map(|v| utf8_locale(&v))
+        .collect();
+    #[cfg(target_os = "macos")]
+    if let Ok(output) = Command::new("defaults").args(["read", "-g", "AppleLocale"]).output() {
+        if let Some(lang) = utf8_locale(&String::from_utf8_lossy(&output.stdout)) {
+            candidates.push(lang);
+        }
+    }
+    candidates.push(FALLBACK_LANG.to_string());
+
+    // Regions without a matching locale (`en_DE` on macOS) would make every
+    // program warn; keep to what's installed when that can be checked.
+    let lang = match installed_locales() {
+        Some(installed) => candidates
+            .into_iter()
+            .find(|c| installed.contains(&normalize_locale(c))),
+        None => candidates.into_iter().next(),
+    };
+
+    let tz = std::env::var("TZ")
+        .ok()
+        .map(|tz| tz.trim().trim_start_matches(':').to_string())
+        .filter(|tz| !tz.is_empty())
+        .or_else(|| {
+            fs::read_link("/etc/localtime")
+                .ok()
+                .and_then(|target| zone_from_localtime(&target.to_string_lossy()))
+        });
+    DetectedLocale { lang, tz }
+}
+
+fn detected() -> &'static DetectedLocale {
+    static DETECTED: OnceLock<DetectedLocale> = OnceLock::new();
+    DETECTED.get_or_init(detect)
+}
+
+/// Load the setting and detect the locale in the background, so the first
+/// session doesn't wait on `locale -a`.
+pub(crate) fn init(app: &AppHandle) {
+    let settings = read_settings(app).unwrap_or_else(|e| {
+        eprintln!("Failed to load locale settings: {e}");
+        LocaleSettingsV1::default()
+    });
+    if let Ok(mut cache) = settings_cache().lock() {
+        *cache = Some(settings);
+    }
+    std::thread::spawn(|| {
+        detected();
+    });
+}
+
+/// LANG/LC_ALL/TZ for a new session, leaving out any the caller already sets.
+pub(crate) fn session_vars(overridden: &[String]) -> Vec<(&'static str, String)> {
+    let propagate = settings_cache()
+        .lock()
+        .ok()
+        .and_then(|c| *c)
+        .unwrap_or_default()
+        .propagate;
+    if !propagate {
+        return Vec::new();
+    }
+    let locale = detected();
+    let mut vars = Vec::new();
+    if let Some(lang) = &locale.lang {
+        vars.push(("LANG", lang.clone()));
+        vars.push(("LC_ALL", lang.clone()));
+    }
+    if let Some(tz) = &locale.tz {
+        vars.push(("TZ", tz.clone()));
+    }
+    vars.retain(|(key, _)| !overridden.iter().any(|k| k == key));
+    vars
+}
+
+/// What new sessions would get with propagation on.
+#[tauri::command]
+pub async fn get_detected_locale() -> Result<DetectedLocale, String> {
+    tauri::async_runtime::spawn_blocking(|| detected().clone())
+        .await
+        .map_err(|e| format!("detect locale task join failed: {e:?}"))
+}
+
+#[tauri::command]
+pub fn get_locale_settings(window: WebviewWindow) -> Result<LocaleSettingsV1, String> {
+    read_settings(window.app_handle())
+}
+
+#[tauri::command]
+pub fn set_locale_settings(window: WebviewWindow, settings: LocaleSettingsV1) -> Result<(), String> {
+    let path = settings_file_path(window.app_handle())?;
+    crate::util::write_json_atomic(&path, &settings)?;
+
+    *settings_cache().lock().map_err(|_| "locale settings cache poisoned")? = Some(settings);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{utf8_locale, zone_from_localtime};
+
+    #[test]
+    fn normalizes_locales_and_zones() {
+        assert_eq!(utf8_locale("en_GB").as_deref(), Some("en_GB.UTF-8"));
+        assert_eq!(utf8_locale("de_DE@rg=chzzzz\n").as_deref(), Some("de_DE.UTF-8"));
+        assert_eq!(utf8_locale("fr_FR.utf8").as_deref(), Some("fr_FR.UTF-8"));
+        assert_eq!(utf8_locale("C"), None);
+        assert_eq!(utf8_locale("en_US.ISO8859-1"), None);
+        assert_eq!(utf8_locale(""), None);
+
+        assert_eq!(
+            zone_from_localtime("/var/db/timezone/zoneinfo/Europe/Berlin").as_deref(),
+            Some("Europe/Berlin")
+        );
+        assert_eq!(zone_from_localtime("/etc/foo"), None);
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::AppHandle;
+
+use crate::util::now_epoch_ms;
+
+const CACHE_FILE_NAME: &str = "login-path-cache-v1.json";
+/// Re-probe at least this often: rc files can source others we don't stamp
+/// (`~/.zshrc` loading nvm, say).
+const MAX_AGE_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Modification time of a file the login shell reads; `None` if it doesn't exist,
+/// so creating one invalidates the cache too.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct RcStamp {
+    path: String,
+    modified_ms: Option<u64>,
+}
+
+/// The login shell's PATH as probed on a previous launch (see `login_shell_path`
+/// in pty.rs), with what it depended on.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LoginPathCacheV1 {
+    shell: String,
+    /// The PATH the probe started from.
+    base_path: String,
+    stamps: Vec<RcStamp>,
+    path: String,
+    computed_at: u64,
+}
+
+fn cache_file_path(app: &AppHandle) -> Option<PathBuf> {
+    crate::util::app_data_file(app, CACHE_FILE_NAME).ok()
+}
+
+/// Startup files an interactive login `shell` reads, plus the shell binary itself
+/// (an upgrade can change its defaults) and macOS's `path_helper` inputs.
+fn rc_files(shell: &str, home: &Path, zdotdir: Option<&Path>) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(shell), PathBuf::from("/etc/paths"), PathBuf::from("/etc/paths.d")];
+    let name = Path::new(shell)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    if name.contains("zsh") {
+        let dotdir = zdotdir.unwrap_or(home);
+        files.extend(["/etc/zshenv", "/etc/zprofile", "/etc/zshrc", "/etc/zlogin"].map(PathBuf::from));
+        files.extend([".zshenv", ".zprofile", ".zshrc", ".zlogin"].map(|f| dotdir.join(f)));
+    } else if name.contains("bash") {
+        files.extend(["/etc/profile", "/etc/bashrc"].map(PathBuf::from));
+        files.extend([".bash_profile", ".bash_login", ".profile", ".bashrc"].map(|f| home.join(f)));
+    } else if name == "fish" {
+        files.push(PathBuf::from("/etc/fish/config.fish"));
+        files.extend(["config.fish", "conf.d"].map(|f| home.join(".config/fish").join(f)));
+    } else {
+        files.push(PathBuf::from("/etc/profile"));
+        files.push(home.join(".profile"));
+    }
+    files
+}
+
+fn stamps(shell: &str) -> Option<Vec<RcStamp>> {
+    let home = dirs::home_dir()?;
+    let zdotdir = std::env::var_os("ZDOTDIR").map(PathBuf::from);
+    let stamps = rc_files(shell, &home, zdotdir.as_deref())
+        .into_iter()
+        .map(|path| RcStamp {
+            modified_ms: fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64),
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect();
+    Some(stamps)
+}
+
+fn is_fresh(cache: &LoginPathCacheV1, shell: &str, base_path: &str, stamps: &[RcStamp], now: u64) -> bool {
+    cache.shell == shell
+        && cache.base_path == base_path
+        && cache.stamps == stamps
+        && now.saturating_sub(cache.computed_at) < MAX_AGE_MS
+        && !cache.path.trim().is_empty()
+}
+
+/// The PATH saved by an earlier launch, if nothing it depended on has changed since.
+pub(crate) fn load(app: &AppHandle, shell: &str, base_path: &str) -> Option<String> {
+    let raw = fs::read_to_string(cache_file_path(app)?).ok()?;
+    let cache: LoginPathCacheV1 = serde_json::from_str(&raw).ok()?;
+    is_fresh(&cache, shell, base_path, &stamps(shell)?, now_epoch_ms()).then_some(cache.path)
+}
+
+/// Save a freshly probed PATH for the next launch. Best effort.
+pub(crate) fn store(app: &AppHandle, shell: &str, base_path: &str, path: &str) {
+    let (Some(file), Some(stamps)) = (cache_file_path(app), stamps(shell)) else {
+        return;
+    };
+    let cache = LoginPathCacheV1 {
+        shell: shell.to_string(),
+        base_path: base_path.to_string(),
+        stamps,
+        path: path.to_string(),
+        computed_at: now_epoch_ms(),
+    };
+    if let Err(e) = write_cache(&file, &cache) {
+        eprintln!("Failed to save login PATH cache: {e}");
+    }
+}
+
+fn write_cache(file: &Path, cache: &LoginPathCacheV1) -> Result<(), String> {
+    crate::util::write_json_atomic(file, cache)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_fresh, rc_files, LoginPathCacheV1, RcStamp, MAX_AGE_MS};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn stamps_the_shells_own_startup_files() {
+        let home = Path::new("/Users/me");
+        let zsh = rc_files("/bin/zsh", home, Some(Path::new("/Users/me/.config/zsh")));
+        assert!(zsh.contains(&PathBuf::from("/Users/me/.config/zsh/.zshrc")));
+        assert!(zsh.contains(&PathBuf::from("/bin/zsh")));
+        assert!(!zsh.contains(&PathBuf::from("/Users/me/.bashrc")));
+        let fish = rc_files("/opt/homebrew/bin/fish", home, None);
+        assert!(fish.contains(&PathBuf::from("/Users/me/.config/fish/conf.d")));
+    }
+
+    #[test]
+    fn stale_when_inputs_change_or_too_old() {
+        let stamps = vec![RcStamp {
+            path: "/Users/me/.zshrc".to_string(),
+            modified_ms: Some(10),
+        }];
+        let cache = LoginPathCacheV1 {
+            shell: "/bin/zsh".to_string(),
+            base_path: "/usr/bin".to_string(),
+            stamps: stamps.clone(),
+            path: "/opt/homebrew/bin:/usr/bin".to_string(),
+            computed_at: 1000,
+        };
+        assert!(is_fresh(&cache, "/bin/zsh", "/usr/bin", &stamps, 2000));
+        assert!(!is_fresh(&cache, "/bin/bash", "/usr/bin", &stamps, 2000));
+        assert!(!is_fresh(&cache, "/bin/zsh", "/usr/bin:/bin", &stamps, 2000));
+        let edited = vec![RcStamp {
+            modified_ms: Some(11),
+            ..stamps[0].clone()
+        }];
+        assert!(!is_fresh(&cache, "/bin/zsh", "/usr/bin", &edited, 2000));
+        assert!(!is_fresh(&cache, "/bin/zsh", "/usr/bin", &stamps, 1000 + MAX_AGE_MS));
+    }
+}
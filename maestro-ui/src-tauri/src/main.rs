@@ -1,42 +1,180 @@
+mod activity;
+mod agent_export;
+mod agent_logs;
 mod app_menu;
 mod app_info;
 mod assets;
+mod audit;
+mod auto_approve;
+mod bell;
+mod benchmark;
+mod bootstrap;
+mod claude_import;
 mod claude_logs;
 mod codex_logs;
+mod command_output;
+mod command_policy;
+mod devcontainer;
+mod doctor;
+mod exec_guard;
+mod external_sessions;
+mod faults;
+mod favorites;
 mod files;
+mod file_limits;
 mod file_manager;
+mod fonts;
+mod fs_operations;
+mod idle;
+mod keymap;
+mod limits;
+mod locale;
+#[cfg(target_os = "macos")]
+mod login_path_cache;
+mod nav_history;
+mod needs_input;
+mod onboarding;
+mod osc;
+mod output_batch;
+mod output_stats;
+mod paste_guard;
 mod pty;
 mod persist;
+mod persistent_sessions;
+mod preflight;
+mod project_ignore;
+mod project_overview;
+mod project_settings;
+mod recent_files;
 mod recording;
+mod recording_export;
+mod restart;
+mod sandbox;
+mod scrollback;
+mod scrollback_spool;
 mod secure;
+mod session_metrics;
+mod session_names;
+mod share;
 mod ssh;
+mod ssh_askpass;
 mod ssh_fs;
+mod ssh_overrides;
+mod ssh_provision;
+mod ssh_retry;
 mod startup;
+mod status;
+mod telemetry;
+mod term_features;
+mod terminal_themes;
+mod throttle;
+mod tmux_sessions;
+mod todos;
+mod touched_files;
+mod transcript;
 mod tray;
+mod tree_snapshots;
+mod util;
+mod zellij_actions;
+mod zellij_layouts;
 
+use agent_export::export_agent_conversation;
+use agent_logs::{
+    cleanup_agent_logs, count_agent_log_lines, get_active_agent_sessions, read_agent_log_range,
+};
 use app_info::get_app_info;
 use assets::{apply_text_assets, save_session_asset};
+use audit::{get_audit_retention, read_audit_log, set_audit_retention};
 use app_menu::{build_app_menu, handle_app_menu_event};
-use claude_logs::{list_claude_session_logs, read_claude_session_log, tail_claude_session_log};
+use bell::{get_bell_settings, set_bell_settings};
+use benchmark::benchmark_pty_throughput;
+use claude_import::import_claude_projects;
+use claude_logs::{
+    get_agent_file_changes, list_claude_session_logs, read_claude_session_log, tail_claude_session_log,
+};
 use codex_logs::{list_codex_session_logs, read_codex_session_log, tail_codex_session_log};
+use command_output::copy_last_command_output;
+use command_policy::{get_command_policy, set_command_policy};
+use devcontainer::{create_devcontainer_session, detect_devcontainer, devcontainer_up};
+use doctor::run_doctor;
+use exec_guard::{confirm_session_exec, get_exec_guard, set_exec_guard};
+use external_sessions::{
+    adopt_external_session, list_all_persistent_backends, list_external_sessions, repair_persistent_sessions,
+};
+use favorites::{add_favorite_path, list_favorite_paths, remove_favorite_path, rename_favorite_path};
 use files::{copy_fs_entry, delete_fs_entry, list_fs_entries, list_project_files, read_text_file, rename_fs_entry, write_text_file};
+use file_limits::{get_file_limits, set_file_limits};
 use file_manager::open_path_in_file_manager;
+use fonts::list_monospace_fonts;
+use fs_operations::{cancel_fs_operation, start_fs_operation};
+use keymap::{get_keymap, reset_keybindings, update_keybinding};
+use limits::{get_agent_resource_limits, set_agent_resource_limits};
+use locale::{get_detected_locale, get_locale_settings, set_locale_settings};
+use nav_history::{
+    file_nav_back, file_nav_forward, file_nav_visit, get_file_nav_state, resolve_breadcrumbs,
+};
+use needs_input::{get_input_prompt_patterns, set_input_prompt_patterns};
+use onboarding::{complete_onboarding_step, dismiss_onboarding, get_onboarding_status, reset_onboarding};
+use output_batch::{get_output_batching, set_output_batching};
+use output_stats::{get_output_stats, report_output_latency};
+use paste_guard::{get_paste_guard, sanitize_paste, set_paste_guard};
 use pty::{
-    close_session, create_session, detach_session, kill_persistent_session, list_persistent_sessions,
-    list_sessions, resize_session, start_session_recording, stop_session_recording, write_to_session,
-    AppState,
+    close_session, close_session_gracefully, create_session, detach_session, get_session_env,
+    get_session_env_summary, inject_env_into_session, list_sessions, paste_to_session, rename_session,
+    resize_session, search_session_output,
+    send_signal_to_session, start_session_recording, stop_session_recording, write_bytes_to_session,
+    write_to_session, AppState,
 };
 use persist::{list_directories, load_persisted_state, load_persisted_state_meta, save_persisted_state, validate_directory};
-use recording::{delete_recording, list_recordings, load_recording};
+use persistent_sessions::{
+    close_persistent_attachment, inspect_persistent_session, kill_persistent_session,
+    list_persistent_attachments, list_persistent_sessions, open_persistent_attachment,
+    resurrect_persistent_session,
+};
+use preflight::run_project_preflight;
+use project_ignore::{get_project_ignore, set_project_ignore};
+use project_overview::get_project_overview;
+use project_settings::{get_project_settings, set_project_settings};
+use recent_files::get_recent_files;
+use recording::{
+    delete_recording, get_recording_meta, list_deleted_recordings, list_recordings, load_recording,
+    load_recording_timeline, restore_recording,
+};
+use recording_export::export_recording_html;
+use scrollback::get_session_scrollback;
+use scrollback_spool::get_spooled_history;
 use secure::{prepare_secure_storage, reset_secure_storage};
+use session_metrics::{get_session_metrics, set_session_metrics_enabled};
+use session_names::suggest_session_name;
+use share::{list_session_shares, start_session_share, stop_session_share};
 use ssh::list_ssh_hosts;
+use ssh_askpass::{answer_ssh_askpass, set_ssh_askpass_handler};
 use ssh_fs::{
-    ssh_default_root, ssh_delete_fs_entry, ssh_download_file, ssh_download_to_temp,
-    ssh_list_fs_entries, ssh_read_text_file, ssh_rename_fs_entry, ssh_upload_file,
-    ssh_write_text_file,
+    ssh_batch_operations, ssh_default_root, ssh_delete_fs_entry, ssh_download_file, ssh_download_to_temp,
+    ssh_get_disk_usage, ssh_kill_process, ssh_list_fs_entries, ssh_list_processes, ssh_read_text_file,
+    ssh_rename_fs_entry, ssh_upload_file, ssh_write_text_file,
+};
+use ssh_overrides::{get_ssh_host_overrides, set_ssh_host_override};
+use ssh_provision::ssh_setup_remote_agent_host;
+use ssh_retry::{
+    cancel_pending_remote_op, get_ssh_retry_policy, list_pending_remote_ops, set_ssh_retry_policy,
 };
 use startup::get_startup_flags;
-use tray::{build_status_tray, set_tray_agent_count, set_tray_recent_sessions, set_tray_status};
+use status::get_status_summary;
+use telemetry::{get_telemetry_status, record_feature_usage, set_telemetry_enabled};
+use terminal_themes::{delete_terminal_theme, get_terminal_theme, import_terminal_theme, list_terminal_themes};
+use transcript::get_session_transcript;
+use todos::scan_todos;
+use touched_files::get_session_touched_files;
+use tree_snapshots::{create_tree_snapshot, diff_tree_snapshots, list_tree_snapshots};
+use tray::{
+    build_status_tray, get_focus_mode, refresh_tray_agents, set_focus_mode, set_tray_agent_count,
+    set_tray_recent_sessions, set_tray_status,
+};
+use zellij_actions::{zellij_new_tab, zellij_run, zellij_write_chars};
+use zellij_layouts::{
+    get_zellij_info, get_zellij_serialization, list_session_layouts, set_zellij_serialization,
+};
 use tauri::Manager;
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -58,6 +196,11 @@ fn allow_window_close(state: tauri::State<'_, AllowCloseState>) {
 }
 
 fn main() {
+    // ssh runs this executable as SSH_ASKPASS; answer the prompt and exit.
+    if let Some(code) = ssh_askpass::run_helper_if_requested() {
+        std::process::exit(code);
+    }
+
     #[cfg(any(target_os = "macos", target_os = "linux"))]
     {
         // Pre-seed PATH with common directories so shell init scripts can run properly.
@@ -99,17 +242,34 @@ fn main() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_drag::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| keymap::handle_global_shortcut(app, shortcut, event))
+                .build(),
+        )
         .menu(|app| build_app_menu(app))
         .on_menu_event(|app, event| handle_app_menu_event(app, event))
         .setup(|app| {
             if let Err(e) = startup::clear_app_data_if_requested(&app.handle()) {
                 eprintln!("Failed to clear app data: {e}");
             }
+            file_limits::init(&app.handle());
+            locale::init(&app.handle());
+            output_batch::init(&app.handle());
+            keymap::init(&app.handle());
+            ssh_overrides::init(&app.handle());
+            ssh_askpass::init(&app.handle());
+            ssh_retry::init(&app.handle());
+            telemetry::init(&app.handle());
             let tray = build_status_tray(&app.handle()).unwrap_or_else(|e| {
                 eprintln!("Failed to create tray icon: {e}");
                 tray::StatusTrayState::disabled()
             });
             app.manage(tray);
+            tray::start_status_ticker(&app.handle());
+            if let Some(window) = app.get_webview_window("main") {
+                app_menu::refresh_dynamic_menus(&window);
+            }
 
             // Open devtools automatically in prod for debugging
             #[cfg(feature = "devtools")]
@@ -176,11 +336,29 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             create_session,
             write_to_session,
+            write_bytes_to_session,
+            paste_to_session,
             resize_session,
+            rename_session,
             close_session,
+            close_session_gracefully,
+            send_signal_to_session,
             detach_session,
             list_sessions,
             list_persistent_sessions,
+            inspect_persistent_session,
+            open_persistent_attachment,
+            list_persistent_attachments,
+            close_persistent_attachment,
+            resurrect_persistent_session,
+            list_external_sessions,
+            list_all_persistent_backends,
+            adopt_external_session,
+            repair_persistent_sessions,
+            start_session_share,
+            stop_session_share,
+            list_session_shares,
+            export_recording_html,
             kill_persistent_session,
             start_session_recording,
             stop_session_recording,
@@ -193,25 +371,69 @@ fn main() {
             list_fs_entries,
             list_project_files,
             read_text_file,
+            get_file_limits,
+            set_file_limits,
+            get_agent_resource_limits,
+            set_agent_resource_limits,
+            list_monospace_fonts,
+            get_recent_files,
+            get_project_ignore,
+            set_project_ignore,
+            get_project_settings,
+            set_project_settings,
+            get_project_overview,
+            list_favorite_paths,
+            add_favorite_path,
+            rename_favorite_path,
+            remove_favorite_path,
+            file_nav_visit,
+            file_nav_back,
+            file_nav_forward,
+            get_file_nav_state,
+            resolve_breadcrumbs,
             write_text_file,
             rename_fs_entry,
             delete_fs_entry,
             copy_fs_entry,
+            start_fs_operation,
+            cancel_fs_operation,
+            get_session_touched_files,
+            scan_todos,
+            create_tree_snapshot,
+            diff_tree_snapshots,
+            list_tree_snapshots,
             ssh_default_root,
             ssh_list_fs_entries,
             ssh_read_text_file,
             ssh_write_text_file,
             ssh_rename_fs_entry,
             ssh_delete_fs_entry,
+            ssh_batch_operations,
+            ssh_get_disk_usage,
+            ssh_list_processes,
+            ssh_kill_process,
+            ssh_setup_remote_agent_host,
             ssh_download_file,
             ssh_upload_file,
             ssh_download_to_temp,
             load_recording,
+            load_recording_timeline,
             list_recordings,
+            get_recording_meta,
             delete_recording,
+            list_deleted_recordings,
+            restore_recording,
             prepare_secure_storage,
             reset_secure_storage,
             list_ssh_hosts,
+            get_ssh_host_overrides,
+            set_ssh_host_override,
+            get_ssh_retry_policy,
+            set_ssh_retry_policy,
+            list_pending_remote_ops,
+            cancel_pending_remote_op,
+            answer_ssh_askpass,
+            set_ssh_askpass_handler,
             apply_text_assets,
             save_session_asset,
             set_tray_agent_count,
@@ -225,7 +447,78 @@ fn main() {
             tail_claude_session_log,
             list_codex_session_logs,
             read_codex_session_log,
-            tail_codex_session_log
+            tail_codex_session_log,
+            cleanup_agent_logs,
+            read_agent_log_range,
+            count_agent_log_lines,
+            get_active_agent_sessions,
+            get_agent_file_changes,
+            export_agent_conversation,
+            get_focus_mode,
+            refresh_tray_agents,
+            set_focus_mode,
+            get_status_summary,
+            get_session_env,
+            get_session_env_summary,
+            inject_env_into_session,
+            get_session_transcript,
+            get_session_scrollback,
+            get_spooled_history,
+            list_session_layouts,
+            get_zellij_info,
+            get_zellij_serialization,
+            set_zellij_serialization,
+            zellij_new_tab,
+            zellij_run,
+            zellij_write_chars,
+            search_session_output,
+            copy_last_command_output,
+            run_project_preflight,
+            run_doctor,
+            get_onboarding_status,
+            complete_onboarding_step,
+            dismiss_onboarding,
+            reset_onboarding,
+            import_claude_projects,
+            get_command_policy,
+            set_command_policy,
+            read_audit_log,
+            get_audit_retention,
+            set_audit_retention,
+            detect_devcontainer,
+            devcontainer_up,
+            create_devcontainer_session,
+            get_session_metrics,
+            set_session_metrics_enabled,
+            suggest_session_name,
+            sanitize_paste,
+            get_paste_guard,
+            get_bell_settings,
+            set_bell_settings,
+            get_locale_settings,
+            set_locale_settings,
+            get_detected_locale,
+            get_output_batching,
+            set_output_batching,
+            get_output_stats,
+            report_output_latency,
+            benchmark_pty_throughput,
+            get_telemetry_status,
+            set_telemetry_enabled,
+            record_feature_usage,
+            get_keymap,
+            update_keybinding,
+            reset_keybindings,
+            list_terminal_themes,
+            get_terminal_theme,
+            import_terminal_theme,
+            delete_terminal_theme,
+            set_paste_guard,
+            confirm_session_exec,
+            get_exec_guard,
+            set_exec_guard,
+            get_input_prompt_patterns,
+            set_input_prompt_patterns
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
@@ -0,0 +1,319 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::{Emitter, WebviewWindow};
+
+use crate::ssh_fs::normalize_posix_path;
+
+const EVENT_FILE_NAV_CHANGED: &str = "file-nav-changed";
+const MAX_HISTORY: usize = 100;
+/// Entries listed each way in `FileNavStateV1`, nearest first.
+const MAX_LISTED: usize = 10;
+
+/// Where a file panel root's browsing stands. Every panel showing the same root
+/// shares it, and it lives in the app process, so a webview reload keeps it.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileNavStateV1 {
+    /// ssh target, `None` for the local filesystem.
+    pub target: Option<String>,
+    pub root: String,
+    /// The directory being shown; `None` before the first visit.
+    pub current: Option<String>,
+    pub can_go_back: bool,
+    pub can_go_forward: bool,
+    /// Where back goes, nearest first.
+    pub back: Vec<String>,
+    pub forward: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Breadcrumb {
+    pub name: String,
+    pub path: String,
+}
+
+#[derive(Default)]
+struct NavHistory {
+    entries: Vec<String>,
+    /// Index of the current entry; meaningless while `entries` is empty.
+    index: usize,
+}
+
+impl NavHistory {
+    fn current(&self) -> Option<&String> {
+        self.entries.get(self.index)
+    }
+
+    /// Go to `path`, dropping the forward entries like a browser does.
+    fn visit(&mut self, path: String) {
+        if self.current() == Some(&path) {
+            return;
+        }
+        if !self.entries.is_empty() {
+            self.entries.truncate(self.index + 1);
+        }
+        self.entries.push(path);
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.remove(0);
+        }
+        self.index = self.entries.len() - 1;
+    }
+
+    fn back(&mut self) -> bool {
+        if self.index == 0 || self.entries.is_empty() {
+            return false;
+        }
+        self.index -= 1;
+        true
+    }
+
+    fn forward(&mut self) -> bool {
+        if self.index + 1 >= self.entries.len() {
+            return false;
+        }
+        self.index += 1;
+        true
+    }
+
+    fn state(&self, target: Option<&str>, root: &str) -> FileNavStateV1 {
+        let back: Vec<String> = if self.entries.is_empty() {
+            Vec::new()
+        } else {
+            self.entries[..self.index].iter().rev().take(MAX_LISTED).cloned().collect()
+        };
+        let forward: Vec<String> = self
+            .entries
+            .iter()
+            .skip(self.index + 1)
+            .take(MAX_LISTED)
+            .cloned()
+            .collect();
+        FileNavStateV1 {
+            target: target.map(str::to_string),
+            root: root.to_string(),
+            current: self.current().cloned(),
+            can_go_back: !back.is_empty(),
+            can_go_forward: !forward.is_empty(),
+            back,
+            forward,
+        }
+    }
+}
+
+/// (ssh target, root).
+type NavKey = (Option<String>, String);
+
+fn histories() -> &'static Mutex<HashMap<NavKey, NavHistory>> {
+    static HISTORIES: OnceLock<Mutex<HashMap<NavKey, NavHistory>>> = OnceLock::new();
+    HISTORIES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn normalize_target(target: Option<String>) -> Option<String> {
+    target.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Lexically resolve `.` and `..` in an absolute local path; history entries needn't
+/// exist any more by the time they're gone back to.
+fn normalize_local_path(raw: &str) -> Result<PathBuf, String> {
+    let path = Path::new(raw.trim());
+    if !path.is_absolute() {
+        return Err("path must be absolute".to_string());
+    }
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    Ok(out)
+}
+
+/// `root` and `path` normalized, with `path` checked to be inside `root`.
+fn resolve(target: Option<&str>, root: &str, path: &str) -> Result<(String, String), String> {
+    if target.is_some() {
+        let root = normalize_posix_path(root)?;
+        let path = normalize_posix_path(path)?;
+        if root != "/" && path != root && !path.starts_with(&format!("{root}/")) {
+            return Err("path is outside root".to_string());
+        }
+        return Ok((root, path));
+    }
+    let root = normalize_local_path(root)?;
+    let path = normalize_local_path(path)?;
+    if !path.starts_with(&root) {
+        return Err("path is outside root".to_string());
+    }
+    Ok((root.to_string_lossy().to_string(), path.to_string_lossy().to_string()))
+}
+
+fn normalize_root(target: Option<&str>, root: &str) -> Result<String, String> {
+    resolve(target, root, root).map(|(root, _)| root)
+}
+
+/// One crumb for the root, then one per directory down to `path`.
+fn breadcrumbs(target: Option<&str>, root: &str, path: &str) -> Result<Vec<Breadcrumb>, String> {
+    let (root, path) = resolve(target, root, path)?;
+    let root_name = |root: &str| {
+        Path::new(root)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| root.to_string())
+    };
+    let mut crumbs = vec![Breadcrumb {
+        name: root_name(&root),
+        path: root.clone(),
+    }];
+    if target.is_some() {
+        let rest = path.strip_prefix(&root).unwrap_or_default();
+        let mut current = root.clone();
+        for part in rest.split('/').filter(|p| !p.is_empty()) {
+            current = crate::ssh_fs::join_posix_path(&current, part);
+            crumbs.push(Breadcrumb {
+                name: part.to_string(),
+                path: current.clone(),
+            });
+        }
+    } else {
+        let rest = Path::new(&path).strip_prefix(&root).map_err(|_| "path is outside root")?;
+        let mut current = PathBuf::from(&root);
+        for part in rest.components() {
+            current.push(part);
+            crumbs.push(Breadcrumb {
+                name: part.as_os_str().to_string_lossy().to_string(),
+                path: current.to_string_lossy().to_string(),
+            });
+        }
+    }
+    Ok(crumbs)
+}
+
+/// Apply `change` to the root's history and tell every panel about the result.
+fn update(
+    window: &WebviewWindow,
+    target: Option<String>,
+    root: &str,
+    change: impl FnOnce(&mut NavHistory),
+) -> Result<FileNavStateV1, String> {
+    let target = normalize_target(target);
+    let root = normalize_root(target.as_deref(), root)?;
+    let state = {
+        let mut histories = histories().lock().map_err(|_| "nav history poisoned")?;
+        let history = histories.entry((target.clone(), root.clone())).or_default();
+        let before = history.state(target.as_deref(), &root);
+        change(history);
+        let state = history.state(target.as_deref(), &root);
+        if state == before {
+            return Ok(state);
+        }
+        state
+    };
+    let _ = window.emit(EVENT_FILE_NAV_CHANGED, state.clone());
+    Ok(state)
+}
+
+/// Record that a panel opened `path` under `root`. Emits `file-nav-changed`.
+#[tauri::command]
+pub fn file_nav_visit(
+    window: WebviewWindow,
+    target: Option<String>,
+    root: String,
+    path: String,
+) -> Result<FileNavStateV1, String> {
+    let checked = normalize_target(target.clone());
+    let (_, path) = resolve(checked.as_deref(), &root, &path)?;
+    update(&window, target, &root, |history| history.visit(path))
+}
+
+/// Step back; `current` in the result is where to go. A no-op at the oldest entry.
+#[tauri::command]
+pub fn file_nav_back(
+    window: WebviewWindow,
+    target: Option<String>,
+    root: String,
+) -> Result<FileNavStateV1, String> {
+    update(&window, target, &root, |history| {
+        history.back();
+    })
+}
+
+#[tauri::command]
+pub fn file_nav_forward(
+    window: WebviewWindow,
+    target: Option<String>,
+    root: String,
+) -> Result<FileNavStateV1, String> {
+    update(&window, target, &root, |history| {
+        history.forward();
+    })
+}
+
+/// The shared state for `root`, for a panel that was just opened or reloaded.
+#[tauri::command]
+pub fn get_file_nav_state(target: Option<String>, root: String) -> Result<FileNavStateV1, String> {
+    let target = normalize_target(target);
+    let root = normalize_root(target.as_deref(), &root)?;
+    let histories = histories().lock().map_err(|_| "nav history poisoned")?;
+    Ok(histories
+        .get(&(target.clone(), root.clone()))
+        .map(|history| history.state(target.as_deref(), &root))
+        .unwrap_or_else(|| NavHistory::default().state(target.as_deref(), &root)))
+}
+
+/// Path segments from `root` down to `path`, each with the directory it opens.
+#[tauri::command]
+pub fn resolve_breadcrumbs(
+    target: Option<String>,
+    root: String,
+    path: String,
+) -> Result<Vec<Breadcrumb>, String> {
+    breadcrumbs(normalize_target(target).as_deref(), &root, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{breadcrumbs, NavHistory, MAX_HISTORY};
+
+    #[test]
+    fn back_forward_and_branching() {
+        let mut history = NavHistory::default();
+        assert!(!history.back());
+        for path in ["/r", "/r/a", "/r/a/b"] {
+            history.visit(path.to_string());
+        }
+        history.visit("/r/a/b".to_string());
+        assert!(history.back());
+        assert!(history.back());
+        assert!(!history.back());
+        assert!(history.forward());
+        let state = history.state(None, "/r");
+        assert_eq!(state.current.as_deref(), Some("/r/a"));
+        assert_eq!((state.back, state.forward), (vec!["/r".to_string()], vec!["/r/a/b".to_string()]));
+
+        // Visiting from the middle drops what was ahead.
+        history.visit("/r/c".to_string());
+        assert!(!history.state(None, "/r").can_go_forward);
+
+        for i in 0..MAX_HISTORY + 5 {
+            history.visit(format!("/r/{i}"));
+        }
+        assert_eq!(history.entries.len(), MAX_HISTORY);
+        assert_eq!(history.current().map(String::as_str), Some("/r/104"));
+    }
+
+    #[test]
+    fn ssh_breadcrumbs_stay_under_root() {
+        let crumbs = breadcrumbs(Some("devbox"), "/home/me/proj/", "/home/me/proj/src/./lib/../bin").unwrap();
+        let paths: Vec<&str> = crumbs.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["/home/me/proj", "/home/me/proj/src", "/home/me/proj/src/bin"]);
+        assert_eq!(crumbs[0].name, "proj");
+        assert!(breadcrumbs(Some("devbox"), "/home/me/proj", "/home/me/other").is_err());
+        assert_eq!(breadcrumbs(Some("devbox"), "/", "/etc").unwrap()[0].name, "/");
+    }
+}
@@ -0,0 +1,165 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{Manager, WebviewWindow};
+
+use crate::util::now_epoch_ms;
+
+pub(crate) const EVENT_SESSION_NEEDS_INPUT: &str = "session-needs-input";
+const CONFIG_FILE_NAME: &str = "input-prompts-v1.json";
+const TAIL_CHARS: usize = 1024;
+
+/// Prompts agents and common CLIs block on.
+const DEFAULT_PATTERNS: &[&str] = &[
+    r"(?i)\[y/n\]|\(y/n\)|\[yes/no\]|\(yes/no\)",
+    r"(?i)press enter to continue|press enter to",
+    r"(?i)do you want to (proceed|continue|make this edit|create|run|allow)",
+    r"(?i)allow (this )?command\?|approve\?",
+    r"❯\s*1\.\s*Yes",
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InputPromptConfigV1 {
+    pub patterns: Vec<String>,
+}
+
+impl Default for InputPromptConfigV1 {
+    fn default() -> Self {
+        Self {
+            patterns: DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionNeedsInput {
+    pub id: String,
+    /// The output text (escape codes removed) that matched.
+    pub prompt: String,
+    pub pattern: String,
+    pub detected_at: u64,
+}
+
+type CompiledPatterns = Arc<Vec<(String, Regex)>>;
+
+fn patterns_cache() -> &'static Mutex<Option<CompiledPatterns>> {
+    static CACHE: OnceLock<Mutex<Option<CompiledPatterns>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn config_file_path(window: &WebviewWindow) -> Result<PathBuf, String> {
+    crate::util::app_data_file(window.app_handle(), CONFIG_FILE_NAME)
+}
+
+fn compile(config: &InputPromptConfigV1) -> Result<CompiledPatterns, String> {
+    let compiled = config
+        .patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            Regex::new(p)
+                .map(|re| (p.to_string(), re))
+                .map_err(|e| format!("invalid pattern {p:?}: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Arc::new(compiled))
+}
+
+fn load_config(window: &WebviewWindow) -> Result<InputPromptConfigV1, String> {
+    let path = config_file_path(window)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(InputPromptConfigV1::default()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn compiled_patterns(window: &WebviewWindow) -> CompiledPatterns {
+    let Ok(mut cache) = patterns_cache().lock() else {
+        return Arc::new(Vec::new());
+    };
+    if let Some(patterns) = cache.as_ref() {
+        return patterns.clone();
+    }
+    let patterns = load_config(window)
+        .and_then(|config| compile(&config))
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load input prompt patterns; using defaults: {e}");
+            compile(&InputPromptConfigV1::default()).unwrap_or_default()
+        });
+    *cache = Some(patterns.clone());
+    patterns
+}
+
+/// Watches one session's output for prompts that block on the user. Fires
+/// once per prompt: `awaiting` stays set until the session receives input.
+pub(crate) struct NeedsInputDetector {
+    id: String,
+    tail: String,
+    awaiting: Arc<AtomicBool>,
+}
+
+impl NeedsInputDetector {
+    pub(crate) fn new(id: String, awaiting: Arc<AtomicBool>) -> Self {
+        Self {
+            id,
+            tail: String::new(),
+            awaiting,
+        }
+    }
+
+    /// Feed a chunk of output; returns the prompt once one is detected.
+    pub(crate) fn feed(&mut self, window: &WebviewWindow, data: &str) -> Option<SessionNeedsInput> {
+        if self.awaiting.load(Ordering::Relaxed) {
+            return None;
+        }
+        self.tail.push_str(&crate::throttle::strip_ansi(data));
+        let len = self.tail.chars().count();
+        if len > TAIL_CHARS {
+            self.tail = self.tail.chars().skip(len - TAIL_CHARS).collect();
+        }
+
+        let patterns = compiled_patterns(window);
+        let (pattern, m) = patterns
+            .iter()
+            .find_map(|(p, re)| re.find(&self.tail).map(|m| (p.clone(), m)))?;
+
+        // Report the whole line(s) around the match, not just the matched words.
+        let start = self.tail[..m.start()].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let end = self.tail[m.end()..]
+            .find('\n')
+            .map(|i| m.end() + i)
+            .unwrap_or(self.tail.len());
+        let prompt = self.tail[start..end].trim().to_string();
+
+        self.awaiting.store(true, Ordering::Relaxed);
+        self.tail.clear();
+        Some(SessionNeedsInput {
+            id: self.id.clone(),
+            prompt,
+            pattern,
+            detected_at: now_epoch_ms(),
+        })
+    }
+}
+
+#[tauri::command]
+pub fn get_input_prompt_patterns(window: WebviewWindow) -> Result<InputPromptConfigV1, String> {
+    load_config(&window)
+}
+
+/// Replace the prompt patterns. Applies to running sessions immediately.
+#[tauri::command]
+pub fn set_input_prompt_patterns(window: WebviewWindow, config: InputPromptConfigV1) -> Result<(), String> {
+    let compiled = compile(&config)?;
+    crate::util::write_json_atomic(&config_file_path(&window)?, &config)?;
+
+    *patterns_cache().lock().map_err(|_| "pattern cache poisoned")? = Some(compiled);
+    Ok(())
+}
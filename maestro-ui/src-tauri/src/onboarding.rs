@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{Manager, WebviewWindow};
+
+use crate::util::now_epoch_ms;
+
+const ONBOARDING_FILE_NAME: &str = "onboarding-v1.json";
+
+pub(crate) const STEP_AGENT_CLI_DETECTED: &str = "agentCliDetected";
+pub(crate) const STEP_SECURE_STORAGE_PREPARED: &str = "secureStoragePrepared";
+pub(crate) const STEP_FIRST_PROJECT_CREATED: &str = "firstProjectCreated";
+pub(crate) const STEP_SHELL_INTEGRATION_VERIFIED: &str = "shellIntegrationVerified";
+
+/// Welcome flow steps, in the order they are presented.
+const STEPS: &[&str] = &[
+    STEP_AGENT_CLI_DETECTED,
+    STEP_SECURE_STORAGE_PREPARED,
+    STEP_FIRST_PROJECT_CREATED,
+    STEP_SHELL_INTEGRATION_VERIFIED,
+];
+
+const AGENT_CLIS: &[&str] = &["claude", "codex", "gemini"];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStepV1 {
+    pub completed_at: u64,
+    #[serde(default)]
+    pub skipped: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStateV1 {
+    #[serde(default)]
+    pub steps: BTreeMap<String, OnboardingStepV1>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dismissed_at: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStepStatus {
+    pub id: String,
+    pub completed: bool,
+    pub skipped: bool,
+    pub completed_at: Option<u64>,
+    /// What satisfied the step, e.g. the agent CLI that was found.
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStatus {
+    pub steps: Vec<OnboardingStepStatus>,
+    pub next_step: Option<String>,
+    pub complete: bool,
+    pub dismissed: bool,
+}
+
+fn onboarding_cache() -> &'static Mutex<Option<OnboardingStateV1>> {
+    static CACHE: OnceLock<Mutex<Option<OnboardingStateV1>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn onboarding_file_path(window: &WebviewWindow) -> Result<PathBuf, String> {
+    crate::util::app_data_file(window.app_handle(), ONBOARDING_FILE_NAME)
+}
+
+fn load_state(window: &WebviewWindow, cache: &mut Option<OnboardingStateV1>) -> Result<OnboardingStateV1, String> {
+    if let Some(state) = cache.as_ref() {
+        return Ok(state.clone());
+    }
+    let path = onboarding_file_path(window)?;
+    let state = match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}"))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => OnboardingStateV1::default(),
+        Err(e) => return Err(format!("read failed: {e}")),
+    };
+    *cache = Some(state.clone());
+    Ok(state)
+}
+
+fn save_state(
+    window: &WebviewWindow,
+    cache: &mut Option<OnboardingStateV1>,
+    state: OnboardingStateV1,
+) -> Result<(), String> {
+    let path = onboarding_file_path(window)?;
+    crate::util::write_json_atomic(&path, &state)?;
+
+    *cache = Some(state);
+    Ok(())
+}
+
+fn validate_step(step: &str) -> Result<&'static str, String> {
+    STEPS
+        .iter()
+        .copied()
+        .find(|s| *s == step.trim())
+        .ok_or_else(|| format!("unknown onboarding step: {step}"))
+}
+
+/// Steps that can be confirmed from the machine and saved state, with what satisfied them.
+fn detect_steps(window: &WebviewWindow) -> Vec<(&'static str, String)> {
+    let mut found: Vec<(&'static str, String)> = Vec::new();
+    if let Some(cli) = AGENT_CLIS
+        .iter()
+        .find(|cli| crate::ssh_fs::find_program_in_path(cli).is_some())
+    {
+        found.push((STEP_AGENT_CLI_DETECTED, cli.to_string()));
+    }
+    if let Some((projects, storage_mode)) = crate::persist::read_setup_facts(window) {
+        if projects > 0 {
+            found.push((STEP_FIRST_PROJECT_CREATED, format!("{projects} project(s)")));
+        }
+        if let Some(mode) = storage_mode {
+            let mode = serde_json::to_value(mode)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            found.push((STEP_SECURE_STORAGE_PREPARED, mode));
+        }
+    }
+    found
+}
+
+fn status_of(state: &OnboardingStateV1) -> OnboardingStatus {
+    let steps: Vec<OnboardingStepStatus> = STEPS
+        .iter()
+        .map(|id| {
+            let saved = state.steps.get(*id);
+            OnboardingStepStatus {
+                id: id.to_string(),
+                completed: saved.is_some(),
+                skipped: saved.map(|s| s.skipped).unwrap_or(false),
+                completed_at: saved.map(|s| s.completed_at),
+                detail: saved.and_then(|s| s.detail.clone()),
+            }
+        })
+        .collect();
+    let next_step = steps.iter().find(|s| !s.completed).map(|s| s.id.clone());
+    OnboardingStatus {
+        complete: next_step.is_none(),
+        next_step,
+        steps,
+        dismissed: state.dismissed_at.is_some(),
+    }
+}
+
+/// Record a step as done from elsewhere in the backend. No-op if already recorded.
+pub(crate) fn mark_step_completed(window: &WebviewWindow, step: &str, detail: Option<String>) {
+    let Ok(mut cache) = onboarding_cache().lock() else {
+        return;
+    };
+    let Ok(mut state) = load_state(window, &mut cache) else {
+        return;
+    };
+    if state.steps.contains_key(step) {
+        return;
+    }
+    state.steps.insert(
+        step.to_string(),
+        OnboardingStepV1 {
+            completed_at: now_epoch_ms(),
+            skipped: false,
+            detail,
+        },
+    );
+    if let Err(e) = save_state(window, &mut cache, state) {
+        eprintln!("Failed to save onboarding state: {e}");
+    }
+}
+
+/// Current onboarding progress. Steps that can be detected (agent CLI on PATH, a saved
+/// project, a chosen secure storage mode) are recorded as completed before returning.
+#[tauri::command]
+pub fn get_onboarding_status(window: WebviewWindow) -> Result<OnboardingStatus, String> {
+    let mut cache = onboarding_cache().lock().map_err(|_| "onboarding cache poisoned")?;
+    let mut state = load_state(&window, &mut cache)?;
+
+    let mut changed = false;
+    for (step, detail) in detect_steps(&window) {
+        if state.steps.contains_key(step) {
+            continue;
+        }
+        state.steps.insert(
+            step.to_string(),
+            OnboardingStepV1 {
+                completed_at: now_epoch_ms(),
+                skipped: false,
+                detail: Some(detail),
+            },
+        );
+        changed = true;
+    }
+    let status = status_of(&state);
+    if changed {
+        save_state(&window, &mut cache, state)?;
+    }
+    Ok(status)
+}
+
+/// Mark a step completed, or skipped when the user chose to move past it.
+#[tauri::command]
+pub fn complete_onboarding_step(
+    window: WebviewWindow,
+    step: String,
+    skipped: Option<bool>,
+) -> Result<OnboardingStatus, String> {
+    let step = validate_step(&step)?;
+    let mut cache = onboarding_cache().lock().map_err(|_| "onboarding cache poisoned")?;
+    let mut state = load_state(&window, &mut cache)?;
+    let skipped = skipped.unwrap_or(false);
+    let replace = state.steps.get(step).map(|s| s.skipped && !skipped).unwrap_or(true);
+    if replace {
+        state.steps.insert(
+            step.to_string(),
+            OnboardingStepV1 {
+                completed_at: now_epoch_ms(),
+                skipped,
+                detail: None,
+            },
+        );
+    }
+    let status = status_of(&state);
+    save_state(&window, &mut cache, state)?;
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn dismiss_onboarding(window: WebviewWindow) -> Result<OnboardingStatus, String> {
+    let mut cache = onboarding_cache().lock().map_err(|_| "onboarding cache poisoned")?;
+    let mut state = load_state(&window, &mut cache)?;
+    state.dismissed_at.get_or_insert_with(now_epoch_ms);
+    let status = status_of(&state);
+    save_state(&window, &mut cache, state)?;
+    Ok(status)
+}
+
+/// Forget all progress so the welcome flow runs again.
+#[tauri::command]
+pub fn reset_onboarding(window: WebviewWindow) -> Result<OnboardingStatus, String> {
+    let mut cache = onboarding_cache().lock().map_err(|_| "onboarding cache poisoned")?;
+    let state = OnboardingStateV1::default();
+    let status = status_of(&state);
+    save_state(&window, &mut cache, state)?;
+    Ok(status)
+}
@@ -0,0 +1,175 @@
+use serde::Serialize;
+
+pub(crate) const EVENT_PTY_TITLE: &str = "pty-title";
+pub(crate) const EVENT_PTY_CWD: &str = "pty-cwd";
+pub(crate) const EVENT_PTY_COMMAND: &str = "pty-command";
+/// Unterminated sequences longer than this are dropped rather than carried forever.
+const MAX_OSC_BYTES: usize = 4096;
+const MAX_TITLE_CHARS: usize = 256;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyTitle {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyCwd {
+    pub id: String,
+    pub cwd: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyCommand {
+    pub id: String,
+    pub command: String,
+    pub at: u64,
+}
+
+/// One complete `ESC ] code ; payload` sequence.
+#[derive(Debug, PartialEq)]
+pub(crate) struct OscSequence {
+    pub code: String,
+    pub payload: String,
+}
+
+/// Pulls OSC sequences out of decoded pty output. Sequences end with BEL or ST
+/// (`ESC \`) and may be split across reads; the unfinished part is kept in `carry`.
+/// BELs outside sequences (the terminal bell) are counted along the way.
+#[derive(Default)]
+pub(crate) struct OscScanner {
+    carry: String,
+    bells: usize,
+}
+
+impl OscScanner {
+    pub(crate) fn feed(&mut self, data: &str) -> Vec<OscSequence> {
+        if self.carry.is_empty() && !data.contains('\x1b') {
+            self.bells += data.matches('\x07').count();
+            return Vec::new();
+        }
+        let text = std::mem::take(&mut self.carry) + data;
+        let mut out = Vec::new();
+        let mut rest = text.as_str();
+        while let Some(at) = rest.find("\x1b]") {
+            self.bells += rest[..at].matches('\x07').count();
+            let body = &rest[at + 2..];
+            let end = body
+                .char_indices()
+                .find(|(i, c)| *c == '\x07' || (*c == '\x1b' && body[i + 1..].starts_with('\\')))
+                .map(|(i, c)| (i, if c == '\x07' { 1 } else { 2 }));
+            let Some((end, terminator_len)) = end else {
+                if body.len() < MAX_OSC_BYTES {
+                    self.carry = rest[at..].to_string();
+                }
+                return out;
+            };
+            let (code, payload) = body[..end].split_once(';').unwrap_or((&body[..end], ""));
+            out.push(OscSequence {
+                code: code.to_string(),
+                payload: payload.to_string(),
+            });
+            rest = &body[end + terminator_len..];
+        }
+        self.bells += rest.matches('\x07').count();
+        // The chunk may end on the escape that starts the next sequence.
+        if rest.ends_with('\x1b') {
+            self.carry = "\x1b".to_string();
+        }
+        out
+    }
+
+    /// Terminal bells seen since the last call.
+    pub(crate) fn take_bells(&mut self) -> usize {
+        std::mem::take(&mut self.bells)
+    }
+}
+
+/// The window title set by an OSC 0 (icon and title) or OSC 2 (title) sequence,
+/// with control characters removed.
+pub(crate) fn title_from(seq: &OscSequence) -> Option<String> {
+    if seq.code != "0" && seq.code != "2" {
+        return None;
+    }
+    Some(
+        seq.payload
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(MAX_TITLE_CHARS)
+            .collect::<String>()
+            .trim()
+            .to_string(),
+    )
+}
+
+/// What the shell integration hooks (see pty.rs) report through OSC 1337.
+#[derive(Debug, PartialEq)]
+pub(crate) enum ShellMarker {
+    /// Printed before every prompt.
+    CurrentDir(String),
+    /// Printed by zsh's preexec hook with the command about to run; empty from
+    /// precmd once it has finished.
+    Command(String),
+}
+
+pub(crate) fn shell_marker(seq: &OscSequence) -> Option<ShellMarker> {
+    if seq.code != "1337" {
+        return None;
+    }
+    if let Some(dir) = seq.payload.strip_prefix("CurrentDir=") {
+        return Some(ShellMarker::CurrentDir(dir.to_string()));
+    }
+    seq.payload
+        .strip_prefix("Command=")
+        .map(|command| ShellMarker::Command(command.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shell_marker, title_from, OscScanner, OscSequence, ShellMarker};
+
+    #[test]
+    fn scans_titles_across_chunks() {
+        let mut scanner = OscScanner::default();
+        assert_eq!(scanner.feed("plain output\r\n"), []);
+        assert_eq!(scanner.feed("x\x1b]0;claude — buil"), []);
+        let seqs = scanner.feed("ding tests\x07more\x1b]2;vim\x1b\\\x1b]1337;CurrentDir=/a\x07\x1b");
+        let titles: Vec<_> = seqs.iter().filter_map(title_from).collect();
+        assert_eq!(titles, ["claude — building tests", "vim"]);
+        assert_eq!(seqs[2].code, "1337");
+        assert_eq!(scanner.take_bells(), 0);
+
+        assert_eq!(
+            scanner.feed("]2;next\x07"),
+            [OscSequence {
+                code: "2".to_string(),
+                payload: "next".to_string()
+            }]
+        );
+        scanner.feed("ring\x07 \x1b]0;t\x07\x07");
+        assert_eq!(scanner.take_bells(), 2);
+    }
+
+    #[test]
+    fn reads_shell_markers_across_chunks() {
+        let mut scanner = OscScanner::default();
+        assert_eq!(scanner.feed("out\x1b]1337;Current"), []);
+        assert_eq!(scanner.feed("Dir=/home/a b"), []);
+        let markers: Vec<_> = scanner
+            .feed("\x07\x1b]1337;Command=\x07% ls\r\n\x1b]1337;Command=ls -la\x07\x1b]1337;SetMark\x07\x1b]0;t\x07")
+            .iter()
+            .filter_map(shell_marker)
+            .collect();
+        assert_eq!(
+            markers,
+            [
+                ShellMarker::CurrentDir("/home/a b".to_string()),
+                ShellMarker::Command(String::new()),
+                ShellMarker::Command("ls -la".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+pub(crate) const EVENT_PTY_OUTPUT: &str = "pty-output";
+const SETTINGS_FILE_NAME: &str = "output-batching-v1.json";
+const DEFAULT_FLUSH_INTERVAL_MS: u64 = 8;
+const MAX_FLUSH_INTERVAL_MS: u64 = 100;
+/// A batch this large is sent right away instead of waiting out the interval.
+const MAX_BATCH_BYTES: usize = 256 * 1024;
+
+fn default_flush_interval_ms() -> u64 {
+    DEFAULT_FLUSH_INTERVAL_MS
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputBatchingV1 {
+    /// `pty-output` chunks read within this many ms of the previous event are
+    /// merged into one event. 0 only merges reads that are already queued.
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+}
+
+impl Default for OutputBatchingV1 {
+    fn default() -> Self {
+        Self {
+            flush_interval_ms: DEFAULT_FLUSH_INTERVAL_MS,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PtyOutput {
+    id: String,
+    data: String,
+    /// Epoch ms of the emit, for the frontend to measure IPC latency against
+    /// (see `report_output_latency`).
+    sent_at: u64,
+}
+
+/// One pty read waiting to be sent.
+struct Chunk {
+    data: String,
+    read_at: Instant,
+}
+
+/// Reads merged into one `pty-output` event.
+struct Batch {
+    data: String,
+    chunks: u64,
+    first_read_at: Instant,
+}
+
+fn settings_cache() -> &'static Mutex<Option<OutputBatchingV1>> {
+    static CACHE: OnceLock<Mutex<Option<OutputBatchingV1>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn settings_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, SETTINGS_FILE_NAME)
+}
+
+fn read_settings(app: &AppHandle) -> Result<OutputBatchingV1, String> {
+    let path = settings_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(OutputBatchingV1::default()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+pub(crate) fn validate(settings: &OutputBatchingV1) -> Result<(), String> {
+    if settings.flush_interval_ms > MAX_FLUSH_INTERVAL_MS {
+        return Err(format!("flushIntervalMs must be at most {MAX_FLUSH_INTERVAL_MS}"));
+    }
+    Ok(())
+}
+
+/// Load the saved interval at startup; sessions read it when they're created.
+pub(crate) fn init(app: &AppHandle) {
+    let settings = read_settings(app)
+        .and_then(|s| validate(&s).map(|_| s))
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load output batching settings: {e}");
+            OutputBatchingV1::default()
+        });
+    if let Ok(mut cache) = settings_cache().lock() {
+        *cache = Some(settings);
+    }
+}
+
+/// The interval new sessions batch with.
+pub(crate) fn flush_interval_ms() -> u64 {
+    settings_cache()
+        .lock()
+        .ok()
+        .and_then(|c| *c)
+        .unwrap_or_default()
+        .flush_interval_ms
+}
+
+/// Merge chunks from `rx` into batches and hand them to `emit` until the channel
+/// closes. Output after a quiet spell goes out immediately, so typing latency is
+/// unchanged; only output arriving within `interval` of the last batch waits.
+fn run_batches(rx: Receiver<Chunk>, interval: Duration, mut emit: impl FnMut(Batch)) {
+    let mut last_emit: Option<Instant> = None;
+    while let Ok(first) = rx.recv() {
+        let mut batch = Batch {
+            data: first.data,
+            chunks: 1,
+            first_read_at: first.read_at,
+        };
+        let deadline = last_emit.map(|at| at + interval).unwrap_or_else(Instant::now);
+        let mut open = true;
+        while batch.data.len() < MAX_BATCH_BYTES {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            let next = if wait.is_zero() {
+                rx.try_recv().map_err(|e| match e {
+                    mpsc::TryRecvError::Empty => RecvTimeoutError::Timeout,
+                    mpsc::TryRecvError::Disconnected => RecvTimeoutError::Disconnected,
+                })
+            } else {
+                rx.recv_timeout(wait)
+            };
+            match next {
+                Ok(more) => {
+                    batch.data.push_str(&more.data);
+                    batch.chunks += 1;
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    open = false;
+                    break;
+                }
+            }
+        }
+        emit(batch);
+        last_emit = Some(Instant::now());
+        if !open {
+            break;
+        }
+    }
+}
+
+/// Sends a session's `pty-output` events from its own thread, batching reads that
+/// arrive close together.
+pub(crate) struct OutputBatcher {
+    tx: Option<Sender<Chunk>>,
+    thread: Option<JoinHandle<()>>,
+    stats: crate::output_stats::SharedOutputStats,
+}
+
+impl OutputBatcher {
+    pub(crate) fn spawn(window: WebviewWindow, id: String) -> Self {
+        Self::spawn_on(window, id, EVENT_PTY_OUTPUT, flush_interval_ms())
+    }
+
+    /// A batcher sending `event` with an explicit interval (see benchmark.rs).
+    pub(crate) fn spawn_on(window: WebviewWindow, id: String, event: &'static str, interval: u64) -> Self {
+        let (tx, rx) = mpsc::channel::<Chunk>();
+        let stats = crate::output_stats::register(&id);
+        let stats_for_thread = stats.clone();
+        let thread = std::thread::spawn(move || {
+            run_batches(rx, Duration::from_millis(interval), |batch| {
+                let bytes = batch.data.len();
+                let sent_at = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                let sent = window
+                    .emit(
+                        event,
+                        PtyOutput {
+                            id: id.clone(),
+                            data: batch.data,
+                            sent_at,
+                        },
+                    )
+                    .is_ok();
+                if let Ok(mut stats) = stats_for_thread.lock() {
+                    stats.record_event(bytes, batch.chunks, batch.first_read_at, sent);
+                }
+            });
+            crate::output_stats::session_closed(&id, &stats_for_thread);
+        });
+        Self {
+            tx: Some(tx),
+            thread: Some(thread),
+            stats,
+        }
+    }
+
+    pub(crate) fn stats(&self) -> crate::output_stats::SharedOutputStats {
+        self.stats.clone()
+    }
+
+    pub(crate) fn push(&self, data: String) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(Chunk {
+                data,
+                read_at: Instant::now(),
+            });
+        }
+    }
+
+    /// Send whatever is still queued and wait for it, so it lands before `pty-exit`.
+    pub(crate) fn finish(mut self) {
+        drop(self.tx.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_output_batching(window: WebviewWindow) -> Result<OutputBatchingV1, String> {
+    read_settings(window.app_handle())
+}
+
+/// Applies to sessions created afterwards.
+#[tauri::command]
+pub fn set_output_batching(window: WebviewWindow, settings: OutputBatchingV1) -> Result<(), String> {
+    validate(&settings)?;
+    let path = settings_file_path(window.app_handle())?;
+    crate::util::write_json_atomic(&path, &settings)?;
+
+    *settings_cache().lock().map_err(|_| "output batching cache poisoned")? = Some(settings);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_batches, Chunk, MAX_BATCH_BYTES};
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn merges_queued_chunks_and_caps_batch_size() {
+        let (tx, rx) = mpsc::channel();
+        let chunk = |data: String| Chunk {
+            data,
+            read_at: Instant::now(),
+        };
+        for data in ["a", "b", "c"] {
+            tx.send(chunk(data.to_string())).unwrap();
+        }
+        tx.send(chunk("x".repeat(MAX_BATCH_BYTES))).unwrap();
+        tx.send(chunk("tail".to_string())).unwrap();
+        drop(tx);
+
+        let mut batches = Vec::new();
+        run_batches(rx, Duration::from_millis(5), |b| batches.push((b.data, b.chunks)));
+        assert_eq!(batches.len(), 2);
+        assert!(batches[0].0.starts_with("abcxxx"));
+        assert_eq!(batches[0].1, 4);
+        assert_eq!(batches[0].0.len(), 3 + MAX_BATCH_BYTES);
+        assert_eq!(batches[1], ("tail".to_string(), 1));
+    }
+}
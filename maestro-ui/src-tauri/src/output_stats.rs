@@ -0,0 +1,217 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Rates are averaged over this much recent time.
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+/// Latency samples kept per session for percentiles; older ones are dropped.
+const MAX_LATENCY_SAMPLES: usize = 2048;
+/// Frontend reports beyond this are ignored as clock skew or a stalled tab.
+const MAX_IPC_LATENCY_MS: f64 = 60_000.0;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyPercentiles {
+    pub samples: usize,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Counters for one session's `pty-output` pipeline (see output_batch.rs).
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputStatsV1 {
+    pub id: String,
+    /// `pty-output` events per second over the last 10 seconds.
+    pub events_per_sec: f64,
+    pub bytes_per_sec: f64,
+    pub events: u64,
+    pub bytes: u64,
+    /// Reads from the pty; each becomes part of exactly one event.
+    pub chunks: u64,
+    /// Reads merged into an event with an earlier read.
+    pub coalesced: u64,
+    /// Events the webview never got because the emit failed.
+    pub dropped: u64,
+    /// From the pty read to the emit returning.
+    pub pipeline_latency: Option<LatencyPercentiles>,
+    /// From the emit to the frontend handling the event, as reported by the frontend.
+    pub ipc_latency: Option<LatencyPercentiles>,
+}
+
+#[derive(Default)]
+pub(crate) struct SessionOutputStats {
+    events: u64,
+    bytes: u64,
+    chunks: u64,
+    coalesced: u64,
+    dropped: u64,
+    /// (emitted at, bytes) for events within `RATE_WINDOW`.
+    recent: VecDeque<(Instant, usize)>,
+    pipeline_ms: VecDeque<f64>,
+    ipc_ms: VecDeque<f64>,
+}
+
+pub(crate) type SharedOutputStats = Arc<Mutex<SessionOutputStats>>;
+
+fn push_sample(samples: &mut VecDeque<f64>, ms: f64) {
+    if samples.len() == MAX_LATENCY_SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(ms);
+}
+
+fn percentiles(samples: &VecDeque<f64>) -> Option<LatencyPercentiles> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted: Vec<f64> = samples.iter().copied().collect();
+    sorted.sort_by(f64::total_cmp);
+    // Nearest-rank percentile.
+    let rank = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1];
+    Some(LatencyPercentiles {
+        samples: sorted.len(),
+        p50_ms: rank(0.5),
+        p90_ms: rank(0.9),
+        p99_ms: rank(0.99),
+        max_ms: sorted[sorted.len() - 1],
+    })
+}
+
+impl SessionOutputStats {
+    fn prune(&mut self, now: Instant) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > RATE_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+    }
+
+    /// One `pty-output` event carrying `chunks` reads, the first read at `first_read_at`.
+    pub(crate) fn record_event(&mut self, bytes: usize, chunks: u64, first_read_at: Instant, sent: bool) {
+        let now = Instant::now();
+        self.chunks += chunks;
+        self.coalesced += chunks.saturating_sub(1);
+        if !sent {
+            self.dropped += 1;
+            return;
+        }
+        self.events += 1;
+        self.bytes += bytes as u64;
+        self.recent.push_back((now, bytes));
+        self.prune(now);
+        push_sample(&mut self.pipeline_ms, now.duration_since(first_read_at).as_secs_f64() * 1000.0);
+    }
+
+    pub(crate) fn snapshot(&mut self, id: &str) -> OutputStatsV1 {
+        self.prune(Instant::now());
+        let window = RATE_WINDOW.as_secs_f64();
+        let recent_bytes: usize = self.recent.iter().map(|(_, bytes)| bytes).sum();
+        OutputStatsV1 {
+            id: id.to_string(),
+            events_per_sec: self.recent.len() as f64 / window,
+            bytes_per_sec: recent_bytes as f64 / window,
+            events: self.events,
+            bytes: self.bytes,
+            chunks: self.chunks,
+            coalesced: self.coalesced,
+            dropped: self.dropped,
+            pipeline_latency: percentiles(&self.pipeline_ms),
+            ipc_latency: percentiles(&self.ipc_ms),
+        }
+    }
+}
+
+fn sessions() -> &'static Mutex<HashMap<String, SharedOutputStats>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, SharedOutputStats>>> = OnceLock::new();
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Fresh counters for a session's output batcher.
+pub(crate) fn register(id: &str) -> SharedOutputStats {
+    let stats = SharedOutputStats::default();
+    if let Ok(mut sessions) = sessions().lock() {
+        sessions.insert(id.to_string(), stats.clone());
+    }
+    stats
+}
+
+pub(crate) fn session_closed(id: &str, stats: &SharedOutputStats) {
+    if let Ok(mut sessions) = sessions().lock() {
+        // A restarted session may already have registered new counters under the id.
+        if sessions.get(id).is_some_and(|s| Arc::ptr_eq(s, stats)) {
+            sessions.remove(id);
+        }
+    }
+}
+
+/// Output pipeline counters for live sessions (or just `id`), for measuring
+/// throughput and latency. `reset` clears them after reading, to time a run.
+#[tauri::command]
+pub fn get_output_stats(id: Option<String>, reset: Option<bool>) -> Result<Vec<OutputStatsV1>, String> {
+    let sessions = sessions().lock().map_err(|_| "output stats poisoned")?;
+    let mut out = Vec::new();
+    for (session_id, stats) in sessions.iter() {
+        if id.as_ref().is_some_and(|id| id != session_id) {
+            continue;
+        }
+        let mut stats = stats.lock().map_err(|_| "output stats poisoned")?;
+        out.push(stats.snapshot(session_id));
+        if reset.unwrap_or(false) {
+            *stats = SessionOutputStats::default();
+        }
+    }
+    out.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(out)
+}
+
+/// Frontend-measured delays between an event's `sentAt` and its handling.
+#[tauri::command]
+pub fn report_output_latency(id: String, samples_ms: Vec<f64>) -> Result<(), String> {
+    let stats = sessions()
+        .lock()
+        .map_err(|_| "output stats poisoned")?
+        .get(&id)
+        .cloned()
+        .ok_or("unknown session")?;
+    let mut stats = stats.lock().map_err(|_| "output stats poisoned")?;
+    for ms in samples_ms {
+        if ms.is_finite() && (0.0..=MAX_IPC_LATENCY_MS).contains(&ms) {
+            push_sample(&mut stats.ipc_ms, ms);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{percentiles, SessionOutputStats};
+    use std::collections::VecDeque;
+    use std::time::Instant;
+
+    #[test]
+    fn counts_coalesced_and_dropped_events() {
+        let mut stats = SessionOutputStats::default();
+        stats.record_event(100, 3, Instant::now(), true);
+        stats.record_event(50, 1, Instant::now(), false);
+        let snapshot = stats.snapshot("s1");
+        assert_eq!((snapshot.events, snapshot.bytes, snapshot.chunks), (1, 100, 4));
+        assert_eq!((snapshot.coalesced, snapshot.dropped), (2, 1));
+        assert_eq!(snapshot.events_per_sec, 0.1);
+        assert_eq!(snapshot.pipeline_latency.map(|l| l.samples), Some(1));
+        assert!(snapshot.ipc_latency.is_none());
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let samples: VecDeque<f64> = (1..=100).rev().map(f64::from).collect();
+        let p = percentiles(&samples).unwrap();
+        assert_eq!((p.p50_ms, p.p90_ms, p.p99_ms, p.max_ms), (50.0, 90.0, 99.0, 100.0));
+        assert_eq!(percentiles(&VecDeque::from([7.0])).unwrap().p99_ms, 7.0);
+    }
+}
@@ -0,0 +1,258 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{Manager, WebviewWindow};
+
+const SETTINGS_FILE_NAME: &str = "paste-guard-v1.json";
+const BRACKETED_PASTE_START: &str = "\x1b[200~";
+const BRACKETED_PASTE_END: &str = "\x1b[201~";
+/// Longest unfinished mode sequence carried over to the next chunk.
+const MAX_MODE_TAIL: usize = 32;
+
+fn default_true() -> bool {
+    true
+}
+
+/// How pasted text is cleaned before it reaches a session (`write_to_session` with
+/// `source: "paste"`).
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteGuardV1 {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Drop trailing newlines so a paste never runs a command on its own.
+    #[serde(default = "default_true")]
+    pub strip_trailing_newlines: bool,
+    /// Refuse pastes that still contain a newline (each line would run as a command).
+    #[serde(default)]
+    pub block_multiline: bool,
+}
+
+impl Default for PasteGuardV1 {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strip_trailing_newlines: true,
+            block_multiline: false,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SanitizedPaste {
+    pub data: String,
+    /// Control and invisible characters removed.
+    pub removed_chars: usize,
+    pub trailing_newlines_removed: bool,
+    /// The cleaned text still spans several lines.
+    pub multiline: bool,
+}
+
+/// What `paste_to_session` did with a paste.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPaste {
+    /// Sent as one bracketed paste because the application asked for them.
+    pub bracketed: bool,
+    pub removed_chars: usize,
+    pub trailing_newlines_removed: bool,
+    pub multiline: bool,
+}
+
+fn settings_cache() -> &'static Mutex<Option<PasteGuardV1>> {
+    static CACHE: OnceLock<Mutex<Option<PasteGuardV1>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn settings_file_path(window: &WebviewWindow) -> Result<PathBuf, String> {
+    crate::util::app_data_file(window.app_handle(), SETTINGS_FILE_NAME)
+}
+
+/// Characters that can act on the terminal or hide what is being pasted: C0/C1
+/// controls (including ESC, which could end bracketed paste early), DEL,
+/// bidi overrides and zero-width characters. Tabs and newlines are kept.
+fn is_unsafe_char(ch: char) -> bool {
+    match ch {
+        '\t' | '\n' | '\r' => false,
+        '\u{200b}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' | '\u{feff}' => true,
+        c => c.is_control(),
+    }
+}
+
+fn sanitize(data: &str, guard: &PasteGuardV1) -> SanitizedPaste {
+    let mut out = String::with_capacity(data.len());
+    let mut removed_chars = 0usize;
+    let mut chars = data.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if is_unsafe_char(ch) {
+            removed_chars += 1;
+            continue;
+        }
+        // Terminals send CR for Enter; fold CRLF/LF so line counting is consistent.
+        if ch == '\r' || ch == '\n' {
+            if ch == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            out.push('\r');
+            continue;
+        }
+        out.push(ch);
+    }
+
+    let mut trailing_newlines_removed = false;
+    if guard.strip_trailing_newlines {
+        let trimmed_len = out.trim_end_matches('\r').len();
+        trailing_newlines_removed = trimmed_len < out.len();
+        out.truncate(trimmed_len);
+    }
+    let multiline = out.contains('\r');
+    SanitizedPaste {
+        data: out,
+        removed_chars,
+        trailing_newlines_removed,
+        multiline,
+    }
+}
+
+fn load_guard(window: &WebviewWindow) -> Result<PasteGuardV1, String> {
+    let mut cache = settings_cache().lock().map_err(|_| "paste guard cache poisoned")?;
+    if let Some(guard) = cache.as_ref() {
+        return Ok(guard.clone());
+    }
+    let path = settings_file_path(window)?;
+    let guard = match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}"))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => PasteGuardV1::default(),
+        Err(e) => return Err(format!("read failed: {e}")),
+    };
+    *cache = Some(guard.clone());
+    Ok(guard)
+}
+
+/// Clean a paste for `write_to_session`. Returns the data unchanged when the guard is
+/// off, and an error when a multi-line paste is blocked.
+pub(crate) fn enforce(window: &WebviewWindow, data: &str) -> Result<String, String> {
+    let guard = load_guard(window)?;
+    if !guard.enabled {
+        return Ok(data.to_string());
+    }
+    let cleaned = sanitize(data, &guard);
+    if guard.block_multiline && cleaned.multiline {
+        return Err("multi-line paste blocked".to_string());
+    }
+    Ok(cleaned.data)
+}
+
+/// Clean a paste for `paste_to_session`. Unlike `enforce` this always strips unsafe
+/// characters: inside a bracketed paste an ESC could end it early. Multi-line pastes
+/// are only refused when they'd run line by line, i.e. when not bracketed.
+pub(crate) fn prepare(window: &WebviewWindow, text: &str, bracketed: bool) -> Result<(String, SessionPaste), String> {
+    let guard = load_guard(window)?;
+    let cleaned = sanitize(text, &guard);
+    if !bracketed && guard.block_multiline && cleaned.multiline {
+        return Err("multi-line paste blocked".to_string());
+    }
+    let outcome = SessionPaste {
+        bracketed,
+        removed_chars: cleaned.removed_chars,
+        trailing_newlines_removed: cleaned.trailing_newlines_removed,
+        multiline: cleaned.multiline,
+    };
+    Ok((cleaned.data, outcome))
+}
+
+pub(crate) fn bracket(text: &str) -> String {
+    format!("{BRACKETED_PASTE_START}{text}{BRACKETED_PASTE_END}")
+}
+
+fn mode_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\x1b\[\?([0-9;]*)([hl])").expect("valid mode regex"))
+}
+
+/// Follows whether the application in a session has turned bracketed paste on
+/// (`CSI ? 2004 h`) or off (`CSI ? 2004 l`); fed by the reader thread.
+#[derive(Default)]
+pub(crate) struct BracketedPasteTracker {
+    tail: String,
+}
+
+impl BracketedPasteTracker {
+    /// The mode as last set in `data`, if it was.
+    pub(crate) fn feed(&mut self, data: &str) -> Option<bool> {
+        if self.tail.is_empty() && !data.contains('\x1b') {
+            return None;
+        }
+        let text = std::mem::take(&mut self.tail) + data;
+        let mut mode = None;
+        let mut end = 0;
+        for caps in mode_regex().captures_iter(&text) {
+            end = caps.get(0).map(|m| m.end()).unwrap_or(end);
+            if caps[1].split(';').any(|p| p == "2004") {
+                mode = Some(&caps[2] == "h");
+            }
+        }
+        // The chunk may stop partway into a sequence.
+        if let Some(esc) = text[end..].rfind('\x1b') {
+            let rest = &text[end + esc..];
+            if rest.len() <= MAX_MODE_TAIL {
+                self.tail = rest.to_string();
+            }
+        }
+        mode
+    }
+}
+
+/// Preview what the paste guard would do with `data`, so the UI can warn before
+/// sending (e.g. "this paste contains hidden characters").
+#[tauri::command]
+pub fn sanitize_paste(window: WebviewWindow, data: String) -> Result<SanitizedPaste, String> {
+    let guard = load_guard(&window)?;
+    Ok(sanitize(&data, &guard))
+}
+
+#[tauri::command]
+pub fn get_paste_guard(window: WebviewWindow) -> Result<PasteGuardV1, String> {
+    load_guard(&window)
+}
+
+#[tauri::command]
+pub fn set_paste_guard(window: WebviewWindow, guard: PasteGuardV1) -> Result<(), String> {
+    let path = settings_file_path(&window)?;
+    crate::util::write_json_atomic(&path, &guard)?;
+
+    *settings_cache().lock().map_err(|_| "paste guard cache poisoned")? = Some(guard);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sanitize, BracketedPasteTracker, PasteGuardV1};
+
+    #[test]
+    fn sanitize_strips_hidden_chars_and_trailing_newline() {
+        let guard = PasteGuardV1::default();
+        let out = sanitize("echo hi\u{202e}\x1b[201~; rm -rf ~\r\n", &guard);
+        assert_eq!(out.data, "echo hi[201~; rm -rf ~");
+        assert_eq!(out.removed_chars, 2);
+        assert!(out.trailing_newlines_removed);
+        assert!(!out.multiline);
+
+        let out = sanitize("a\nb\r\nc", &guard);
+        assert_eq!(out.data, "a\rb\rc");
+        assert!(out.multiline);
+    }
+
+    #[test]
+    fn tracks_bracketed_paste_mode_across_chunks() {
+        let mut modes = BracketedPasteTracker::default();
+        assert_eq!(modes.feed("plain"), None);
+        assert_eq!(modes.feed("\x1b[?1049h\x1b[?20"), None);
+        assert_eq!(modes.feed("04h> "), Some(true));
+        assert_eq!(modes.feed("\x1b[1m bold \x1b[0m"), None);
+        assert_eq!(modes.feed("\x1b[?25;2004l\x1b[?2004h\x1b[?2004l"), Some(false));
+    }
+}
@@ -118,12 +118,7 @@ pub struct PersistedStateMetaV1 {
 }
 
 fn state_file_path(window: &WebviewWindow) -> Result<PathBuf, String> {
-    let dir = window
-        .app_handle()
-        .path()
-        .app_data_dir()
-        .map_err(|_| "unknown app data dir".to_string())?;
-    Ok(dir.join("state-v1.json"))
+    crate::util::app_data_file(window.app_handle(), "state-v1.json")
 }
 
 #[tauri::command]
@@ -155,7 +150,7 @@ pub fn load_persisted_state_meta(window: WebviewWindow) -> Result<Option<Persist
     }))
 }
 
-fn expand_home(input: &str) -> String {
+pub(crate) fn expand_home(input: &str) -> String {
     let trimmed = input.trim();
     if trimmed == "~" {
         return home_dir().unwrap_or_else(|| trimmed.to_string());
@@ -179,6 +174,150 @@ fn home_dir() -> Option<String> {
     }
 }
 
+/// The active project's `(id, title)` from the saved state, if any.
+pub(crate) fn read_active_project(window: &WebviewWindow) -> Option<(String, String)> {
+    let raw = fs::read_to_string(state_file_path(window).ok()?).ok()?;
+    let state: PersistedStateV1 = serde_json::from_str(&raw).ok()?;
+    if state.schema_version != 1 {
+        return None;
+    }
+    state
+        .projects
+        .into_iter()
+        .find(|p| p.id == state.active_project_id)
+        .map(|p| (p.id, p.title))
+}
+
+/// Title of the saved project `project_id`, if it exists.
+pub(crate) fn read_project_title(window: &WebviewWindow, project_id: &str) -> Option<String> {
+    let raw = fs::read_to_string(state_file_path(window).ok()?).ok()?;
+    let state: PersistedStateV1 = serde_json::from_str(&raw).ok()?;
+    state
+        .projects
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .map(|p| p.title)
+}
+
+/// `(id, title)` of every saved project, in saved order.
+pub(crate) fn read_project_titles(window: &WebviewWindow) -> Vec<(String, String)> {
+    let Some(raw) = state_file_path(window)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+    else {
+        return Vec::new();
+    };
+    let Ok(state) = serde_json::from_str::<PersistedStateV1>(&raw) else {
+        return Vec::new();
+    };
+    state.projects.into_iter().map(|p| (p.id, p.title)).collect()
+}
+
+/// Project count and chosen secure storage mode from the saved state (see onboarding.rs).
+pub(crate) fn read_setup_facts(window: &WebviewWindow) -> Option<(usize, Option<SecureStorageModeV1>)> {
+    let raw = fs::read_to_string(state_file_path(window).ok()?).ok()?;
+    let state: PersistedStateV1 = serde_json::from_str(&raw).ok()?;
+    if state.schema_version != 1 {
+        return None;
+    }
+    Some((state.projects.len(), state.secure_storage_mode))
+}
+
+/// `(id, expanded base path)` of every saved project that has a base path.
+pub(crate) fn read_project_paths(window: &WebviewWindow) -> Vec<(String, String)> {
+    let Some(raw) = state_file_path(window)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+    else {
+        return Vec::new();
+    };
+    let Ok(state) = serde_json::from_str::<PersistedStateV1>(&raw) else {
+        return Vec::new();
+    };
+    state
+        .projects
+        .into_iter()
+        .filter_map(|p| {
+            let base = expand_home(p.base_path.as_deref()?);
+            (!base.is_empty()).then_some((p.id, base))
+        })
+        .collect()
+}
+
+/// Expanded base path of `project_id`, if the project has one.
+pub(crate) fn read_project_base_path(window: &WebviewWindow, project_id: &str) -> Option<String> {
+    let raw = fs::read_to_string(state_file_path(window).ok()?).ok()?;
+    let state: PersistedStateV1 = serde_json::from_str(&raw).ok()?;
+    state
+        .projects
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .and_then(|p| p.base_path)
+        .map(|base| expand_home(&base))
+        .filter(|base| !base.is_empty())
+}
+
+/// Append projects to the saved state, skipping any whose base path is already a project.
+/// Returns the projects actually added. Environments are written back exactly as stored.
+pub(crate) fn append_projects(
+    window: &WebviewWindow,
+    projects: Vec<PersistedProjectV1>,
+) -> Result<Vec<PersistedProjectV1>, String> {
+    let path = state_file_path(window)?;
+    let mut state: PersistedStateV1 = match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}"))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => PersistedStateV1 {
+            schema_version: 1,
+            secure_storage_mode: None,
+            projects: Vec::new(),
+            active_project_id: String::new(),
+            sessions: Vec::new(),
+            active_session_by_project: HashMap::new(),
+            prompts: Vec::new(),
+            environments: Vec::new(),
+            assets: Vec::new(),
+            agent_shortcut_ids: None,
+            asset_settings: None,
+            closed_project_ids: None,
+        },
+        Err(e) => return Err(format!("read failed: {e}")),
+    };
+    if state.schema_version != 1 {
+        return Err("unsupported schema version".to_string());
+    }
+
+    let mut added: Vec<PersistedProjectV1> = Vec::new();
+    for project in projects {
+        let exists = state
+            .projects
+            .iter()
+            .any(|p| p.base_path.is_some() && p.base_path == project.base_path);
+        if !exists {
+            state.projects.push(project.clone());
+            added.push(project);
+        }
+    }
+    if added.is_empty() {
+        return Ok(added);
+    }
+    if state.active_project_id.is_empty() {
+        state.active_project_id = added[0].id.clone();
+    }
+
+    let dir = path.parent().ok_or("invalid state path")?;
+    fs::create_dir_all(dir).map_err(|e| format!("create dir failed: {e}"))?;
+    let json = serde_json::to_string_pretty(&state).map_err(|e| format!("serialize failed: {e}"))?;
+    let tmp = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&tmp).map_err(|e| format!("write temp failed: {e}"))?;
+    crate::faults::write_all(&mut file, json.as_bytes()).map_err(|e| format!("write temp failed: {e}"))?;
+    file.write_all(b"\n")
+        .map_err(|e| format!("write temp failed: {e}"))?;
+    file.sync_all().ok();
+    drop(file);
+    fs::rename(&tmp, &path).map_err(|e| format!("rename failed: {e}"))?;
+    Ok(added)
+}
+
 #[tauri::command]
 pub fn load_persisted_state(window: WebviewWindow) -> Result<Option<PersistedStateV1>, String> {
     let path = state_file_path(&window)?;
@@ -264,6 +403,7 @@ pub fn save_persisted_state(window: WebviewWindow, state: PersistedStateV1) -> R
 
     // Best-effort: ensure the directory entry for the rename is durable.
     let _ = fs::File::open(dir).and_then(|dir_handle| dir_handle.sync_all());
+    crate::app_menu::refresh_dynamic_menus(&window);
     Ok(())
 }
 
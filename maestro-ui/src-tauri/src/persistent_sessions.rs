@@ -0,0 +1,751 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::util::now_epoch_ms;
+#[cfg(target_family = "unix")]
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, State, WebviewWindow};
+
+use crate::pty::{AppState, SessionInfo};
+
+const REGISTRY_FILE_NAME: &str = "persistent-sessions-v1.json";
+/// Names of multiplexer sessions Maestro starts (tmux backend, zellij layouts) begin
+/// with this; external_sessions.rs leaves them out of the external session list.
+pub(crate) const SESSION_PREFIX: &str = "agents-ui";
+/// Long names make `list-sessions` unreadable, and zellij's socket path is length limited.
+const MAX_SESSION_NAME_CHARS: usize = 60;
+/// Sessions not seen running for this long are dropped from the registry.
+const MAX_REGISTRY_AGE_MS: u64 = 90 * 24 * 60 * 60 * 1000;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentSessionInfo {
+    pub persist_id: String,
+    pub session_name: String,
+    /// `tmux` or `zellij`.
+    pub backend: String,
+    /// Epoch ms; for zellij, worked back from the age it reports, so to the second.
+    pub created_at: Option<u64>,
+    /// At least one client is attached.
+    pub attached: bool,
+    /// Attached clients; `None` if the backend couldn't say.
+    pub clients: Option<u32>,
+    /// A zellij session whose server has exited; attaching resurrects it.
+    pub exited: bool,
+    /// Started from this app data dir, rather than by another Maestro install (a dev
+    /// build, say) sharing the same multiplexer.
+    pub owned: bool,
+}
+
+/// A Maestro session that is a client of a persistent session. Zellij sessions can
+/// have several, each its own pty, to show one session in two panes or windows.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentAttachment {
+    pub id: String,
+    pub session_name: String,
+    pub started_at: u64,
+}
+
+/// A pane of a persistent session running something other than its shell.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentPane {
+    /// The tab (zellij) or window (tmux) the pane is in.
+    pub tab: Option<String>,
+    /// The command line, as far as the backend knows it; tmux only has the program.
+    pub command: String,
+}
+
+/// What is running in a persistent session, for the UI to warn with before killing it.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentSessionInspection {
+    pub session: PersistentSessionInfo,
+    pub panes: Vec<PersistentPane>,
+    /// Epoch ms of the latest activity seen: tmux's window activity, or output read
+    /// by an attached Maestro session.
+    pub last_activity_at: Option<u64>,
+}
+
+/// A session this install started, kept to tell its sessions from other installs'.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RegisteredSession {
+    session_name: String,
+    backend: String,
+    persist_id: String,
+    registered_at: u64,
+}
+
+/// The multiplexer session a persistent Maestro session runs in: named after its
+/// persist id, so relaunching with the same one reattaches, or after the session id.
+pub(crate) fn session_name(persist_id: Option<&str>, session_id: &str) -> String {
+    let base = persist_id
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| session_id.to_string());
+    let name = crate::recording::sanitize_recording_id(&format!("{SESSION_PREFIX}-{base}"));
+    name.chars().take(MAX_SESSION_NAME_CHARS).collect()
+}
+
+/// Serializes reads and rewrites of the registry file.
+fn registry_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn registry_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, REGISTRY_FILE_NAME)
+}
+
+fn read_registry(path: &std::path::Path) -> Vec<RegisteredSession> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_registry(path: &std::path::Path, sessions: &[RegisteredSession]) -> Result<(), String> {
+    crate::util::write_json_atomic(path, sessions)
+}
+
+/// Note that this install started (or reattached to) `session_name`. Best effort.
+pub(crate) fn register(app: &AppHandle, backend: &str, session_name: &str, persist_id: &str) {
+    let Ok(path) = registry_path(app) else {
+        return;
+    };
+    let Ok(_guard) = registry_lock().lock() else {
+        return;
+    };
+    let now = now_epoch_ms();
+    let mut sessions = read_registry(&path);
+    sessions.retain(|s| {
+        !(s.backend == backend && s.session_name == session_name)
+            && now.saturating_sub(s.registered_at) < MAX_REGISTRY_AGE_MS
+    });
+    sessions.push(RegisteredSession {
+        session_name: session_name.to_string(),
+        backend: backend.to_string(),
+        persist_id: persist_id.to_string(),
+        registered_at: now,
+    });
+    if let Err(e) = write_registry(&path, &sessions) {
+        eprintln!("Failed to register persistent session: {e}");
+    }
+}
+
+/// Forget a session that has been ended. Best effort.
+fn unregister(app: &AppHandle, backend: &str, session_name: &str) {
+    let Ok(path) = registry_path(app) else {
+        return;
+    };
+    let Ok(_guard) = registry_lock().lock() else {
+        return;
+    };
+    let mut sessions = read_registry(&path);
+    let before = sessions.len();
+    sessions.retain(|s| !(s.backend == backend && s.session_name == session_name));
+    if sessions.len() != before {
+        if let Err(e) = write_registry(&path, &sessions) {
+            eprintln!("Failed to unregister persistent session: {e}");
+        }
+    }
+}
+
+/// Seconds in a zellij age such as `2h 3m 10s` or `1day 4h` (humantime's format).
+fn parse_zellij_age(age: &str) -> Option<u64> {
+    let mut total = 0u64;
+    for token in age.split_whitespace() {
+        let split = token.find(|c: char| !c.is_ascii_digit())?;
+        let (value, unit) = token.split_at(split);
+        let value: u64 = value.parse().ok()?;
+        let seconds = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "day" | "days" => 24 * 60 * 60,
+            "week" | "weeks" => 7 * 24 * 60 * 60,
+            "month" | "months" => 2_630_016,
+            "year" | "years" => 31_557_600,
+            "ms" | "us" | "ns" => 0,
+            _ => return None,
+        };
+        total += value * seconds;
+    }
+    Some(total)
+}
+
+/// One line of `zellij list-sessions --no-formatting`:
+/// `name [Created 2h 3m ago] (EXITED - attach to resurrect)`.
+/// Returns the name, its age in seconds and whether it has exited.
+fn parse_zellij_session_line(line: &str) -> Option<(String, Option<u64>, bool)> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let (name, rest) = match line.find(" [Created ") {
+        Some(at) => (&line[..at], &line[at + " [Created ".len()..]),
+        None => (line.split_whitespace().next()?, ""),
+    };
+    let age = rest.split_once(" ago]").and_then(|(age, _)| parse_zellij_age(age));
+    Some((name.trim().to_string(), age, rest.contains("(EXITED")))
+}
+
+#[cfg(target_family = "unix")]
+fn zellij_client_count(zellij: &std::path::Path, session: &str) -> Option<u32> {
+    let output = Command::new(zellij)
+        .args(["--session", session, "action", "list-clients"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // A header line, then one line per client.
+    let lines = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .count();
+    Some(lines.saturating_sub(1) as u32)
+}
+
+#[cfg(target_family = "unix")]
+fn list_zellij(now: u64) -> Vec<PersistentSessionInfo> {
+    let Some(zellij) = crate::ssh_fs::find_program_in_path("zellij") else {
+        return Vec::new();
+    };
+    let Ok(output) = Command::new(&zellij).args(["list-sessions", "--no-formatting"]).output() else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_zellij_session_line)
+        .filter(|(name, _, _)| name.starts_with(SESSION_PREFIX))
+        .map(|(name, age, exited)| {
+            let clients = if exited { Some(0) } else { zellij_client_count(&zellij, &name) };
+            PersistentSessionInfo {
+                persist_id: crate::scrollback_spool::zellij_persist_id(&name),
+                backend: "zellij".to_string(),
+                created_at: age.map(|secs| now.saturating_sub(secs * 1000)),
+                attached: clients.is_some_and(|c| c > 0),
+                clients,
+                exited,
+                owned: false,
+                session_name: name,
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_family = "unix")]
+fn list_tmux() -> Vec<PersistentSessionInfo> {
+    let Some(tmux) = crate::ssh_fs::find_program_in_path("tmux") else {
+        return Vec::new();
+    };
+    let Ok(output) = Command::new(tmux)
+        .args(["list-sessions", "-F", "#{session_name}\t#{session_created}\t#{session_attached}"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?.to_string();
+            if !name.starts_with(SESSION_PREFIX) {
+                return None;
+            }
+            let created_at = fields.next().and_then(|s| s.parse::<u64>().ok()).map(|s| s * 1000);
+            let clients = fields.next().and_then(|s| s.parse::<u32>().ok());
+            Some(PersistentSessionInfo {
+                persist_id: name.clone(),
+                backend: "tmux".to_string(),
+                created_at,
+                attached: clients.is_some_and(|c| c > 0),
+                clients,
+                exited: false,
+                owned: false,
+                session_name: name,
+            })
+        })
+        .collect()
+}
+
+fn list_sessions(app: &AppHandle) -> Vec<PersistentSessionInfo> {
+    #[cfg(target_family = "unix")]
+    let mut sessions = {
+        let mut sessions = list_zellij(now_epoch_ms());
+        sessions.extend(list_tmux());
+        sessions
+    };
+    #[cfg(not(target_family = "unix"))]
+    let mut sessions: Vec<PersistentSessionInfo> = Vec::new();
+
+    let registry = registry_path(app)
+        .map(|path| {
+            let _guard = registry_lock().lock();
+            read_registry(&path)
+        })
+        .unwrap_or_default();
+    for session in &mut sessions {
+        if let Some(registered) = registry
+            .iter()
+            .find(|r| r.backend == session.backend && r.session_name == session.session_name)
+        {
+            session.owned = true;
+            session.persist_id = registered.persist_id.clone();
+        }
+    }
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    sessions
+}
+
+/// Shells, which a pane running nothing else is left at.
+#[cfg(target_family = "unix")]
+const SHELLS: &[&str] = &["bash", "dash", "fish", "ksh", "nu", "sh", "tcsh", "zsh"];
+
+/// Command panes of `zellij action dump-layout` output. Zellij records the program a
+/// pane is running, other than its shell, as `pane command="…" { args "…" }`; tab and
+/// pane templates are skipped.
+fn parse_zellij_layout_panes(layout: &str) -> Vec<PersistentPane> {
+    fn attr(line: &str, key: &str) -> Option<String> {
+        let start = line.find(&format!("{key}=\""))? + key.len() + 2;
+        let len = line[start..].find('"')?;
+        Some(line[start..start + len].to_string())
+    }
+    fn quoted(line: &str) -> Vec<&str> {
+        line.split('"').skip(1).step_by(2).collect()
+    }
+    let mut panes = Vec::new();
+    let mut depth = 0usize;
+    let mut skip_from: Option<usize> = None;
+    let mut tab: Option<String> = None;
+    let mut pane_at: Option<usize> = None;
+    for line in layout.lines().map(str::trim) {
+        let word = line.split([' ', '{']).next().unwrap_or_default();
+        if skip_from.is_none() {
+            if matches!(
+                word,
+                "new_tab_template"
+                    | "tab_template"
+                    | "pane_template"
+                    | "swap_tiled_layout"
+                    | "swap_floating_layout"
+            ) {
+                skip_from = Some(depth);
+            } else if word == "tab" {
+                tab = attr(line, "name");
+            } else if word == "pane" {
+                if let Some(command) = attr(line, "command") {
+                    panes.push(PersistentPane {
+                        tab: tab.clone(),
+                        command,
+                    });
+                    pane_at = Some(depth);
+                }
+            } else if word == "args" && pane_at.is_some() {
+                if let Some(pane) = panes.last_mut() {
+                    for arg in quoted(line) {
+                        pane.command.push(' ');
+                        pane.command.push_str(arg);
+                    }
+                }
+            }
+        }
+        depth = (depth + line.matches('{').count()).saturating_sub(line.matches('}').count());
+        if pane_at.is_some_and(|at| depth <= at) {
+            pane_at = None;
+        }
+        if skip_from.is_some_and(|at| depth <= at) {
+            skip_from = None;
+        }
+    }
+    panes
+}
+
+#[cfg(target_family = "unix")]
+fn inspect_zellij(session: &str) -> Vec<PersistentPane> {
+    let Some(zellij) = crate::ssh_fs::find_program_in_path("zellij") else {
+        return Vec::new();
+    };
+    Command::new(zellij)
+        .args(["--session", session, "action", "dump-layout"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_zellij_layout_panes(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Panes not sitting at a shell, and the latest window activity in epoch ms.
+#[cfg(target_family = "unix")]
+fn inspect_tmux(session: &str) -> (Vec<PersistentPane>, Option<u64>) {
+    let Some(tmux) = crate::ssh_fs::find_program_in_path("tmux") else {
+        return (Vec::new(), None);
+    };
+    let Ok(output) = Command::new(tmux)
+        .args(["list-panes", "-s", "-t", &format!("={session}")])
+        .args(["-F", "#{window_name}\t#{pane_current_command}\t#{window_activity}"])
+        .output()
+    else {
+        return (Vec::new(), None);
+    };
+    let mut panes = Vec::new();
+    let mut last_activity = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut fields = line.split('\t');
+        let (Some(window), Some(command)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let activity = fields.next().and_then(|s| s.parse::<u64>().ok()).map(|s| s * 1000);
+        last_activity = last_activity.max(activity);
+        let program = command.trim().trim_start_matches('-');
+        if !program.is_empty() && !SHELLS.contains(&program) {
+            panes.push(PersistentPane {
+                tab: Some(window.to_string()),
+                command: command.trim().to_string(),
+            });
+        }
+    }
+    (panes, last_activity)
+}
+
+/// The session `wanted` names: its persist id, its session name, or the persist id the
+/// session name was made from.
+fn find_session(sessions: Vec<PersistentSessionInfo>, wanted: &str) -> Option<PersistentSessionInfo> {
+    let wanted = wanted.trim();
+    if wanted.is_empty() {
+        return None;
+    }
+    let prefixed = session_name(Some(wanted), "");
+    sessions
+        .into_iter()
+        .find(|s| s.persist_id == wanted || s.session_name == wanted || s.session_name == prefixed)
+}
+
+pub(crate) async fn find_session_async(
+    window: &WebviewWindow,
+    wanted: &str,
+) -> Result<PersistentSessionInfo, String> {
+    let app = window.app_handle().clone();
+    let sessions = tauri::async_runtime::spawn_blocking(move || list_sessions(&app))
+        .await
+        .map_err(|e| format!("list persistent sessions task join failed: {e:?}"))?;
+    find_session(sessions, wanted).ok_or_else(|| "unknown persistent session".to_string())
+}
+
+/// Multiplexer sessions Maestro started (tmux backend sessions and zellij layouts),
+/// newest first, with their state and which of them this install started.
+#[tauri::command]
+pub async fn list_persistent_sessions(window: WebviewWindow) -> Result<Vec<PersistentSessionInfo>, String> {
+    let app = window.app_handle().clone();
+    tauri::async_runtime::spawn_blocking(move || list_sessions(&app))
+        .await
+        .map_err(|e| format!("list persistent sessions task join failed: {e:?}"))
+}
+
+/// Bring back a zellij session that was serialized but whose server is gone (after
+/// a reboot, say) by attaching a new Maestro session to it, which makes zellij
+/// rebuild its panes. `persist_id` may also be the session name.
+#[tauri::command]
+pub async fn resurrect_persistent_session(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    persist_id: String,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    project_id: Option<String>,
+) -> Result<SessionInfo, String> {
+    #[cfg(target_family = "unix")]
+    {
+        let session = find_session_async(&window, &persist_id).await?;
+        if session.backend != "zellij" {
+            return Err("only zellij sessions can be resurrected".to_string());
+        }
+        if !session.exited {
+            return Err("session is still running".to_string());
+        }
+        let name = session.session_name;
+        let command = format!("exec zellij attach {}", crate::ssh_fs::shell_escape_posix(&name));
+        let info = crate::pty::create_session(
+            window,
+            state,
+            crate::pty::CreateSessionOptions {
+                session: crate::pty::SessionRequest {
+                    name: Some(name.clone()),
+                    command: Some(command),
+                    cols,
+                    rows,
+                    persist_id: Some(crate::scrollback_spool::zellij_persist_id(&name)),
+                    project_id,
+                    multiplexer: Some(("zellij".to_string(), name.clone())),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await?;
+        crate::telemetry::record_feature("zellij-resurrect");
+        Ok(info)
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        let _ = (window, state, persist_id, cols, rows, project_id);
+        Err("persistent sessions are only supported on unix".to_string())
+    }
+}
+
+/// End the multiplexer session behind a persistent Maestro session, along with
+/// everything running in it. `persist_id` may also be the session name. Ending one
+/// that is already gone is not an error, since the UI calls this after closing.
+#[tauri::command]
+pub async fn kill_persistent_session(window: WebviewWindow, persist_id: String) -> Result<(), String> {
+    #[cfg(target_family = "unix")]
+    {
+        if persist_id.trim().is_empty() {
+            return Err("missing persist id".to_string());
+        }
+        let app = window.app_handle().clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let Some(session) = find_session(list_sessions(&app), &persist_id) else {
+                return Ok(());
+            };
+            let program = crate::ssh_fs::find_program_in_path(&session.backend)
+                .ok_or_else(|| format!("{} not found", session.backend))?;
+            let output = match session.backend.as_str() {
+                "tmux" => Command::new(program)
+                    .args(["kill-session", "-t", &format!("={}", session.session_name)])
+                    .output(),
+                _ => Command::new(program)
+                    .args(["delete-session", "--force", &session.session_name])
+                    .output(),
+            }
+            .map_err(|e| format!("{} failed: {e}", session.backend))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("{} failed: {}", session.backend, stderr.trim()));
+            }
+            unregister(&app, &session.backend, &session.session_name);
+            crate::telemetry::record_feature("persistent-kill");
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("kill persistent session task join failed: {e:?}"))?
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        let _ = (window, persist_id);
+        Err("persistent sessions are only supported on unix".to_string())
+    }
+}
+
+/// What is running in a persistent session (its command panes) and when it last did
+/// something, so the UI can warn before `kill_persistent_session`. `persist_id` may
+/// also be the session name.
+#[tauri::command]
+pub async fn inspect_persistent_session(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    persist_id: String,
+) -> Result<PersistentSessionInspection, String> {
+    let session = find_session_async(&window, &persist_id).await?;
+    let client_activity = state
+        .multiplexer_clients(&session.backend, &session.session_name)
+        .into_iter()
+        .filter_map(|(id, _)| state.last_output_at(&id))
+        .filter(|at| *at > 0)
+        .max();
+    #[cfg(target_family = "unix")]
+    {
+        let name = session.session_name.clone();
+        let (backend, exited) = (session.backend.clone(), session.exited);
+        let (panes, activity) = tauri::async_runtime::spawn_blocking(move || match backend.as_str() {
+            "tmux" => inspect_tmux(&name),
+            _ if exited => (Vec::new(), None),
+            _ => (inspect_zellij(&name), None),
+        })
+        .await
+        .map_err(|e| format!("inspect persistent session task join failed: {e:?}"))?;
+        Ok(PersistentSessionInspection {
+            session,
+            panes,
+            last_activity_at: activity.max(client_activity),
+        })
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        Ok(PersistentSessionInspection {
+            session,
+            panes: Vec::new(),
+            last_activity_at: client_activity,
+        })
+    }
+}
+
+/// Open another client of a running zellij session in a new Maestro session, so it
+/// can be shown next to the first. Close it with `close_persistent_attachment`.
+#[tauri::command]
+pub async fn open_persistent_attachment(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    persist_id: String,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    project_id: Option<String>,
+) -> Result<SessionInfo, String> {
+    #[cfg(target_family = "unix")]
+    {
+        let session = find_session_async(&window, &persist_id).await?;
+        if session.backend != "zellij" {
+            return Err("only zellij sessions can have more than one attachment".to_string());
+        }
+        if session.exited {
+            return Err("session has exited; resurrect it first".to_string());
+        }
+        let name = session.session_name;
+        let command = format!("exec zellij attach {}", crate::ssh_fs::shell_escape_posix(&name));
+        // No persist id: the first client already spools the session's output.
+        let info = crate::pty::create_session(
+            window,
+            state,
+            crate::pty::CreateSessionOptions {
+                session: crate::pty::SessionRequest {
+                    name: Some(name.clone()),
+                    command: Some(command),
+                    cols,
+                    rows,
+                    project_id,
+                    multiplexer: Some(("zellij".to_string(), name)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await?;
+        crate::telemetry::record_feature("zellij-attachment");
+        Ok(info)
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        let _ = (window, state, persist_id, cols, rows, project_id);
+        Err("persistent sessions are only supported on unix".to_string())
+    }
+}
+
+/// The Maestro sessions attached to a persistent session, oldest first.
+#[tauri::command]
+pub async fn list_persistent_attachments(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    persist_id: String,
+) -> Result<Vec<PersistentAttachment>, String> {
+    let session = find_session_async(&window, &persist_id).await?;
+    Ok(state
+        .multiplexer_clients(&session.backend, &session.session_name)
+        .into_iter()
+        .map(|(id, started_at)| PersistentAttachment {
+            id,
+            session_name: session.session_name.clone(),
+            started_at,
+        })
+        .collect())
+}
+
+/// Close one attachment; the persistent session and its other attachments carry on.
+#[tauri::command]
+pub fn close_persistent_attachment(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state.detach(&id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_session, parse_zellij_age, parse_zellij_layout_panes, parse_zellij_session_line, session_name,
+        PersistentPane, PersistentSessionInfo,
+    };
+
+    #[test]
+    fn parses_zellij_list_sessions_lines() {
+        assert_eq!(
+            parse_zellij_session_line("agents-ui-api [Created 1day 2h 3m ago] (current)"),
+            Some(("agents-ui-api".to_string(), Some(93_780), false))
+        );
+        assert_eq!(
+            parse_zellij_session_line("agents-ui-7 [Created 45s ago] (EXITED - attach to resurrect)"),
+            Some(("agents-ui-7".to_string(), Some(45), true))
+        );
+        assert_eq!(parse_zellij_session_line("bare-name"), Some(("bare-name".to_string(), None, false)));
+        assert_eq!(parse_zellij_age("3weeks 1fortnight"), None);
+    }
+
+    #[test]
+    fn finds_sessions_by_persist_id_or_name() {
+        let session = |name: &str, persist_id: &str| PersistentSessionInfo {
+            persist_id: persist_id.to_string(),
+            session_name: name.to_string(),
+            backend: "zellij".to_string(),
+            created_at: None,
+            attached: false,
+            clients: None,
+            exited: false,
+            owned: true,
+        };
+        let sessions = vec![
+            session("agents-ui-api", "zellij-agents-ui-api"),
+            session("agents-ui-web", "web"),
+        ];
+        let found = |wanted: &str| find_session(sessions.clone(), wanted).map(|s| s.session_name);
+        assert_eq!(found("zellij-agents-ui-api").as_deref(), Some("agents-ui-api"));
+        assert_eq!(found("agents-ui-api").as_deref(), Some("agents-ui-api"));
+        assert_eq!(found("api").as_deref(), Some("agents-ui-api"));
+        assert_eq!(found(" web ").as_deref(), Some("agents-ui-web"));
+        assert_eq!(found(""), None);
+        assert_eq!(found("db"), None);
+    }
+
+    #[test]
+    fn lists_command_panes_of_a_zellij_layout() {
+        let layout = r#"layout {
+    cwd "/home/me/api"
+    tab name="agent" focus=true {
+        pane size=1 borderless=true {
+            plugin location="zellij:tab-bar"
+        }
+        pane split_direction="vertical" {
+            pane command="cargo" cwd="api" focus=true {
+                args "build" "--release"
+                start_suspended true
+            }
+            pane
+        }
+    }
+    tab name="logs" {
+        pane command="tail" {
+            args "-f" "log.txt"
+        }
+    }
+    new_tab_template {
+        pane command="htop"
+    }
+}"#;
+        let pane = |tab: &str, command: &str| PersistentPane {
+            tab: Some(tab.to_string()),
+            command: command.to_string(),
+        };
+        assert_eq!(
+            parse_zellij_layout_panes(layout),
+            vec![pane("agent", "cargo build --release"), pane("logs", "tail -f log.txt")]
+        );
+    }
+
+    #[test]
+    fn session_names_are_prefixed_and_safe() {
+        assert_eq!(session_name(None, "12"), "agents-ui-12");
+        assert_eq!(session_name(Some("proj:api.dev"), "12"), "agents-ui-proj_api_dev");
+        assert_eq!(session_name(Some(&"x".repeat(100)), "12").len(), 60);
+    }
+}
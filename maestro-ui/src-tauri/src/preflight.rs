@@ -0,0 +1,215 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const NODE_VERSION_FILES: &[&str] = &[".nvmrc", ".node-version"];
+const PYTHON_VERSION_FILES: &[&str] = &[".python-version"];
+const ENV_EXAMPLE_FILES: &[&str] = &[".env.example", ".env.sample", ".env.template"];
+const ENV_FILES: &[&str] = &[".env", ".env.local"];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightFinding {
+    /// `node`, `python`, or `env`.
+    pub check: String,
+    /// `ok`, `warning`, or `error`.
+    pub status: String,
+    pub message: String,
+    /// Project file the requirement came from.
+    pub source: Option<String>,
+    pub expected: Option<String>,
+    pub found: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectPreflight {
+    pub root: String,
+    /// False when any finding is an error.
+    pub ok: bool,
+    pub findings: Vec<PreflightFinding>,
+}
+
+fn finding(check: &str, status: &str, message: String, source: &str) -> PreflightFinding {
+    PreflightFinding {
+        check: check.to_string(),
+        status: status.to_string(),
+        message,
+        source: Some(source.to_string()),
+        expected: None,
+        found: None,
+    }
+}
+
+/// First non-empty, non-comment line of the first version file present.
+fn read_version_file(root: &Path, names: &[&str]) -> Option<(String, String)> {
+    names.iter().find_map(|name| {
+        let raw = fs::read_to_string(root.join(name)).ok()?;
+        let version = raw
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with('#'))?
+            .to_string();
+        Some((name.to_string(), version))
+    })
+}
+
+/// Runs `<program> --version` and returns the first version-looking token.
+fn tool_version(programs: &[&str]) -> Option<String> {
+    programs.iter().find_map(|program| {
+        let path = crate::ssh_fs::find_program_in_path(program)?;
+        let out = Command::new(path).arg("--version").output().ok()?;
+        // Python 2 prints its version to stderr.
+        let text = format!(
+            "{}{}",
+            String::from_utf8_lossy(&out.stdout),
+            String::from_utf8_lossy(&out.stderr)
+        );
+        text.split_whitespace()
+            .map(|t| t.trim_start_matches('v'))
+            .find(|t| t.starts_with(|c: char| c.is_ascii_digit()))
+            .map(|t| t.to_string())
+    })
+}
+
+/// Whether `found` satisfies a pinned `expected` version: every numeric component
+/// given in `expected` must match (`18` accepts `18.17.0`). None for specs that
+/// aren't plain versions (`lts/*`, `pypy3.9`, virtualenv names).
+fn version_matches(expected: &str, found: &str) -> Option<bool> {
+    let expected = expected.trim().trim_start_matches('v');
+    let wanted: Vec<&str> = expected.split('.').collect();
+    if wanted.iter().any(|c| c.is_empty() || !c.chars().all(|ch| ch.is_ascii_digit())) {
+        return None;
+    }
+    let have: Vec<&str> = found.trim().trim_start_matches('v').split('.').collect();
+    Some(wanted.iter().enumerate().all(|(i, c)| {
+        have.get(i)
+            .map(|h| h.chars().take_while(|ch| ch.is_ascii_digit()).collect::<String>() == *c)
+            .unwrap_or(false)
+    }))
+}
+
+fn check_tool_version(
+    root: &Path,
+    check: &str,
+    files: &[&str],
+    programs: &[&str],
+    findings: &mut Vec<PreflightFinding>,
+) {
+    let Some((source, expected)) = read_version_file(root, files) else {
+        return;
+    };
+    let found = tool_version(programs);
+    let (status, message) = match found.as_deref() {
+        None => ("error", format!("{} not found on PATH ({source} requires {expected})", programs[0])),
+        Some(found) => match version_matches(&expected, found) {
+            Some(true) => ("ok", format!("{} {found} matches {source}", programs[0])),
+            Some(false) => ("error", format!("{source} requires {expected}, found {found}")),
+            None => ("warning", format!("{source} requests \"{expected}\", which can't be checked; found {found}")),
+        },
+    };
+    let mut f = finding(check, status, message, &source);
+    f.expected = Some(expected);
+    f.found = found;
+    findings.push(f);
+}
+
+/// Variable names declared in a dotenv file (`KEY=value`, optionally `export KEY=value`).
+fn parse_env_keys(contents: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, _)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let valid = !key.is_empty()
+            && !key.starts_with(|c: char| c.is_ascii_digit())
+            && key.chars().all(|c| c == '_' || c.is_ascii_alphanumeric());
+        if valid && !keys.iter().any(|k| k == key) {
+            keys.push(key.to_string());
+        }
+    }
+    keys
+}
+
+fn check_env_vars(root: &Path, findings: &mut Vec<PreflightFinding>) {
+    let Some((source, contents)) = ENV_EXAMPLE_FILES
+        .iter()
+        .find_map(|name| Some((name.to_string(), fs::read_to_string(root.join(name)).ok()?)))
+    else {
+        return;
+    };
+
+    let defined: HashSet<String> = ENV_FILES
+        .iter()
+        .filter_map(|name| fs::read_to_string(root.join(name)).ok())
+        .flat_map(|contents| parse_env_keys(&contents))
+        .collect();
+    let missing: Vec<String> = parse_env_keys(&contents)
+        .into_iter()
+        .filter(|key| !defined.contains(key) && std::env::var_os(key).is_none())
+        .collect();
+
+    let mut f = if missing.is_empty() {
+        finding("env", "ok", format!("all variables in {source} are set"), &source)
+    } else {
+        finding(
+            "env",
+            "warning",
+            format!("{} variable(s) from {source} are not set: {}", missing.len(), missing.join(", ")),
+            &source,
+        )
+    };
+    f.expected = Some(source.clone());
+    f.found = (!missing.is_empty()).then(|| missing.join(","));
+    findings.push(f);
+}
+
+/// Checks a project's declared tooling (.nvmrc/.node-version, .python-version) against what's
+/// installed and reports variables from .env.example missing from .env, .env.local and the
+/// app environment. Projects that declare nothing get an empty, passing report.
+#[tauri::command]
+pub async fn run_project_preflight(root: String) -> Result<ProjectPreflight, String> {
+    let root = root.trim().to_string();
+    if !Path::new(&root).is_dir() {
+        return Err("project root is not a directory".to_string());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = Path::new(&root);
+        let mut findings: Vec<PreflightFinding> = Vec::new();
+        check_tool_version(dir, "node", NODE_VERSION_FILES, &["node"], &mut findings);
+        check_tool_version(dir, "python", PYTHON_VERSION_FILES, &["python3", "python"], &mut findings);
+        check_env_vars(dir, &mut findings);
+        let ok = !findings.iter().any(|f| f.status == "error");
+        ProjectPreflight { root, ok, findings }
+    })
+    .await
+    .map_err(|e| format!("preflight task join failed: {e:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_matches_pinned_prefix() {
+        assert_eq!(version_matches("18", "18.17.0"), Some(true));
+        assert_eq!(version_matches("v20.1", "20.10.0"), Some(false));
+        assert_eq!(version_matches("3.11.4", "3.11.4rc1"), Some(true));
+        assert_eq!(version_matches("lts/*", "20.10.0"), None);
+    }
+
+    #[test]
+    fn parse_env_keys_reads_dotenv_lines() {
+        let keys = parse_env_keys("# db\nDATABASE_URL=\nexport API_KEY=abc\n\n1BAD=x\nAPI_KEY=dup\nnot a line\n");
+        assert_eq!(keys, vec!["DATABASE_URL".to_string(), "API_KEY".to_string()]);
+    }
+}
@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::ssh::matches_glob;
+
+const PROJECT_IGNORE_FILE_NAME: &str = "project-ignore-v1.json";
+const MAX_PATTERNS: usize = 200;
+const MAX_PATTERN_LEN: usize = 500;
+
+/// Extra glob patterns for a project's file listing, on top of the built-in skips
+/// (dotfiles, node_modules, target, dist, build, coverage).
+///
+/// A pattern without a `/` matches a file or directory name at any depth
+/// (`*.generated.ts`, `.venv`). One with a `/` matches the path from the project
+/// root (`packages/*/gen`, `docs/**/*.pdf`), where `**` spans any number of
+/// directories. A trailing `/` limits a pattern to directories.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectIgnoreV1 {
+    /// Hidden along with everything under them.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Shown even when a built-in skip or an `ignore` pattern would hide them.
+    /// Only patterns with a `/` reach into a hidden directory (`dist/types/**`);
+    /// a bare name only brings back entries whose directory is already shown.
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ProjectIgnoreFileV1 {
+    #[serde(default)]
+    projects: HashMap<String, ProjectIgnoreV1>,
+}
+
+struct Pattern {
+    segments: Vec<String>,
+    /// Written with a `/`, so matched from the root rather than against any name.
+    anchored: bool,
+    dir_only: bool,
+}
+
+impl Pattern {
+    fn parse(raw: &str) -> Self {
+        let dir_only = raw.ends_with('/');
+        let trimmed = raw.trim_start_matches('/').trim_end_matches('/');
+        let anchored = raw.trim_end_matches('/').contains('/');
+        Self {
+            segments: trimmed.split('/').filter(|s| !s.is_empty()).map(str::to_string).collect(),
+            anchored,
+            dir_only,
+        }
+    }
+
+    fn matches(&self, rel: &[&str], is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if !self.anchored {
+            return rel.last().is_some_and(|name| matches_glob(&self.segments[0], name));
+        }
+        match_segments(&self.segments, rel)
+    }
+
+    /// Whether something under the directory `rel` could match, so it's worth
+    /// walking even though the directory itself is hidden.
+    fn may_match_below(&self, rel: &[&str]) -> bool {
+        self.anchored && prefix_matches(&self.segments, rel)
+    }
+}
+
+fn match_segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| match_segments(rest, &path[skip..]))
+        }
+        Some((first, rest)) => path
+            .split_first()
+            .is_some_and(|(name, tail)| matches_glob(first, name) && match_segments(rest, tail)),
+    }
+}
+
+fn prefix_matches(pattern: &[String], dir: &[&str]) -> bool {
+    match (pattern.split_first(), dir.split_first()) {
+        (None, _) => false,
+        (Some(_), None) => true,
+        (Some((first, _)), Some(_)) if first == "**" => true,
+        (Some((first, rest)), Some((name, tail))) => matches_glob(first, name) && prefix_matches(rest, tail),
+    }
+}
+
+/// A project's patterns, ready to apply while walking its tree.
+#[derive(Default)]
+pub(crate) struct ProjectFilter {
+    ignore: Vec<Pattern>,
+    include: Vec<Pattern>,
+}
+
+/// What to do with one entry met during a walk.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Visit {
+    Show,
+    Skip,
+    /// A hidden directory walked only for included entries under it; its children
+    /// start out hidden.
+    DescendHidden,
+}
+
+impl ProjectFilter {
+    fn new(rules: &ProjectIgnoreV1) -> Self {
+        Self {
+            ignore: rules.ignore.iter().map(|p| Pattern::parse(p)).collect(),
+            include: rules.include.iter().map(|p| Pattern::parse(p)).collect(),
+        }
+    }
+
+    /// `rel` is the entry's path from the project root with `/` separators.
+    /// `hidden` is true for built-in skips and for entries of a `DescendHidden`
+    /// directory.
+    pub(crate) fn visit(&self, rel: &str, is_dir: bool, hidden: bool) -> Visit {
+        let segments: Vec<&str> = rel.split('/').filter(|s| !s.is_empty()).collect();
+        if self.include.iter().any(|p| p.matches(&segments, is_dir)) {
+            return Visit::Show;
+        }
+        let hidden = hidden || self.ignore.iter().any(|p| p.matches(&segments, is_dir));
+        if !hidden {
+            return Visit::Show;
+        }
+        if is_dir && self.include.iter().any(|p| p.may_match_below(&segments)) {
+            return Visit::DescendHidden;
+        }
+        Visit::Skip
+    }
+}
+
+fn ignore_cache() -> &'static Mutex<Option<HashMap<String, ProjectIgnoreV1>>> {
+    static CACHE: OnceLock<Mutex<Option<HashMap<String, ProjectIgnoreV1>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn ignore_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, PROJECT_IGNORE_FILE_NAME)
+}
+
+fn read_ignore(app: &AppHandle) -> Result<HashMap<String, ProjectIgnoreV1>, String> {
+    let path = ignore_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str::<ProjectIgnoreFileV1>(&raw)
+            .map(|f| f.projects)
+            .map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn write_ignore(app: &AppHandle, projects: &HashMap<String, ProjectIgnoreV1>) -> Result<(), String> {
+    let path = ignore_file_path(app)?;
+    crate::util::write_json_atomic(&path, &ProjectIgnoreFileV1 {
+        projects: projects.clone(),
+    })
+}
+
+/// Trim patterns, drop blanks and duplicates, and reject ones that can't work.
+fn normalize_patterns(patterns: &[String]) -> Result<Vec<String>, String> {
+    let mut out: Vec<String> = Vec::new();
+    for raw in patterns {
+        let pattern = raw.trim();
+        if pattern.is_empty() || out.iter().any(|p| p == pattern) {
+            continue;
+        }
+        if pattern.len() > MAX_PATTERN_LEN {
+            return Err(format!("pattern is too long (max {MAX_PATTERN_LEN} chars)"));
+        }
+        if pattern.starts_with('!') {
+            return Err(format!("negated pattern not supported, add it to include instead: {pattern}"));
+        }
+        if pattern.contains('\\') {
+            return Err(format!("use / as the path separator: {pattern}"));
+        }
+        if pattern.split('/').any(|s| s == "." || s == "..") {
+            return Err(format!("pattern must not contain . or .. segments: {pattern}"));
+        }
+        if pattern.trim_matches('/').is_empty() {
+            return Err(format!("pattern matches nothing: {pattern}"));
+        }
+        out.push(pattern.to_string());
+    }
+    if out.len() > MAX_PATTERNS {
+        return Err(format!("too many patterns (max {MAX_PATTERNS})"));
+    }
+    Ok(out)
+}
+
+fn with_projects<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut HashMap<String, ProjectIgnoreV1>) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut cache = ignore_cache().lock().map_err(|_| "project ignore cache poisoned")?;
+    if cache.is_none() {
+        *cache = Some(read_ignore(app)?);
+    }
+    f(cache.as_mut().ok_or("project ignore cache missing")?)
+}
+
+/// The filter for `project_id`; without one, only the built-in skips apply.
+pub(crate) fn filter_for(app: &AppHandle, project_id: Option<&str>) -> Result<ProjectFilter, String> {
+    let Some(project_id) = project_id.map(str::trim).filter(|p| !p.is_empty()) else {
+        return Ok(ProjectFilter::default());
+    };
+    with_projects(app, |projects| {
+        Ok(projects.get(project_id).map(ProjectFilter::new).unwrap_or_default())
+    })
+}
+
+#[tauri::command]
+pub fn get_project_ignore(window: WebviewWindow, project_id: String) -> Result<ProjectIgnoreV1, String> {
+    with_projects(window.app_handle(), |projects| {
+        Ok(projects.get(&project_id).cloned().unwrap_or_default())
+    })
+}
+
+/// Replace a project's patterns; returns them as stored (trimmed, deduplicated).
+/// Empty lists remove the project's entry.
+#[tauri::command]
+pub fn set_project_ignore(
+    window: WebviewWindow,
+    project_id: String,
+    rules: ProjectIgnoreV1,
+) -> Result<ProjectIgnoreV1, String> {
+    let project_id = project_id.trim().to_string();
+    if project_id.is_empty() {
+        return Err("project id is required".to_string());
+    }
+    let rules = ProjectIgnoreV1 {
+        ignore: normalize_patterns(&rules.ignore)?,
+        include: normalize_patterns(&rules.include)?,
+    };
+    let app = window.app_handle();
+    with_projects(app, |projects| {
+        if rules == ProjectIgnoreV1::default() {
+            projects.remove(&project_id);
+        } else {
+            projects.insert(project_id, rules.clone());
+        }
+        write_ignore(app, projects)
+    })?;
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_patterns, ProjectFilter, ProjectIgnoreV1, Visit};
+
+    fn filter(ignore: &[&str], include: &[&str]) -> ProjectFilter {
+        ProjectFilter::new(&ProjectIgnoreV1 {
+            ignore: ignore.iter().map(|p| p.to_string()).collect(),
+            include: include.iter().map(|p| p.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn ignore_and_include_patterns() {
+        let f = filter(&["*.gen.ts", "packages/*/out/", "docs/**/*.pdf"], &["dist/types/**", ".github"]);
+        assert_eq!(f.visit("src/api.gen.ts", false, false), Visit::Skip);
+        assert_eq!(f.visit("src/api.ts", false, false), Visit::Show);
+        assert_eq!(f.visit("packages/web/out", true, false), Visit::Skip);
+        assert_eq!(f.visit("packages/web/out", false, false), Visit::Show);
+        assert_eq!(f.visit("docs/a.pdf", false, false), Visit::Skip);
+        assert_eq!(f.visit("docs/x/y/a.pdf", false, false), Visit::Skip);
+
+        // Built-in skips can be undone, and a hidden dir is walked for includes under it.
+        assert_eq!(f.visit(".github", true, true), Visit::Show);
+        assert_eq!(f.visit("dist", true, true), Visit::DescendHidden);
+        assert_eq!(f.visit("dist/main.js", false, true), Visit::Skip);
+        assert_eq!(f.visit("dist/types", true, true), Visit::Show);
+        assert_eq!(f.visit("node_modules", true, true), Visit::Skip);
+    }
+
+    #[test]
+    fn patterns_are_normalized_and_checked() {
+        let raw = vec![" *.log ".to_string(), "".to_string(), "*.log".to_string(), "gen/".to_string()];
+        assert_eq!(normalize_patterns(&raw).unwrap(), ["*.log", "gen/"]);
+        assert!(normalize_patterns(&["!keep".to_string()]).is_err());
+        assert!(normalize_patterns(&["../up".to_string()]).is_err());
+        assert!(normalize_patterns(&["/".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,678 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::agent_export::escape_html;
+
+const README_NAMES: &[&str] = &["README.md", "README.markdown", "README", "README.txt", "README.rst"];
+const MAX_README_BYTES: usize = 256 * 1024;
+const MAX_SCANNED_FILES: usize = 20_000;
+/// Manifests are looked for at the root and this many directories below it.
+const MANIFEST_DEPTH: usize = 2;
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "coverage"];
+
+/// File name and kind of the manifests reported in `ProjectOverview::manifests`.
+const MANIFESTS: &[(&str, &str)] = &[
+    ("package.json", "npm"),
+    ("Cargo.toml", "cargo"),
+    ("pyproject.toml", "python"),
+    ("requirements.txt", "python"),
+    ("setup.py", "python"),
+    ("go.mod", "go"),
+    ("Makefile", "make"),
+    ("pom.xml", "maven"),
+    ("build.gradle", "gradle"),
+    ("build.gradle.kts", "gradle"),
+    ("Gemfile", "ruby"),
+    ("composer.json", "composer"),
+    ("Dockerfile", "docker"),
+    ("docker-compose.yml", "docker"),
+    ("compose.yaml", "docker"),
+];
+
+/// (manifest kind, dependency, framework) for frameworks detected from dependencies.
+const FRAMEWORKS: &[(&str, &str, &str)] = &[
+    ("npm", "next", "Next.js"),
+    ("npm", "react", "React"),
+    ("npm", "vue", "Vue"),
+    ("npm", "nuxt", "Nuxt"),
+    ("npm", "svelte", "Svelte"),
+    ("npm", "@sveltejs/kit", "SvelteKit"),
+    ("npm", "@angular/core", "Angular"),
+    ("npm", "astro", "Astro"),
+    ("npm", "@remix-run/react", "Remix"),
+    ("npm", "express", "Express"),
+    ("npm", "@nestjs/core", "NestJS"),
+    ("npm", "electron", "Electron"),
+    ("npm", "@tauri-apps/api", "Tauri"),
+    ("npm", "vite", "Vite"),
+    ("npm", "tailwindcss", "Tailwind CSS"),
+    ("npm", "jest", "Jest"),
+    ("npm", "vitest", "Vitest"),
+    ("cargo", "tauri", "Tauri"),
+    ("cargo", "axum", "Axum"),
+    ("cargo", "actix-web", "Actix Web"),
+    ("cargo", "rocket", "Rocket"),
+    ("cargo", "leptos", "Leptos"),
+    ("cargo", "bevy", "Bevy"),
+    ("python", "django", "Django"),
+    ("python", "flask", "Flask"),
+    ("python", "fastapi", "FastAPI"),
+    ("python", "pytest", "pytest"),
+    ("go", "github.com/gin-gonic/gin", "Gin"),
+    ("go", "github.com/labstack/echo", "Echo"),
+    ("go", "github.com/gofiber/fiber", "Fiber"),
+];
+
+const LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("py", "Python"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("swift", "Swift"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("dart", "Dart"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("lua", "Lua"),
+    ("zig", "Zig"),
+    ("sh", "Shell"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("html", "HTML"),
+    ("css", "CSS"),
+    ("scss", "SCSS"),
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReadme {
+    /// Relative to the project root.
+    pub path: String,
+    pub markdown: String,
+    /// Markdown rendered to HTML with everything escaped; raw HTML in the README shows
+    /// as text. Non-markdown READMEs come back as one `<pre>` block.
+    pub html: String,
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectLanguage {
+    pub name: String,
+    pub files: usize,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectManifest {
+    /// Relative to the project root.
+    pub path: String,
+    /// `npm`, `cargo`, `python`, `go`, `make`, ... (see `MANIFESTS`).
+    pub kind: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectScript {
+    pub name: String,
+    /// `npm` for package.json scripts, `make` for Makefile targets.
+    pub source: String,
+    /// The manifest that declares it, relative to the project root.
+    pub manifest: String,
+    /// The manifest's directory, where `run` has to be run.
+    pub cwd: String,
+    /// What the script does: the npm script, or the target's recipe.
+    pub command: Option<String>,
+    /// Shell command for a "run script" action, e.g. `pnpm run 'build'`.
+    pub run: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectOverview {
+    pub root: String,
+    pub readme: Option<ProjectReadme>,
+    /// By number of files, most first.
+    pub languages: Vec<ProjectLanguage>,
+    pub frameworks: Vec<String>,
+    pub manifests: Vec<ProjectManifest>,
+    pub scripts: Vec<ProjectScript>,
+    /// Stopped counting languages at the file limit.
+    pub truncated: bool,
+}
+
+fn skipped(name: &str) -> bool {
+    name.starts_with('.') || SKIPPED_DIRS.contains(&name)
+}
+
+fn read_readme(root: &Path) -> Option<ProjectReadme> {
+    let names: Vec<String> = fs::read_dir(root)
+        .ok()?
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    let name = README_NAMES
+        .iter()
+        .find_map(|wanted| names.iter().find(|n| n.eq_ignore_ascii_case(wanted)))?;
+    let bytes = fs::read(root.join(name)).ok()?;
+    let truncated = bytes.len() > MAX_README_BYTES;
+    let mut text = String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_README_BYTES)]).to_string();
+    if truncated {
+        // Drop a character cut in half at the limit.
+        text.truncate(text.trim_end_matches('\u{FFFD}').len());
+    }
+    let lower = name.to_lowercase();
+    let html = if lower.ends_with(".md") || lower.ends_with(".markdown") {
+        render_markdown(&text)
+    } else {
+        format!("<pre>{}</pre>", escape_html(&text))
+    };
+    Some(ProjectReadme {
+        path: name.clone(),
+        markdown: text,
+        html,
+        truncated,
+    })
+}
+
+/// File counts per language, skipping hidden and build directories.
+fn count_languages(root: &Path) -> (Vec<ProjectLanguage>, bool) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut scanned = 0;
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            if scanned >= MAX_SCANNED_FILES {
+                return (sorted_languages(counts), true);
+            }
+            if skipped(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            scanned += 1;
+            let ext = path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if let Some((_, language)) = LANGUAGES.iter().find(|(e, _)| *e == ext) {
+                *counts.entry(language).or_default() += 1;
+            }
+        }
+    }
+    (sorted_languages(counts), false)
+}
+
+fn sorted_languages(counts: HashMap<&str, usize>) -> Vec<ProjectLanguage> {
+    let mut languages: Vec<ProjectLanguage> = counts
+        .into_iter()
+        .map(|(name, files)| ProjectLanguage {
+            name: name.to_string(),
+            files,
+        })
+        .collect();
+    languages.sort_by(|a, b| b.files.cmp(&a.files).then_with(|| a.name.cmp(&b.name)));
+    languages
+}
+
+fn find_manifests(root: &Path) -> Vec<ProjectManifest> {
+    let mut manifests = Vec::new();
+    let mut dirs = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, depth)) = dirs.pop() {
+        for (file, kind) in MANIFESTS {
+            let path = dir.join(file);
+            if !path.is_file() {
+                continue;
+            }
+            if let Ok(rel) = path.strip_prefix(root) {
+                manifests.push(ProjectManifest {
+                    path: rel.to_string_lossy().to_string(),
+                    kind: kind.to_string(),
+                });
+            }
+        }
+        if depth >= MANIFEST_DEPTH {
+            continue;
+        }
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() && !skipped(&entry.file_name().to_string_lossy()) {
+                dirs.push((path, depth + 1));
+            }
+        }
+    }
+    manifests.sort_by(|a, b| a.path.cmp(&b.path));
+    manifests
+}
+
+/// Whether a requirements or TOML line declares `name`, e.g. `flask>=2` or `"django",`.
+fn declares(line: &str, name: &str) -> bool {
+    let line = line.trim().trim_start_matches(['"', '\'']).to_lowercase();
+    line.strip_prefix(name)
+        .is_some_and(|rest| !rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+}
+
+fn detect_frameworks(root: &Path, manifests: &[ProjectManifest]) -> Vec<String> {
+    let mut found = BTreeSet::new();
+    for manifest in manifests {
+        let Ok(raw) = fs::read_to_string(root.join(&manifest.path)) else {
+            continue;
+        };
+        let npm_deps: Vec<String> = if manifest.kind == "npm" {
+            serde_json::from_str::<serde_json::Value>(&raw)
+                .ok()
+                .map(|pkg| {
+                    ["dependencies", "devDependencies", "peerDependencies"]
+                        .iter()
+                        .filter_map(|key| pkg.get(key)?.as_object())
+                        .flat_map(|deps| deps.keys().cloned())
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        for (kind, dep, framework) in FRAMEWORKS {
+            if *kind != manifest.kind {
+                continue;
+            }
+            let hit = match manifest.kind.as_str() {
+                "npm" => npm_deps.iter().any(|d| d == dep),
+                "go" => raw.lines().any(|l| l.contains(dep)),
+                _ => raw.lines().any(|l| declares(l, dep)),
+            };
+            if hit {
+                found.insert(framework.to_string());
+            }
+        }
+    }
+    found.into_iter().collect()
+}
+
+/// The package manager a package.json directory uses, going by lockfiles there or at
+/// the root of a workspace.
+fn package_manager(dir: &Path, root: &Path) -> &'static str {
+    const LOCKFILES: &[(&str, &str)] = &[
+        ("pnpm-lock.yaml", "pnpm"),
+        ("yarn.lock", "yarn"),
+        ("bun.lock", "bun"),
+        ("bun.lockb", "bun"),
+        ("package-lock.json", "npm"),
+    ];
+    [dir, root]
+        .iter()
+        .find_map(|d| LOCKFILES.iter().find(|(f, _)| d.join(f).is_file()).map(|(_, pm)| *pm))
+        .unwrap_or("npm")
+}
+
+fn npm_scripts(package_json: &str) -> Vec<(String, String)> {
+    let Ok(pkg) = serde_json::from_str::<serde_json::Value>(package_json) else {
+        return Vec::new();
+    };
+    pkg.get("scripts")
+        .and_then(|s| s.as_object())
+        .map(|scripts| {
+            scripts
+                .iter()
+                .filter_map(|(name, cmd)| Some((name.clone(), cmd.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn make_target_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^([A-Za-z0-9][A-Za-z0-9_./-]*)\s*::?(?:[^=:]|$)").expect("valid make target regex")
+    })
+}
+
+/// Explicit targets in declaration order with their recipes. Pattern rules, special
+/// targets like `.PHONY` and variable assignments are left out.
+fn make_targets(makefile: &str) -> Vec<(String, Option<String>)> {
+    let mut targets: Vec<(String, Vec<String>)> = Vec::new();
+    let mut in_recipe = false;
+    for line in makefile.lines() {
+        if let Some(recipe) = line.strip_prefix('\t') {
+            if in_recipe {
+                if let Some((_, lines)) = targets.last_mut() {
+                    lines.push(recipe.trim().to_string());
+                }
+            }
+            continue;
+        }
+        in_recipe = false;
+        let Some(caps) = make_target_regex().captures(line) else {
+            continue;
+        };
+        let name = caps[1].to_string();
+        if !targets.iter().any(|(n, _)| *n == name) {
+            targets.push((name, Vec::new()));
+            in_recipe = true;
+        }
+    }
+    targets
+        .into_iter()
+        .map(|(name, lines)| {
+            let recipe = lines.into_iter().filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n");
+            (name, (!recipe.is_empty()).then_some(recipe))
+        })
+        .collect()
+}
+
+fn collect_scripts(root: &Path, manifests: &[ProjectManifest]) -> Vec<ProjectScript> {
+    let mut scripts = Vec::new();
+    for manifest in manifests {
+        let path = root.join(&manifest.path);
+        let Ok(raw) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let dir = path.parent().unwrap_or(root);
+        let cwd = dir.to_string_lossy().to_string();
+        match manifest.kind.as_str() {
+            "npm" => {
+                let pm = package_manager(dir, root);
+                for (name, command) in npm_scripts(&raw) {
+                    scripts.push(ProjectScript {
+                        run: format!("{pm} run {}", crate::ssh_fs::shell_escape_posix(&name)),
+                        name,
+                        source: "npm".to_string(),
+                        manifest: manifest.path.clone(),
+                        cwd: cwd.clone(),
+                        command: Some(command),
+                    });
+                }
+            }
+            "make" => {
+                for (name, recipe) in make_targets(&raw) {
+                    scripts.push(ProjectScript {
+                        run: format!("make {}", crate::ssh_fs::shell_escape_posix(&name)),
+                        name,
+                        source: "make".to_string(),
+                        manifest: manifest.path.clone(),
+                        cwd: cwd.clone(),
+                        command: recipe,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    scripts
+}
+
+/// Images (`![alt](src)`) and links (`[text](href)`), matched on the raw text in
+/// one pass so that no pattern ever sees HTML generated for another.
+fn link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(!?)\[([^\]]*)\]\(([^)\s]+)\)").expect("valid link regex"))
+}
+
+fn bold_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\*\*([^*<>]+)\*\*").expect("valid bold regex"))
+}
+
+/// Links and images only keep web, anchor and relative targets; `javascript:` and
+/// friends become plain text.
+fn safe_url(url: &str) -> bool {
+    let lower = url.to_lowercase();
+    ["http://", "https://", "mailto:", "#"].iter().any(|p| lower.starts_with(p))
+        || !lower.split('/').next().unwrap_or_default().contains(':')
+}
+
+/// Escaped text with `**bold**`; it only ever wraps text, never attributes.
+fn render_text(text: &str) -> String {
+    bold_regex()
+        .replace_all(&escape_html(text), "<strong>$1</strong>")
+        .to_string()
+}
+
+/// Images, links and bold in text outside code spans. Each captured part is
+/// escaped on its own.
+fn render_links(text: &str) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for caps in link_regex().captures_iter(text) {
+        let whole = caps.get(0).expect("regex match");
+        let (label, url) = (&caps[2], &caps[3]);
+        let is_image = !caps[1].is_empty();
+        if !safe_url(url) || (!is_image && label.is_empty()) {
+            continue;
+        }
+        out.push_str(&render_text(&text[last..whole.start()]));
+        if is_image {
+            out.push_str(&format!("<img src=\"{}\" alt=\"{}\">", escape_html(url), escape_html(label)));
+        } else {
+            out.push_str(&format!("<a href=\"{}\">{}</a>", escape_html(url), render_text(label)));
+        }
+        last = whole.end();
+    }
+    out.push_str(&render_text(&text[last..]));
+    out
+}
+
+/// Code spans, images, links and bold. Everything else is escaped text.
+fn render_inline(text: &str) -> String {
+    let parts: Vec<&str> = text.split('`').collect();
+    let mut out = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        // An unmatched trailing backtick stays literal.
+        if i % 2 == 1 && i + 1 < parts.len() {
+            out.push_str(&format!("<code>{}</code>", escape_html(part)));
+            continue;
+        }
+        if i % 2 == 1 {
+            out.push('`');
+        }
+        out.push_str(&render_links(part));
+    }
+    out
+}
+
+#[derive(Default)]
+struct MarkdownRenderer {
+    html: String,
+    paragraph: Vec<String>,
+    /// `ul` or `ol` while a list is open.
+    list: Option<&'static str>,
+}
+
+impl MarkdownRenderer {
+    fn close_paragraph(&mut self) {
+        if !self.paragraph.is_empty() {
+            let text = std::mem::take(&mut self.paragraph).join(" ");
+            self.html.push_str(&format!("<p>{}</p>\n", render_inline(&text)));
+        }
+    }
+
+    fn close_list(&mut self) {
+        if let Some(tag) = self.list.take() {
+            self.html.push_str(&format!("</{tag}>\n"));
+        }
+    }
+
+    fn close_blocks(&mut self) {
+        self.close_paragraph();
+        self.close_list();
+    }
+}
+
+fn list_item(line: &str) -> Option<(&'static str, &str)> {
+    if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|m| line.strip_prefix(m)) {
+        return Some(("ul", item));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let item = line[digits..].strip_prefix(". ")?;
+    (digits > 0).then_some(("ol", item))
+}
+
+/// A small subset of markdown, enough for a README on a dashboard: headings,
+/// paragraphs, lists, block quotes, rules, fenced code and the inline forms in
+/// `render_inline`.
+fn render_markdown(source: &str) -> String {
+    let mut r = MarkdownRenderer::default();
+    let mut fence: Option<(String, Vec<&str>)> = None;
+    for line in source.lines() {
+        if let Some((lang, code)) = fence.as_mut() {
+            if line.trim_start().starts_with("```") {
+                let class = if lang.is_empty() {
+                    String::new()
+                } else {
+                    format!(" class=\"language-{}\"", escape_html(lang))
+                };
+                let code = escape_html(&code.join("\n"));
+                r.html.push_str(&format!("<pre><code{class}>{code}</code></pre>\n"));
+                fence = None;
+            } else {
+                code.push(line);
+            }
+            continue;
+        }
+        let trimmed = line.trim();
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            r.close_blocks();
+            fence = Some((lang.trim().to_string(), Vec::new()));
+            continue;
+        }
+        if trimmed.is_empty() {
+            r.close_blocks();
+            continue;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            r.close_blocks();
+            let text = trimmed[level..].trim().trim_end_matches('#').trim();
+            r.html.push_str(&format!("<h{level}>{}</h{level}>\n", render_inline(text)));
+            continue;
+        }
+        if matches!(trimmed, "---" | "***" | "___") {
+            r.close_blocks();
+            r.html.push_str("<hr>\n");
+            continue;
+        }
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            r.close_blocks();
+            r.html.push_str(&format!("<blockquote><p>{}</p></blockquote>\n", render_inline(quote.trim())));
+            continue;
+        }
+        if let Some((tag, item)) = list_item(trimmed) {
+            r.close_paragraph();
+            if r.list != Some(tag) {
+                r.close_list();
+                r.html.push_str(&format!("<{tag}>\n"));
+                r.list = Some(tag);
+            }
+            r.html.push_str(&format!("<li>{}</li>\n", render_inline(item)));
+            continue;
+        }
+        r.close_list();
+        r.paragraph.push(trimmed.to_string());
+    }
+    if let Some((_, code)) = fence {
+        r.html.push_str(&format!("<pre><code>{}</code></pre>\n", escape_html(&code.join("\n"))));
+    }
+    r.close_blocks();
+    r.html
+}
+
+/// README, languages, frameworks, manifests and runnable scripts of a local project,
+/// for the project dashboard and its "run script" actions.
+#[tauri::command]
+pub async fn get_project_overview(root: String) -> Result<ProjectOverview, String> {
+    let root = root.trim().to_string();
+    if !Path::new(&root).is_dir() {
+        return Err("project root is not a directory".to_string());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = Path::new(&root);
+        let manifests = find_manifests(dir);
+        let (languages, truncated) = count_languages(dir);
+        ProjectOverview {
+            readme: read_readme(dir),
+            languages,
+            frameworks: detect_frameworks(dir, &manifests),
+            scripts: collect_scripts(dir, &manifests),
+            manifests,
+            truncated,
+            root,
+        }
+    })
+    .await
+    .map_err(|e| format!("project overview task join failed: {e:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_from_package_json_and_makefile() {
+        let scripts = npm_scripts(r#"{"scripts": {"build": "vite build", "bad": 1}}"#);
+        assert_eq!(scripts, vec![("build".to_string(), "vite build".to_string())]);
+
+        let makefile = ".PHONY: test\nCC := gcc\nall: build\nbuild:\n\tcargo build\n\n\t\n\
+                        %.o: %.c\n\t$(CC) $<\n\
+                        test:: build\n\tcargo test\n\tcargo clippy\n";
+        assert_eq!(
+            make_targets(makefile),
+            vec![
+                ("all".to_string(), None),
+                ("build".to_string(), Some("cargo build".to_string())),
+                ("test".to_string(), Some("cargo test\ncargo clippy".to_string())),
+            ]
+        );
+        assert!(declares("Flask>=2.0", "flask"));
+        assert!(!declares("flask-cors==4", "flask"));
+    }
+
+    #[test]
+    fn link_text_cannot_break_out_of_an_image() {
+        let html = render_inline("![x](nope/[y) z](onerror=window.onerror=alert;throw/**/1//)");
+        assert_eq!(html, "<img src=\"nope/[y\" alt=\"x\"> z](onerror=window.onerror=alert;throw/**/1//)");
+        let html = render_inline("[**a\" onmouseover=\"x**](https://e.com/?q=\"x\") ![\"](https://e.com/a.png)");
+        assert!(!html.contains("\" on"), "{html}");
+        assert!(html.starts_with("<a href=\"https://e.com/?q=&quot;x&quot;\"><strong>a&quot;"), "{html}");
+    }
+
+    #[test]
+    fn markdown_is_rendered_and_escaped() {
+        let html = render_markdown(
+            "# Title\n\nSome **bold** `<code>` and [docs](docs/a.md).\nMore.\n\n\
+             - one\n- [x](javascript:alert(1))\n\n\
+             ```sh\nnpm i <pkg>\n```\n<script>",
+        );
+        assert_eq!(
+            html,
+            "<h1>Title</h1>\n\
+             <p>Some <strong>bold</strong> <code>&lt;code&gt;</code> \
+             and <a href=\"docs/a.md\">docs</a>. More.</p>\n\
+             <ul>\n<li>one</li>\n<li>[x](javascript:alert(1))</li>\n</ul>\n\
+             <pre><code class=\"language-sh\">npm i &lt;pkg&gt;</code></pre>\n\
+             <p>&lt;script&gt;</p>\n"
+        );
+    }
+}
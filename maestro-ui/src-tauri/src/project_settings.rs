@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+const PROJECT_SETTINGS_FILE_NAME: &str = "project-settings-v1.json";
+
+/// Backend-only project settings. They live in their own file because the frontend
+/// rewrites state-v1.json from its own project model, which doesn't carry them.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSettingsV1 {
+    /// Where session recordings are stored. `None` keeps them in app data. A relative
+    /// dir (e.g. `.maestro/recordings`) is resolved against the project's base path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recording_dir: Option<String>,
+    /// Filesystem sandbox for sessions spawned in this project, unless `create_session`
+    /// is given its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<crate::sandbox::SandboxProfileV1>,
+    /// Prompts answered automatically in this project's sessions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_approve_rules: Vec<crate::auto_approve::AutoApproveRuleV1>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ProjectSettingsFileV1 {
+    #[serde(default)]
+    projects: HashMap<String, ProjectSettingsV1>,
+}
+
+fn settings_cache() -> &'static Mutex<Option<HashMap<String, ProjectSettingsV1>>> {
+    static CACHE: OnceLock<Mutex<Option<HashMap<String, ProjectSettingsV1>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn settings_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, PROJECT_SETTINGS_FILE_NAME)
+}
+
+fn read_settings(app: &AppHandle) -> Result<HashMap<String, ProjectSettingsV1>, String> {
+    let path = settings_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str::<ProjectSettingsFileV1>(&raw)
+            .map(|f| f.projects)
+            .map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn write_settings(app: &AppHandle, projects: &HashMap<String, ProjectSettingsV1>) -> Result<(), String> {
+    let path = settings_file_path(app)?;
+    crate::util::write_json_atomic(&path, &ProjectSettingsFileV1 {
+        projects: projects.clone(),
+    })
+}
+
+/// Trim values, drop blank ones and reject ones that can't work.
+fn normalize(settings: ProjectSettingsV1) -> Result<ProjectSettingsV1, String> {
+    let recording_dir = settings
+        .recording_dir
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty());
+    crate::auto_approve::validate_rules(&settings.auto_approve_rules)?;
+    Ok(ProjectSettingsV1 {
+        recording_dir,
+        sandbox: settings.sandbox,
+        auto_approve_rules: settings.auto_approve_rules,
+    })
+}
+
+fn with_projects<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut HashMap<String, ProjectSettingsV1>) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut cache = settings_cache().lock().map_err(|_| "project settings cache poisoned")?;
+    if cache.is_none() {
+        *cache = Some(read_settings(app)?);
+    }
+    f(cache.as_mut().ok_or("project settings cache missing")?)
+}
+
+/// Settings saved for `project_id`, or the defaults when it has none.
+pub(crate) fn settings_for(app: &AppHandle, project_id: &str) -> Result<ProjectSettingsV1, String> {
+    with_projects(app, |projects| Ok(projects.get(project_id).cloned().unwrap_or_default()))
+}
+
+/// `explicit` when given, otherwise the sandbox saved for `project_id`.
+fn sandbox_from(
+    projects: &HashMap<String, ProjectSettingsV1>,
+    project_id: Option<&str>,
+    explicit: Option<crate::sandbox::SandboxProfileV1>,
+) -> Option<crate::sandbox::SandboxProfileV1> {
+    explicit.or_else(|| projects.get(project_id?)?.sandbox.clone())
+}
+
+/// The sandbox a session in `project_id` runs under.
+pub(crate) fn sandbox_for(
+    app: &AppHandle,
+    project_id: Option<&str>,
+    explicit: Option<crate::sandbox::SandboxProfileV1>,
+) -> Result<Option<crate::sandbox::SandboxProfileV1>, String> {
+    let project_id = project_id.map(str::trim).filter(|p| !p.is_empty());
+    if explicit.is_some() || project_id.is_none() {
+        return Ok(explicit);
+    }
+    with_projects(app, |projects| Ok(sandbox_from(projects, project_id, explicit)))
+}
+
+#[tauri::command]
+pub fn get_project_settings(window: WebviewWindow, project_id: String) -> Result<ProjectSettingsV1, String> {
+    settings_for(window.app_handle(), &project_id)
+}
+
+/// Replace a project's settings; returns them as stored. Default settings remove
+/// the project's entry.
+#[tauri::command]
+pub fn set_project_settings(
+    window: WebviewWindow,
+    project_id: String,
+    settings: ProjectSettingsV1,
+) -> Result<ProjectSettingsV1, String> {
+    let project_id = project_id.trim().to_string();
+    if project_id.is_empty() {
+        return Err("project id is required".to_string());
+    }
+    let settings = normalize(settings)?;
+    let app = window.app_handle();
+    with_projects(app, |projects| {
+        if settings == ProjectSettingsV1::default() {
+            projects.remove(&project_id);
+        } else {
+            projects.insert(project_id.clone(), settings.clone());
+        }
+        write_settings(app, projects)
+    })?;
+    crate::auto_approve::invalidate(&project_id);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize, sandbox_from, ProjectSettingsV1};
+    use crate::sandbox::SandboxProfileV1;
+    use std::collections::HashMap;
+
+    #[test]
+    fn blank_settings_are_dropped() {
+        let settings = normalize(ProjectSettingsV1 {
+            recording_dir: Some("  .maestro/recordings ".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(settings.recording_dir.as_deref(), Some(".maestro/recordings"));
+
+        let blank = normalize(ProjectSettingsV1 {
+            recording_dir: Some("  ".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(blank, ProjectSettingsV1::default());
+    }
+
+    #[test]
+    fn explicit_sandbox_overrides_the_project_one() {
+        let project = SandboxProfileV1 {
+            read_paths: vec!["/opt/shared".to_string()],
+            ..Default::default()
+        };
+        let explicit = SandboxProfileV1 {
+            root: Some("/work/app".to_string()),
+            ..Default::default()
+        };
+        let projects = HashMap::from([(
+            "p1".to_string(),
+            ProjectSettingsV1 {
+                sandbox: Some(project.clone()),
+                ..Default::default()
+            },
+        )]);
+
+        assert_eq!(sandbox_from(&projects, Some("p1"), None), Some(project));
+        assert_eq!(sandbox_from(&projects, Some("p1"), Some(explicit.clone())), Some(explicit));
+        assert_eq!(sandbox_from(&projects, Some("p2"), None), None);
+        assert_eq!(sandbox_from(&projects, None, None), None);
+    }
+}
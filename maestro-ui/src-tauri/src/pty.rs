@@ -1,14 +1,17 @@
+use base64::Engine;
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{BufWriter, Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
-use tauri::{Emitter, State, WebviewWindow};
+use std::time::Instant;
+use tauri::{Emitter, Manager, State, WebviewWindow};
+
+use crate::util::now_epoch_ms;
 
 #[cfg(target_os = "macos")]
 #[derive(Default)]
@@ -22,6 +25,8 @@ struct LoginPathCache {
 struct AppStateInner {
     next_id: AtomicU64,
     sessions: Mutex<HashMap<String, PtySession>>,
+    /// Sessions waiting out a restart delay; closing one takes it out again.
+    pending_restarts: Mutex<HashSet<String>>,
     #[cfg(target_os = "macos")]
     login_path_cache: Mutex<LoginPathCache>,
 }
@@ -39,6 +44,41 @@ struct PtySession {
     child: Box<dyn portable_pty::Child + Send>,
     recording: Option<SessionRecording>,
     closing: bool,
+    is_shell: bool,
+    /// Created by an automation path; commands injected into it are checked
+    /// against the command policy (see command_policy.rs).
+    automated: bool,
+    /// Names of the custom env vars the session was created with (values are never kept).
+    env_keys: Vec<String>,
+    /// Job object enforcing resource limits; dropping it ends the process tree.
+    #[cfg(windows)]
+    job: Option<crate::limits::JobLimit>,
+    /// Set once a needs-input prompt has been reported; cleared by the next write.
+    awaiting_input: Arc<AtomicBool>,
+    /// The application has enabled bracketed paste; updated by the reader thread.
+    bracketed_paste: Arc<AtomicBool>,
+    /// Epoch ms of the last chunk read from the pty, updated by the reader thread.
+    last_output_at: Arc<AtomicU64>,
+    /// Directory name of the on-disk transcript (see transcript.rs).
+    transcript_key: String,
+    /// Command line typed so far, tracked for the exec guard (see exec_guard.rs).
+    typed_line: String,
+    /// The same for the command policy of an automated session (see command_policy.rs).
+    policy_line: String,
+    /// Backend and name of the tmux or zellij session this one is a client of; closing
+    /// it leaves that session running (see `detach_session`).
+    multiplexer: Option<(String, String)>,
+    /// Set while the active recording has an output track; read by the reader thread.
+    record_output: Arc<AtomicBool>,
+    /// Recent raw output, fed by the reader thread (see scrollback.rs).
+    scrollback: crate::scrollback::SharedScrollback,
+    start_cwd: Option<String>,
+    terminal: crate::term_features::ResolvedTerminal,
+    /// Directory last reported by the shell integration, updated by the reader thread.
+    reported_cwd: Arc<Mutex<Option<String>>>,
+    /// `MAESTRO_SESSION_ID` from the session env, which agents echo into their logs.
+    maestro_session_id: Option<String>,
+    started_at: u64,
 }
 
 struct SessionRecording {
@@ -49,33 +89,330 @@ struct SessionRecording {
     unflushed_bytes: usize,
     input_buffer: String,
     enc_key: Option<[u8; 32]>,
+    record_input: bool,
+    /// Output read since `output_pending_t`, written as one event (see `record_output`).
+    output_pending: String,
+    output_pending_t: u64,
 }
 
+/// Output chunks arriving within this window of the first one share an event.
+const RECORDING_OUTPUT_COALESCE_MS: u64 = 50;
+const RECORDING_OUTPUT_MAX_EVENT_BYTES: usize = 64 * 1024;
+
 #[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct SessionInfo {
     pub id: String,
     pub name: String,
     pub command: String,
     pub cwd: Option<String>,
+    /// Process group in the foreground of the terminal (unix only).
+    pub foreground_pid: Option<u32>,
+    pub foreground_command: Option<String>,
+    pub terminal: crate::term_features::ResolvedTerminal,
+}
+
+/// Point-in-time view of a session for status reporting (see status.rs).
+pub(crate) struct SessionActivity {
+    pub id: String,
+    pub name: String,
+    pub command: String,
+    pub is_shell: bool,
+    pub last_output_at: u64,
+    /// Classified by activity.rs; `None` before the session is registered there.
+    pub activity: Option<crate::activity::ActivityState>,
+    pub recording_id: Option<String>,
+    pub pid: Option<u32>,
+}
+
+/// What's needed to find a session's agent logs (see touched_files.rs).
+pub(crate) struct SessionLogContext {
+    pub cwd: Option<String>,
+    pub start_cwd: Option<String>,
+    pub maestro_session_id: Option<String>,
+    pub started_at: u64,
+}
+
+impl AppState {
+    /// Write to a session from the backend (auto-approvals). Automated sessions check
+    /// it against the command policy like any other input.
+    pub(crate) fn write_injected(&self, id: &str, data: &str) -> Result<(), String> {
+        self.write_backend_input(id, data, true)
+    }
+
+    /// Write backend input the command policy has already seen: bootstrap steps,
+    /// checked when the session was spawned.
+    pub(crate) fn write_prechecked(&self, id: &str, data: &str) -> Result<(), String> {
+        self.write_backend_input(id, data, false)
+    }
+
+    fn write_backend_input(&self, id: &str, data: &str, screen: bool) -> Result<(), String> {
+        let mut sessions = self.inner.sessions.lock().map_err(|_| "state poisoned")?;
+        let s = sessions.get_mut(id).ok_or("unknown session")?;
+        if s.closing {
+            return Err("session is closing".to_string());
+        }
+        if screen && s.automated {
+            crate::command_policy::screen_input(&mut s.policy_line, data)?;
+        }
+        s.writer
+            .write_all(data.as_bytes())
+            .map_err(|e| format!("write failed: {e}"))?;
+        s.writer.flush().ok();
+        Ok(())
+    }
+
+    /// Answer a command the exec guard held (see exec_guard.rs). Approving writes only
+    /// the held Enter; the input queued behind it then goes through the command policy
+    /// and the exec guard again from a fresh line, and may be held at the next match.
+    pub(crate) fn answer_held_exec(
+        &self,
+        window: &WebviewWindow,
+        id: &str,
+        answer: crate::exec_guard::ExecAnswer,
+    ) -> Result<(), String> {
+        let mut sessions = self.inner.sessions.lock().map_err(|_| "state poisoned")?;
+        let s = sessions.get_mut(id).ok_or("unknown session")?;
+        if s.closing {
+            return Err("session is closing".to_string());
+        }
+        let (enter, queued, is_user) = match answer {
+            crate::exec_guard::ExecAnswer::Approved { enter, queued, is_user } => (enter, queued, is_user),
+            crate::exec_guard::ExecAnswer::Rejected => ("\u{15}".to_string(), String::new(), false),
+        };
+        write_input(s, enter.as_bytes(), false)?;
+        s.typed_line.clear();
+        s.policy_line.clear();
+        if queued.is_empty() {
+            return Ok(());
+        }
+
+        if s.automated {
+            crate::command_policy::screen_input(&mut s.policy_line, &queued)?;
+        }
+        let guard_user_input = is_user && !s.automated;
+        let data = match crate::exec_guard::screen(window, &mut s.typed_line, &queued, guard_user_input) {
+            Some(held) => {
+                let before = held.before.clone();
+                crate::exec_guard::hold(window, id, held, is_user);
+                before
+            }
+            None => queued,
+        };
+        write_input(s, data.as_bytes(), false)
+    }
+
+    /// Answer a prompt on behalf of the user (see auto_approve.rs).
+    pub(crate) fn write_auto_response(&self, id: &str, response: &str) -> Result<(), String> {
+        self.write_injected(id, response)?;
+        let sessions = self.inner.sessions.lock().map_err(|_| "state poisoned")?;
+        if let Some(s) = sessions.get(id) {
+            s.awaiting_input.store(false, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Append pty output to the session's recording output track (reader thread).
+    fn record_output(&self, id: &str, data: &str) {
+        let Ok(mut sessions) = self.inner.sessions.lock() else {
+            return;
+        };
+        let Some(s) = sessions.get_mut(id) else {
+            return;
+        };
+        let Some(rec) = s.recording.as_mut() else {
+            return;
+        };
+        let t = rec.started_at.elapsed().as_millis() as u64;
+        if let Err(e) = buffer_recording_output(rec, t, data).and_then(|_| flush_recording(rec, false)) {
+            eprintln!("Failed to write recording output: {e}");
+            s.recording = None;
+            s.record_output.store(false, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn session_activity(&self) -> Result<Vec<SessionActivity>, String> {
+        let sessions = self.inner.sessions.lock().map_err(|_| "state poisoned")?;
+        Ok(sessions
+            .iter()
+            .filter(|(_, s)| !s.closing)
+            .map(|(id, s)| SessionActivity {
+                id: id.clone(),
+                name: s.name.clone(),
+                command: s.command.clone(),
+                is_shell: s.is_shell,
+                last_output_at: s.last_output_at.load(Ordering::Relaxed),
+                activity: crate::activity::state_of(id),
+                recording_id: s.recording.as_ref().map(|r| r.id.clone()),
+                pid: s.child.process_id(),
+            })
+            .collect())
+    }
+
+    pub(crate) fn transcript_key(&self, id: &str) -> Option<String> {
+        let sessions = self.inner.sessions.lock().ok()?;
+        sessions.get(id).map(|s| s.transcript_key.clone())
+    }
+
+    pub(crate) fn log_context(&self, id: &str) -> Option<SessionLogContext> {
+        let sessions = self.inner.sessions.lock().ok()?;
+        let s = sessions.get(id)?;
+        Some(SessionLogContext {
+            cwd: s.reported_cwd.lock().ok().and_then(|c| c.clone()),
+            start_cwd: s.start_cwd.clone(),
+            maestro_session_id: s.maestro_session_id.clone(),
+            started_at: s.started_at,
+        })
+    }
+
+    pub(crate) fn scrollback(&self, id: &str) -> Option<crate::scrollback::SharedScrollback> {
+        let sessions = self.inner.sessions.lock().ok()?;
+        sessions.get(id).map(|s| s.scrollback.clone())
+    }
+
+    /// Epoch ms of the session's latest output (0 before any); `None` once it's gone.
+    pub(crate) fn last_output_at(&self, id: &str) -> Option<u64> {
+        let sessions = self.inner.sessions.lock().ok()?;
+        sessions.get(id).map(|s| s.last_output_at.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn pty_size(&self, id: &str) -> Option<PtySize> {
+        let sessions = self.inner.sessions.lock().ok()?;
+        sessions.get(id).and_then(|s| s.master.get_size().ok())
+    }
+
+    pub(crate) fn resize(&self, id: &str, size: PtySize) -> Result<(), String> {
+        let sessions = self.inner.sessions.lock().map_err(|_| "state poisoned")?;
+        let s = sessions.get(id).ok_or("unknown session")?;
+        if s.closing {
+            return Ok(());
+        }
+        s.master.resize(size).map_err(|e| format!("resize failed: {e}"))
+    }
+
+    /// Sessions that are clients of the tmux or zellij session `name`, oldest first,
+    /// with their start time.
+    pub(crate) fn multiplexer_clients(&self, backend: &str, name: &str) -> Vec<(String, u64)> {
+        let Ok(sessions) = self.inner.sessions.lock() else {
+            return Vec::new();
+        };
+        let mut clients: Vec<(String, u64)> = sessions
+            .iter()
+            .filter(|(_, s)| !s.closing)
+            .filter(|(_, s)| s.multiplexer.as_ref().is_some_and(|(b, n)| b == backend && n == name))
+            .map(|(id, s)| (id.clone(), s.started_at))
+            .collect();
+        clients.sort_by_key(|(_, started_at)| *started_at);
+        clients
+    }
+
+    /// See `detach_session`.
+    pub(crate) fn detach(&self, id: &str) -> Result<(), String> {
+        let mut sessions = self.inner.sessions.lock().map_err(|_| "state poisoned")?;
+        let session = sessions.get_mut(id).ok_or("unknown session")?;
+        if session.multiplexer.is_none() {
+            return Err("session is not attached to a tmux or zellij session".to_string());
+        }
+        if session.closing {
+            return Ok(());
+        }
+        // Only the client goes; the multiplexer server keeps the session and its programs.
+        session.closing = true;
+        let _ = session.child.kill();
+        Ok(())
+    }
+
+    /// Drop a restart that is still waiting out its delay; its `pty-exit` follows.
+    fn cancel_restart(&self, id: &str) {
+        if let Ok(mut pending) = self.inner.pending_restarts.lock() {
+            pending.remove(id);
+        }
+    }
 }
 
 #[derive(Serialize, Clone)]
-struct PtyOutput {
-    id: String,
-    data: String,
+#[serde(rename_all = "camelCase")]
+pub struct SessionEnvSummary {
+    pub id: String,
+    pub keys: Vec<String>,
 }
 
 #[derive(Serialize, Clone)]
 struct PtyExit {
     id: String,
     exit_code: Option<u32>,
+    started_at: u64,
+    ended_at: u64,
+    duration_ms: u64,
+    /// Whether the process was ended by a signal rather than exiting normally.
+    signaled: bool,
+    signal: Option<String>,
+    /// Last lines of output (escape codes removed), oldest first.
+    last_lines: Vec<String>,
+}
+
+/// Lines of trailing output included in `pty-exit`.
+const EXIT_TAIL_LINES: usize = 20;
+/// Raw output kept by the reader thread to produce the exit tail.
+const EXIT_TAIL_BYTES: usize = 16 * 1024;
+
+#[cfg(target_family = "unix")]
+fn signal_name(signal: u32) -> Option<&'static str> {
+    Some(match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        6 => "SIGABRT",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        14 => "SIGALRM",
+        15 => "SIGTERM",
+        _ => return None,
+    })
+}
+
+/// (signaled, signal) for an exit status. Besides a real signal exit, a shell-style
+/// `128 + n` status counts as signal `n`, since agent commands run under `sh -c`.
+fn exit_signal(status: Option<&portable_pty::ExitStatus>) -> (bool, Option<String>) {
+    let Some(status) = status else {
+        return (false, None);
+    };
+    if let Some(desc) = status.to_string().strip_prefix("Terminated by ") {
+        return (true, Some(desc.to_string()));
+    }
+    #[cfg(target_family = "unix")]
+    if let Some(name) = status.exit_code().checked_sub(128).and_then(signal_name) {
+        return (true, Some(name.to_string()));
+    }
+    (false, None)
+}
+
+/// Last `EXIT_TAIL_LINES` non-empty lines of `tail`, with escape codes removed.
+fn exit_tail_lines(tail: &str) -> Vec<String> {
+    let text = crate::throttle::strip_ansi(tail);
+    let mut lines: Vec<String> = text
+        .lines()
+        .map(|l| l.rsplit('\r').next().unwrap_or(l).trim_end().to_string())
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    let skip = lines.len().saturating_sub(EXIT_TAIL_LINES);
+    lines.drain(..skip);
+    lines
 }
 
-fn now_epoch_ms() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+/// Render custom env vars for logs without exposing values: `KEY=<N chars>`.
+#[cfg(debug_assertions)]
+fn masked_env_summary(vars: &HashMap<String, String>) -> String {
+    let mut entries: Vec<String> = vars
+        .iter()
+        .filter(|(k, _)| valid_env_key(k))
+        .map(|(k, v)| format!("{}=<{} chars>", k.trim(), v.chars().count()))
+        .collect();
+    entries.sort();
+    entries.join(" ")
 }
 
 fn valid_env_key(key: &str) -> bool {
@@ -155,6 +492,21 @@ fn default_user_shell() -> String {
     }
 }
 
+/// PATH that new sessions start with: the login shell's PATH on macOS (GUI apps
+/// don't inherit it), the app's own PATH elsewhere.
+pub(crate) fn computed_login_path() -> Option<String> {
+    let base = std::env::var("PATH").ok().filter(|p| !p.trim().is_empty());
+    #[cfg(target_os = "macos")]
+    {
+        let base = base.unwrap_or_default();
+        login_shell_path(&default_user_shell(), &base).or_else(|| (!base.is_empty()).then_some(base))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        base
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn login_shell_path(shell: &str, base_path: &str) -> Option<String> {
     let shell_name = Path::new(shell)
@@ -273,26 +625,7 @@ fn login_shell_path(shell: &str, base_path: &str) -> Option<String> {
     None
 }
 
-#[derive(Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct PersistentSessionInfo {
-    pub persist_id: String,
-    pub session_name: String,
-}
-
-#[tauri::command]
-pub fn list_persistent_sessions(_window: WebviewWindow) -> Result<Vec<PersistentSessionInfo>, String> {
-    // Persistent sessions (tmux) have been removed. Always return empty.
-    Ok(Vec::new())
-}
-
-#[tauri::command]
-pub fn kill_persistent_session(_window: WebviewWindow, _persist_id: String) -> Result<(), String> {
-    // Persistent sessions (tmux) have been removed.
-    Err("persistent sessions are no longer supported".to_string())
-}
-
-fn write_recording_event(rec: &mut SessionRecording, t: u64, data: &str) -> Result<(), String> {
+fn write_recording_event(rec: &mut SessionRecording, t: u64, data: &str, output: bool) -> Result<(), String> {
     let data = match rec.enc_key.as_ref() {
         Some(key) => crate::secure::encrypt_string_with_key(
             key,
@@ -301,10 +634,12 @@ fn write_recording_event(rec: &mut SessionRecording, t: u64, data: &str) -> Resu
         )?,
         None => data.to_string(),
     };
-    let line = crate::recording::RecordingLineV1::Input(crate::recording::RecordingEventV1 {
-        t,
-        data,
-    });
+    let event = crate::recording::RecordingEventV1 { t, data };
+    let line = if output {
+        crate::recording::RecordingLineV1::Output(event)
+    } else {
+        crate::recording::RecordingLineV1::Input(event)
+    };
     let json = serde_json::to_string(&line).map_err(|e| format!("serialize failed: {e}"))?;
     rec.writer
         .write_all(json.as_bytes())
@@ -350,7 +685,7 @@ fn skip_osc(iter: &mut std::iter::Peekable<std::str::Chars<'_>>) {
     }
 }
 
-fn skip_escape_sequence(iter: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+pub(crate) fn skip_escape_sequence(iter: &mut std::iter::Peekable<std::str::Chars<'_>>) {
     match iter.peek().copied() {
         Some('[') => {
             iter.next();
@@ -373,8 +708,12 @@ fn skip_escape_sequence(iter: &mut std::iter::Peekable<std::str::Chars<'_>>) {
 }
 
 fn record_user_input(rec: &mut SessionRecording, data: &str) -> Result<(), String> {
+    if !rec.record_input {
+        return Ok(());
+    }
     let t = rec.started_at.elapsed().as_millis() as u64;
     let mut wrote_any = false;
+    write_pending_output(rec)?;
 
     let mut iter = data.chars().peekable();
     while let Some(ch) = iter.next() {
@@ -386,13 +725,13 @@ fn record_user_input(rec: &mut SessionRecording, data: &str) -> Result<(), Strin
                 }
                 let mut line = std::mem::take(&mut rec.input_buffer);
                 line.push('\r');
-                write_recording_event(rec, t, &line)?;
+                write_recording_event(rec, t, &line, false)?;
                 wrote_any = true;
             }
             '\n' => {
                 let mut line = std::mem::take(&mut rec.input_buffer);
                 line.push('\n');
-                write_recording_event(rec, t, &line)?;
+                write_recording_event(rec, t, &line, false)?;
                 wrote_any = true;
             }
             '\u{7f}' | '\u{8}' => {
@@ -408,7 +747,35 @@ fn record_user_input(rec: &mut SessionRecording, data: &str) -> Result<(), Strin
         }
     }
 
-    let should_flush = wrote_any
+    flush_recording(rec, wrote_any)
+}
+
+/// Coalesce pty output into fewer, larger events: a chunk joins the pending event
+/// unless that started more than `RECORDING_OUTPUT_COALESCE_MS` ago or is too big.
+fn buffer_recording_output(rec: &mut SessionRecording, t: u64, data: &str) -> Result<(), String> {
+    let stale = t.saturating_sub(rec.output_pending_t) >= RECORDING_OUTPUT_COALESCE_MS
+        || rec.output_pending.len() >= RECORDING_OUTPUT_MAX_EVENT_BYTES;
+    if !rec.output_pending.is_empty() && stale {
+        write_pending_output(rec)?;
+    }
+    if rec.output_pending.is_empty() {
+        rec.output_pending_t = t;
+    }
+    rec.output_pending.push_str(data);
+    Ok(())
+}
+
+/// Write buffered output so events stay in time order (before input, on stop/exit).
+fn write_pending_output(rec: &mut SessionRecording) -> Result<(), String> {
+    if rec.output_pending.is_empty() {
+        return Ok(());
+    }
+    let data = std::mem::take(&mut rec.output_pending);
+    write_recording_event(rec, rec.output_pending_t, &data, true)
+}
+
+fn flush_recording(rec: &mut SessionRecording, force: bool) -> Result<(), String> {
+    let should_flush = force
         || rec.unflushed_bytes >= 16 * 1024
         || rec.last_flush.elapsed().as_millis() >= 1500;
     if should_flush {
@@ -436,7 +803,7 @@ fn unique_name(existing: &HashMap<String, PtySession>, base: &str) -> String {
     }
 }
 
-fn decode_utf8_stream(carry: &mut Vec<u8>, chunk: &[u8]) -> String {
+pub(crate) fn decode_utf8_stream(carry: &mut Vec<u8>, chunk: &[u8]) -> String {
     if chunk.is_empty() {
         return String::new();
     }
@@ -476,7 +843,6 @@ fn decode_utf8_stream(carry: &mut Vec<u8>, chunk: &[u8]) -> String {
     out
 }
 
-#[cfg(target_family = "unix")]
 fn sh_single_quote(s: &str) -> String {
     let mut out = String::with_capacity(s.len() + 2);
     out.push('\'');
@@ -491,6 +857,12 @@ fn sh_single_quote(s: &str) -> String {
     out
 }
 
+/// Per-session ZDOTDIR holding the startup files from `write_zsh_startup_files`.
+#[cfg(target_family = "unix")]
+fn zsh_dotdir(id: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("agents-ui-zdotdir-{id}"))
+}
+
 #[cfg(target_family = "unix")]
 fn write_zsh_startup_files(temp_dir: &Path, orig_dir: &Path) -> Result<(), String> {
     let zshenv = temp_dir.join(".zshenv");
@@ -551,6 +923,48 @@ __agents_ui_emit_cwd
     Ok(())
 }
 
+/// Name of a running process, for the session list.
+fn process_name(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        fs::read_to_string(format!("/proc/{pid}/comm"))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+    #[cfg(all(target_family = "unix", not(target_os = "linux")))]
+    {
+        let pid = pid.to_string();
+        let out = Command::new("ps").args(["-o", "comm=", "-p", pid.as_str()]).output().ok()?;
+        let name = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        let name = name.rsplit('/').next().unwrap_or(&name).trim_start_matches('-').to_string();
+        (!name.is_empty()).then_some(name)
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Working directory of a process, where the OS exposes it cheaply (Linux).
+fn process_cwd(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        fs::read_link(format!("/proc/{pid}/cwd"))
+            .ok()
+            .map(|p| p.to_string_lossy().to_string())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Live sessions with their working directory and foreground process. The cwd is
+/// what the shell integration last reported, falling back to the foreground
+/// process's directory on Linux and then to the start directory.
 #[tauri::command]
 pub fn list_sessions(state: State<'_, AppState>) -> Result<Vec<SessionInfo>, String> {
     let sessions = state
@@ -558,109 +972,594 @@ pub fn list_sessions(state: State<'_, AppState>) -> Result<Vec<SessionInfo>, Str
         .sessions
         .lock()
         .map_err(|_| "state poisoned")?;
-    Ok(sessions
+    let mut out: Vec<(SessionInfo, Option<String>)> = sessions
         .iter()
-        .map(|(id, s)| SessionInfo {
-            id: id.clone(),
-            name: s.name.clone(),
-            command: s.command.clone(),
-            cwd: None,
+        .map(|(id, s)| {
+            #[cfg(target_family = "unix")]
+            let foreground_pid = s
+                .master
+                .process_group_leader()
+                .and_then(|pid| u32::try_from(pid).ok())
+                .or_else(|| s.child.process_id());
+            #[cfg(not(target_family = "unix"))]
+            let foreground_pid = s.child.process_id();
+            let info = SessionInfo {
+                id: id.clone(),
+                name: s.name.clone(),
+                command: s.command.clone(),
+                cwd: s.reported_cwd.lock().ok().and_then(|c| c.clone()),
+                foreground_pid,
+                foreground_command: None,
+                terminal: s.terminal.clone(),
+            };
+            (info, s.start_cwd.clone())
         })
-        .collect())
+        .collect();
+    drop(sessions);
+
+    // Looking up processes can spawn `ps`; do it without holding the session lock.
+    for (info, start_cwd) in &mut out {
+        if let Some(pid) = info.foreground_pid {
+            info.foreground_command = process_name(pid);
+            if info.cwd.is_none() {
+                info.cwd = process_cwd(pid);
+            }
+        }
+        if info.cwd.is_none() {
+            info.cwd = start_cwd.take();
+        }
+    }
+    Ok(out.into_iter().map(|(info, _)| info).collect())
 }
 
+/// Names of the custom env vars a session was started with. Values are never returned.
 #[tauri::command]
-pub fn create_session(
-    window: WebviewWindow,
-    state: State<'_, AppState>,
-    name: Option<String>,
-    command: Option<String>,
-    cwd: Option<String>,
-    cols: Option<u16>,
-    rows: Option<u16>,
-    env_vars: Option<HashMap<String, String>>,
-    persistent: Option<bool>,
-    persist_id: Option<String>,
-) -> Result<SessionInfo, String> {
-    // persistent and persist_id are accepted for API compatibility but ignored
-    let _ = persistent;
-    let _ = persist_id;
+pub fn get_session_env_summary(state: State<'_, AppState>, id: String) -> Result<SessionEnvSummary, String> {
+    let sessions = state
+        .inner
+        .sessions
+        .lock()
+        .map_err(|_| "state poisoned")?;
+    let s = sessions.get(&id).ok_or("unknown session")?;
+    Ok(SessionEnvSummary {
+        id,
+        keys: s.env_keys.clone(),
+    })
+}
 
-    #[cfg(target_family = "unix")]
-    let shell = default_user_shell();
-    #[cfg(not(target_family = "unix"))]
-    let shell = std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string());
+/// Key fragments whose values are masked by `get_session_env` unless revealed.
+const SECRET_ENV_MARKERS: &[&str] = &["KEY", "TOKEN", "SECRET", "PASSWORD", "PASSWD", "CREDENTIAL", "AUTH"];
 
-    let command = command.unwrap_or_default().trim().to_string();
-    let is_shell = command.is_empty();
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEnvVar {
+    pub key: String,
+    /// The value, or `<N chars>` when masked.
+    pub value: String,
+    pub masked: bool,
+}
 
-    let cwd = cwd
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .filter(|s| Path::new(s).is_dir())
-        .or_else(|| {
-            #[cfg(target_family = "unix")]
-            {
-                std::env::var("HOME").ok().filter(|s| Path::new(s).is_dir())
-            }
-            #[cfg(not(target_family = "unix"))]
-            {
-                std::env::var("USERPROFILE").ok().filter(|s| Path::new(s).is_dir())
-            }
-        });
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEnv {
+    pub id: String,
+    pub pid: u32,
+    pub vars: Vec<SessionEnvVar>,
+}
 
-    #[cfg(target_family = "unix")]
-    let (program, args, shown_command) = if is_shell {
-        (
-            shell.clone(),
-            vec!["-l".to_string()],
-            format!("{shell} -l"),
-        )
-    } else {
-        // When running a command, always use a POSIX-compatible shell (/bin/sh)
-        // because the command string uses POSIX syntax (;, $VAR, exec, etc.)
-        let posix_shell = if Path::new("/bin/bash").is_file() {
-            "/bin/bash".to_string()
-        } else {
-            "/bin/sh".to_string()
-        };
-        // Preserve injected environment variables for spawned Maestro sessions.
-        // Login shells can source profile files that overwrite env vars such as
-        // MAESTRO_MANIFEST_PATH and MAESTRO_SESSION_ID.
-        let shell_flag = if env_vars.is_some() { "-c" } else { "-lc" };
-        (
-            posix_shell.clone(),
-            vec![shell_flag.to_string(), command.clone()],
-            format!("{posix_shell} {shell_flag} {command}"),
-        )
-    };
+fn is_secret_env_key(key: &str) -> bool {
+    let upper = key.to_ascii_uppercase();
+    SECRET_ENV_MARKERS.iter().any(|m| upper.contains(m))
+}
 
+/// `KEY=value` pairs from a NUL-separated environ block.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_environ(raw: &[u8]) -> Vec<(String, String)> {
+    raw.split(|b| *b == 0)
+        .filter_map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            let (key, value) = entry.split_once('=')?;
+            valid_env_key(key).then(|| (key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Environment of a running process: /proc on Linux; on macOS `ps eww`, which
+/// appends the environment to the command line, so values containing spaces are cut.
+fn process_environ(pid: u32) -> Result<Vec<(String, String)>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let raw = fs::read(format!("/proc/{pid}/environ")).map_err(|e| format!("read environ failed: {e}"))?;
+        Ok(parse_environ(&raw))
+    }
+    #[cfg(all(target_family = "unix", not(target_os = "linux")))]
+    {
+        let output = Command::new("ps")
+            .args(["eww", "-o", "command=", "-p", &pid.to_string()])
+            .output()
+            .map_err(|e| format!("ps failed: {e}"))?;
+        if !output.status.success() {
+            return Err("ps failed: process not found".to_string());
+        }
+        let text = String::from_utf8_lossy(&output.stdout);
+        let mut vars: Vec<(String, String)> = text
+            .split_whitespace()
+            .rev()
+            .map_while(|token| {
+                let (key, value) = token.split_once('=')?;
+                valid_env_key(key).then(|| (key.to_string(), value.to_string()))
+            })
+            .collect();
+        vars.reverse();
+        Ok(vars)
+    }
     #[cfg(not(target_family = "unix"))]
-    let (program, args, shown_command) = if is_shell {
-        (shell.clone(), Vec::new(), shell.clone())
-    } else {
-        (
-            shell.clone(),
-            vec!["/C".to_string(), command.clone()],
-            format!("{shell} /C {command}"),
-        )
-    };
+    {
+        let _ = pid;
+        Err("reading a process environment is not supported on this platform".to_string())
+    }
+}
 
-    let size = PtySize {
-        rows: rows.unwrap_or(24),
-        cols: cols.unwrap_or(80),
-        pixel_width: 0,
-        pixel_height: 0,
+/// The environment the session's process actually runs with, read from the OS, so
+/// users can check which API keys and PATH reached the agent. Values of keys that
+/// look secret (`*KEY*`, `*TOKEN*`, ...) are masked unless `reveal_secrets` is set.
+#[tauri::command]
+pub fn get_session_env(
+    state: State<'_, AppState>,
+    id: String,
+    reveal_secrets: Option<bool>,
+) -> Result<SessionEnv, String> {
+    let pid = {
+        let sessions = state.inner.sessions.lock().map_err(|_| "state poisoned")?;
+        let s = sessions.get(&id).ok_or("unknown session")?;
+        s.child.process_id().ok_or("session has no process")?
     };
+    let reveal = reveal_secrets.unwrap_or(false);
+    let mut vars: Vec<SessionEnvVar> = process_environ(pid)?
+        .into_iter()
+        .map(|(key, value)| {
+            let masked = !reveal && is_secret_env_key(&key);
+            let value = if masked {
+                format!("<{} chars>", value.chars().count())
+            } else {
+                value
+            };
+            SessionEnvVar { key, value, masked }
+        })
+        .collect();
+    vars.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(SessionEnv { id, pid, vars })
+}
 
-    let pty_system = native_pty_system();
-    let pair = pty_system
-        .openpty(size)
-        .map_err(|e| format!("openpty failed: {e}"))?;
-
-    let id = state.inner.next_id.fetch_add(1, Ordering::Relaxed).to_string();
+/// One command line that sets `vars` in the given interactive shell.
+fn env_export_line(shell: &str, vars: &[(String, String)]) -> Result<String, String> {
+    let name = Path::new(shell)
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let line = match name.as_str() {
+        "fish" => vars
+            .iter()
+            .map(|(k, v)| format!("set -gx {k} '{}'", v.replace('\\', "\\\\").replace('\'', "\\'")))
+            .collect::<Vec<_>>()
+            .join("; "),
+        "pwsh" | "powershell" => vars
+            .iter()
+            .map(|(k, v)| format!("$env:{k} = '{}'", v.replace('\'', "''")))
+            .collect::<Vec<_>>()
+            .join("; "),
+        "cmd" => {
+            // `%` expands even inside quotes and there is no way to escape it at the prompt.
+            if vars.iter().any(|(_, v)| v.contains('%') || v.contains('"')) {
+                return Err("values containing % or \" can't be set in cmd.exe".to_string());
+            }
+            vars.iter()
+                .map(|(k, v)| format!("set \"{k}={v}\""))
+                .collect::<Vec<_>>()
+                .join(" & ")
+        }
+        _ => format!(
+            "export {}",
+            vars.iter()
+                .map(|(k, v)| format!("{k}={}", sh_single_quote(v)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+    };
+    Ok(line)
+}
 
-    eprintln!("[PTY] Creating session: id={}, command='{}', cwd={:?}", id, shown_command, cwd);
+/// Export variables into a running shell session by typing the shell's own set/export
+/// statement, so switching an environment profile doesn't need a new terminal. The
+/// line starts with a space, which keeps it out of history in bash (`ignorespace`) and
+/// zsh (`HIST_IGNORE_SPACE`). Only shell sessions are supported: an agent process
+/// can't pick up new variables. Returns the session's updated env summary.
+#[tauri::command]
+pub fn inject_env_into_session(
+    state: State<'_, AppState>,
+    id: String,
+    vars: HashMap<String, String>,
+) -> Result<SessionEnvSummary, String> {
+    let mut vars: Vec<(String, String)> = vars
+        .into_iter()
+        .map(|(k, v)| (k.trim().to_string(), v))
+        .collect();
+    if vars.is_empty() {
+        return Err("no variables to inject".to_string());
+    }
+    if let Some((key, _)) = vars.iter().find(|(k, _)| !valid_env_key(k)) {
+        return Err(format!("invalid variable name: {key}"));
+    }
+    if let Some((key, _)) = vars.iter().find(|(_, v)| v.chars().any(|c| c.is_control())) {
+        return Err(format!("value of {key} contains control characters"));
+    }
+    vars.sort();
+
+    let mut sessions = state
+        .inner
+        .sessions
+        .lock()
+        .map_err(|_| "state poisoned")?;
+    let s = sessions.get_mut(&id).ok_or("unknown session")?;
+    if s.closing {
+        return Err("session is closing".to_string());
+    }
+    if !s.is_shell {
+        return Err("variables can only be injected into shell sessions".to_string());
+    }
+    let shell = s.command.split_whitespace().next().unwrap_or_default();
+    let line = env_export_line(shell, &vars)?;
+    s.writer
+        .write_all(format!(" {line}\r").as_bytes())
+        .map_err(|e| format!("write failed: {e}"))?;
+    s.writer.flush().ok();
+
+    s.env_keys.extend(vars.into_iter().map(|(k, _)| k));
+    s.env_keys.sort();
+    s.env_keys.dedup();
+    Ok(SessionEnvSummary {
+        id,
+        keys: s.env_keys.clone(),
+    })
+}
+
+pub(crate) const EVENT_PTY_CREATE_PROGRESS: &str = "pty-create-progress";
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyCreateProgress {
+    /// The id the session will have once `create_session` returns.
+    pub id: String,
+    /// `starting`, `resolving-path` (macOS, first shell only, when the PATH saved by
+    /// an earlier launch is stale: probing the login shell, up to a few seconds) or
+    /// `spawning`.
+    pub stage: String,
+    pub at: u64,
+}
+
+fn emit_create_progress(window: &WebviewWindow, id: &str, stage: &str) {
+    let _ = window.emit(
+        EVENT_PTY_CREATE_PROGRESS,
+        PtyCreateProgress {
+            id: id.to_string(),
+            stage: stage.to_string(),
+            at: now_epoch_ms(),
+        },
+    );
+}
+
+/// What a session was created with, kept to respawn it under a restart policy.
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionRequest {
+    pub name: Option<String>,
+    pub command: Option<String>,
+    pub cwd: Option<String>,
+    pub cols: Option<u16>,
+    pub rows: Option<u16>,
+    pub env_vars: Option<HashMap<String, String>>,
+    pub persist_id: Option<String>,
+    pub automated: Option<bool>,
+    pub sandbox: Option<crate::sandbox::SandboxProfileV1>,
+    pub resource_limits: Option<crate::limits::ResourceLimitsV1>,
+    pub project_id: Option<String>,
+    pub bootstrap: Option<Vec<String>>,
+    pub terminal: Option<crate::term_features::TerminalFeaturesV1>,
+    pub idle_timeout_minutes: Option<u32>,
+    /// The command as asked for, when `command` wraps it in a tmux or zellij launcher.
+    /// The command policy judges this one.
+    #[serde(skip)]
+    pub policy_command: Option<String>,
+    /// Backend and name of the tmux or zellij session this one is a client of.
+    #[serde(skip)]
+    pub multiplexer: Option<(String, String)>,
+}
+
+/// `create_session`'s options: the session itself plus how it is run and restarted.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSessionOptions {
+    #[serde(flatten)]
+    pub(crate) session: SessionRequest,
+    /// Keep the session running in a multiplexer (see `backend`) after it is closed.
+    pub persistent: Option<bool>,
+    pub restart: Option<crate::restart::RestartPolicyV1>,
+    /// A zellij layout from `list_session_layouts`.
+    pub layout: Option<String>,
+    /// `tmux` or `zellij`, for persistent sessions.
+    pub backend: Option<String>,
+    /// The agent profile (effect id, e.g. `claude`) the session runs. Its saved
+    /// resource limits apply unless `resourceLimits` is given.
+    pub agent: Option<String>,
+}
+
+/// Runs off the invoke thread: the first shell probes the login shell's PATH, which
+/// can take seconds. Progress is reported as `pty-create-progress`.
+#[tauri::command]
+pub async fn create_session(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    options: CreateSessionOptions,
+) -> Result<SessionInfo, String> {
+    let CreateSessionOptions {
+        session: mut request,
+        persistent,
+        restart,
+        layout,
+        backend,
+        agent,
+    } = options;
+    // persistent needs a backend: "tmux", or "zellij", which runs the default layout unless
+    // another is given. Otherwise persist_id only names the transcript (and, for zellij
+    // attaches and layouts, the scrollback spool)
+    let backend = backend.map(|b| b.trim().to_ascii_lowercase()).filter(|b| !b.is_empty());
+    if let Some(backend) = backend.as_deref() {
+        crate::tmux_sessions::validate_backend(backend)?;
+    }
+    let persistent = persistent.unwrap_or(false);
+    let tmux = persistent && backend.as_deref() == Some("tmux");
+    let zellij = persistent && backend.as_deref() == Some("zellij");
+    if tmux && cfg!(not(target_family = "unix")) {
+        return Err("tmux sessions are only supported on unix".to_string());
+    }
+    // Checked here as well as in spawn_session so an unsupported request fails before
+    // any progress event, layout file or registry entry.
+    crate::bootstrap::normalize_steps(request.bootstrap.clone())?;
+    if let Some(policy) = restart.as_ref() {
+        crate::restart::validate(policy)?;
+    }
+    if let Some(minutes) = request.idle_timeout_minutes {
+        crate::idle::validate(minutes)?;
+    }
+    if request.resource_limits.is_none() {
+        if let Some(agent) = agent.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+            request.resource_limits = crate::limits::for_agent(window.app_handle(), agent)?;
+        }
+    }
+    let layout = layout
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .or_else(|| zellij.then(|| crate::zellij_layouts::DEFAULT_LAYOUT.to_string()));
+    if let Some(layout) = layout.as_deref() {
+        if cfg!(not(target_family = "unix")) {
+            return Err("session layouts need zellij, which is only supported on unix".to_string());
+        }
+        if tmux {
+            return Err("layouts run in zellij; they can't be combined with the tmux backend".to_string());
+        }
+        crate::zellij_layouts::validate(layout)?;
+    }
+    let features: Vec<&str> = [
+        ("sandbox", request.sandbox.is_some()),
+        ("resource-limits", request.resource_limits.is_some()),
+        ("bootstrap", request.bootstrap.as_ref().is_some_and(|steps| !steps.is_empty())),
+        ("restart-policy", restart.is_some()),
+        ("idle-timeout", request.idle_timeout_minutes.is_some()),
+        ("zellij-layout", layout.is_some() && !zellij),
+        ("zellij-persistence", zellij),
+        ("tmux-persistence", tmux),
+    ]
+    .into_iter()
+    .filter_map(|(feature, used)| used.then_some(feature))
+    .collect();
+    let command = request.command.clone();
+
+    let app_state = state.inner().clone();
+    let id = app_state.inner.next_id.fetch_add(1, Ordering::Relaxed).to_string();
+    emit_create_progress(&window, &id, "starting");
+    let info = tauri::async_runtime::spawn_blocking(move || {
+        if layout.is_some() || tmux {
+            // Judge the command before it's wrapped, and before a layout file or registry
+            // entry is written for it.
+            let command = request.command.clone().unwrap_or_default();
+            if request.automated.unwrap_or(false) && !command.trim().is_empty() {
+                crate::command_policy::check_automation_command(&window, command.trim())?;
+            }
+            request.policy_command = Some(command);
+        }
+        if let Some(layout) = layout.as_deref() {
+            // The agent runs in a pane of a zellij session laid out by a generated KDL file;
+            // the zellij persist id spools its output like an attached external session.
+            let session = crate::persistent_sessions::session_name(request.persist_id.as_deref(), &id);
+            let command = crate::zellij_layouts::prepare(
+                window.app_handle(),
+                layout,
+                &session,
+                request.command.as_deref(),
+            )?;
+            request.command = Some(command);
+            request.multiplexer = Some(("zellij".to_string(), session.clone()));
+            let persist_id = crate::scrollback_spool::zellij_persist_id(&session);
+            crate::persistent_sessions::register(window.app_handle(), "zellij", &session, &persist_id);
+            request.persist_id = Some(persist_id);
+        }
+        if tmux {
+            let session = crate::persistent_sessions::session_name(request.persist_id.as_deref(), &id);
+            let command = crate::tmux_sessions::attach_command(&session, request.command.as_deref())?;
+            let persist_id = request.persist_id.clone().unwrap_or_else(|| session.clone());
+            crate::persistent_sessions::register(window.app_handle(), "tmux", &session, &persist_id);
+            request.command = Some(command);
+            request.multiplexer = Some(("tmux".to_string(), session));
+        }
+        spawn_session(
+            window,
+            &app_state,
+            request,
+            Some(id),
+            restart.map(crate::restart::RestartState::new),
+        )
+    })
+    .await
+    .map_err(|e| format!("create session task join failed: {e:?}"))??;
+
+    crate::telemetry::record_session_started(command.as_deref());
+    for feature in features {
+        crate::telemetry::record_feature(feature);
+    }
+    Ok(info)
+}
+
+/// Start a session under `reuse_id` (allocated by `create_session`, or that of a
+/// session being restarted), or a fresh id.
+fn spawn_session(
+    window: WebviewWindow,
+    state: &AppState,
+    request: SessionRequest,
+    reuse_id: Option<String>,
+    restart: Option<crate::restart::RestartState>,
+) -> Result<SessionInfo, String> {
+    let restart = restart.map(|restart| (request.clone(), restart));
+    let SessionRequest {
+        name,
+        command,
+        cwd,
+        cols,
+        rows,
+        env_vars,
+        persist_id,
+        automated,
+        sandbox,
+        resource_limits,
+        project_id,
+        bootstrap,
+        terminal,
+        idle_timeout_minutes,
+        policy_command,
+        multiplexer,
+    } = request;
+    let automated = automated.unwrap_or(false);
+
+    #[cfg(target_family = "unix")]
+    let shell = default_user_shell();
+    #[cfg(not(target_family = "unix"))]
+    let shell = std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string());
+
+    let command = command.unwrap_or_default().trim().to_string();
+    let is_shell = command.is_empty();
+    if automated {
+        crate::command_policy::preload(&window)?;
+    }
+    let policy_command = policy_command.unwrap_or_else(|| command.clone());
+    if automated && !policy_command.trim().is_empty() {
+        crate::command_policy::check_automation_command(&window, policy_command.trim())?;
+    }
+    let bootstrap_steps = crate::bootstrap::normalize_steps(bootstrap)?;
+    let terminal = crate::term_features::resolve(terminal.as_ref())?;
+    let sandbox = crate::project_settings::sandbox_for(window.app_handle(), project_id.as_deref(), sandbox)?;
+    if automated {
+        for step in &bootstrap_steps {
+            crate::command_policy::check_automation_command(&window, step)?;
+        }
+    }
+
+    let cwd = cwd
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .filter(|s| Path::new(s).is_dir())
+        .or_else(|| {
+            #[cfg(target_family = "unix")]
+            {
+                std::env::var("HOME").ok().filter(|s| Path::new(s).is_dir())
+            }
+            #[cfg(not(target_family = "unix"))]
+            {
+                std::env::var("USERPROFILE").ok().filter(|s| Path::new(s).is_dir())
+            }
+        });
+
+    #[cfg(target_family = "unix")]
+    let (program, args, shown_command) = if is_shell {
+        (
+            shell.clone(),
+            vec!["-l".to_string()],
+            format!("{shell} -l"),
+        )
+    } else {
+        // When running a command, always use a POSIX-compatible shell (/bin/sh)
+        // because the command string uses POSIX syntax (;, $VAR, exec, etc.)
+        let posix_shell = if Path::new("/bin/bash").is_file() {
+            "/bin/bash".to_string()
+        } else {
+            "/bin/sh".to_string()
+        };
+        // Preserve injected environment variables for spawned Maestro sessions.
+        // Login shells can source profile files that overwrite env vars such as
+        // MAESTRO_MANIFEST_PATH and MAESTRO_SESSION_ID.
+        let shell_flag = if env_vars.is_some() { "-c" } else { "-lc" };
+        let script = if bootstrap_steps.is_empty() {
+            command.clone()
+        } else {
+            crate::bootstrap::wrap_agent_script(&bootstrap_steps, &command)
+        };
+        (
+            posix_shell.clone(),
+            vec![shell_flag.to_string(), script],
+            format!("{posix_shell} {shell_flag} {command}"),
+        )
+    };
+
+    #[cfg(not(target_family = "unix"))]
+    let (program, args, shown_command) = if is_shell {
+        (shell.clone(), Vec::new(), shell.clone())
+    } else {
+        (
+            shell.clone(),
+            vec!["/C".to_string(), command.clone()],
+            format!("{shell} /C {command}"),
+        )
+    };
+
+    let id = reuse_id.unwrap_or_else(|| state.inner.next_id.fetch_add(1, Ordering::Relaxed).to_string());
+
+    let (program, args) = match sandbox.as_ref() {
+        Some(profile) => {
+            #[cfg(target_family = "unix")]
+            let startup_dir = is_shell.then(|| zsh_dotdir(&id));
+            #[cfg(not(target_family = "unix"))]
+            let startup_dir: Option<PathBuf> = None;
+            crate::sandbox::wrap_command(profile, cwd.as_deref(), startup_dir.as_deref(), program, args)?
+        }
+        None => (program, args),
+    };
+    #[cfg(target_family = "unix")]
+    let (program, args) = match resource_limits.as_ref() {
+        Some(limits) => crate::limits::wrap_command(limits, program, args)?,
+        None => (program, args),
+    };
+
+    let size = PtySize {
+        rows: rows.unwrap_or(24),
+        cols: cols.unwrap_or(80),
+        pixel_width: 0,
+        pixel_height: 0,
+    };
+
+    let pty_system = native_pty_system();
+    let pair = pty_system
+        .openpty(size)
+        .map_err(|e| format!("openpty failed: {e}"))?;
+
+    eprintln!("[PTY] Creating session: id={}, command='{}', cwd={:?}", id, shown_command, cwd);
 
     let mut cmd = CommandBuilder::new(program);
     cmd.args(args);
@@ -669,17 +1568,32 @@ pub fn create_session(
         .map(|vars| vars.contains_key("PATH"))
         .unwrap_or(false);
 
+    let maestro_session_id = env_vars
+        .as_ref()
+        .and_then(|vars| vars.get("MAESTRO_SESSION_ID"))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    let mut env_keys: Vec<String> = Vec::new();
     if let Some(vars) = env_vars {
+        #[cfg(debug_assertions)]
+        if !vars.is_empty() {
+            eprintln!("[PTY] Session env: id={}, vars=[{}]", id, masked_env_summary(&vars));
+        }
         for (k, v) in vars {
             let key = k.trim();
             if !valid_env_key(key) {
                 continue;
             }
             cmd.env(key, v);
+            env_keys.push(key.to_string());
         }
     }
-    cmd.env("TERM", "xterm-256color");
-    cmd.env("COLORTERM", "truecolor");
+    env_keys.sort();
+    env_keys.dedup();
+    for (key, value) in crate::locale::session_vars(&env_keys) {
+        cmd.env(key, value);
+    }
+    crate::term_features::apply(&terminal, &mut cmd);
     #[cfg(target_family = "unix")]
     if cmd.get_env("SHELL").is_none() {
         cmd.env("SHELL", shell.clone());
@@ -725,7 +1639,16 @@ pub fn create_session(
                 if cache.initialized && cache.shell.as_deref() == Some(shell.as_str()) {
                     cache.path.clone()
                 } else {
-                    let computed = login_shell_path(&shell, &fallback_path);
+                    // Saved by an earlier launch unless the shell's startup files changed.
+                    let app = window.app_handle();
+                    let computed = crate::login_path_cache::load(app, &shell, &fallback_path).or_else(|| {
+                        emit_create_progress(&window, &id, "resolving-path");
+                        let computed = login_shell_path(&shell, &fallback_path);
+                        if let Some(path) = computed.as_deref() {
+                            crate::login_path_cache::store(app, &shell, &fallback_path, path);
+                        }
+                        computed
+                    });
                     cache.initialized = true;
                     cache.shell = Some(shell.clone());
                     cache.path = computed.clone();
@@ -808,7 +1731,7 @@ pub fn create_session(
                 .or_else(|| std::env::var("HOME").ok().filter(|s| Path::new(s).is_dir()));
 
             if let Some(orig_dotdir) = orig_dotdir {
-                let dotdir = Some(std::env::temp_dir().join(format!("agents-ui-zdotdir-{id}")));
+                let dotdir = Some(zsh_dotdir(&id));
 
                 if let Some(dotdir) = dotdir {
                     if fs::create_dir_all(&dotdir).is_ok()
@@ -821,11 +1744,26 @@ pub fn create_session(
         }
     }
 
+    emit_create_progress(&window, &id, "spawning");
     let child = pair
         .slave
         .spawn_command(cmd)
         .map_err(|e| format!("spawn failed: {e}"))?;
 
+    #[cfg(windows)]
+    let mut child = child;
+    #[cfg(windows)]
+    let job = match (resource_limits.as_ref(), child.process_id()) {
+        (Some(limits), Some(pid)) => match crate::limits::apply_to_process(limits, pid) {
+            Ok(job) => job,
+            Err(e) => {
+                let _ = child.kill();
+                return Err(e);
+            }
+        },
+        _ => None,
+    };
+
     let mut reader = pair
         .master
         .try_clone_reader()
@@ -846,6 +1784,14 @@ pub fn create_session(
     let base_trimmed = base_name.trim();
     let base_trimmed = if base_trimmed.is_empty() { "session" } else { base_trimmed };
     let final_name = unique_name(&sessions, base_trimmed);
+    let last_output_at = Arc::new(AtomicU64::new(0));
+    let record_output = Arc::new(AtomicBool::new(false));
+    let awaiting_input = Arc::new(AtomicBool::new(false));
+    let bracketed_paste = Arc::new(AtomicBool::new(false));
+    let scrollback = crate::scrollback::ScrollbackBuffer::shared();
+    let reported_cwd: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let started_at = now_epoch_ms();
+    let transcript_key = crate::transcript::transcript_key(persist_id.as_deref(), &id, started_at);
 
     sessions.insert(
         id.clone(),
@@ -857,28 +1803,164 @@ pub fn create_session(
             child,
             recording: None,
             closing: false,
+            is_shell,
+            automated,
+            env_keys,
+            #[cfg(windows)]
+            job,
+            awaiting_input: awaiting_input.clone(),
+            bracketed_paste: bracketed_paste.clone(),
+            last_output_at: last_output_at.clone(),
+            transcript_key: transcript_key.clone(),
+            typed_line: String::new(),
+            policy_line: String::new(),
+            multiplexer,
+            record_output: record_output.clone(),
+            scrollback: scrollback.clone(),
+            start_cwd: cwd.clone(),
+            terminal: terminal.clone(),
+            reported_cwd: reported_cwd.clone(),
+            maestro_session_id,
+            started_at,
         },
     );
     drop(sessions);
+    crate::activity::register(&window, &id, is_shell, awaiting_input.clone());
+    if let Some(minutes) = idle_timeout_minutes {
+        crate::idle::register(&window, &id, minutes);
+    }
+    crate::app_menu::refresh_dynamic_menus(&window);
+
+    let mut bootstrap = crate::bootstrap::BootstrapRunner::new(
+        id.clone(),
+        bootstrap_steps,
+        is_shell.then_some(shell.as_str()),
+    );
+    if let Some(runner) = bootstrap.as_mut() {
+        runner.start(&window, state);
+    }
 
     let id_for_thread = id.clone();
-    let state_for_thread = state.inner().clone();
+    let state_for_thread = state.clone();
+    let mut throttle = (!is_shell).then(|| crate::throttle::ThrottleDetector::new(id.clone(), &shown_command));
+    let mut needs_input = crate::needs_input::NeedsInputDetector::new(id.clone(), awaiting_input);
+    let mut auto_approver = crate::auto_approve::AutoApprover::new(project_id);
+    let mut transcript = crate::transcript::TranscriptWriter::open(&window, &transcript_key);
+    let mut spool = crate::scrollback_spool::SpoolWriter::open(&window, &id, persist_id.as_deref());
+    // Shell sessions report their cwd via OSC 1337 once the prompt hooks run.
+    let mut shell_integration_pending = is_shell;
+    let shell_for_thread = shell.clone();
     std::thread::spawn(move || {
         let mut buf = [0u8; 8192];
         let mut utf8_carry: Vec<u8> = Vec::new();
+        let mut exit_tail = String::new();
+        let mut osc = crate::osc::OscScanner::default();
+        let mut paste_modes = crate::paste_guard::BracketedPasteTracker::default();
+        let mut last_title: Option<String> = None;
+        let mut bell = crate::bell::BellDetector::new(id_for_thread.clone());
+        let output = crate::output_batch::OutputBatcher::spawn(window.clone(), id_for_thread.clone());
         loop {
             match reader.read(&mut buf) {
                 Ok(0) => break,
                 Ok(n) => {
+                    last_output_at.store(now_epoch_ms(), Ordering::Relaxed);
+                    crate::idle::touch(&id_for_thread);
+                    if let Some(t) = transcript.as_mut() {
+                        t.feed(&buf[..n]);
+                    }
+                    if let Ok(mut scrollback) = scrollback.lock() {
+                        scrollback.push(&buf[..n]);
+                    }
                     let data = decode_utf8_stream(&mut utf8_carry, &buf[..n]);
+                    if let Some(s) = spool.as_mut() {
+                        s.feed(&data);
+                    }
+                    if record_output.load(Ordering::Relaxed) && !data.is_empty() {
+                        state_for_thread.record_output(&id_for_thread, &data);
+                    }
+                    if let Some(event) = throttle.as_mut().and_then(|t| t.feed(&data)) {
+                        let _ = window.emit(crate::throttle::EVENT_AGENT_THROTTLED, event);
+                    }
+                    if let Some(runner) = bootstrap.as_mut() {
+                        runner.feed(&window, &state_for_thread, &data);
+                    }
+                    for seq in osc.feed(&data) {
+                        match crate::osc::shell_marker(&seq) {
+                            Some(crate::osc::ShellMarker::CurrentDir(dir)) if is_shell && !dir.is_empty() => {
+                                if shell_integration_pending {
+                                    shell_integration_pending = false;
+                                    crate::onboarding::mark_step_completed(
+                                        &window,
+                                        crate::onboarding::STEP_SHELL_INTEGRATION_VERIFIED,
+                                        Some(shell_for_thread.clone()),
+                                    );
+                                }
+                                let changed = match reported_cwd.lock() {
+                                    Ok(mut cwd) if cwd.as_deref() != Some(dir.as_str()) => {
+                                        *cwd = Some(dir.clone());
+                                        true
+                                    }
+                                    _ => false,
+                                };
+                                if changed {
+                                    let _ = window.emit(
+                                        crate::osc::EVENT_PTY_CWD,
+                                        crate::osc::PtyCwd {
+                                            id: id_for_thread.clone(),
+                                            cwd: dir,
+                                        },
+                                    );
+                                }
+                                continue;
+                            }
+                            // The empty marker only says the previous command finished.
+                            Some(crate::osc::ShellMarker::Command(command)) if is_shell && !command.is_empty() => {
+                                let _ = window.emit(
+                                    crate::osc::EVENT_PTY_COMMAND,
+                                    crate::osc::PtyCommand {
+                                        id: id_for_thread.clone(),
+                                        command,
+                                        at: now_epoch_ms(),
+                                    },
+                                );
+                                continue;
+                            }
+                            Some(_) => continue,
+                            None => {}
+                        }
+                        let Some(title) = crate::osc::title_from(&seq) else {
+                            continue;
+                        };
+                        if last_title.as_deref() != Some(title.as_str()) {
+                            last_title = Some(title.clone());
+                            let _ = window.emit(
+                                crate::osc::EVENT_PTY_TITLE,
+                                crate::osc::PtyTitle {
+                                    id: id_for_thread.clone(),
+                                    title,
+                                },
+                            );
+                        }
+                    }
+                    bell.feed(&window, osc.take_bells());
+                    if let Some(enabled) = paste_modes.feed(&data) {
+                        bracketed_paste.store(enabled, Ordering::Relaxed);
+                    }
+                    if let Some(prompt) = needs_input.feed(&window, &data) {
+                        auto_approver.handle(&window, &state_for_thread, prompt);
+                    }
+                    crate::activity::feed(&id_for_thread, &data);
                     if !data.is_empty() {
-                        let _ = window.emit(
-                            "pty-output",
-                            PtyOutput {
-                                id: id_for_thread.clone(),
-                                data,
-                            },
-                        );
+                        exit_tail.push_str(&data);
+                        if exit_tail.len() > EXIT_TAIL_BYTES * 2 {
+                            let mut cut = exit_tail.len() - EXIT_TAIL_BYTES;
+                            while !exit_tail.is_char_boundary(cut) {
+                                cut += 1;
+                            }
+                            exit_tail.drain(..cut);
+                        }
+                        crate::share::feed(&id_for_thread, &data);
+                        output.push(data);
                     }
                 }
                 Err(_) => break,
@@ -888,29 +1970,70 @@ pub fn create_session(
         if !utf8_carry.is_empty() {
             let data = String::from_utf8_lossy(&utf8_carry).to_string();
             if !data.is_empty() {
-                let _ = window.emit(
-                    "pty-output",
-                    PtyOutput {
-                        id: id_for_thread.clone(),
-                        data,
-                    },
-                );
+                output.push(data);
             }
         }
+        output.finish();
+
+        drop(transcript);
+        drop(spool);
+        crate::share::session_closed(&id_for_thread);
+        crate::exec_guard::session_closed(&id_for_thread);
+        crate::activity::session_closed(&id_for_thread);
+        crate::idle::session_closed(&id_for_thread);
 
         let session = match state_for_thread.inner.sessions.lock() {
             Ok(mut sessions) => sessions.remove(&id_for_thread),
             Err(_) => None,
         };
-
-        let exit_code = session
-            .and_then(|mut s| s.child.wait().ok().map(|status| status.exit_code()));
+        crate::app_menu::refresh_dynamic_menus(&window);
+
+        // Restart with the name and size the session has now.
+        let restart = match &session {
+            Some(s) if !s.closing => restart.map(|(mut request, restart)| {
+                request.name = Some(s.name.clone());
+                if let Ok(size) = s.master.get_size() {
+                    request.cols = Some(size.cols);
+                    request.rows = Some(size.rows);
+                }
+                (request, restart)
+            }),
+            _ => None,
+        };
+        let status = session.and_then(|mut s| {
+            if let Some(rec) = s.recording.as_mut() {
+                if let Err(e) = write_pending_output(rec).and_then(|_| flush_recording(rec, true)) {
+                    eprintln!("Failed to finish recording: {e}");
+                }
+            }
+            s.child.wait().ok()
+        });
+        let (signaled, signal) = exit_signal(status.as_ref());
+        let ended_at = now_epoch_ms();
+        let exit_code = status.as_ref().map(|status| status.exit_code());
+
+        if let Some((request, mut restart)) = restart {
+            let failed = status.as_ref().is_some_and(|status| !status.success());
+            let delay = failed.then(|| restart.next_delay(ended_at.saturating_sub(started_at))).flatten();
+            if let Some(delay) = delay {
+                let state = &state_for_thread;
+                if restart_session(&window, state, &id_for_thread, request, restart, delay, exit_code) {
+                    return;
+                }
+            }
+        }
 
         let _ = window.emit(
             "pty-exit",
             PtyExit {
                 id: id_for_thread,
                 exit_code,
+                started_at,
+                ended_at,
+                duration_ms: ended_at.saturating_sub(started_at),
+                signaled,
+                signal,
+                last_lines: exit_tail_lines(&exit_tail),
             },
         );
     });
@@ -920,6 +2043,104 @@ pub fn create_session(
         name: final_name,
         command: shown_command,
         cwd,
+        foreground_pid: None,
+        foreground_command: None,
+        terminal,
+    })
+}
+
+/// Respawn a session that exited non-zero under the same id once `delay` has
+/// passed. Returns false if it was closed while waiting or failed to start again,
+/// in which case the exit is reported as usual.
+fn restart_session(
+    window: &WebviewWindow,
+    state: &AppState,
+    id: &str,
+    request: SessionRequest,
+    restart: crate::restart::RestartState,
+    delay: std::time::Duration,
+    exit_code: Option<u32>,
+) -> bool {
+    if let Ok(mut pending) = state.inner.pending_restarts.lock() {
+        pending.insert(id.to_string());
+    }
+    std::thread::sleep(delay);
+    let still_wanted = state
+        .inner
+        .pending_restarts
+        .lock()
+        .map(|mut pending| pending.remove(id))
+        .unwrap_or(false);
+    if !still_wanted {
+        return false;
+    }
+    eprintln!("[PTY] Restarting session: id={id}, attempt={}", restart.attempts);
+    match spawn_session(window.clone(), state, request, Some(id.to_string()), Some(restart)) {
+        Ok(_) => {
+            let _ = window.emit(
+                crate::restart::EVENT_PTY_RESTARTED,
+                crate::restart::PtyRestarted {
+                    id: id.to_string(),
+                    attempt: restart.attempts,
+                    max_restarts: restart.policy.max_restarts,
+                    exit_code,
+                    delay_ms: delay.as_millis() as u64,
+                },
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("[PTY] Restart failed: id={id}: {e}");
+            false
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionOutputSearch {
+    pub matches: Vec<crate::scrollback::SessionOutputMatch>,
+    /// Output offset where the searched text starts; older output is no longer kept.
+    pub from: u64,
+    /// More matches exist than `max_results`.
+    pub truncated: bool,
+}
+
+/// Search the session's recent output (the scrollback buffer, 512 KiB) for `query`,
+/// as a regex when `regex` is set. Matching ignores case unless `case_sensitive` is set.
+#[tauri::command]
+pub fn search_session_output(
+    state: State<'_, AppState>,
+    id: String,
+    query: String,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    max_results: Option<usize>,
+) -> Result<SessionOutputSearch, String> {
+    if query.is_empty() {
+        return Err("empty search query".to_string());
+    }
+    let pattern = if regex.unwrap_or(false) {
+        query
+    } else {
+        regex::escape(&query)
+    };
+    let re = regex::RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive.unwrap_or(false))
+        .size_limit(1 << 20)
+        .build()
+        .map_err(|e| format!("invalid pattern: {e}"))?;
+
+    let buffer = state.scrollback(&id).ok_or("unknown session")?;
+    let (output, from) = buffer
+        .lock()
+        .map_err(|_| "scrollback poisoned")?
+        .tail(crate::scrollback::SCROLLBACK_BYTES);
+    let (matches, truncated) = crate::scrollback::search_lines(&output, &re, max_results.unwrap_or(200).clamp(1, 5000));
+    Ok(SessionOutputSearch {
+        matches,
+        from,
+        truncated,
     })
 }
 
@@ -936,8 +2157,32 @@ pub fn start_session_recording(
     cwd: Option<String>,
     effect_id: Option<String>,
     bootstrap_command: Option<String>,
+    tracks: Option<Vec<String>>,
 ) -> Result<String, String> {
     let safe_id = crate::recording::sanitize_recording_id(&recording_id);
+    let mut tracks: Vec<String> = tracks
+        .unwrap_or_else(|| vec![crate::recording::TRACK_INPUT.to_string()])
+        .into_iter()
+        .map(|t| t.trim().to_ascii_lowercase())
+        .collect();
+    tracks.sort();
+    tracks.dedup();
+    if tracks.is_empty() {
+        return Err("at least one recording track is required".to_string());
+    }
+    if let Some(unknown) = tracks
+        .iter()
+        .find(|t| t.as_str() != crate::recording::TRACK_INPUT && t.as_str() != crate::recording::TRACK_OUTPUT)
+    {
+        return Err(format!("unknown recording track: {unknown}"));
+    }
+    let record_input = tracks.iter().any(|t| t == crate::recording::TRACK_INPUT);
+    let record_output = tracks.iter().any(|t| t == crate::recording::TRACK_OUTPUT);
+    // The project's recording dir setting, or app data when it has none.
+    let storage_dir = crate::project_settings::settings_for(window.app_handle(), &project_id)?.recording_dir;
+    let base_path = storage_dir
+        .as_ref()
+        .and_then(|_| crate::persist::read_project_base_path(&window, &project_id));
     let encrypt_enabled = encrypt.unwrap_or(true);
     let enc_key = if encrypt_enabled {
         Some(crate::secure::get_or_create_master_key(&window)?)
@@ -956,7 +2201,12 @@ pub fn start_session_recording(
         return Err("already recording".to_string());
     }
 
-    let path = crate::recording::recording_file_path(&window, &safe_id)?;
+    let path = crate::recording::prepare_recording_path(
+        &window,
+        &safe_id,
+        storage_dir.as_deref(),
+        base_path.as_deref(),
+    )?;
     let dir = path.parent().ok_or("invalid recording path")?;
     fs::create_dir_all(dir).map_err(|e| format!("create dir failed: {e}"))?;
 
@@ -988,8 +2238,17 @@ pub fn start_session_recording(
         effect_id,
         bootstrap_command,
         encrypted: Some(encrypt_enabled),
+        tracks: Some(
+            tracks
+                .iter()
+                .map(|id| crate::recording::RecordingTrackV1 {
+                    format: if id == crate::recording::TRACK_OUTPUT { "raw" } else { "lines" }.to_string(),
+                    id: id.clone(),
+                })
+                .collect(),
+        ),
     };
-    let line = crate::recording::RecordingLineV1::Meta(meta);
+    let line = crate::recording::RecordingLineV1::Meta(meta.clone());
     let json = serde_json::to_string(&line).map_err(|e| format!("serialize failed: {e}"))?;
     writer
         .write_all(json.as_bytes())
@@ -997,6 +2256,10 @@ pub fn start_session_recording(
     writer.write_all(b"\n").map_err(|e| format!("write failed: {e}"))?;
     writer.flush().map_err(|e| format!("flush failed: {e}"))?;
 
+    if let Err(e) = crate::recording::index_recording(&window, &safe_id, &path, meta) {
+        eprintln!("Failed to index recording {safe_id}: {e}");
+    }
+
     s.recording = Some(SessionRecording {
         id: safe_id.clone(),
         writer,
@@ -1005,7 +2268,11 @@ pub fn start_session_recording(
         unflushed_bytes: 0,
         input_buffer: String::new(),
         enc_key,
+        record_input,
+        output_pending: String::new(),
+        output_pending_t: 0,
     });
+    s.record_output.store(record_output, Ordering::Relaxed);
 
     Ok(safe_id)
 }
@@ -1019,21 +2286,85 @@ pub fn stop_session_recording(state: State<'_, AppState>, id: String) -> Result<
         .map_err(|_| "state poisoned")?;
     let s = sessions.get_mut(&id).ok_or("unknown session")?;
 
+    s.record_output.store(false, Ordering::Relaxed);
     let mut rec = match s.recording.take() {
         Some(r) => r,
         None => return Ok(None),
     };
+    write_pending_output(&mut rec)?;
     rec.writer.flush().map_err(|e| format!("flush failed: {e}"))?;
     Ok(Some(rec.id))
 }
 
 #[tauri::command]
 pub fn write_to_session(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    id: String,
+    data: String,
+    source: Option<String>,
+) -> Result<(), String> {
+    let mut sessions = state
+        .inner
+        .sessions
+        .lock()
+        .map_err(|_| "state poisoned")?;
+    let s = sessions.get_mut(&id).ok_or("unknown session")?;
+    if s.closing {
+        return Ok(());
+    }
+    crate::idle::touch(&id);
+
+    // Pastes count as user input but are cleaned first (see paste_guard.rs).
+    let is_paste = source.as_deref() == Some("paste");
+    let is_user = is_paste || source.as_deref() == Some("user");
+    let data = if is_paste {
+        crate::paste_guard::enforce(&window, &data)?
+    } else {
+        data
+    };
+    // Input behind a held command is screened once it's answered (`answer_held_exec`).
+    if crate::exec_guard::queue_if_pending(&id, &data) {
+        return Ok(());
+    }
+    // `source` comes from the caller, so it earns an automated session no exemption.
+    if s.automated {
+        crate::command_policy::screen_input(&mut s.policy_line, &data)?;
+    }
+    // A guarded command is typed out but its Enter waits for confirm_session_exec.
+    let held = crate::exec_guard::screen(&window, &mut s.typed_line, &data, is_user && !s.automated);
+    let data = match held {
+        Some(held) => {
+            let before = held.before.clone();
+            crate::exec_guard::hold(&window, &id, held, is_user);
+            before
+        }
+        None => data,
+    };
+    write_input(s, data.as_bytes(), is_user)
+}
+
+/// `write_to_session` for raw bytes, base64-encoded, such as a zmodem transfer or key
+/// sequences that aren't valid UTF-8. Input that is valid UTF-8 goes through
+/// `write_to_session` unchanged. Anything else is written as is, which automated
+/// sessions refuse whatever its source (their command policy can't inspect it) and
+/// which waits for no exec guard: it fails while a command is held for confirmation.
+#[tauri::command]
+pub fn write_bytes_to_session(
+    window: WebviewWindow,
     state: State<'_, AppState>,
     id: String,
     data: String,
     source: Option<String>,
 ) -> Result<(), String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|e| format!("base64 decode failed: {e}"))?;
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => return write_to_session(window, state, id, text, source),
+        Err(e) => e.into_bytes(),
+    };
+
     let mut sessions = state
         .inner
         .sessions
@@ -1043,53 +2374,168 @@ pub fn write_to_session(
     if s.closing {
         return Ok(());
     }
+    let is_user = matches!(source.as_deref(), Some("user") | Some("paste"));
+    if s.automated {
+        return Err("automated sessions only accept UTF-8 input".to_string());
+    }
+    if crate::exec_guard::has_pending(&id) {
+        return Err("a command is waiting for confirmation".to_string());
+    }
+    crate::idle::touch(&id);
+    // The shell's line can't be followed through binary input.
+    s.typed_line.clear();
+    write_input(s, &bytes, is_user)
+}
 
+/// Write input to the pty, clearing needs-input and recording it when it's the user's.
+fn write_input(s: &mut PtySession, data: &[u8], is_user: bool) -> Result<(), String> {
     s.writer
-        .write_all(data.as_bytes())
+        .write_all(data)
         .map_err(|e| format!("write failed: {e}"))?;
     s.writer.flush().ok();
+    s.awaiting_input.store(false, Ordering::Relaxed);
 
-    let is_user = source.as_deref() == Some("user");
     if is_user {
         let mut rec_err: Option<String> = None;
         if let Some(rec) = s.recording.as_mut() {
-            if let Err(e) = record_user_input(rec, &data) {
+            if let Err(e) = record_user_input(rec, &String::from_utf8_lossy(data)) {
                 rec_err = Some(e);
             }
         }
         if let Some(err) = rec_err {
             eprintln!("Failed to write recording event: {err}");
             s.recording = None;
+            s.record_output.store(false, Ordering::Relaxed);
         }
     }
     Ok(())
 }
 
+/// Paste text into a session. When the application has turned on bracketed paste the
+/// text goes in as one bracketed paste, so a multi-line prompt reaches an agent as a
+/// single input instead of running line by line; otherwise it's cleaned like any
+/// paste (see paste_guard.rs). Unsafe control characters are stripped either way.
 #[tauri::command]
-pub fn resize_session(
+pub fn paste_to_session(
+    window: WebviewWindow,
     state: State<'_, AppState>,
     id: String,
-    cols: u16,
-    rows: u16,
-) -> Result<(), String> {
-    let sessions = state
+    text: String,
+) -> Result<crate::paste_guard::SessionPaste, String> {
+    let mut sessions = state
         .inner
         .sessions
         .lock()
         .map_err(|_| "state poisoned")?;
-    let s = sessions.get(&id).ok_or("unknown session")?;
+    let s = sessions.get_mut(&id).ok_or("unknown session")?;
     if s.closing {
-        return Ok(());
+        return Err("session is closing".to_string());
     }
-    s.master
-        .resize(PtySize {
+    crate::idle::touch(&id);
+
+    let bracketed = s.bracketed_paste.load(Ordering::Relaxed);
+    let (cleaned, outcome) = crate::paste_guard::prepare(&window, &text, bracketed)?;
+    if !bracketed {
+        if crate::exec_guard::queue_if_pending(&id, &cleaned) {
+            return Ok(outcome);
+        }
+        if s.automated {
+            crate::command_policy::screen_input(&mut s.policy_line, &cleaned)?;
+        }
+        let held = crate::exec_guard::screen(&window, &mut s.typed_line, &cleaned, !s.automated);
+        let data = match held {
+            Some(held) => {
+                let before = held.before.clone();
+                crate::exec_guard::hold(&window, &id, held, true);
+                before
+            }
+            None => cleaned,
+        };
+        write_input(s, data.as_bytes(), true)?;
+        return Ok(outcome);
+    }
+
+    let data = crate::paste_guard::bracket(&cleaned);
+    if crate::exec_guard::queue_if_pending(&id, &data) {
+        return Ok(outcome);
+    }
+    // A bracketed paste runs nothing by itself; the policy judges it with the rest of
+    // the line at the next Enter.
+    if s.automated {
+        s.policy_line.push_str(&cleaned);
+    }
+    // Nothing runs until the next Enter, which the exec guard screens against the line
+    // as typed so far, pasted lines included.
+    s.typed_line.push_str(&cleaned.replace('\r', " "));
+    write_input(s, data.as_bytes(), true)?;
+    Ok(outcome)
+}
+
+#[tauri::command]
+pub fn resize_session(
+    state: State<'_, AppState>,
+    id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    state.resize(
+        &id,
+        PtySize {
             rows,
             cols,
             pixel_width: 0,
             pixel_height: 0,
-        })
-        .map_err(|e| format!("resize failed: {e}"))?;
-    Ok(())
+        },
+    )
+}
+
+pub(crate) const EVENT_PTY_RENAMED: &str = "pty-renamed";
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyRenamed {
+    pub id: String,
+    pub name: String,
+}
+
+/// Relabel a running session. The name is made unique the same way as at creation
+/// (`agent`, `agent-2`, ...); the name actually used is returned and sent as
+/// `pty-renamed`.
+#[tauri::command]
+pub fn rename_session(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+) -> Result<String, String> {
+    let base = name.trim();
+    if base.is_empty() {
+        return Err("session name must not be empty".to_string());
+    }
+    let mut sessions = state
+        .inner
+        .sessions
+        .lock()
+        .map_err(|_| "state poisoned")?;
+    let current = sessions.get(&id).ok_or("unknown session")?.name.clone();
+    if current == base {
+        return Ok(current);
+    }
+    let final_name = unique_name(&sessions, base);
+    if let Some(session) = sessions.get_mut(&id) {
+        session.name = final_name.clone();
+    }
+    drop(sessions);
+
+    let _ = window.emit(
+        EVENT_PTY_RENAMED,
+        PtyRenamed {
+            id,
+            name: final_name.clone(),
+        },
+    );
+    crate::app_menu::refresh_dynamic_menus(&window);
+    Ok(final_name)
 }
 
 #[tauri::command]
@@ -1100,6 +2546,7 @@ pub fn close_session(state: State<'_, AppState>, id: String) -> Result<(), Strin
         .lock()
         .map_err(|_| "state poisoned")?;
     let Some(session) = sessions.get_mut(&id) else {
+        state.cancel_restart(&id);
         return Ok(());
     };
 
@@ -1111,8 +2558,266 @@ pub fn close_session(state: State<'_, AppState>, id: String) -> Result<(), Strin
     Ok(())
 }
 
+/// Signals `send_signal_to_session` accepts.
+const SESSION_SIGNALS: &[&str] = &["INT", "TERM", "HUP", "QUIT", "USR1", "USR2", "KILL", "STOP", "CONT"];
+
+/// Parent pid of every process, for checking that a pid belongs to a session.
+#[cfg(target_family = "unix")]
+fn process_parents() -> HashMap<u32, u32> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut parents = HashMap::new();
+        for entry in fs::read_dir("/proc").into_iter().flatten().flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else {
+                continue;
+            };
+            // `pid (comm) state ppid ...`; comm may itself contain spaces or parens.
+            let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else {
+                continue;
+            };
+            let ppid = stat
+                .rsplit_once(')')
+                .and_then(|(_, rest)| rest.split_whitespace().nth(1))
+                .and_then(|p| p.parse::<u32>().ok());
+            if let Some(ppid) = ppid {
+                parents.insert(pid, ppid);
+            }
+        }
+        parents
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let Ok(output) = Command::new("ps").args(["-A", "-o", "pid=,ppid="]).output() else {
+            return HashMap::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+            })
+            .collect()
+    }
+}
+
+/// Whether `pid` is `root` or one of its descendants.
+#[cfg_attr(not(target_family = "unix"), allow(dead_code))]
+fn is_descendant(parents: &HashMap<u32, u32>, root: u32, pid: u32) -> bool {
+    let mut current = pid;
+    // Bounded walk in case the table has a cycle from pid reuse mid-read.
+    for _ in 0..parents.len() + 1 {
+        if current == root {
+            return true;
+        }
+        match parents.get(&current) {
+            Some(&parent) if parent != current && parent > 1 => current = parent,
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Send a signal (`INT`, `TERM`, `HUP`, `USR1`, ...; a `SIG` prefix is fine) to the
+/// session's child process, or to `pid` when it is one of the child's descendants.
+/// Interrupts an agent without closing the session. On Windows only `INT` is
+/// supported, delivered as Ctrl-C.
+#[tauri::command]
+pub fn send_signal_to_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    signal: String,
+    pid: Option<u32>,
+) -> Result<(), String> {
+    let signal = signal.trim().trim_start_matches("SIG").to_ascii_uppercase();
+    if !SESSION_SIGNALS.contains(&signal.as_str()) {
+        return Err(format!("unsupported signal: {signal}"));
+    }
+    let audit_target = format!("{id}:{signal}");
+    let result = (|| -> Result<(), String> {
+        let mut sessions = state.inner.sessions.lock().map_err(|_| "state poisoned")?;
+        let session = sessions.get_mut(&id).ok_or("unknown session")?;
+        if session.closing {
+            return Err("session is closing".to_string());
+        }
+
+        #[cfg(target_family = "unix")]
+        {
+            let root = session.child.process_id().ok_or("session has no process")?;
+            drop(sessions);
+            let target = pid.unwrap_or(root);
+            if target != root && !is_descendant(&process_parents(), root, target) {
+                return Err(format!("process {target} is not part of this session"));
+            }
+            let output = Command::new("kill")
+                .args(["-s", signal.as_str(), &target.to_string()])
+                .output()
+                .map_err(|e| format!("kill failed: {e}"))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "kill failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            Ok(())
+        }
+        #[cfg(not(target_family = "unix"))]
+        {
+            if signal != "INT" || pid.is_some() {
+                return Err("only INT to the session itself is supported on Windows".to_string());
+            }
+            session
+                .writer
+                .write_all(b"\x03")
+                .map_err(|e| format!("write failed: {e}"))?;
+            session.writer.flush().ok();
+            Ok(())
+        }
+    })();
+    crate::audit::record_result(&app, "session.signal", &audit_target, &result);
+    result
+}
+
+/// Close a session whose idle timeout ran out (see idle.rs).
+pub(crate) fn close_idle_session(window: &WebviewWindow, id: String) {
+    let state = window.state::<AppState>();
+    if let Err(e) = close_session_gracefully(window.clone(), state, id, None) {
+        eprintln!("[PTY] Idle close failed: {e}");
+    }
+}
+
+pub(crate) const EVENT_PTY_CLOSE_PROGRESS: &str = "pty-close-progress";
+const GRACEFUL_CLOSE_DEFAULT_MS: u64 = 5000;
+const GRACEFUL_CLOSE_POLL_MS: u64 = 100;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyCloseProgress {
+    pub id: String,
+    /// `terminating` (asked to exit), `killing` (timeout reached), `exited`, or `failed`
+    /// when it was still running after the kill.
+    pub stage: String,
+    pub at: u64,
+}
+
+/// Ask the session's process group to exit. Interactive shells ignore SIGTERM, so they
+/// also get SIGHUP, as when a terminal window closes.
+#[cfg(target_family = "unix")]
+fn request_exit(session: &mut PtySession) {
+    let Some(pid) = session.child.process_id() else {
+        return;
+    };
+    let group = format!("-{pid}");
+    let signals: &[&str] = if session.is_shell { &["-TERM", "-HUP"] } else { &["-TERM"] };
+    for signal in signals {
+        let _ = Command::new("kill").args([*signal, "--", group.as_str()]).status();
+    }
+}
+
+/// No signals on Windows: interrupt whatever runs, then ask the shell to exit.
+#[cfg(not(target_family = "unix"))]
+fn request_exit(session: &mut PtySession) {
+    let input: &[u8] = if session.is_shell { b"\x03exit\r" } else { b"\x03" };
+    let _ = session.writer.write_all(input);
+    session.writer.flush().ok();
+}
+
+/// Kill the session's whole process group, so children that outlived the shell's exit
+/// request go too.
+#[cfg(target_family = "unix")]
+fn force_exit(session: &mut PtySession) {
+    let Some(pid) = session.child.process_id() else {
+        let _ = session.child.kill();
+        return;
+    };
+    let group = format!("-{pid}");
+    let _ = Command::new("kill").args(["-KILL", "--", group.as_str()]).status();
+}
+
+#[cfg(not(target_family = "unix"))]
+fn force_exit(session: &mut PtySession) {
+    let _ = session.child.kill();
+}
+
+/// Close a session the polite way: SIGTERM (Ctrl-C on Windows), then SIGKILL to its
+/// process group if it is still running after `timeout_ms` (default 5s). Recordings and multiplexers get
+/// the chance to flush and detach. Progress arrives as `pty-close-progress` events;
+/// `pty-exit` follows as usual.
+#[tauri::command]
+pub fn close_session_gracefully(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    id: String,
+    timeout_ms: Option<u64>,
+) -> Result<(), String> {
+    let timeout_ms = timeout_ms.unwrap_or(GRACEFUL_CLOSE_DEFAULT_MS).clamp(100, 60_000);
+    {
+        let mut sessions = state.inner.sessions.lock().map_err(|_| "state poisoned")?;
+        let Some(session) = sessions.get_mut(&id) else {
+            state.cancel_restart(&id);
+            return Ok(());
+        };
+        if session.closing {
+            return Ok(());
+        }
+        request_exit(session);
+        session.closing = true;
+    }
+
+    let emit = move |window: &WebviewWindow, id: &str, stage: &str| {
+        let _ = window.emit(
+            EVENT_PTY_CLOSE_PROGRESS,
+            PtyCloseProgress {
+                id: id.to_string(),
+                stage: stage.to_string(),
+                at: now_epoch_ms(),
+            },
+        );
+    };
+    emit(&window, &id, "terminating");
+
+    // The reader thread removes the session once the child's output ends.
+    let state = state.inner().clone();
+    std::thread::spawn(move || {
+        let running = |state: &AppState| {
+            state
+                .inner
+                .sessions
+                .lock()
+                .map(|sessions| sessions.contains_key(&id))
+                .unwrap_or(false)
+        };
+        let deadline = Instant::now() + std::time::Duration::from_millis(timeout_ms);
+        while Instant::now() < deadline {
+            if !running(&state) {
+                emit(&window, &id, "exited");
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(GRACEFUL_CLOSE_POLL_MS));
+        }
+
+        emit(&window, &id, "killing");
+        if let Ok(mut sessions) = state.inner.sessions.lock() {
+            if let Some(session) = sessions.get_mut(&id) {
+                force_exit(session);
+            }
+        }
+        let deadline = Instant::now() + std::time::Duration::from_millis(GRACEFUL_CLOSE_DEFAULT_MS);
+        while Instant::now() < deadline {
+            if !running(&state) {
+                emit(&window, &id, "exited");
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(GRACEFUL_CLOSE_POLL_MS));
+        }
+        emit(&window, &id, "failed");
+    });
+    Ok(())
+}
+
+/// Close a session that is a tmux or zellij client, leaving the multiplexer session
+/// running to be attached again; `kill_persistent_session` ends that too.
 #[tauri::command]
-pub fn detach_session(_state: State<'_, AppState>, _id: String) -> Result<(), String> {
-    // Detach was tmux-specific. No longer supported.
-    Err("detach is no longer supported (tmux removed)".to_string())
+pub fn detach_session(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state.detach(&id)
 }
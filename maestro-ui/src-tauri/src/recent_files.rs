@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::util::now_epoch_ms;
+
+const RECENT_FILES_FILE_NAME: &str = "recent-files-v1.json";
+/// Files kept per project, most recent first.
+const MAX_RECENT_FILES: usize = 50;
+
+/// A file opened with `read_text_file` or `ssh_read_text_file`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFile {
+    pub path: String,
+    pub root: String,
+    /// ssh target for remote files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub opened_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct RecentFilesV1 {
+    #[serde(default)]
+    projects: HashMap<String, Vec<RecentFile>>,
+}
+
+fn recent_cache() -> &'static Mutex<Option<HashMap<String, Vec<RecentFile>>>> {
+    static CACHE: OnceLock<Mutex<Option<HashMap<String, Vec<RecentFile>>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn recent_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, RECENT_FILES_FILE_NAME)
+}
+
+fn read_recent(app: &AppHandle) -> Result<HashMap<String, Vec<RecentFile>>, String> {
+    let path = recent_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str::<RecentFilesV1>(&raw)
+            .map(|r| r.projects)
+            .map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn write_recent(app: &AppHandle, projects: &HashMap<String, Vec<RecentFile>>) -> Result<(), String> {
+    let path = recent_file_path(app)?;
+    crate::util::write_json_atomic(&path, &RecentFilesV1 {
+        projects: projects.clone(),
+    })
+}
+
+/// Move `file` to the front of `list`, dropping an older entry for the same file
+/// and anything past the cap.
+fn push_recent(list: &mut Vec<RecentFile>, file: RecentFile) {
+    list.retain(|f| !(f.path == file.path && f.target == file.target));
+    list.insert(0, file);
+    list.truncate(MAX_RECENT_FILES);
+}
+
+/// Note that a file was opened in `project_id`. Failures are logged, never returned:
+/// the read itself already succeeded.
+pub(crate) fn record(app: &AppHandle, project_id: Option<&str>, target: Option<&str>, root: &str, path: &str) {
+    let Some(project_id) = project_id.map(str::trim).filter(|p| !p.is_empty()) else {
+        return;
+    };
+    let result = (|| -> Result<(), String> {
+        let mut cache = recent_cache().lock().map_err(|_| "recent files cache poisoned")?;
+        if cache.is_none() {
+            *cache = Some(read_recent(app)?);
+        }
+        let projects = cache.as_mut().ok_or("recent files cache missing")?;
+        push_recent(
+            projects.entry(project_id.to_string()).or_default(),
+            RecentFile {
+                path: path.to_string(),
+                root: root.to_string(),
+                target: target.map(|t| t.trim().to_string()),
+                opened_at: now_epoch_ms(),
+            },
+        );
+        write_recent(app, projects)
+    })();
+    if let Err(e) = result {
+        eprintln!("Failed to record recent file: {e}");
+    }
+}
+
+/// Files recently opened in a project, most recent first.
+#[tauri::command]
+pub fn get_recent_files(window: WebviewWindow, project_id: String) -> Result<Vec<RecentFile>, String> {
+    let mut cache = recent_cache().lock().map_err(|_| "recent files cache poisoned")?;
+    if cache.is_none() {
+        *cache = Some(read_recent(window.app_handle())?);
+    }
+    Ok(cache
+        .as_ref()
+        .and_then(|projects| projects.get(&project_id))
+        .cloned()
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{push_recent, RecentFile, MAX_RECENT_FILES};
+
+    fn file(path: &str, target: Option<&str>) -> RecentFile {
+        RecentFile {
+            path: path.to_string(),
+            root: "/p".to_string(),
+            target: target.map(str::to_string),
+            opened_at: 0,
+        }
+    }
+
+    #[test]
+    fn reopening_moves_to_front_and_list_is_capped() {
+        let mut list = Vec::new();
+        push_recent(&mut list, file("/p/a", None));
+        push_recent(&mut list, file("/p/a", Some("devbox")));
+        push_recent(&mut list, file("/p/b", None));
+        push_recent(&mut list, file("/p/a", None));
+        let order: Vec<_> = list.iter().map(|f| (f.path.as_str(), f.target.as_deref())).collect();
+        assert_eq!(order, [("/p/a", None), ("/p/b", None), ("/p/a", Some("devbox"))]);
+
+        for i in 0..MAX_RECENT_FILES + 5 {
+            push_recent(&mut list, file(&format!("/p/{i}"), None));
+        }
+        assert_eq!(list.len(), MAX_RECENT_FILES);
+        assert_eq!(list[0].path, format!("/p/{}", MAX_RECENT_FILES + 4));
+    }
+}
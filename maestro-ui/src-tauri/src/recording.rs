@@ -1,9 +1,30 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 use tauri::{Manager, WebviewWindow};
 
+use crate::util::now_epoch_ms;
+
+const INDEX_FILE_NAME: &str = "index-v1.json";
+const TRASH_DIR_NAME: &str = ".trash";
+const TRASH_RETENTION_MS: u64 = 30 * 24 * 60 * 60 * 1000; // 30 days
+
+pub const TRACK_INPUT: &str = "input";
+pub const TRACK_OUTPUT: &str = "output";
+
+/// One channel recorded under a recording id. `format` is `lines` for the input
+/// track (one event per entered line, control keys removed) and `raw` for the
+/// output track (pty output exactly as read).
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingTrackV1 {
+    pub id: String,
+    pub format: String,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RecordingMetaV1 {
@@ -17,6 +38,9 @@ pub struct RecordingMetaV1 {
     pub bootstrap_command: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub encrypted: Option<bool>,
+    /// Tracks present in the file. Missing on older recordings, which only have input.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracks: Option<Vec<RecordingTrackV1>>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -31,6 +55,7 @@ pub struct RecordingEventV1 {
 pub enum RecordingLineV1 {
     Meta(RecordingMetaV1),
     Input(RecordingEventV1),
+    Output(RecordingEventV1),
 }
 
 #[derive(Serialize, Clone)]
@@ -39,6 +64,29 @@ pub struct LoadedRecordingV1 {
     pub recording_id: String,
     pub meta: Option<RecordingMetaV1>,
     pub events: Vec<RecordingEventV1>,
+    /// Output track events; only loaded when asked for.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub output_events: Vec<RecordingEventV1>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingTimelineOptions {
+    /// Longest pause kept between events; longer idle gaps are shortened to this.
+    pub max_idle_ms: Option<u64>,
+    /// Playback speed multiplier applied after idle compression.
+    pub speed: Option<f64>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingTimelineV1 {
+    pub recording_id: String,
+    pub meta: Option<RecordingMetaV1>,
+    /// Events with `t` already adjusted for idle compression and speed.
+    pub events: Vec<RecordingEventV1>,
+    pub duration_ms: u64,
+    pub original_duration_ms: u64,
 }
 
 #[derive(Serialize, Clone)]
@@ -48,6 +96,45 @@ pub struct RecordingIndexEntryV1 {
     pub meta: Option<RecordingMetaV1>,
 }
 
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedRecordingEntryV1 {
+    pub recording_id: String,
+    pub deleted_at: u64,
+    pub meta: Option<RecordingMetaV1>,
+}
+
+/// On-disk index of known recordings, so listing doesn't have to open every file.
+///
+/// `meta` is only present for recordings whose meta line has been parsed (because we
+/// wrote it, or because it was listed or fetched with `get_recording_meta`).
+/// `external_dirs` lists per-project storage locations (see `prepare_recording_path`)
+/// that are scanned alongside the app data recordings dir.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct RecordingIndexV1 {
+    schema_version: u32,
+    entries: HashMap<String, RecordingIndexRecordV1>,
+    #[serde(default)]
+    external_dirs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RecordingIndexRecordV1 {
+    created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    meta: Option<RecordingMetaV1>,
+    /// Directory holding the recording; `None` means the app data recordings dir.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dir: Option<String>,
+}
+
+fn recording_index_cache() -> &'static Mutex<Option<RecordingIndexV1>> {
+    static CACHE: OnceLock<Mutex<Option<RecordingIndexV1>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
 pub fn sanitize_recording_id(input: &str) -> String {
     let trimmed = input.trim();
     if trimmed.is_empty() {
@@ -66,23 +153,210 @@ pub fn sanitize_recording_id(input: &str) -> String {
 }
 
 pub fn recording_file_path(window: &WebviewWindow, recording_id: &str) -> Result<PathBuf, String> {
-    let app_data = window
-        .app_handle()
-        .path()
-        .app_data_dir()
-        .map_err(|_| "unknown app data dir".to_string())?;
-    Ok(app_data
-        .join("recordings")
-        .join(format!("{recording_id}.jsonl")))
+    Ok(recordings_dir(window)?.join(format!("{recording_id}.jsonl")))
 }
 
 fn recordings_dir(window: &WebviewWindow) -> Result<PathBuf, String> {
-    let app_data = window
-        .app_handle()
-        .path()
-        .app_data_dir()
-        .map_err(|_| "unknown app data dir".to_string())?;
-    Ok(app_data.join("recordings"))
+    crate::util::app_data_file(window.app_handle(), "recordings")
+}
+
+fn file_modified_ms(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn load_index_file(dir: &Path) -> RecordingIndexV1 {
+    let raw = match fs::read_to_string(dir.join(INDEX_FILE_NAME)) {
+        Ok(s) => s,
+        Err(_) => return RecordingIndexV1::default(),
+    };
+    match serde_json::from_str::<RecordingIndexV1>(&raw) {
+        Ok(index) if index.schema_version == 1 => index,
+        _ => RecordingIndexV1::default(),
+    }
+}
+
+fn save_index_file(dir: &Path, index: &RecordingIndexV1) -> Result<(), String> {
+    let json = serde_json::to_string(index).map_err(|e| format!("serialize failed: {e}"))?;
+    crate::util::write_atomic(&dir.join(INDEX_FILE_NAME), json.as_bytes())
+}
+
+/// All directories recordings may live in, keyed the same way as
+/// `RecordingIndexRecordV1::dir`.
+fn index_dirs(app_dir: &Path, index: &RecordingIndexV1) -> Vec<(Option<String>, PathBuf)> {
+    let mut dirs = vec![(None, app_dir.to_path_buf())];
+    for dir in &index.external_dirs {
+        dirs.push((Some(dir.clone()), PathBuf::from(dir)));
+    }
+    dirs
+}
+
+fn record_file_path(app_dir: &Path, recording_id: &str, record: &RecordingIndexRecordV1) -> PathBuf {
+    let dir = match record.dir.as_deref() {
+        Some(dir) => PathBuf::from(dir),
+        None => app_dir.to_path_buf(),
+    };
+    dir.join(format!("{recording_id}.jsonl"))
+}
+
+/// Reconcile the index with the recordings directories. Only directory entries
+/// are inspected; recording files are never opened here.
+fn sync_index_with_dirs(app_dir: &Path, index: &mut RecordingIndexV1) -> bool {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut changed = false;
+
+    for (dir_key, dir) in index_dirs(app_dir, index) {
+        let read_dir = match fs::read_dir(&dir) {
+            Ok(rd) => rd,
+            Err(_) => continue,
+        };
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let Some(recording_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            // The first directory wins if the same id exists in several places.
+            if !seen.insert(recording_id.to_string()) {
+                continue;
+            }
+            let known = index
+                .entries
+                .get(recording_id)
+                .map(|record| record.dir == dir_key)
+                .unwrap_or(false);
+            if !known {
+                index.entries.insert(
+                    recording_id.to_string(),
+                    RecordingIndexRecordV1 {
+                        created_at: file_modified_ms(&path),
+                        meta: None,
+                        dir: dir_key.clone(),
+                    },
+                );
+                changed = true;
+            }
+        }
+    }
+
+    let before = index.entries.len();
+    index.entries.retain(|id, _| seen.contains(id));
+    changed || index.entries.len() != before
+}
+
+/// Run `f` against the cached index (loading it from disk on first use) and
+/// persist the index afterwards if `f` reports a change.
+fn with_index<T>(
+    dir: &Path,
+    f: impl FnOnce(&mut RecordingIndexV1) -> (T, bool),
+) -> Result<T, String> {
+    let mut cache = recording_index_cache()
+        .lock()
+        .map_err(|_| "recording index poisoned".to_string())?;
+    let index = cache.get_or_insert_with(|| {
+        let mut index = load_index_file(dir);
+        index.schema_version = 1;
+        index
+    });
+    let (out, changed) = f(index);
+    if changed {
+        if let Err(e) = save_index_file(dir, index) {
+            eprintln!("Failed to save recording index: {e}");
+        }
+    }
+    Ok(out)
+}
+
+/// Directory a project's recording dir setting points at. Relative settings
+/// (e.g. `.maestro/recordings`) are resolved against the project's base path and
+/// are rejected for projects without one.
+fn resolve_storage_dir(storage_dir: &str, base_path: Option<&str>) -> Result<PathBuf, String> {
+    let dir = PathBuf::from(crate::persist::expand_home(storage_dir));
+    if dir.is_absolute() {
+        return Ok(dir);
+    }
+    match base_path.map(str::trim).filter(|b| !b.is_empty()) {
+        Some(base) => Ok(Path::new(base).join(dir)),
+        None => Err("relative recording storage dir needs a project base path".to_string()),
+    }
+}
+
+/// Resolve where a recording should be written. `storage_dir` is the project's
+/// configured recording location and `base_path` the project's root; when no
+/// location is set the recording goes to the app data dir.
+pub fn prepare_recording_path(
+    window: &WebviewWindow,
+    recording_id: &str,
+    storage_dir: Option<&str>,
+    base_path: Option<&str>,
+) -> Result<PathBuf, String> {
+    let storage_dir = storage_dir.map(|s| s.trim()).filter(|s| !s.is_empty());
+    let Some(storage_dir) = storage_dir else {
+        return recording_file_path(window, recording_id);
+    };
+
+    let dir = resolve_storage_dir(storage_dir, base_path)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("create dir failed: {e}"))?;
+
+    let app_dir = recordings_dir(window)?;
+    let key = dir.to_string_lossy().to_string();
+    with_index(&app_dir, |index| {
+        if index.external_dirs.contains(&key) {
+            return ((), false);
+        }
+        index.external_dirs.push(key.clone());
+        ((), true)
+    })?;
+
+    Ok(dir.join(format!("{recording_id}.jsonl")))
+}
+
+/// Record a freshly written recording's meta in the index. `path` is the file
+/// returned by `prepare_recording_path`.
+pub fn index_recording(
+    window: &WebviewWindow,
+    recording_id: &str,
+    path: &Path,
+    meta: RecordingMetaV1,
+) -> Result<(), String> {
+    let app_dir = recordings_dir(window)?;
+    let dir = path
+        .parent()
+        .filter(|p| *p != app_dir.as_path())
+        .map(|p| p.to_string_lossy().to_string());
+    with_index(&app_dir, |index| {
+        index.entries.insert(
+            recording_id.to_string(),
+            RecordingIndexRecordV1 {
+                created_at: meta.created_at,
+                meta: Some(meta),
+                dir,
+            },
+        );
+        ((), true)
+    })
+}
+
+/// Path of an existing recording, honoring per-project storage locations.
+fn resolve_recording_path(window: &WebviewWindow, recording_id: &str) -> Result<PathBuf, String> {
+    let app_dir = recordings_dir(window)?;
+    let indexed = with_index(&app_dir, |index| {
+        let path = index
+            .entries
+            .get(recording_id)
+            .map(|record| record_file_path(&app_dir, recording_id, record));
+        (path, false)
+    })?;
+    match indexed {
+        Some(path) => Ok(path),
+        None => recording_file_path(window, recording_id),
+    }
 }
 
 fn read_recording_meta(path: &PathBuf) -> Result<Option<RecordingMetaV1>, String> {
@@ -108,21 +382,26 @@ fn read_recording_meta(path: &PathBuf) -> Result<Option<RecordingMetaV1>, String
     Ok(None)
 }
 
+/// Load a recording's meta and input events. Pass `include_output` to also load the
+/// output track, when the recording has one.
 #[tauri::command]
 pub fn load_recording(
     window: WebviewWindow,
     recording_id: String,
     decrypt: Option<bool>,
+    include_output: Option<bool>,
 ) -> Result<LoadedRecordingV1, String> {
     let safe_id = sanitize_recording_id(&recording_id);
-    let path = recording_file_path(&window, &safe_id)?;
+    let path = resolve_recording_path(&window, &safe_id)?;
     let file = fs::File::open(&path).map_err(|e| format!("open failed: {e}"))?;
     let reader = BufReader::new(file);
 
     let mut meta: Option<RecordingMetaV1> = None;
     let mut events: Vec<RecordingEventV1> = Vec::new();
+    let mut output_events: Vec<RecordingEventV1> = Vec::new();
     let mut key: Option<[u8; 32]> = None;
     let decrypt_allowed = decrypt.unwrap_or(true);
+    let include_output = include_output.unwrap_or(false);
 
     for line in reader.lines() {
         let line = line.map_err(|e| format!("read failed: {e}"))?;
@@ -132,90 +411,414 @@ pub fn load_recording(
         }
         let parsed: RecordingLineV1 =
             serde_json::from_str(trimmed).map_err(|e| format!("parse failed: {e}"))?;
-        match parsed {
+        let (mut ev, is_output) = match parsed {
             RecordingLineV1::Meta(m) => {
                 if meta.is_none() {
                     meta = Some(m);
                 }
+                continue;
             }
-            RecordingLineV1::Input(mut ev) => {
-                if crate::secure::is_probably_encrypted_value(&ev.data) {
-                    if !decrypt_allowed {
-                        return Err(
-                            "Recording is encrypted. Enable macOS Keychain encryption to replay it."
-                                .to_string(),
-                        );
-                    }
-                    if key.is_none() {
-                        key = Some(crate::secure::get_or_create_master_key(&window)?);
-                    }
-                    if let Some(key) = key.as_ref() {
-                        ev.data = crate::secure::decrypt_string_with_key(
-                            key,
-                            crate::secure::SecretContext::Recording,
-                            &ev.data,
-                        )?;
-                    }
-                }
-                events.push(ev);
+            RecordingLineV1::Input(ev) => (ev, false),
+            RecordingLineV1::Output(_) if !include_output => continue,
+            RecordingLineV1::Output(ev) => (ev, true),
+        };
+        if crate::secure::is_probably_encrypted_value(&ev.data) {
+            if !decrypt_allowed {
+                return Err(
+                    "Recording is encrypted. Enable macOS Keychain encryption to replay it."
+                        .to_string(),
+                );
+            }
+            if key.is_none() {
+                key = Some(crate::secure::get_or_create_master_key(&window)?);
             }
+            if let Some(key) = key.as_ref() {
+                ev.data = crate::secure::decrypt_string_with_key(
+                    key,
+                    crate::secure::SecretContext::Recording,
+                    &ev.data,
+                )?;
+            }
+        }
+        if is_output {
+            output_events.push(ev);
+        } else {
+            events.push(ev);
         }
     }
 
+    if key.is_some() {
+        crate::audit::record(window.app_handle(), "recording.decrypt", &safe_id, None);
+    }
+
     Ok(LoadedRecordingV1 {
         recording_id: safe_id,
         meta,
         events,
+        output_events,
+    })
+}
+
+const DEFAULT_MAX_IDLE_MS: u64 = 2000;
+
+/// Rewrite event times so no gap exceeds `max_idle_ms`, then scale by `1 / speed`.
+fn build_timeline(events: Vec<RecordingEventV1>, max_idle_ms: u64, speed: f64) -> Vec<RecordingEventV1> {
+    let mut prev_t = 0u64;
+    let mut elapsed = 0u64;
+    events
+        .into_iter()
+        .map(|ev| {
+            let gap = ev.t.saturating_sub(prev_t);
+            prev_t = ev.t.max(prev_t);
+            elapsed += gap.min(max_idle_ms);
+            RecordingEventV1 {
+                t: (elapsed as f64 / speed).round() as u64,
+                data: ev.data,
+            }
+        })
+        .collect()
+}
+
+/// Load a recording with playback timing worked out up front: idle gaps longer than
+/// `maxIdleMs` (default 2s) are shortened and times are scaled by `speed`, so the
+/// player can schedule events directly.
+#[tauri::command]
+pub async fn load_recording_timeline(
+    window: WebviewWindow,
+    recording_id: String,
+    options: Option<RecordingTimelineOptions>,
+) -> Result<RecordingTimelineV1, String> {
+    let options = options.unwrap_or_default();
+    let speed = options.speed.unwrap_or(1.0);
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err("speed must be a positive number".to_string());
+    }
+    let max_idle_ms = options.max_idle_ms.unwrap_or(DEFAULT_MAX_IDLE_MS);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let loaded = load_recording(window, recording_id, Some(true), None)?;
+        let original_duration_ms = loaded.events.last().map(|e| e.t).unwrap_or(0);
+        let events = build_timeline(loaded.events, max_idle_ms, speed);
+        Ok(RecordingTimelineV1 {
+            recording_id: loaded.recording_id,
+            meta: loaded.meta,
+            duration_ms: events.last().map(|e| e.t).unwrap_or(0),
+            events,
+            original_duration_ms,
+        })
     })
+    .await
+    .map_err(|e| format!("load recording timeline task join failed: {e:?}"))?
+}
+
+/// One page of the index, newest first. Records on the page whose meta hasn't been
+/// parsed yet (recordings made before the index existed, or found by a directory
+/// scan) are backfilled from their files. Returns whether the index changed.
+fn list_index_page(
+    app_dir: &Path,
+    index: &mut RecordingIndexV1,
+    offset: usize,
+    limit: usize,
+) -> (Vec<RecordingIndexEntryV1>, bool) {
+    let mut changed = sync_index_with_dirs(app_dir, index);
+
+    let mut ids: Vec<(String, u64)> = index
+        .entries
+        .iter()
+        .map(|(id, record)| (id.clone(), record.created_at))
+        .collect();
+    ids.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut page = Vec::new();
+    for (recording_id, _) in ids.into_iter().skip(offset).take(limit) {
+        let Some(record) = index.entries.get_mut(&recording_id) else {
+            continue;
+        };
+        if record.meta.is_none() {
+            let path = record_file_path(app_dir, &recording_id, record);
+            if let Ok(Some(meta)) = read_recording_meta(&path) {
+                record.created_at = meta.created_at;
+                record.meta = Some(meta);
+                changed = true;
+            }
+        }
+        page.push(RecordingIndexEntryV1 {
+            recording_id,
+            meta: record.meta.clone(),
+        });
+    }
+    (page, changed)
 }
 
 #[tauri::command]
-pub fn list_recordings(window: WebviewWindow) -> Result<Vec<RecordingIndexEntryV1>, String> {
+pub fn list_recordings(
+    window: WebviewWindow,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<RecordingIndexEntryV1>, String> {
     let dir = recordings_dir(&window)?;
-    let read_dir = match fs::read_dir(&dir) {
+    with_index(&dir, |index| {
+        list_index_page(&dir, index, offset.unwrap_or(0), limit.unwrap_or(usize::MAX))
+    })
+}
+
+/// Parse (and cache) the meta line of a single recording.
+#[tauri::command]
+pub fn get_recording_meta(
+    window: WebviewWindow,
+    recording_id: String,
+) -> Result<Option<RecordingMetaV1>, String> {
+    let safe_id = sanitize_recording_id(&recording_id);
+    let dir = recordings_dir(&window)?;
+
+    let cached = with_index(&dir, |index| {
+        let meta = index.entries.get(&safe_id).and_then(|r| r.meta.clone());
+        (meta, false)
+    })?;
+    if cached.is_some() {
+        return Ok(cached);
+    }
+
+    let path = resolve_recording_path(&window, &safe_id)?;
+    let meta = read_recording_meta(&path)?;
+    if let Some(meta) = meta.as_ref() {
+        with_index(&dir, |index| {
+            let Some(record) = index.entries.get_mut(&safe_id) else {
+                return ((), false);
+            };
+            record.created_at = meta.created_at;
+            record.meta = Some(meta.clone());
+            ((), true)
+        })?;
+    }
+    Ok(meta)
+}
+
+/// Trashed recordings are stored as `<recording_id>.<deleted_at_ms>.jsonl`.
+/// Sanitized recording ids never contain `.`, so the split is unambiguous.
+fn parse_trash_file_name(path: &Path) -> Option<(String, u64)> {
+    if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    let (recording_id, deleted_at) = stem.rsplit_once('.')?;
+    let deleted_at = deleted_at.parse::<u64>().ok()?;
+    if recording_id.is_empty() {
+        return None;
+    }
+    Some((recording_id.to_string(), deleted_at))
+}
+
+fn list_trash_files(trash_dir: &Path) -> Vec<(PathBuf, String, u64)> {
+    let read_dir = match fs::read_dir(trash_dir) {
         Ok(rd) => rd,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
-        Err(e) => return Err(format!("read dir failed: {e}")),
+        Err(_) => return Vec::new(),
     };
+    read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let (recording_id, deleted_at) = parse_trash_file_name(&path)?;
+            Some((path, recording_id, deleted_at))
+        })
+        .collect()
+}
 
-    let mut out: Vec<RecordingIndexEntryV1> = Vec::new();
-
-    for entry in read_dir {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
-        }
-        if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
-            continue;
+/// Permanently remove trashed recordings older than the retention window.
+fn purge_expired_trash(trash_dir: &Path) {
+    let cutoff = now_epoch_ms().saturating_sub(TRASH_RETENTION_MS);
+    for (path, _, deleted_at) in list_trash_files(trash_dir) {
+        if deleted_at < cutoff {
+            if let Err(e) = fs::remove_file(&path) {
+                eprintln!("Failed to purge trashed recording {path:?}: {e}");
+            }
         }
-        let recording_id = match path.file_stem().and_then(|s| s.to_str()) {
-            Some(s) => s.to_string(),
-            None => continue,
-        };
-        let meta = read_recording_meta(&path).ok().flatten();
-        out.push(RecordingIndexEntryV1 { recording_id, meta });
     }
+}
 
-    out.sort_by(|a, b| {
-        let a_created = a.meta.as_ref().map(|m| m.created_at).unwrap_or(0);
-        let b_created = b.meta.as_ref().map(|m| m.created_at).unwrap_or(0);
-        b_created.cmp(&a_created)
-    });
+/// Every trash directory, paired with the recordings directory it belongs to.
+fn trash_dirs(window: &WebviewWindow) -> Result<Vec<(Option<String>, PathBuf)>, String> {
+    let app_dir = recordings_dir(window)?;
+    with_index(&app_dir, |index| {
+        let dirs = index_dirs(&app_dir, index)
+            .into_iter()
+            .map(|(key, dir)| (key, dir.join(TRASH_DIR_NAME)))
+            .collect();
+        (dirs, false)
+    })
+}
+
+/// Move a recording into the `.trash` folder next to it. It can be brought back
+/// with `restore_recording` until the retention window expires.
+#[tauri::command]
+pub fn delete_recording(window: WebviewWindow, recording_id: String) -> Result<(), String> {
+    let safe_id = sanitize_recording_id(&recording_id);
+    let path = resolve_recording_path(&window, &safe_id)?;
+    let dir = recordings_dir(&window)?;
+    let trash_dir = path
+        .parent()
+        .map(|p| p.join(TRASH_DIR_NAME))
+        .ok_or("invalid recording path")?;
+
+    if path.is_file() {
+        fs::create_dir_all(&trash_dir).map_err(|e| format!("create dir failed: {e}"))?;
+        let trashed = trash_dir.join(format!("{safe_id}.{}.jsonl", now_epoch_ms()));
+        let result = fs::rename(&path, &trashed).map_err(|e| format!("delete failed: {e}"));
+        crate::audit::record_result(window.app_handle(), "recording.delete", &safe_id, &result);
+        result?;
+    }
+    purge_expired_trash(&trash_dir);
+
+    with_index(&dir, |index| {
+        let removed = index.entries.remove(&safe_id).is_some();
+        ((), removed)
+    })
+}
 
+#[tauri::command]
+pub fn list_deleted_recordings(window: WebviewWindow) -> Result<Vec<DeletedRecordingEntryV1>, String> {
+    let mut out: Vec<DeletedRecordingEntryV1> = Vec::new();
+    for (_, trash_dir) in trash_dirs(&window)? {
+        purge_expired_trash(&trash_dir);
+        for (path, recording_id, deleted_at) in list_trash_files(&trash_dir) {
+            out.push(DeletedRecordingEntryV1 {
+                recording_id,
+                deleted_at,
+                meta: read_recording_meta(&path).ok().flatten(),
+            });
+        }
+    }
+
+    out.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
     Ok(out)
 }
 
+/// Restore the most recently trashed copy of a recording into the directory it
+/// was deleted from.
 #[tauri::command]
-pub fn delete_recording(window: WebviewWindow, recording_id: String) -> Result<(), String> {
+pub fn restore_recording(window: WebviewWindow, recording_id: String) -> Result<(), String> {
     let safe_id = sanitize_recording_id(&recording_id);
-    let path = recording_file_path(&window, &safe_id)?;
-    match fs::remove_file(&path) {
-        Ok(_) => Ok(()),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
-        Err(e) => Err(format!("delete failed: {e}")),
+    let dir = recordings_dir(&window)?;
+
+    let already_indexed = with_index(&dir, |index| (index.entries.contains_key(&safe_id), false))?;
+    if already_indexed {
+        return Err("a recording with this id already exists".to_string());
+    }
+
+    let mut newest: Option<(Option<String>, PathBuf, u64)> = None;
+    for (dir_key, trash_dir) in trash_dirs(&window)? {
+        for (path, id, deleted_at) in list_trash_files(&trash_dir) {
+            if id != safe_id {
+                continue;
+            }
+            if newest.as_ref().map(|(_, _, t)| deleted_at > *t).unwrap_or(true) {
+                newest = Some((dir_key.clone(), path, deleted_at));
+            }
+        }
+    }
+    let (dir_key, trashed, _) = newest.ok_or("deleted recording not found")?;
+
+    let target_dir = match dir_key.as_deref() {
+        Some(d) => PathBuf::from(d),
+        None => dir.clone(),
+    };
+    let path = target_dir.join(format!("{safe_id}.jsonl"));
+    if path.exists() {
+        return Err("a recording with this id already exists".to_string());
+    }
+    fs::rename(&trashed, &path).map_err(|e| format!("restore failed: {e}"))?;
+
+    let meta = read_recording_meta(&path).ok().flatten();
+    let created_at = meta
+        .as_ref()
+        .map(|m| m.created_at)
+        .unwrap_or_else(|| file_modified_ms(&path));
+    with_index(&dir, |index| {
+        index.entries.insert(
+            safe_id.clone(),
+            RecordingIndexRecordV1 {
+                created_at,
+                meta,
+                dir: dir_key,
+            },
+        );
+        ((), true)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_timeline, list_index_page, resolve_storage_dir, RecordingEventV1, RecordingIndexV1, RecordingLineV1, RecordingMetaV1,
+    };
+    use std::fs;
+
+    fn meta(created_at: u64, name: &str) -> RecordingMetaV1 {
+        RecordingMetaV1 {
+            schema_version: 1,
+            created_at,
+            name: Some(name.to_string()),
+            project_id: "p1".to_string(),
+            session_persist_id: "s1".to_string(),
+            cwd: None,
+            effect_id: None,
+            bootstrap_command: None,
+            encrypted: Some(false),
+            tracks: None,
+        }
+    }
+
+    #[test]
+    fn relative_storage_dirs_resolve_against_the_project() {
+        let base = std::env::temp_dir().join("maestro-project");
+        let base_str = base.to_string_lossy().to_string();
+
+        let dir = resolve_storage_dir(".maestro/recordings", Some(&base_str)).unwrap();
+        assert_eq!(dir, base.join(".maestro").join("recordings"));
+        assert!(resolve_storage_dir(".maestro/recordings", None).is_err());
+        assert!(resolve_storage_dir(".maestro/recordings", Some("  ")).is_err());
+
+        let absolute = std::env::temp_dir().join("elsewhere");
+        let dir = resolve_storage_dir(&absolute.to_string_lossy(), None).unwrap();
+        assert_eq!(dir, absolute);
+    }
+
+    #[test]
+    fn listing_backfills_meta_for_unindexed_recordings() {
+        let dir = std::env::temp_dir().join(format!("maestro-recording-list-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (id, created_at) in [("old", 1_000), ("new", 2_000)] {
+            let line = serde_json::to_string(&RecordingLineV1::Meta(meta(created_at, id))).unwrap();
+            fs::write(dir.join(format!("{id}.jsonl")), format!("{line}\n")).unwrap();
+        }
+
+        let mut index = RecordingIndexV1::default();
+        let (page, changed) = list_index_page(&dir, &mut index, 0, 1);
+        assert!(changed);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].recording_id, "new");
+        assert_eq!(page[0].meta.as_ref().and_then(|m| m.name.as_deref()), Some("new"));
+        // Only the requested page is parsed.
+        assert!(index.entries["old"].meta.is_none());
+
+        let (page, changed) = list_index_page(&dir, &mut index, 1, 10);
+        assert!(changed);
+        assert_eq!(page[0].meta.as_ref().map(|m| m.created_at), Some(1_000));
+
+        let (_, changed) = list_index_page(&dir, &mut index, 0, 10);
+        assert!(!changed);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn build_timeline_caps_idle_gaps_and_scales() {
+        let events = [0, 100, 60_000, 60_050]
+            .into_iter()
+            .map(|t| RecordingEventV1 { t, data: String::new() })
+            .collect();
+        let times: Vec<u64> = build_timeline(events, 1000, 2.0).into_iter().map(|e| e.t).collect();
+        assert_eq!(times, vec![0, 50, 550, 575]);
     }
 }
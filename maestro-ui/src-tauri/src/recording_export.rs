@@ -0,0 +1,159 @@
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{Manager, WebviewWindow};
+
+use crate::agent_export::escape_html;
+
+/// Plays the recorded input back with its original timing. Plain JS, no external
+/// assets, so the file opens anywhere.
+const PLAYER_SCRIPT: &str = r#"
+const rec = JSON.parse(document.getElementById("rec").textContent);
+const screen = document.getElementById("screen");
+const pos = document.getElementById("pos");
+const events = rec.events;
+let i = 0, text = "", timer = null, speed = 1;
+function esc(s) { return s.replace(/\x1b\[[0-9;?]*[ -\/]*[@-~]/g, "").replace(/\x1b\][^\x07]*\x07/g, ""); }
+function apply(data) {
+  for (const ch of esc(data)) {
+    if (ch === "\r") text += "\n";
+    else if (ch === "\x7f" || ch === "\b") text = text.slice(0, -1);
+    else if (ch === "\x03") text += "^C\n";
+    else if (ch >= " " || ch === "\n" || ch === "\t") text += ch;
+  }
+}
+function render() {
+  screen.textContent = text;
+  screen.scrollTop = screen.scrollHeight;
+  const t = i > 0 ? events[i - 1].t : 0;
+  pos.textContent = i + "/" + events.length + " · " + (t / 1000).toFixed(1) + "s";
+}
+function step() {
+  if (i >= events.length) { stop(); return; }
+  apply(events[i].data);
+  i++;
+  render();
+  if (i < events.length) {
+    const gap = Math.min(events[i].t - events[i - 1].t, 3000) / speed;
+    timer = setTimeout(step, Math.max(gap, 0));
+  } else stop();
+}
+function play() { if (timer) return; document.getElementById("play").textContent = "Pause"; if (i >= events.length) { i = 0; text = ""; } step(); }
+function stop() { clearTimeout(timer); timer = null; document.getElementById("play").textContent = "Play"; }
+document.getElementById("play").onclick = () => (timer ? stop() : play());
+document.getElementById("restart").onclick = () => { stop(); i = 0; text = ""; render(); };
+document.getElementById("end").onclick = () => { stop(); while (i < events.length) apply(events[i++].data); render(); };
+document.getElementById("speed").onchange = (e) => { speed = Number(e.target.value) || 1; };
+render();
+"#;
+
+const PLAYER_STYLE: &str = concat!(
+    "body{font-family:-apple-system,system-ui,sans-serif;max-width:960px;margin:2em auto;padding:0 1em}\n",
+    ".bar{display:flex;gap:.5em;align-items:center;margin:.75em 0}\n",
+    "pre{background:#1e1e1e;color:#ddd;padding:.75em;border-radius:4px;white-space:pre-wrap;overflow:auto}\n",
+    "#screen{height:28em}\n.meta{color:#666;font-size:.9em}\n",
+);
+
+fn render_html(title: &str, meta_line: &str, recording: &serde_json::Value, output: Option<&str>) -> String {
+    // `</` inside the JSON would otherwise close the <script> element early.
+    let data = recording.to_string().replace("</", "<\\/");
+    let mut out = format!(
+        concat!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n",
+            "<style>\n{style}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<div class=\"meta\">{meta}</div>\n",
+            "<div class=\"bar\"><button id=\"play\">Play</button><button id=\"restart\">Restart</button>",
+            "<button id=\"end\">Show all</button><select id=\"speed\"><option value=\"1\">1x</option>",
+            "<option value=\"2\">2x</option><option value=\"4\">4x</option><option value=\"10\">10x</option></select>",
+            "<span id=\"pos\"></span></div>\n<pre id=\"screen\"></pre>\n",
+        ),
+        title = escape_html(title),
+        style = PLAYER_STYLE,
+        meta = escape_html(meta_line),
+    );
+    if let Some(output) = output {
+        out.push_str("<h2>Session output</h2>\n<pre>");
+        out.push_str(&escape_html(output));
+        out.push_str("</pre>\n");
+    }
+    out.push_str(&format!(
+        "<script type=\"application/json\" id=\"rec\">{data}</script>\n<script>{PLAYER_SCRIPT}</script>\n</body>\n</html>\n"
+    ));
+    out
+}
+
+/// Write a recording as one self-contained HTML file with a built-in player, so it can be
+/// attached to a ticket and viewed without Maestro. Encrypted recordings are decrypted.
+/// When the session's on-disk transcript still exists its output is included as well.
+/// `dest` may be a file path or a directory (then `<recording id>.html` is written there).
+#[tauri::command]
+pub async fn export_recording_html(
+    window: WebviewWindow,
+    recording_id: String,
+    dest: String,
+    include_output: Option<bool>,
+) -> Result<String, String> {
+    let dest = dest.trim().to_string();
+    if dest.is_empty() {
+        return Err("destination is required".to_string());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let loaded = crate::recording::load_recording(window.clone(), recording_id, Some(true), None)?;
+        let meta = loaded.meta.as_ref();
+        let title = meta
+            .and_then(|m| m.name.clone())
+            .unwrap_or_else(|| loaded.recording_id.clone());
+        let duration_ms = loaded.events.last().map(|e| e.t).unwrap_or(0);
+        let mut meta_line = format!("{} input events · {:.1}s", loaded.events.len(), duration_ms as f64 / 1000.0);
+        if let Some(cwd) = meta.and_then(|m| m.cwd.as_deref()) {
+            meta_line.push_str(&format!(" · {cwd}"));
+        }
+
+        let output = if include_output.unwrap_or(true) {
+            meta.map(|m| crate::recording::sanitize_recording_id(&m.session_persist_id))
+                .and_then(|key| crate::transcript::read_transcript_tail(&window, &key))
+                .map(|text| crate::throttle::strip_ansi(&text).into_owned())
+        } else {
+            None
+        };
+
+        let recording = json!({
+            "recordingId": loaded.recording_id,
+            "meta": loaded.meta,
+            "events": loaded.events,
+        });
+        let html = render_html(&title, &meta_line, &recording, output.as_deref());
+
+        let mut path = PathBuf::from(&dest);
+        if path.is_dir() {
+            path = path.join(format!("{}.html", loaded.recording_id));
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if !Path::new(parent).is_dir() {
+                return Err("destination directory does not exist".to_string());
+            }
+        }
+        fs::write(&path, html).map_err(|e| format!("write failed: {e}"))?;
+
+        let shown = path.to_string_lossy().to_string();
+        crate::audit::record(window.app_handle(), "recording.export", &loaded.recording_id, None);
+        Ok(shown)
+    })
+    .await
+    .map_err(|e| format!("export recording task join failed: {e:?}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_html;
+    use serde_json::json;
+
+    #[test]
+    fn render_html_keeps_script_data_closed() {
+        let rec = json!({ "events": [{ "t": 0, "data": "echo '</script><b>'\r" }] });
+        let html = render_html("a <b>", "1 input events", &rec, Some("<out>"));
+        assert_eq!(html.matches("</script>").count(), 2);
+        assert!(html.contains("<title>a &lt;b&gt;</title>"));
+        assert!(html.contains("&lt;out&gt;"));
+    }
+}
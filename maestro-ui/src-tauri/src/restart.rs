@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub(crate) const EVENT_PTY_RESTARTED: &str = "pty-restarted";
+
+const DEFAULT_MAX_RESTARTS: u32 = 3;
+const DEFAULT_BACKOFF_MS: u64 = 1000;
+const MAX_RESTARTS_LIMIT: u32 = 100;
+const MAX_BACKOFF_MS: u64 = 5 * 60 * 1000;
+/// A run at least this long counts as healthy and resets the restart count, so a
+/// daemon that crashes once a day isn't given up on after a few days.
+const STABLE_RUN_MS: u64 = 10 * 60 * 1000;
+
+fn default_max_restarts() -> u32 {
+    DEFAULT_MAX_RESTARTS
+}
+
+fn default_backoff_ms() -> u64 {
+    DEFAULT_BACKOFF_MS
+}
+
+/// Respawn a session whose process exits non-zero, with the same command and env.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartPolicyV1 {
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    /// Delay before the first restart, doubled for each one after (up to 5 minutes).
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyRestarted {
+    pub id: String,
+    /// 1 for the first restart since the last healthy run.
+    pub attempt: u32,
+    pub max_restarts: u32,
+    pub exit_code: Option<u32>,
+    pub delay_ms: u64,
+}
+
+pub(crate) fn validate(policy: &RestartPolicyV1) -> Result<(), String> {
+    if policy.max_restarts > MAX_RESTARTS_LIMIT {
+        return Err(format!("maxRestarts must be at most {MAX_RESTARTS_LIMIT}"));
+    }
+    if policy.backoff_ms > MAX_BACKOFF_MS {
+        return Err(format!("backoffMs must be at most {MAX_BACKOFF_MS}"));
+    }
+    Ok(())
+}
+
+/// Restarts used so far, carried from one run of a session to the next.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RestartState {
+    pub policy: RestartPolicyV1,
+    pub attempts: u32,
+}
+
+impl RestartState {
+    pub(crate) fn new(policy: RestartPolicyV1) -> Self {
+        Self { policy, attempts: 0 }
+    }
+
+    /// How long to wait before restarting after a failed run of `ran_ms`, or
+    /// `None` once the restarts are used up.
+    pub(crate) fn next_delay(&mut self, ran_ms: u64) -> Option<Duration> {
+        if ran_ms >= STABLE_RUN_MS {
+            self.attempts = 0;
+        }
+        if self.attempts >= self.policy.max_restarts {
+            return None;
+        }
+        let delay = self
+            .policy
+            .backoff_ms
+            .saturating_mul(1 << self.attempts.min(20))
+            .min(MAX_BACKOFF_MS);
+        self.attempts += 1;
+        Some(Duration::from_millis(delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RestartPolicyV1, RestartState, STABLE_RUN_MS};
+    use std::time::Duration;
+
+    #[test]
+    fn backs_off_until_restarts_run_out() {
+        let mut state = RestartState::new(RestartPolicyV1 {
+            max_restarts: 3,
+            backoff_ms: 500,
+        });
+        let delays: Vec<_> = (0..4).map(|_| state.next_delay(100)).collect();
+        assert_eq!(
+            delays,
+            [
+                Some(Duration::from_millis(500)),
+                Some(Duration::from_millis(1000)),
+                Some(Duration::from_millis(2000)),
+                None
+            ]
+        );
+        // A long healthy run earns the restarts back.
+        assert_eq!(state.next_delay(STABLE_RUN_MS), Some(Duration::from_millis(500)));
+        assert_eq!(state.attempts, 1);
+    }
+}
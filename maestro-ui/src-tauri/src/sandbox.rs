@@ -0,0 +1,344 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Filesystem sandbox for a spawned session. The project root (default: the
+/// session cwd) and `write_paths` are writable; `read_paths` are read-only.
+/// Everything else under the home directory is hidden, and the rest of the
+/// system is read-only apart from temp dirs.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxProfileV1 {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    #[serde(default)]
+    pub read_paths: Vec<String>,
+    #[serde(default)]
+    pub write_paths: Vec<String>,
+}
+
+#[cfg_attr(not(any(target_os = "macos", target_os = "linux")), allow(dead_code))]
+struct ResolvedProfile {
+    root: PathBuf,
+    read_paths: Vec<PathBuf>,
+    write_paths: Vec<PathBuf>,
+}
+
+fn canonical_dir(path: &str) -> Result<PathBuf, String> {
+    let trimmed = path.trim();
+    let expanded = match trimmed.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .ok_or("cannot determine home directory")?
+            .join(rest),
+        None => PathBuf::from(trimmed),
+    };
+    if !expanded.is_absolute() {
+        return Err(format!("sandbox path must be absolute: {trimmed}"));
+    }
+    expanded
+        .canonicalize()
+        .map_err(|e| format!("sandbox path {trimmed} not accessible: {e}"))
+}
+
+fn resolve(profile: &SandboxProfileV1, cwd: Option<&str>) -> Result<ResolvedProfile, String> {
+    let root = profile
+        .root
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .or(cwd)
+        .ok_or("sandboxed sessions need a project root or cwd")?;
+    let root = canonical_dir(root)?;
+    let home = dirs::home_dir().and_then(|h| h.canonicalize().ok());
+    if home.as_deref() == Some(root.as_path()) || root.parent().is_none() {
+        return Err("sandbox root must not be the home or filesystem root".to_string());
+    }
+    let collect = |paths: &[String]| -> Result<Vec<PathBuf>, String> {
+        paths
+            .iter()
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| canonical_dir(p))
+            .collect()
+    };
+    Ok(ResolvedProfile {
+        root,
+        read_paths: collect(&profile.read_paths)?,
+        write_paths: collect(&profile.write_paths)?,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn sbpl_quote(path: &std::path::Path) -> String {
+    let raw = path.to_string_lossy();
+    format!("\"{}\"", raw.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Seatbelt profile: hide `home` except the allowed paths and deny writes
+/// outside the writable ones; everything else stays allowed.
+#[cfg(target_os = "macos")]
+fn seatbelt_profile(profile: &ResolvedProfile, home: &Path) -> String {
+    let subpaths = |paths: &[&PathBuf]| -> String {
+        paths
+            .iter()
+            .map(|p| format!(" (subpath {})", sbpl_quote(p)))
+            .collect()
+    };
+    let writable: Vec<&PathBuf> = std::iter::once(&profile.root)
+        .chain(profile.write_paths.iter())
+        .collect();
+    let readable: Vec<&PathBuf> = writable
+        .iter()
+        .copied()
+        .chain(profile.read_paths.iter())
+        .collect();
+
+    format!(
+        concat!(
+            "(version 1)\n",
+            "(allow default)\n",
+            "(deny file-read* (subpath {home}))\n",
+            "(allow file-read* (literal {home}){readable})\n",
+            "(deny file-write*)\n",
+            "(allow file-write*{writable} (subpath \"/private/tmp\") (subpath \"/private/var/folders\")",
+            " (literal \"/dev/null\") (regex #\"^/dev/tty\") (literal \"/dev/ptmx\"))\n",
+        ),
+        home = sbpl_quote(home),
+        readable = subpaths(&readable),
+        writable = subpaths(&writable),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn path_arg(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// bubblewrap: read-only `/` with a private /tmp and an empty `home`, then the
+/// allowed paths bound back in on top. `startup_dir` (shell startup files pty.rs
+/// writes to the temp dir) is bound read-only so the private /tmp doesn't hide it.
+#[cfg(target_os = "linux")]
+fn bwrap_args(profile: &ResolvedProfile, home: Option<&Path>, startup_dir: Option<&Path>) -> Vec<String> {
+    let mut out: Vec<String> = [
+        "--die-with-parent",
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev",
+        "/dev",
+        "--proc",
+        "/proc",
+        "--tmpfs",
+        "/tmp",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    if let Some(dir) = startup_dir {
+        out.extend(["--ro-bind-try".to_string(), path_arg(dir), path_arg(dir)]);
+    }
+    if let Some(home) = home {
+        out.extend(["--tmpfs".to_string(), path_arg(home)]);
+    }
+    for p in &profile.read_paths {
+        out.extend(["--ro-bind".to_string(), path_arg(p), path_arg(p)]);
+    }
+    for p in std::iter::once(&profile.root).chain(profile.write_paths.iter()) {
+        out.extend(["--bind".to_string(), path_arg(p), path_arg(p)]);
+    }
+    out
+}
+
+/// firejail: the whitelist hides the rest of the home dir, `--read-only=/` makes
+/// the whole system read-only, and only the root, `write_paths` and the temp
+/// dirs are made writable again.
+#[cfg(target_os = "linux")]
+fn firejail_args(profile: &ResolvedProfile, temp_dir: &Path) -> Vec<String> {
+    let mut out: Vec<String> = vec!["--quiet".to_string(), "--noprofile".to_string()];
+    for p in std::iter::once(&profile.root)
+        .chain(profile.write_paths.iter())
+        .chain(profile.read_paths.iter())
+    {
+        out.push(format!("--whitelist={}", path_arg(p)));
+    }
+    out.push("--read-only=/".to_string());
+    let mut writable: Vec<&Path> = vec![Path::new("/tmp"), Path::new("/var/tmp")];
+    if !writable.contains(&temp_dir) {
+        writable.push(temp_dir);
+    }
+    for p in std::iter::once(&profile.root)
+        .chain(profile.write_paths.iter())
+        .map(PathBuf::as_path)
+        .chain(writable)
+    {
+        out.push(format!("--read-write={}", path_arg(p)));
+    }
+    for p in &profile.read_paths {
+        out.push(format!("--read-only={}", path_arg(p)));
+    }
+    out
+}
+
+#[cfg(target_os = "linux")]
+fn linux_wrapper(
+    profile: &ResolvedProfile,
+    startup_dir: Option<&Path>,
+    program: String,
+    args: Vec<String>,
+) -> Result<(String, Vec<String>), String> {
+    let (launcher, mut out) = if let Some(bwrap) = crate::ssh_fs::find_program_in_path("bwrap") {
+        let home = dirs::home_dir();
+        (bwrap, bwrap_args(profile, home.as_deref(), startup_dir))
+    } else if let Some(firejail) = crate::ssh_fs::find_program_in_path("firejail") {
+        (firejail, firejail_args(profile, &std::env::temp_dir()))
+    } else {
+        return Err("sandboxing requires bubblewrap (bwrap) or firejail to be installed".to_string());
+    };
+    out.push("--".to_string());
+    out.push(program);
+    out.extend(args);
+    Ok((path_arg(&launcher), out))
+}
+
+/// Wrap `program args` so it runs inside the platform sandbox described by `profile`.
+/// `startup_dir` is a per-session temp dir the shell reads its startup files from.
+pub(crate) fn wrap_command(
+    profile: &SandboxProfileV1,
+    cwd: Option<&str>,
+    startup_dir: Option<&Path>,
+    program: String,
+    args: Vec<String>,
+) -> Result<(String, Vec<String>), String> {
+    let resolved = resolve(profile, cwd)?;
+
+    #[cfg(target_os = "macos")]
+    {
+        // Temp dirs stay readable under seatbelt, so `startup_dir` needs no rule.
+        let _ = startup_dir;
+        let home = dirs::home_dir()
+            .ok_or("cannot determine home directory")?
+            .canonicalize()
+            .map_err(|e| format!("home dir not accessible: {e}"))?;
+        let mut out = vec!["-p".to_string(), seatbelt_profile(&resolved, &home), program];
+        out.extend(args);
+        Ok(("/usr/bin/sandbox-exec".to_string(), out))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux_wrapper(&resolved, startup_dir, program, args)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (resolved, startup_dir, program, args);
+        Err("sandboxed sessions are not supported on this platform".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve, SandboxProfileV1};
+
+    #[test]
+    fn resolves_and_checks_sandbox_paths() {
+        let base = std::env::temp_dir().join(format!("sandbox-resolve-{}", std::process::id()));
+        std::fs::create_dir_all(base.join("project/../shared")).unwrap();
+        std::fs::create_dir_all(base.join("project")).unwrap();
+        let base = base.canonicalize().unwrap();
+        let project = base.join("project");
+
+        let profile = SandboxProfileV1 {
+            root: None,
+            read_paths: vec![format!("{}/project/../shared", base.display()), " ".to_string()],
+            write_paths: Vec::new(),
+        };
+        let resolved = resolve(&profile, project.to_str()).unwrap();
+        assert_eq!(resolved.root, project);
+        assert_eq!(resolved.read_paths, [base.join("shared")]);
+
+        assert!(resolve(&SandboxProfileV1::default(), None).is_err());
+        assert!(resolve(&SandboxProfileV1::default(), Some("/")).is_err());
+        assert!(resolve(&SandboxProfileV1::default(), Some("relative/dir")).is_err());
+        let missing = SandboxProfileV1 {
+            write_paths: vec![base.join("missing").to_string_lossy().to_string()],
+            ..SandboxProfileV1::default()
+        };
+        assert!(resolve(&missing, project.to_str()).is_err());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn builds_bwrap_and_firejail_args() {
+        use super::{bwrap_args, firejail_args, ResolvedProfile};
+        use std::path::{Path, PathBuf};
+
+        let profile = ResolvedProfile {
+            root: PathBuf::from("/home/a/project"),
+            read_paths: vec![PathBuf::from("/home/a/.cargo")],
+            write_paths: vec![PathBuf::from("/srv/cache")],
+        };
+        let bwrap = bwrap_args(&profile, Some(Path::new("/home/a")), Some(Path::new("/tmp/zdot-1")));
+        let tail = [
+            "--tmpfs",
+            "/tmp",
+            "--ro-bind-try",
+            "/tmp/zdot-1",
+            "/tmp/zdot-1",
+            "--tmpfs",
+            "/home/a",
+            "--ro-bind",
+            "/home/a/.cargo",
+            "/home/a/.cargo",
+            "--bind",
+            "/home/a/project",
+            "/home/a/project",
+            "--bind",
+            "/srv/cache",
+            "/srv/cache",
+        ];
+        assert!(bwrap.ends_with(&tail.map(String::from)));
+
+        let firejail = firejail_args(&profile, Path::new("/tmp"));
+        assert_eq!(
+            firejail,
+            [
+                "--quiet",
+                "--noprofile",
+                "--whitelist=/home/a/project",
+                "--whitelist=/srv/cache",
+                "--whitelist=/home/a/.cargo",
+                "--read-only=/",
+                "--read-write=/home/a/project",
+                "--read-write=/srv/cache",
+                "--read-write=/tmp",
+                "--read-write=/var/tmp",
+                "--read-only=/home/a/.cargo",
+            ]
+        );
+        assert!(firejail_args(&profile, Path::new("/run/user/1000/tmp"))
+            .contains(&"--read-write=/run/user/1000/tmp".to_string()));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn builds_seatbelt_profile() {
+        use super::{seatbelt_profile, ResolvedProfile};
+        use std::path::{Path, PathBuf};
+
+        let profile = ResolvedProfile {
+            root: PathBuf::from("/Users/a/project"),
+            read_paths: vec![PathBuf::from("/Users/a/\"quoted\"")],
+            write_paths: Vec::new(),
+        };
+        let sbpl = seatbelt_profile(&profile, Path::new("/Users/a"));
+        assert!(sbpl.contains("(deny file-read* (subpath \"/Users/a\"))"));
+        assert!(sbpl.contains(concat!(
+            "(allow file-read* (literal \"/Users/a\") (subpath \"/Users/a/project\")",
+            " (subpath \"/Users/a/\\\"quoted\\\"\"))",
+        )));
+        assert!(sbpl.contains(concat!(
+            "(allow file-write* (subpath \"/Users/a/project\")",
+            " (subpath \"/private/tmp\")",
+        )));
+    }
+}
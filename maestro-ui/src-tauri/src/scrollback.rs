@@ -0,0 +1,216 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::{State, WebviewWindow};
+
+use crate::pty::AppState;
+
+/// Raw output kept in memory per session for replay into a fresh terminal view.
+pub(crate) const SCROLLBACK_BYTES: usize = 512 * 1024;
+/// How far past a truncated start we look for a line break to resume from.
+const RESYNC_WINDOW: usize = 4 * 1024;
+
+pub(crate) type SharedScrollback = Arc<Mutex<ScrollbackBuffer>>;
+
+/// Ring buffer over the most recent pty output, fed by the reader thread.
+pub(crate) struct ScrollbackBuffer {
+    data: VecDeque<u8>,
+    capacity: usize,
+    /// Bytes read from the pty since the session started.
+    total: u64,
+}
+
+impl ScrollbackBuffer {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            data: VecDeque::with_capacity(capacity.min(64 * 1024)),
+            capacity,
+            total: 0,
+        }
+    }
+
+    pub(crate) fn shared() -> SharedScrollback {
+        Arc::new(Mutex::new(Self::new(SCROLLBACK_BYTES)))
+    }
+
+    pub(crate) fn push(&mut self, bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        let bytes = &bytes[bytes.len().saturating_sub(self.capacity)..];
+        let overflow = (self.data.len() + bytes.len()).saturating_sub(self.capacity);
+        self.data.drain(..overflow);
+        self.data.extend(bytes);
+    }
+
+    /// The last `max_bytes` of output as (data, offset of its first byte). A cut start
+    /// resumes at the next line break, so replay doesn't begin inside a UTF-8
+    /// character or an escape sequence.
+    pub(crate) fn tail(&self, max_bytes: usize) -> (String, u64) {
+        let skip = self.data.len().saturating_sub(max_bytes);
+        let mut bytes: Vec<u8> = self.data.iter().skip(skip).copied().collect();
+        let mut start = self.total - bytes.len() as u64;
+        if start > 0 {
+            let resume = bytes
+                .iter()
+                .take(RESYNC_WINDOW)
+                .position(|b| *b == b'\n')
+                .map(|i| i + 1)
+                .unwrap_or_else(|| bytes.iter().take_while(|b| (**b & 0xC0) == 0x80).count());
+            bytes.drain(..resume);
+            start += resume as u64;
+        }
+        (String::from_utf8_lossy(&bytes).to_string(), start)
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionOutputMatch {
+    /// 1-based line within the searched output.
+    pub line: usize,
+    /// The line with escape codes removed.
+    pub text: String,
+    /// Character range of the match within `text`.
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Find `re` in terminal output, line by line, after removing escape codes. A line
+/// redrawn with carriage returns (progress bars) is searched as last drawn.
+pub(crate) fn search_lines(output: &str, re: &regex::Regex, max_results: usize) -> (Vec<SessionOutputMatch>, bool) {
+    let text = crate::throttle::strip_ansi(output);
+    let mut matches = Vec::new();
+    for (i, raw) in text.split('\n').enumerate() {
+        let line = raw.trim_end_matches('\r');
+        let line = line.rsplit('\r').next().unwrap_or(line);
+        for m in re.find_iter(line) {
+            if matches.len() == max_results {
+                return (matches, true);
+            }
+            matches.push(SessionOutputMatch {
+                line: i + 1,
+                text: line.to_string(),
+                start: line[..m.start()].chars().count(),
+                end: line[..m.end()].chars().count(),
+            });
+        }
+    }
+    (matches, false)
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionScrollback {
+    pub id: String,
+    pub data: String,
+    /// Output offset of the first returned byte.
+    pub from: u64,
+    /// Output offset just past the last byte; later `pty-output` events continue here.
+    pub to: u64,
+    /// Earlier output was dropped, either by the buffer size or by `max_bytes`.
+    pub truncated: bool,
+    /// The data came from the on-disk transcript rather than the memory buffer.
+    pub from_disk: bool,
+}
+
+/// Recent output of a session, for repopulating xterm.js after a tab is recreated or
+/// the window reloads. Defaults to everything in the memory buffer (512 KiB). With
+/// `include_disk`, falls back to the session transcript when the buffer has dropped
+/// output or the session is gone (ids may then be transcript keys).
+#[tauri::command]
+pub async fn get_session_scrollback(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    id: String,
+    max_bytes: Option<usize>,
+    include_disk: Option<bool>,
+) -> Result<SessionScrollback, String> {
+    let max_bytes = max_bytes.unwrap_or(SCROLLBACK_BYTES).max(1);
+    let include_disk = include_disk.unwrap_or(false);
+    let buffer = state.scrollback(&id);
+    let key = state.transcript_key(&id);
+
+    let memory = match buffer {
+        Some(buffer) => {
+            let buffer = buffer.lock().map_err(|_| "scrollback poisoned")?;
+            let (data, from) = buffer.tail(max_bytes);
+            Some(SessionScrollback {
+                id: id.clone(),
+                data,
+                from,
+                to: buffer.total,
+                truncated: from > 0,
+                from_disk: false,
+            })
+        }
+        None => None,
+    };
+    let wants_disk = match &memory {
+        Some(m) => m.truncated && (m.to - m.from) < max_bytes as u64,
+        None => true,
+    };
+    if !include_disk || !wants_disk {
+        return memory.ok_or_else(|| "unknown session".to_string());
+    }
+
+    let key = key.unwrap_or_else(|| crate::recording::sanitize_recording_id(&id));
+    let disk = tauri::async_runtime::spawn_blocking(move || crate::transcript::read_transcript_tail(&window, &key))
+        .await
+        .map_err(|e| format!("read scrollback task join failed: {e:?}"))?;
+    let Some(text) = disk else {
+        return memory.ok_or_else(|| "no output recorded for session".to_string());
+    };
+
+    let mut disk_buffer = ScrollbackBuffer::new(max_bytes);
+    disk_buffer.push(text.as_bytes());
+    let (data, _) = disk_buffer.tail(max_bytes);
+    if memory.as_ref().is_some_and(|m| m.data.len() >= data.len()) {
+        return memory.ok_or_else(|| "unknown session".to_string());
+    }
+    // Transcript offsets don't line up with the live counter; anchor on the end.
+    let to = memory.as_ref().map(|m| m.to).unwrap_or(text.len() as u64);
+    Ok(SessionScrollback {
+        id,
+        from: to.saturating_sub(data.len() as u64),
+        to,
+        truncated: data.len() < text.len(),
+        data,
+        from_disk: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{search_lines, ScrollbackBuffer};
+
+    #[test]
+    fn ring_keeps_recent_output_from_a_line_start() {
+        let mut buffer = ScrollbackBuffer::new(16);
+        buffer.push(b"hello\r\n");
+        assert_eq!(buffer.tail(64), ("hello\r\n".to_string(), 0));
+
+        buffer.push(b"first line\r\nsecond\r\n");
+        assert_eq!(buffer.total, 27);
+        // The buffer starts inside "first line"; replay resumes at the next line.
+        assert_eq!(buffer.tail(64), ("second\r\n".to_string(), 19));
+
+        let mut buffer = ScrollbackBuffer::new(4);
+        buffer.push("aé€".as_bytes());
+        assert_eq!(buffer.tail(4), ("€".to_string(), 3));
+    }
+
+    #[test]
+    fn search_reports_lines_and_char_ranges() {
+        let out = "build ok\r\n\x1b[31merror\x1b[0m: café missing\r\n 10%\r 100% error\r\n";
+        let re = regex::Regex::new("(?i)error").unwrap();
+        let (matches, truncated) = search_lines(out, &re, 10);
+        assert!(!truncated);
+        assert_eq!(matches.len(), 2);
+        assert_eq!((matches[0].line, matches[0].text.as_str()), (2, "error: café missing"));
+        assert_eq!((matches[1].line, matches[1].start, matches[1].end), (3, 6, 11));
+
+        let re = regex::Regex::new("é").unwrap();
+        let (matches, _) = search_lines(out, &re, 10);
+        assert_eq!((matches[0].start, matches[0].end), (10, 11));
+        assert!(search_lines(out, &regex::Regex::new("o").unwrap(), 1).1);
+    }
+}
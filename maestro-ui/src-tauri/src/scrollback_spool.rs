@@ -0,0 +1,174 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{Manager, WebviewWindow};
+
+use crate::scrollback::ScrollbackBuffer;
+
+const SPOOL_DIR_NAME: &str = "scrollback-spool";
+const SPOOL_EXT: &str = "log";
+/// Persist ids given to sessions attached to a zellij session; only these are spooled.
+const ZELLIJ_PERSIST_PREFIX: &str = "zellij-";
+/// Once a spool grows past this it is cut back to the most recent half.
+const MAX_SPOOL_BYTES: usize = 2 * 1024 * 1024;
+const FLUSH_BYTES: usize = 16 * 1024;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpooledHistory {
+    pub id: String,
+    pub persist_id: String,
+    /// Decoded output from earlier attaches, oldest first.
+    pub data: String,
+    /// Older output was dropped, either by the spool cap or by `max_bytes`.
+    pub truncated: bool,
+}
+
+/// The persist id for a Maestro session attached to the zellij session `name`.
+pub(crate) fn zellij_persist_id(name: &str) -> String {
+    crate::recording::sanitize_recording_id(&format!("{ZELLIJ_PERSIST_PREFIX}{name}"))
+}
+
+fn spool_key(persist_id: Option<&str>) -> Option<String> {
+    persist_id
+        .map(str::trim)
+        .filter(|p| p.starts_with(ZELLIJ_PERSIST_PREFIX))
+        .map(crate::recording::sanitize_recording_id)
+}
+
+fn spool_path(window: &WebviewWindow, key: &str) -> Option<PathBuf> {
+    let dir = window.app_handle().path().app_data_dir().ok()?.join(SPOOL_DIR_NAME);
+    Some(dir.join(format!("{key}.{SPOOL_EXT}")))
+}
+
+/// The last `max_bytes` of `data`, starting on a line (see `ScrollbackBuffer::tail`).
+fn keep_tail(data: &[u8], max_bytes: usize) -> (String, bool) {
+    let mut buffer = ScrollbackBuffer::new(max_bytes);
+    buffer.push(data);
+    let (text, from) = buffer.tail(max_bytes);
+    (text, from > 0)
+}
+
+/// What each attached session's spool held when it was opened, by session id.
+fn history() -> &'static Mutex<HashMap<String, (String, String)>> {
+    static HISTORY: OnceLock<Mutex<HashMap<String, (String, String)>>> = OnceLock::new();
+    HISTORY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Appends a zellij-attached session's decoded output to `scrollback-spool/<persist id>.log`,
+/// so the history is still there after the app restarts and the session is reattached.
+/// Owned by the session's reader thread.
+pub(crate) struct SpoolWriter {
+    session_id: String,
+    path: PathBuf,
+    len: usize,
+    pending: String,
+    last_flush: Instant,
+}
+
+impl SpoolWriter {
+    /// None unless `persist_id` is a zellij persist id and the spool dir can be created.
+    pub(crate) fn open(window: &WebviewWindow, session_id: &str, persist_id: Option<&str>) -> Option<Self> {
+        let key = spool_key(persist_id)?;
+        let path = spool_path(window, &key)?;
+        fs::create_dir_all(path.parent()?).ok()?;
+
+        let previous = fs::read(&path).unwrap_or_default();
+        if let Ok(mut history) = history().lock() {
+            let text = String::from_utf8_lossy(&previous).to_string();
+            history.insert(session_id.to_string(), (key, text));
+        }
+        Some(Self {
+            session_id: session_id.to_string(),
+            path,
+            len: previous.len(),
+            pending: String::new(),
+            last_flush: Instant::now(),
+        })
+    }
+
+    pub(crate) fn feed(&mut self, data: &str) {
+        self.pending.push_str(data);
+        if self.pending.len() >= FLUSH_BYTES || self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        self.last_flush = Instant::now();
+        if self.pending.is_empty() {
+            return;
+        }
+        let written = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(self.pending.as_bytes()));
+        if written.is_err() {
+            return;
+        }
+        self.len += self.pending.len();
+        self.pending.clear();
+        if self.len > MAX_SPOOL_BYTES {
+            self.compact();
+        }
+    }
+
+    fn compact(&mut self) {
+        let Ok(data) = fs::read(&self.path) else {
+            return;
+        };
+        let (kept, _) = keep_tail(&data, MAX_SPOOL_BYTES / 2);
+        let tmp = self.path.with_extension(format!("{SPOOL_EXT}.tmp"));
+        if fs::write(&tmp, kept.as_bytes()).is_ok() && fs::rename(&tmp, &self.path).is_ok() {
+            self.len = kept.len();
+        }
+    }
+}
+
+impl Drop for SpoolWriter {
+    fn drop(&mut self) {
+        self.flush();
+        if let Ok(mut history) = history().lock() {
+            history.remove(&self.session_id);
+        }
+    }
+}
+
+/// Output spooled for a zellij-attached session before this attach, for the UI to show
+/// above the live terminal. Defaults to the last 512 KiB.
+#[tauri::command]
+pub fn get_spooled_history(id: String, max_bytes: Option<usize>) -> Result<SpooledHistory, String> {
+    let max_bytes = max_bytes.unwrap_or(crate::scrollback::SCROLLBACK_BYTES).max(1);
+    let history = history().lock().map_err(|_| "spool history poisoned")?;
+    let (persist_id, text) = history.get(&id).ok_or("no spooled history for session")?;
+    let (data, cut) = keep_tail(text.as_bytes(), max_bytes);
+    Ok(SpooledHistory {
+        id,
+        persist_id: persist_id.clone(),
+        data,
+        truncated: cut,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keep_tail, spool_key, zellij_persist_id};
+
+    #[test]
+    fn spools_only_zellij_persist_ids() {
+        let persist_id = zellij_persist_id("work dev");
+        assert_eq!(persist_id, "zellij-work_dev");
+        assert_eq!(spool_key(Some(&persist_id)).as_deref(), Some("zellij-work_dev"));
+        assert_eq!(spool_key(Some("project-1")), None);
+        assert_eq!(spool_key(None), None);
+
+        assert_eq!(keep_tail(b"old line\nnew line\n", 12), ("new line\n".to_string(), true));
+        assert_eq!(keep_tail(b"all\n", 12), ("all\n".to_string(), false));
+    }
+}
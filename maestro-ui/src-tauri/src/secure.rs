@@ -7,6 +7,8 @@ use std::sync::{Mutex, OnceLock};
 use tauri::Manager;
 use tauri::WebviewWindow;
 
+use crate::faults::FaultDomain;
+
 const KEYCHAIN_ACCOUNT: &str = "agents-ui-data-key-v1";
 const ENC_PREFIX: &str = "enc:v1:";
 const NONCE_LEN: usize = 12;
@@ -71,6 +73,7 @@ fn get_or_create_master_key_uncached(window: &WebviewWindow) -> Result<[u8; KEY_
     let entry = keyring::Entry::new(&service, KEYCHAIN_ACCOUNT)
         .map_err(|e| format!("keychain init failed: {e}"))?;
 
+    crate::faults::inject(FaultDomain::Keychain, "keychain read")?;
     match entry.get_password() {
         Ok(encoded) => {
             let decoded = BASE64
@@ -91,6 +94,7 @@ fn get_or_create_master_key_uncached(window: &WebviewWindow) -> Result<[u8; KEY_
     OsRng.fill_bytes(&mut key);
     let encoded = BASE64.encode(key);
 
+    crate::faults::inject(FaultDomain::Keychain, "keychain write")?;
     entry
         .set_password(&encoded)
         .map_err(|e| format!("keychain write failed: {e}"))?;
@@ -99,6 +103,7 @@ fn get_or_create_master_key_uncached(window: &WebviewWindow) -> Result<[u8; KEY_
 
 pub fn get_or_create_master_key(window: &WebviewWindow) -> Result<[u8; KEY_LEN], String> {
     let cache = master_key_cache();
+    crate::faults::maybe_poison(cache);
     let mut state = cache.lock().map_err(|_| "secure storage cache poisoned".to_string())?;
     match &*state {
         MasterKeyCacheState::Ready(key) => return Ok(*key),
@@ -106,7 +111,9 @@ pub fn get_or_create_master_key(window: &WebviewWindow) -> Result<[u8; KEY_LEN],
         MasterKeyCacheState::Uninitialized => {}
     }
 
-    match get_or_create_master_key_uncached(window) {
+    let result = get_or_create_master_key_uncached(window);
+    crate::audit::record_result(window.app_handle(), "secret.read", KEYCHAIN_ACCOUNT, &result);
+    match result {
         Ok(key) => {
             *state = MasterKeyCacheState::Ready(key);
             Ok(key)
@@ -119,6 +126,24 @@ pub fn get_or_create_master_key(window: &WebviewWindow) -> Result<[u8; KEY_LEN],
     }
 }
 
+/// Whether the data key can be read from the keychain, without creating one.
+/// Ok(false) means no key has been stored yet.
+pub(crate) fn probe_master_key(window: &WebviewWindow) -> Result<bool, String> {
+    if let Ok(state) = master_key_cache().lock() {
+        if let MasterKeyCacheState::Ready(_) = &*state {
+            return Ok(true);
+        }
+    }
+    let entry = keyring::Entry::new(&keychain_service(window), KEYCHAIN_ACCOUNT)
+        .map_err(|e| format!("keychain init failed: {e}"))?;
+    crate::faults::inject(FaultDomain::Keychain, "keychain read")?;
+    match entry.get_password() {
+        Ok(_) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(format!("keychain read failed: {e}")),
+    }
+}
+
 pub fn reset_master_key_cache() -> Result<(), String> {
     let cache = master_key_cache();
     let mut state = cache.lock().map_err(|_| "secure storage cache poisoned".to_string())?;
@@ -129,6 +154,11 @@ pub fn reset_master_key_cache() -> Result<(), String> {
 #[tauri::command]
 pub fn prepare_secure_storage(window: WebviewWindow) -> Result<(), String> {
     let _ = get_or_create_master_key(&window)?;
+    crate::onboarding::mark_step_completed(
+        &window,
+        crate::onboarding::STEP_SECURE_STORAGE_PREPARED,
+        Some("keychain".to_string()),
+    );
     Ok(())
 }
 
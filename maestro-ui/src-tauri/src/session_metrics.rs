@@ -0,0 +1,255 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::pty::AppState;
+
+const EVENT_SESSION_METRICS: &str = "session-metrics";
+const DEFAULT_INTERVAL_MS: u64 = 2000;
+const MIN_INTERVAL_MS: u64 = 500;
+
+/// Network usage of a session's process tree. Byte counts cover sockets that
+/// are currently open, so they can drop when connections close; rates are
+/// clamped at zero.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMetricsV1 {
+    pub id: String,
+    pub pid: Option<u32>,
+    pub process_count: usize,
+    pub connections: u32,
+    pub net_rx_bytes: u64,
+    pub net_tx_bytes: u64,
+    pub net_rx_bytes_per_sec: u64,
+    pub net_tx_bytes_per_sec: u64,
+}
+
+#[derive(Default, Clone, Copy)]
+struct NetCounters {
+    rx: u64,
+    tx: u64,
+    connections: u32,
+}
+
+/// Bumped on every enable/disable so a superseded sampler thread exits.
+fn sampler_generation() -> &'static Mutex<u64> {
+    static GENERATION: OnceLock<Mutex<u64>> = OnceLock::new();
+    GENERATION.get_or_init(|| Mutex::new(0))
+}
+
+fn run_capture(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// parent pid -> child pids, for walking each session's process tree.
+#[cfg(target_family = "unix")]
+fn process_children() -> HashMap<u32, Vec<u32>> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let Some(out) = run_capture("ps", &["-axo", "pid=,ppid="]) else {
+        return children;
+    };
+    for line in out.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(pid), Some(ppid)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if let (Ok(pid), Ok(ppid)) = (pid.parse::<u32>(), ppid.parse::<u32>()) {
+            children.entry(ppid).or_default().push(pid);
+        }
+    }
+    children
+}
+
+#[cfg(not(target_family = "unix"))]
+fn process_children() -> HashMap<u32, Vec<u32>> {
+    HashMap::new()
+}
+
+fn descendants(root: u32, children: &HashMap<u32, Vec<u32>>) -> Vec<u32> {
+    let mut out = vec![root];
+    let mut i = 0;
+    while i < out.len() {
+        if let Some(kids) = children.get(&out[i]) {
+            out.extend(kids.iter().copied().filter(|k| !out.contains(k)).collect::<Vec<_>>());
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Per-pid TCP byte counters from `ss -tinpH`: each socket is a line with
+/// `users:(("name",pid=N,fd=M))` followed by an indented info line with
+/// `bytes_sent:`/`bytes_received:`.
+#[cfg(target_os = "linux")]
+fn net_counters_by_pid() -> HashMap<u32, NetCounters> {
+    let mut by_pid: HashMap<u32, NetCounters> = HashMap::new();
+    let Some(out) = run_capture("ss", &["-tinpH"]) else {
+        return by_pid;
+    };
+    let field = |line: &str, key: &str| -> u64 {
+        line.split_whitespace()
+            .find_map(|tok| tok.strip_prefix(key))
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+    };
+    let mut current: Option<u32> = None;
+    for line in out.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            current = line
+                .split("pid=")
+                .nth(1)
+                .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+                .and_then(|pid| pid.parse().ok());
+            if let Some(pid) = current {
+                by_pid.entry(pid).or_default().connections += 1;
+            }
+            continue;
+        }
+        if let Some(pid) = current.take() {
+            let counters = by_pid.entry(pid).or_default();
+            counters.rx += field(line, "bytes_received:");
+            counters.tx += field(line, "bytes_sent:");
+        }
+    }
+    by_pid
+}
+
+/// Per-pid byte counters from one `nettop` snapshot (`name.pid,bytes_in,bytes_out,`).
+#[cfg(target_os = "macos")]
+fn net_counters_by_pid() -> HashMap<u32, NetCounters> {
+    let mut by_pid: HashMap<u32, NetCounters> = HashMap::new();
+    let Some(out) = run_capture("/usr/bin/nettop", &["-P", "-L", "1", "-x", "-J", "bytes_in,bytes_out"])
+    else {
+        return by_pid;
+    };
+    for line in out.lines().skip(1) {
+        let mut cols = line.split(',');
+        let (Some(name), Some(rx), Some(tx)) = (cols.next(), cols.next(), cols.next()) else {
+            continue;
+        };
+        let Some(pid) = name.rsplit_once('.').and_then(|(_, pid)| pid.parse::<u32>().ok()) else {
+            continue;
+        };
+        by_pid.insert(
+            pid,
+            NetCounters {
+                rx: rx.trim().parse().unwrap_or(0),
+                tx: tx.trim().parse().unwrap_or(0),
+                connections: 0,
+            },
+        );
+    }
+    by_pid
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn net_counters_by_pid() -> HashMap<u32, NetCounters> {
+    HashMap::new()
+}
+
+fn sample(
+    state: &AppState,
+    previous: &mut HashMap<String, (Instant, NetCounters)>,
+) -> Result<Vec<SessionMetricsV1>, String> {
+    let sessions = state.session_activity()?;
+    let children = process_children();
+    let counters = net_counters_by_pid();
+    let now = Instant::now();
+
+    let mut out = Vec::with_capacity(sessions.len());
+    let mut next: HashMap<String, (Instant, NetCounters)> = HashMap::new();
+    for session in sessions {
+        let pids = session.pid.map(|pid| descendants(pid, &children)).unwrap_or_default();
+        let mut total = NetCounters::default();
+        for pid in &pids {
+            if let Some(c) = counters.get(pid) {
+                total.rx += c.rx;
+                total.tx += c.tx;
+                total.connections += c.connections;
+            }
+        }
+
+        let (rx_rate, tx_rate) = match previous.get(&session.id) {
+            Some((at, prev)) => {
+                let secs = now.duration_since(*at).as_secs_f64().max(0.001);
+                (
+                    (total.rx.saturating_sub(prev.rx) as f64 / secs) as u64,
+                    (total.tx.saturating_sub(prev.tx) as f64 / secs) as u64,
+                )
+            }
+            None => (0, 0),
+        };
+        next.insert(session.id.clone(), (now, total));
+
+        out.push(SessionMetricsV1 {
+            id: session.id,
+            pid: session.pid,
+            process_count: pids.len(),
+            connections: total.connections,
+            net_rx_bytes: total.rx,
+            net_tx_bytes: total.tx,
+            net_rx_bytes_per_sec: rx_rate,
+            net_tx_bytes_per_sec: tx_rate,
+        });
+    }
+    *previous = next;
+    Ok(out)
+}
+
+/// Start (or stop) the `session-metrics` event stream, sampled every
+/// `intervalMs` (default 2s).
+#[tauri::command]
+pub fn set_session_metrics_enabled(
+    app: AppHandle,
+    enabled: bool,
+    interval_ms: Option<u64>,
+) -> Result<(), String> {
+    let generation = {
+        let mut generation = sampler_generation().lock().map_err(|_| "metrics state poisoned")?;
+        *generation += 1;
+        *generation
+    };
+    if !enabled {
+        return Ok(());
+    }
+
+    let interval = Duration::from_millis(interval_ms.unwrap_or(DEFAULT_INTERVAL_MS).max(MIN_INTERVAL_MS));
+    std::thread::spawn(move || {
+        let mut previous = HashMap::new();
+        loop {
+            let current = sampler_generation().lock().map(|g| *g).unwrap_or(0);
+            if current != generation {
+                break;
+            }
+            let state = app.state::<AppState>();
+            match sample(state.inner(), &mut previous) {
+                Ok(metrics) => {
+                    let _ = app.emit(EVENT_SESSION_METRICS, metrics);
+                }
+                Err(e) => eprintln!("session metrics sample failed: {e}"),
+            }
+            std::thread::sleep(interval);
+        }
+    });
+    Ok(())
+}
+
+/// One-off sample; rates are zero since there is no previous sample to compare.
+#[tauri::command]
+pub fn get_session_metrics(app: AppHandle) -> Result<Vec<SessionMetricsV1>, String> {
+    let state = app.state::<AppState>();
+    sample(state.inner(), &mut HashMap::new())
+}
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+use tauri::{State, WebviewWindow};
+
+use crate::pty::AppState;
+use crate::util::now_epoch_ms;
+
+/// Longest slug taken from a project title or profile name.
+const MAX_PART_LEN: usize = 16;
+
+/// Lowercase ASCII alphanumerics, every other run of chars collapsed to one `-`.
+fn slug(input: &str) -> String {
+    let mut out = String::new();
+    for ch in input.chars() {
+        if ch.is_ascii_alphanumeric() {
+            out.push(ch.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    out.truncate(MAX_PART_LEN);
+    out.trim_end_matches('-').to_string()
+}
+
+/// `MMDD` (UTC) for an epoch timestamp.
+fn short_date(epoch_ms: u64) -> String {
+    // Civil-from-days (Howard Hinnant), valid for all dates after 1970.
+    let days = (epoch_ms / 86_400_000) as i64 + 719_468;
+    let era = days / 146_097;
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    format!("{month:02}{day:02}")
+}
+
+/// `<project>-<profile>-<MMDD>`, skipping missing parts, with `-2`, `-3`, … appended
+/// until it doesn't collide with a name in `taken`.
+fn suggest_name(taken: &HashSet<String>, project: Option<&str>, profile: Option<&str>, epoch_ms: u64) -> String {
+    let mut parts: Vec<String> = [project, profile]
+        .into_iter()
+        .flatten()
+        .map(slug)
+        .filter(|s| !s.is_empty())
+        .collect();
+    if parts.is_empty() {
+        parts.push("session".to_string());
+    }
+    parts.push(short_date(epoch_ms));
+    let base = parts.join("-");
+
+    if !taken.contains(&base) {
+        return base;
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or(base)
+}
+
+/// Suggest a name for a new session from its project and agent profile plus today's
+/// date (e.g. `api-claude-0612`), unique among the open sessions.
+#[tauri::command]
+pub fn suggest_session_name(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+    profile: Option<String>,
+) -> Result<String, String> {
+    let project = project_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .and_then(|id| crate::persist::read_project_title(&window, &id));
+    let taken: HashSet<String> = state.session_activity()?.into_iter().map(|s| s.name).collect();
+    Ok(suggest_name(&taken, project.as_deref(), profile.as_deref(), now_epoch_ms()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{short_date, suggest_name};
+    use std::collections::HashSet;
+
+    #[test]
+    fn suggest_name_combines_parts_and_avoids_collisions() {
+        // 2024-06-12T10:00:00Z
+        let at = 1_718_186_400_000;
+        assert_eq!(short_date(at), "0612");
+
+        let mut taken = HashSet::new();
+        assert_eq!(suggest_name(&taken, Some("My API!"), Some("Claude"), at), "my-api-claude-0612");
+        assert_eq!(suggest_name(&taken, None, None, at), "session-0612");
+
+        taken.insert("api-claude-0612".to_string());
+        taken.insert("api-claude-0612-2".to_string());
+        assert_eq!(suggest_name(&taken, Some("api"), Some("claude"), at), "api-claude-0612-3");
+    }
+}
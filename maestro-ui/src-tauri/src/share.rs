@@ -0,0 +1,467 @@
+use rand_core::{OsRng, RngCore};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::pty::AppState;
+use crate::util::now_epoch_ms;
+
+/// Output kept per shared session for viewers that join late or fall behind.
+const SHARE_BUFFER_BYTES: usize = 256 * 1024;
+const ACCEPT_POLL: Duration = Duration::from_millis(200);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Connections served at once; more are turned away until one finishes.
+const MAX_CONNECTIONS: usize = 16;
+/// Shares of an exited session stay viewable this long, then are dropped.
+const CLOSED_SHARE_TTL_MS: u64 = 10 * 60 * 1000;
+const PRUNE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// xterm.js from the frontend's node_modules, copied in by build.rs; empty when the
+/// app was built without it.
+const XTERM_JS: &str = include_str!(concat!(env!("OUT_DIR"), "/xterm.js"));
+const XTERM_CSS: &str = include_str!(concat!(env!("OUT_DIR"), "/xterm.css"));
+/// Without xterm.js the viewer can't render anything, so shares answer 503.
+const VIEWER_BUNDLED: bool = !XTERM_JS.is_empty() && !XTERM_CSS.is_empty();
+
+const VIEWER_HTML: &str = r#"<!doctype html>
+<html><head><meta charset="utf-8"><title>Maestro session</title>
+<link rel="stylesheet" href="/assets/xterm.css">
+<style>html,body{margin:0;height:100%;background:#111;color:#ccc;font:13px monospace}#t{height:calc(100% - 24px)}#s{padding:4px 8px}</style>
+</head><body><div id="s">connecting…</div><div id="t"></div>
+<script src="/assets/xterm.js"></script>
+<script>
+const status = document.getElementById("s");
+const term = new Terminal({ disableStdin: true, convertEol: false, scrollback: 5000 });
+term.open(document.getElementById("t"));
+let from = 0;
+async function poll() {
+  try {
+    const r = await fetch(location.pathname + "/output?from=" + from, { cache: "no-store" });
+    if (r.status !== 200) { status.textContent = "share ended"; return; }
+    const j = await r.json();
+    if (j.data) term.write(j.data);
+    from = j.to;
+    status.textContent = j.name + (j.closed ? " — session ended (read-only)" : " — live (read-only)");
+    if (j.closed) return;
+  } catch (e) { status.textContent = "reconnecting…"; }
+  setTimeout(poll, 500);
+}
+poll();
+</script></body></html>
+"#;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionShareInfo {
+    pub token: String,
+    pub session_id: String,
+    pub url: String,
+    pub created_at: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShareOutput {
+    name: String,
+    data: String,
+    from: u64,
+    to: u64,
+    closed: bool,
+}
+
+struct Share {
+    session_id: String,
+    name: String,
+    created_at: u64,
+    /// Raw offset of `buffer[0]` in the session's output since sharing started.
+    start: u64,
+    buffer: Vec<u8>,
+    /// Epoch ms the session exited; the share expires `CLOSED_SHARE_TTL_MS` later.
+    closed_at: Option<u64>,
+}
+
+struct ShareServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+struct ShareRegistry {
+    shares: HashMap<String, Share>,
+    server: Option<ShareServer>,
+}
+
+fn registry() -> &'static Mutex<ShareRegistry> {
+    static REGISTRY: OnceLock<Mutex<ShareRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(ShareRegistry::default()))
+}
+
+fn new_token() -> String {
+    let mut bytes = [0u8; 24];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Address teammates on the LAN can reach: the interface used for outbound traffic.
+/// The UDP connect sends nothing; it only picks a route.
+fn lan_ip() -> IpAddr {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|s| s.connect("192.0.2.1:80").map(|_| s))
+        .and_then(|s| s.local_addr())
+        .map(|a| a.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+/// Called by the pty reader thread with every chunk of session output.
+pub(crate) fn feed(session_id: &str, data: &str) {
+    let Ok(mut reg) = registry().lock() else {
+        return;
+    };
+    for share in reg.shares.values_mut().filter(|s| s.session_id == session_id) {
+        share.push(data);
+    }
+}
+
+/// Called when a session exits; viewers see the final output and a closed flag.
+pub(crate) fn session_closed(session_id: &str) {
+    if let Ok(mut reg) = registry().lock() {
+        for share in reg.shares.values_mut().filter(|s| s.session_id == session_id) {
+            share.closed_at.get_or_insert_with(now_epoch_ms);
+        }
+    }
+}
+
+impl Share {
+    /// Append output, dropping the oldest once over `SHARE_BUFFER_BYTES`.
+    fn push(&mut self, data: &str) {
+        self.buffer.extend_from_slice(data.as_bytes());
+        if self.buffer.len() > SHARE_BUFFER_BYTES {
+            let mut drop_len = self.buffer.len() - SHARE_BUFFER_BYTES;
+            // Cut at a char boundary so the buffer stays valid UTF-8.
+            while self.buffer.get(drop_len).is_some_and(|b| b & 0xC0 == 0x80) {
+                drop_len += 1;
+            }
+            self.buffer.drain(..drop_len);
+            self.start += drop_len as u64;
+        }
+    }
+}
+
+impl ShareRegistry {
+    /// Drop shares whose session exited more than `CLOSED_SHARE_TTL_MS` ago, and stop
+    /// the server once none remain.
+    fn prune_expired(&mut self, now: u64) {
+        self.shares
+            .retain(|_, s| s.closed_at.is_none_or(|at| now.saturating_sub(at) < CLOSED_SHARE_TTL_MS));
+        if self.shares.is_empty() {
+            if let Some(server) = self.server.take() {
+                server.stop.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn output(&self, token: &str, from: u64) -> Option<ShareOutput> {
+        let share = self.shares.get(token)?;
+        let end = share.start + share.buffer.len() as u64;
+        let from = from.clamp(share.start, end);
+        let slice = &share.buffer[(from - share.start) as usize..];
+        Some(ShareOutput {
+            name: share.name.clone(),
+            data: String::from_utf8_lossy(slice).to_string(),
+            from,
+            to: end,
+            closed: share.closed_at.is_some(),
+        })
+    }
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) {
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nReferrer-Policy: no-referrer\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(body);
+}
+
+/// Status, content type and body for a request against the shares in `reg`; only
+/// GETs of a viewer page, its output and the viewer assets are served.
+fn route(reg: &Mutex<ShareRegistry>, method: &str, target: &str) -> (&'static str, &'static str, Vec<u8>) {
+    const NOT_FOUND: (&str, &str) = ("404 Not Found", "text/plain");
+    if method != "GET" {
+        return ("405 Method Not Allowed", "text/plain", b"read-only".to_vec());
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let unavailable = || {
+        let body = b"share viewer unavailable: this build has no xterm.js (run bun install before building)";
+        ("503 Service Unavailable", "text/plain", body.to_vec())
+    };
+    let found = match segments.as_slice() {
+        ["assets", "xterm.js" | "xterm.css"] if !VIEWER_BUNDLED => return unavailable(),
+        ["assets", "xterm.js"] => Some(("text/javascript; charset=utf-8", XTERM_JS.as_bytes().to_vec())),
+        ["assets", "xterm.css"] => Some(("text/css; charset=utf-8", XTERM_CSS.as_bytes().to_vec())),
+        ["s", token] => {
+            let known = reg.lock().map(|r| r.shares.contains_key(*token)).unwrap_or(false);
+            if known && !VIEWER_BUNDLED {
+                return unavailable();
+            }
+            known.then(|| ("text/html; charset=utf-8", VIEWER_HTML.as_bytes().to_vec()))
+        }
+        ["s", token, "output"] => {
+            let from = query
+                .split('&')
+                .find_map(|kv| kv.strip_prefix("from="))
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(0);
+            reg.lock()
+                .ok()
+                .and_then(|r| r.output(token, from))
+                .and_then(|out| serde_json::to_vec(&out).ok())
+                .map(|body| ("application/json", body))
+        }
+        _ => None,
+    };
+    match found {
+        Some((content_type, body)) => ("200 OK", content_type, body),
+        None => (NOT_FOUND.0, NOT_FOUND.1, b"not found".to_vec()),
+    }
+}
+
+fn handle_connection(mut stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(REQUEST_TIMEOUT));
+    let mut request_line = String::new();
+    if BufReader::new(&stream).read_line(&mut request_line).is_err() {
+        return;
+    }
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return;
+    };
+    let (status, content_type, body) = route(registry(), method, target);
+    write_response(&mut stream, status, content_type, &body);
+}
+
+/// Counts a connection being served until dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn acquire(active: &Arc<AtomicUsize>) -> Option<Self> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < MAX_CONNECTIONS).then_some(n + 1))
+            .ok()
+            .map(|_| Self(active.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn start_server(port: u16) -> Result<ShareServer, String> {
+    let listener = TcpListener::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port))
+        .map_err(|e| format!("bind failed: {e}"))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("listen failed: {e}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("listen failed: {e}"))?
+        .port();
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_for_thread = stop.clone();
+
+    std::thread::spawn(move || {
+        let active = Arc::new(AtomicUsize::new(0));
+        let mut last_prune = Instant::now();
+        while !stop_for_thread.load(Ordering::Relaxed) {
+            if last_prune.elapsed() >= PRUNE_INTERVAL {
+                last_prune = Instant::now();
+                if let Ok(mut reg) = registry().lock() {
+                    reg.prune_expired(now_epoch_ms());
+                }
+            }
+            match listener.accept() {
+                Ok((mut stream, _)) => {
+                    let _ = stream.set_nonblocking(false);
+                    let Some(slot) = ConnectionSlot::acquire(&active) else {
+                        let _ = stream.set_write_timeout(Some(ACCEPT_POLL));
+                        write_response(&mut stream, "503 Service Unavailable", "text/plain", b"busy");
+                        continue;
+                    };
+                    std::thread::spawn(move || {
+                        let _slot = slot;
+                        handle_connection(stream);
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL),
+                Err(_) => std::thread::sleep(ACCEPT_POLL),
+            }
+        }
+    });
+
+    Ok(ShareServer { port, stop })
+}
+
+/// Serve a read-only live view of a session on the LAN. Returns a tokenized URL; anyone
+/// with it can watch (but not type into) the session until the share is stopped. The
+/// viewer only sees output produced after sharing starts.
+#[tauri::command]
+pub fn start_session_share(
+    state: State<'_, AppState>,
+    id: String,
+    port: Option<u16>,
+) -> Result<SessionShareInfo, String> {
+    let name = state
+        .session_activity()?
+        .into_iter()
+        .find(|s| s.id == id)
+        .map(|s| s.name)
+        .ok_or("unknown session")?;
+
+    let mut reg = registry().lock().map_err(|_| "share registry poisoned")?;
+    reg.prune_expired(now_epoch_ms());
+    if reg.server.is_none() {
+        reg.server = Some(start_server(port.unwrap_or(0))?);
+    }
+    let server_port = reg.server.as_ref().map(|s| s.port).unwrap_or_default();
+
+    let token = new_token();
+    let created_at = now_epoch_ms();
+    reg.shares.insert(
+        token.clone(),
+        Share {
+            session_id: id.clone(),
+            name,
+            created_at,
+            start: 0,
+            buffer: Vec::new(),
+            closed_at: None,
+        },
+    );
+    Ok(SessionShareInfo {
+        url: format!("http://{}:{server_port}/s/{token}", lan_ip()),
+        token,
+        session_id: id,
+        created_at,
+    })
+}
+
+/// Revoke a share link. The web server shuts down once no shares remain.
+#[tauri::command]
+pub fn stop_session_share(token: String) -> Result<(), String> {
+    let mut reg = registry().lock().map_err(|_| "share registry poisoned")?;
+    reg.shares.remove(&token).ok_or("unknown share")?;
+    if reg.shares.is_empty() {
+        if let Some(server) = reg.server.take() {
+            server.stop.store(true, Ordering::Relaxed);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_session_shares() -> Result<Vec<SessionShareInfo>, String> {
+    let mut reg = registry().lock().map_err(|_| "share registry poisoned")?;
+    reg.prune_expired(now_epoch_ms());
+    let port = reg.server.as_ref().map(|s| s.port).unwrap_or_default();
+    let ip = lan_ip();
+    let mut shares: Vec<SessionShareInfo> = reg
+        .shares
+        .iter()
+        .map(|(token, s)| SessionShareInfo {
+            token: token.clone(),
+            session_id: s.session_id.clone(),
+            url: format!("http://{ip}:{port}/s/{token}"),
+            created_at: s.created_at,
+        })
+        .collect();
+    shares.sort_by_key(|s| s.created_at);
+    Ok(shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{route, Share, ShareRegistry, CLOSED_SHARE_TTL_MS, SHARE_BUFFER_BYTES, VIEWER_BUNDLED};
+    use std::sync::Mutex;
+
+    fn insert_share(reg: &mut ShareRegistry, token: &str, start: u64, buffer: &[u8], closed_at: Option<u64>) {
+        reg.shares.insert(
+            token.to_string(),
+            Share {
+                session_id: "1".to_string(),
+                name: "api".to_string(),
+                created_at: 0,
+                start,
+                buffer: buffer.to_vec(),
+                closed_at,
+            },
+        );
+    }
+
+    #[test]
+    fn output_offsets_are_clamped_to_the_buffer() {
+        let mut reg = ShareRegistry::default();
+        insert_share(&mut reg, "offsets", 100, b"hello", None);
+        let out = reg.output("offsets", 0).unwrap();
+        assert_eq!((out.from, out.to, out.data.as_str()), (100, 105, "hello"));
+        let out = reg.output("offsets", 102).unwrap();
+        assert_eq!((out.from, out.data.as_str()), (102, "llo"));
+        let out = reg.output("offsets", 500).unwrap();
+        assert_eq!((out.from, out.to, out.data.as_str()), (105, 105, ""));
+        assert!(reg.output("unknown", 0).is_none());
+    }
+
+    #[test]
+    fn trims_the_buffer_at_char_boundaries() {
+        let mut reg = ShareRegistry::default();
+        insert_share(&mut reg, "trim", 0, b"", None);
+        let share = reg.shares.get_mut("trim").unwrap();
+        share.push(&format!("\u{e9}{}", "a".repeat(SHARE_BUFFER_BYTES - 1)));
+        assert_eq!((share.start, share.buffer.len()), (2, SHARE_BUFFER_BYTES - 1));
+        assert!(std::str::from_utf8(&share.buffer).is_ok());
+    }
+
+    #[test]
+    fn routes_only_known_shares() {
+        let mut reg = ShareRegistry::default();
+        insert_share(&mut reg, "routes", 0, b"abc", Some(crate::util::now_epoch_ms()));
+        let reg = Mutex::new(reg);
+        let (status, content_type, body) = route(&reg, "GET", "/s/routes");
+        if VIEWER_BUNDLED {
+            assert_eq!((status, content_type), ("200 OK", "text/html; charset=utf-8"));
+            assert!(String::from_utf8(body).unwrap().contains("/assets/xterm.js"));
+        } else {
+            assert_eq!(status, "503 Service Unavailable");
+        }
+        let (status, _, body) = route(&reg, "GET", "/s/routes/output?from=1");
+        assert_eq!(status, "200 OK");
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!((json["data"].as_str(), json["closed"].as_bool()), (Some("bc"), Some(true)));
+
+        assert_eq!(route(&reg, "POST", "/s/routes").0, "405 Method Not Allowed");
+        assert_eq!(route(&reg, "GET", "/s/nope").0, "404 Not Found");
+        assert_eq!(route(&reg, "GET", "/s/routes/../../etc/passwd").0, "404 Not Found");
+        assert_eq!(route(&reg, "GET", "/").0, "404 Not Found");
+    }
+
+    #[test]
+    fn closed_shares_expire() {
+        let mut reg = ShareRegistry::default();
+        insert_share(&mut reg, "open", 0, b"", None);
+        insert_share(&mut reg, "closed", 0, b"", Some(1_000));
+        reg.prune_expired(1_000 + CLOSED_SHARE_TTL_MS - 1);
+        assert!(reg.shares.contains_key("closed"));
+        reg.prune_expired(1_000 + CLOSED_SHARE_TTL_MS);
+        assert!(!reg.shares.contains_key("closed"));
+        assert!(reg.shares.contains_key("open"));
+    }
+}
@@ -8,6 +8,7 @@ struct HostOptions {
     host_name: Option<String>,
     user: Option<String>,
     port: Option<u16>,
+    proxy_jump: Option<String>,
 }
 
 #[derive(Serialize, Clone)]
@@ -17,6 +18,18 @@ pub struct SshHostEntry {
     pub host_name: Option<String>,
     pub user: Option<String>,
     pub port: Option<u16>,
+    pub proxy_jump: Option<String>,
+    /// Maestro-side overrides apply (see ssh_overrides.rs); `user` and `port` include them.
+    pub has_overrides: bool,
+}
+
+/// One intermediate host on the way to an ssh target.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct JumpHop {
+    /// The hop as written in ProxyJump (`alias`, `user@host:port`).
+    pub spec: String,
+    /// The name ssh connects to for this hop (HostName, or the bare host).
+    pub host: String,
 }
 
 fn home_dir() -> Option<PathBuf> {
@@ -51,6 +64,9 @@ fn merge_first_wins(dst: &mut HostOptions, src: &HostOptions) {
     if dst.port.is_none() {
         dst.port = src.port;
     }
+    if dst.proxy_jump.is_none() {
+        dst.proxy_jump = src.proxy_jump.clone();
+    }
 }
 
 fn tokenize_line(line: &str) -> Vec<String> {
@@ -105,7 +121,7 @@ fn contains_glob(s: &str) -> bool {
     s.chars().any(|c| matches!(c, '*' | '?' | '['))
 }
 
-fn matches_glob(pattern: &str, text: &str) -> bool {
+pub(crate) fn matches_glob(pattern: &str, text: &str) -> bool {
     fn inner(pat: &[char], txt: &[char], pi: usize, ti: usize) -> bool {
         if pi >= pat.len() {
             return ti >= txt.len();
@@ -378,6 +394,16 @@ fn collect_from_config(
                     current_options.port = Some(port);
                 }
             }
+            "proxyjump" => {
+                if current_patterns.is_empty() {
+                    continue;
+                }
+                // Keep "none" so it still wins over a later wildcard block.
+                let value = tokens.get(1).map(|s| s.trim()).unwrap_or("");
+                if !value.is_empty() {
+                    current_options.proxy_jump = Some(value.to_string());
+                }
+            }
             _ => {}
         }
     }
@@ -386,25 +412,98 @@ fn collect_from_config(
     Ok(())
 }
 
-#[tauri::command]
-pub fn list_ssh_hosts() -> Result<Vec<SshHostEntry>, String> {
+fn load_host_options() -> Result<HashMap<String, HostOptions>, String> {
     let home = home_dir().ok_or("unable to determine home directory")?;
     let config_path = home.join(".ssh").join("config");
+    let mut entries: HashMap<String, HostOptions> = HashMap::new();
     if !config_path.exists() {
-        return Ok(Vec::new());
+        return Ok(entries);
     }
 
     let mut visited: HashSet<PathBuf> = HashSet::new();
-    let mut entries: HashMap<String, HostOptions> = HashMap::new();
     collect_from_config(&config_path, &mut entries, &mut visited, 0, false)?;
+    Ok(entries)
+}
+
+/// Host part of `user@host:port` or `ssh://user@host:port`.
+pub(crate) fn hop_host(spec: &str) -> &str {
+    let spec = spec.strip_prefix("ssh://").unwrap_or(spec);
+    let host = spec.rsplit_once('@').map(|(_, h)| h).unwrap_or(spec);
+    if let Some(rest) = host.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or(rest);
+    }
+    match host.split_once(':') {
+        Some((h, port)) if port.chars().all(|c| c.is_ascii_digit()) => h,
+        _ => host,
+    }
+}
+
+fn expand_jump_chain(
+    entries: &HashMap<String, HostOptions>,
+    host: &str,
+    seen: &mut HashSet<String>,
+    out: &mut Vec<JumpHop>,
+) {
+    let Some(chain) = entries.get(host).and_then(|o| o.proxy_jump.as_deref()) else {
+        return;
+    };
+    if chain.eq_ignore_ascii_case("none") || !seen.insert(host.to_string()) {
+        return;
+    }
+    for (i, spec) in chain.split(',').map(str::trim).filter(|s| !s.is_empty()).enumerate() {
+        let hop = hop_host(spec);
+        // ssh reaches the first hop using that hop's own ProxyJump; later hops are
+        // tunnelled through the earlier ones and their ProxyJump is ignored.
+        if i == 0 {
+            expand_jump_chain(entries, hop, seen, out);
+        }
+        let resolved = entries.get(hop).and_then(|o| o.host_name.clone());
+        out.push(JumpHop {
+            spec: spec.to_string(),
+            host: resolved.unwrap_or_else(|| hop.to_string()),
+        });
+    }
+}
+
+fn jump_chain_from(entries: &HashMap<String, HostOptions>, target: &str) -> Vec<JumpHop> {
+    let mut out = Vec::new();
+    expand_jump_chain(entries, hop_host(target.trim()), &mut HashSet::new(), &mut out);
+    out
+}
+
+/// The full ProxyJump chain for an ssh target, in connection order, including the
+/// ProxyJump of the first hop. Only concrete `Host` aliases are considered;
+/// ssh still applies wildcard blocks itself through the config file.
+pub(crate) fn resolve_jump_chain(target: &str) -> Vec<JumpHop> {
+    match load_host_options() {
+        Ok(entries) => jump_chain_from(&entries, target),
+        Err(e) => {
+            eprintln!("ssh config load failed: {e}");
+            Vec::new()
+        }
+    }
+}
+
+#[tauri::command]
+pub fn list_ssh_hosts() -> Result<Vec<SshHostEntry>, String> {
+    let mut entries = load_host_options()?;
+    let overrides = crate::ssh_overrides::all();
+    for alias in overrides.keys() {
+        entries.entry(alias.clone()).or_default();
+    }
 
     let mut out: Vec<SshHostEntry> = entries
         .into_iter()
-        .map(|(alias, opts)| SshHostEntry {
-            alias,
-            host_name: opts.host_name,
-            user: opts.user,
-            port: opts.port,
+        .map(|(alias, opts)| {
+            let o = overrides.get(&alias);
+            SshHostEntry {
+                host_name: opts.host_name,
+                user: o.and_then(|o| o.user.clone()).or(opts.user),
+                port: o.and_then(|o| o.port).or(opts.port),
+                proxy_jump: opts.proxy_jump,
+                has_overrides: o.is_some(),
+                alias,
+            }
         })
         .collect();
 
@@ -412,3 +511,40 @@ pub fn list_ssh_hosts() -> Result<Vec<SshHostEntry>, String> {
     Ok(out)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::{jump_chain_from, HostOptions, JumpHop};
+    use std::collections::HashMap;
+
+    #[test]
+    fn jump_chain_follows_nested_hops() {
+        let mut entries: HashMap<String, HostOptions> = HashMap::new();
+        let host = |host_name: Option<&str>, proxy_jump: Option<&str>| HostOptions {
+            host_name: host_name.map(str::to_string),
+            proxy_jump: proxy_jump.map(str::to_string),
+            ..HostOptions::default()
+        };
+        entries.insert("db".to_string(), host(Some("10.0.2.5"), Some("inner")));
+        entries.insert("inner".to_string(), host(Some("10.0.1.1"), Some("edge")));
+        entries.insert("edge".to_string(), host(None, Some("none")));
+
+        let hop = |spec: &str, host: &str| JumpHop {
+            spec: spec.to_string(),
+            host: host.to_string(),
+        };
+        assert_eq!(
+            jump_chain_from(&entries, "deploy@db"),
+            vec![hop("edge", "edge"), hop("inner", "10.0.1.1")]
+        );
+        assert_eq!(
+            jump_chain_from(&HashMap::new(), "plain"),
+            Vec::<JumpHop>::new()
+        );
+
+        entries.insert("web".to_string(), host(None, Some("ops@gw.example.com:2222,inner")));
+        assert_eq!(
+            jump_chain_from(&entries, "web"),
+            vec![hop("ops@gw.example.com:2222", "gw.example.com"), hop("inner", "10.0.1.1")]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::util::now_epoch_ms;
+
+pub(crate) const EVENT_SSH_ASKPASS_REQUEST: &str = "ssh-askpass-request";
+
+/// Set on ssh's environment; when present, this executable runs as the askpass
+/// helper instead of starting the app.
+const ENV_PORT: &str = "MAESTRO_ASKPASS_PORT";
+const ENV_TOKEN: &str = "MAESTRO_ASKPASS_TOKEN";
+const ENV_TARGET: &str = "MAESTRO_ASKPASS_TARGET";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a prompt waits for the user before ssh is told the prompt was cancelled.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// An ssh password, passphrase or confirmation prompt waiting on the UI. Answer it
+/// with `answer_ssh_askpass`.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SshAskpassRequest {
+    pub id: String,
+    pub target: String,
+    pub prompt: String,
+    /// False for yes/no confirmations, which can be shown in clear text.
+    pub secret: bool,
+    pub requested_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HelperRequest {
+    token: String,
+    target: String,
+    prompt: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HelperResponse {
+    answer: Option<String>,
+}
+
+struct Bridge {
+    app: AppHandle,
+    port: u16,
+    token: String,
+    helper: PathBuf,
+}
+
+fn bridge() -> &'static OnceLock<Bridge> {
+    static BRIDGE: OnceLock<Bridge> = OnceLock::new();
+    &BRIDGE
+}
+
+/// Set once the UI listens for `ssh-askpass-request`; until then prompts would wait
+/// out `ANSWER_TIMEOUT` with nobody to answer them.
+static HANDLER_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+fn pending() -> &'static Mutex<HashMap<String, mpsc::Sender<Option<String>>>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, mpsc::Sender<Option<String>>>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn new_token() -> String {
+    let mut bytes = [0u8; 24];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Host key and similar confirmations ask for yes/no; everything else is a secret.
+fn is_secret_prompt(prompt: &str) -> bool {
+    let lower = prompt.to_ascii_lowercase();
+    !(lower.contains("(yes/no") || lower.contains("[y/n]"))
+}
+
+/// Entry point for the helper mode, called first thing in `main`. Returns the exit
+/// code when this process was started by ssh as `SSH_ASKPASS`.
+pub fn run_helper_if_requested() -> Option<i32> {
+    let port = std::env::var(ENV_PORT).ok()?;
+    let request = HelperRequest {
+        token: std::env::var(ENV_TOKEN).unwrap_or_default(),
+        target: std::env::var(ENV_TARGET).unwrap_or_default(),
+        prompt: std::env::args().nth(1).unwrap_or_default(),
+    };
+    let answer = (|| -> Option<String> {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port.parse::<u16>().ok()?)).ok()?;
+        let mut line = serde_json::to_string(&request).ok()?;
+        line.push('\n');
+        stream.write_all(line.as_bytes()).ok()?;
+        let mut reply = String::new();
+        BufReader::new(&stream).read_line(&mut reply).ok()?;
+        serde_json::from_str::<HelperResponse>(&reply).ok()?.answer
+    })();
+    match answer {
+        Some(answer) => {
+            println!("{answer}");
+            Some(0)
+        }
+        None => Some(1),
+    }
+}
+
+fn handle_connection(stream: TcpStream) {
+    let Some(bridge) = bridge().get() else {
+        return;
+    };
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+    let mut line = String::new();
+    if BufReader::new(&stream).read_line(&mut line).is_err() {
+        return;
+    }
+    let Ok(request) = serde_json::from_str::<HelperRequest>(&line) else {
+        return;
+    };
+    if request.token != bridge.token {
+        return;
+    }
+    // With no UI listening (it stopped since ssh was started), cancel rather than wait.
+    let answer = if HANDLER_REGISTERED.load(Ordering::Relaxed) {
+        ask_ui(bridge, request)
+    } else {
+        None
+    };
+
+    let mut reply = serde_json::to_string(&HelperResponse { answer }).unwrap_or_default();
+    reply.push('\n');
+    let mut stream = stream;
+    let _ = stream.write_all(reply.as_bytes());
+}
+
+fn ask_ui(bridge: &Bridge, request: HelperRequest) -> Option<String> {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    let id = format!("askpass-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    let (tx, rx) = mpsc::channel();
+    if let Ok(mut pending) = pending().lock() {
+        pending.insert(id.clone(), tx);
+    }
+    let _ = bridge.app.emit(
+        EVENT_SSH_ASKPASS_REQUEST,
+        SshAskpassRequest {
+            id: id.clone(),
+            secret: is_secret_prompt(&request.prompt),
+            target: request.target,
+            prompt: request.prompt.trim().to_string(),
+            requested_at: now_epoch_ms(),
+        },
+    );
+    let answer = rx.recv_timeout(ANSWER_TIMEOUT).ok().flatten();
+    if let Ok(mut pending) = pending().lock() {
+        pending.remove(&id);
+    }
+    answer
+}
+
+/// Start listening for askpass helpers on loopback. Without it, or until the UI calls
+/// `set_ssh_askpass_handler`, ssh keeps running in batch mode and prompts fail as before.
+pub(crate) fn init(app: &AppHandle) {
+    let helper = match std::env::current_exe() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("ssh askpass disabled: {e}");
+            return;
+        }
+    };
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("ssh askpass disabled: bind failed: {e}");
+            return;
+        }
+    };
+    let Ok(port) = listener.local_addr().map(|a| a.port()) else {
+        return;
+    };
+    let _ = bridge().set(Bridge {
+        app: app.clone(),
+        port,
+        token: new_token(),
+        helper,
+    });
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            std::thread::spawn(move || handle_connection(stream));
+        }
+    });
+}
+
+pub(crate) fn is_available() -> bool {
+    bridge().get().is_some() && HANDLER_REGISTERED.load(Ordering::Relaxed)
+}
+
+/// Point ssh's password and passphrase prompts at the bridge. Pair with
+/// `BatchMode=no`, see `ssh_fs::ssh_common_args`.
+pub(crate) fn configure(cmd: &mut Command, target: &str) {
+    let Some(bridge) = bridge().get().filter(|_| is_available()) else {
+        return;
+    };
+    cmd.env("SSH_ASKPASS", &bridge.helper);
+    cmd.env("SSH_ASKPASS_REQUIRE", "force");
+    // OpenSSH before 8.4 only uses SSH_ASKPASS when DISPLAY is set.
+    if std::env::var_os("DISPLAY").is_none() {
+        cmd.env("DISPLAY", "maestro:0");
+    }
+    cmd.env(ENV_PORT, bridge.port.to_string());
+    cmd.env(ENV_TOKEN, &bridge.token);
+    cmd.env(ENV_TARGET, target);
+}
+
+/// Called by the UI once it handles `ssh-askpass-request` (and with false when it
+/// stops). Only then do ssh prompts go to the UI instead of failing in batch mode.
+#[tauri::command]
+pub fn set_ssh_askpass_handler(registered: bool) -> Result<(), String> {
+    HANDLER_REGISTERED.store(registered, Ordering::Relaxed);
+    if !registered {
+        // Cancel prompts nobody will answer now.
+        let waiting: Vec<_> = pending()
+            .lock()
+            .map_err(|_| "askpass state poisoned")?
+            .drain()
+            .map(|(_, tx)| tx)
+            .collect();
+        for tx in waiting {
+            let _ = tx.send(None);
+        }
+    }
+    Ok(())
+}
+
+/// Answer an `ssh-askpass-request`. `None` cancels the prompt, which fails the login.
+#[tauri::command]
+pub fn answer_ssh_askpass(id: String, answer: Option<String>) -> Result<(), String> {
+    let tx = pending()
+        .lock()
+        .map_err(|_| "askpass state poisoned")?
+        .remove(&id)
+        .ok_or("unknown or expired prompt")?;
+    tx.send(answer).map_err(|_| "prompt already timed out".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_secret_prompt;
+
+    #[test]
+    fn confirmations_are_not_secret() {
+        assert!(is_secret_prompt("Enter passphrase for key '/home/a/.ssh/id_ed25519': "));
+        assert!(is_secret_prompt("deploy@db's password: "));
+        assert!(!is_secret_prompt("Are you sure you want to continue connecting (yes/no/[fingerprint])? "));
+    }
+}
@@ -1,13 +1,19 @@
+use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
 
+use tauri::AppHandle;
+
 use crate::files::FsEntry;
+use crate::ssh::{hop_host, resolve_jump_chain, JumpHop};
 
-const MAX_TEXT_FILE_BYTES: usize = 2 * 1024 * 1024;
-const BINARY_CHECK_BYTES: usize = 8 * 1024;
+const MAX_BATCH_OPS: usize = 500;
+const MAX_PROCESSES: usize = 500;
+/// Signals `ssh_kill_process` accepts.
+const KILL_SIGNALS: &[&str] = &["TERM", "INT", "HUP", "QUIT", "KILL", "STOP", "CONT"];
 
-fn find_program_in_path(name: &str) -> Option<PathBuf> {
+pub(crate) fn find_program_in_path(name: &str) -> Option<PathBuf> {
     let path = std::env::var_os("PATH")?;
     for dir in std::env::split_paths(&path) {
         let candidate = dir.join(name);
@@ -61,7 +67,7 @@ fn find_program_in_common_locations(name: &str) -> Option<PathBuf> {
     }
 }
 
-fn program_path(name: &str) -> Result<PathBuf, String> {
+pub(crate) fn program_path(name: &str) -> Result<PathBuf, String> {
     if let Some(found) = find_program_in_path(name) {
         return Ok(found);
     }
@@ -73,7 +79,7 @@ fn program_path(name: &str) -> Result<PathBuf, String> {
     ))
 }
 
-fn normalize_posix_path(raw: &str) -> Result<String, String> {
+pub(crate) fn normalize_posix_path(raw: &str) -> Result<String, String> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
         return Err("path is empty".to_string());
@@ -116,7 +122,7 @@ fn ensure_not_root(root: &str, path: &str, verb: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn join_posix_path(dir: &str, name: &str) -> String {
+pub(crate) fn join_posix_path(dir: &str, name: &str) -> String {
     if dir == "/" {
         format!("/{name}")
     } else {
@@ -170,16 +176,28 @@ fn user_ssh_config_path() -> Option<PathBuf> {
     home_dir().map(|h| h.join(".ssh").join("config"))
 }
 
-fn ssh_common_args() -> Result<Vec<String>, String> {
+fn ssh_common_args(target: &str, jump_chain: &[JumpHop]) -> Result<Vec<String>, String> {
     let control = control_path()?;
     let mut out: Vec<String> = Vec::new();
     if let Some(cfg) = user_ssh_config_path().filter(|p| p.is_file()) {
         out.push("-F".to_string());
         out.push(cfg.to_string_lossy().to_string());
     }
+    // First value wins in ssh, so per-host overrides go ahead of the defaults below.
+    out.extend(crate::ssh_overrides::args_for_target(target));
+    if !jump_chain.is_empty() {
+        // ssh, sftp and scp all accept ProxyJump as an option, unlike -J.
+        let hops: Vec<&str> = jump_chain.iter().map(|h| h.spec.as_str()).collect();
+        out.push("-o".to_string());
+        out.push(format!("ProxyJump={}", hops.join(",")));
+    }
+    // With the askpass bridge and a UI handling its prompts, password and passphrase
+    // prompts go to the UI instead of failing; host keys are still never prompted for
+    // (StrictHostKeyChecking).
+    let batch_mode = if crate::ssh_askpass::is_available() { "no" } else { "yes" };
     out.extend([
         "-o".to_string(),
-        "BatchMode=yes".to_string(),
+        format!("BatchMode={batch_mode}"),
         "-o".to_string(),
         "ConnectTimeout=6".to_string(),
         "-o".to_string(),
@@ -200,7 +218,54 @@ fn ssh_common_args() -> Result<Vec<String>, String> {
     Ok(out)
 }
 
-fn output_to_error(prefix: &str, output: &Output) -> String {
+/// Explain a failed connection in terms of the jump host that broke, instead of the
+/// bare ssh message (which often only says "Connection closed by UNKNOWN").
+fn jump_failure(target: &str, chain: &[JumpHop], stderr: &str) -> Option<String> {
+    let last = chain.last()?;
+    let lines: Vec<&str> = stderr.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    for hop in chain {
+        let alias = hop_host(&hop.spec);
+        let mentions = |l: &str| {
+            l.contains(&format!("host {} ", hop.host))
+                || l.contains(&format!("hostname {}:", alias))
+                || l.starts_with(&format!("{alias}:"))
+                || l.contains(&format!("@{alias}:"))
+        };
+        if let Some(line) = lines.iter().find(|l| mentions(l)) {
+            return Some(format!("jump host {} failed: {line}", hop.spec));
+        }
+    }
+    if let Some(line) = lines
+        .iter()
+        .find(|l| l.contains("open failed") || l.contains("stdio forwarding failed"))
+    {
+        return Some(format!("jump host {} could not reach {target}: {line}", last.spec));
+    }
+    let closed = lines
+        .iter()
+        .any(|l| l.contains("Connection closed by UNKNOWN") || l.starts_with("kex_exchange_identification"));
+    if closed {
+        let hops: Vec<&str> = chain.iter().map(|h| h.spec.as_str()).collect();
+        return Some(format!(
+            "connection through jump hosts {} failed: {}",
+            hops.join(" -> "),
+            lines.join("; ")
+        ));
+    }
+    None
+}
+
+/// ssh, sftp and scp exit with 255 when the connection itself fails.
+fn check_jump_hosts(target: &str, chain: &[JumpHop], output: Output) -> Result<Output, String> {
+    if output.status.code() == Some(255) {
+        if let Some(e) = jump_failure(target, chain, &String::from_utf8_lossy(&output.stderr)) {
+            return Err(e);
+        }
+    }
+    Ok(output)
+}
+
+pub(crate) fn output_to_error(prefix: &str, output: &Output) -> String {
     let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
     let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
     if !stderr.is_empty() {
@@ -212,7 +277,7 @@ fn output_to_error(prefix: &str, output: &Output) -> String {
     format!("{prefix}: command failed")
 }
 
-fn shell_escape_posix(value: &str) -> String {
+pub(crate) fn shell_escape_posix(value: &str) -> String {
     let mut out = String::with_capacity(value.len() + 2);
     out.push('\'');
     for ch in value.chars() {
@@ -226,7 +291,7 @@ fn shell_escape_posix(value: &str) -> String {
     out
 }
 
-fn build_sh_c_command(script: &str, argv0: Option<&str>, args: &[String]) -> String {
+pub(crate) fn build_sh_c_command(script: &str, argv0: Option<&str>, args: &[String]) -> String {
     let mut out = String::new();
     out.push_str("sh -c ");
     out.push_str(&shell_escape_posix(script));
@@ -241,9 +306,16 @@ fn build_sh_c_command(script: &str, argv0: Option<&str>, args: &[String]) -> Str
     out
 }
 
-fn run_ssh(target: &str, remote_args: &[String], stdin: Option<&[u8]>) -> Result<Output, String> {
+pub(crate) fn run_ssh(target: &str, remote_args: &[String], stdin: Option<&[u8]>) -> Result<Output, String> {
+    crate::ssh_retry::run_with_retry(|| run_ssh_once(target, remote_args, stdin))
+}
+
+fn run_ssh_once(target: &str, remote_args: &[String], stdin: Option<&[u8]>) -> Result<Output, String> {
+    crate::faults::inject(crate::faults::FaultDomain::Ssh, "run ssh")?;
+    let chain = resolve_jump_chain(target);
     let mut cmd = Command::new(program_path("ssh")?);
-    cmd.args(ssh_common_args()?);
+    crate::ssh_askpass::configure(&mut cmd, target);
+    cmd.args(ssh_common_args(target, &chain)?);
     cmd.arg(target);
     cmd.args(remote_args);
     match stdin {
@@ -257,7 +329,7 @@ fn run_ssh(target: &str, remote_args: &[String], stdin: Option<&[u8]>) -> Result
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
-    if let Some(input) = stdin {
+    let output = if let Some(input) = stdin {
         let mut child = cmd.spawn().map_err(|e| format!("spawn ssh failed: {e}"))?;
         if let Some(mut child_stdin) = child.stdin.take() {
             child_stdin
@@ -266,15 +338,22 @@ fn run_ssh(target: &str, remote_args: &[String], stdin: Option<&[u8]>) -> Result
         }
         child
             .wait_with_output()
-            .map_err(|e| format!("wait ssh failed: {e}"))
+            .map_err(|e| format!("wait ssh failed: {e}"))?
     } else {
-        cmd.output().map_err(|e| format!("run ssh failed: {e}"))
-    }
+        cmd.output().map_err(|e| format!("run ssh failed: {e}"))?
+    };
+    check_jump_hosts(target, &chain, output)
 }
 
 fn run_sftp_batch(target: &str, batch: &str) -> Result<Output, String> {
+    crate::ssh_retry::run_with_retry(|| run_sftp_batch_once(target, batch))
+}
+
+fn run_sftp_batch_once(target: &str, batch: &str) -> Result<Output, String> {
+    let chain = resolve_jump_chain(target);
     let mut cmd = Command::new(program_path("sftp")?);
-    cmd.args(ssh_common_args()?);
+    crate::ssh_askpass::configure(&mut cmd, target);
+    cmd.args(ssh_common_args(target, &chain)?);
     cmd.arg("-q");
     cmd.arg("-b");
     cmd.arg("-");
@@ -289,9 +368,10 @@ fn run_sftp_batch(target: &str, batch: &str) -> Result<Output, String> {
             .write_all(batch.as_bytes())
             .map_err(|e| format!("write sftp stdin failed: {e}"))?;
     }
-    child
+    let output = child
         .wait_with_output()
-        .map_err(|e| format!("wait sftp failed: {e}"))
+        .map_err(|e| format!("wait sftp failed: {e}"))?;
+    check_jump_hosts(target, &chain, output)
 }
 
 fn sftp_escape_arg(value: &str) -> String {
@@ -443,14 +523,34 @@ fn ssh_list_fs_entries_sync(target: String, root: String, path: String) -> Resul
     Ok(parse_sftp_ls(&path, &String::from_utf8_lossy(&output.stdout)))
 }
 
+/// Read a remote text file within `root`; `max_bytes` and `project_id` work as in
+/// `read_text_file`.
 #[tauri::command]
-pub async fn ssh_read_text_file(target: String, root: String, path: String) -> Result<String, String> {
-    tauri::async_runtime::spawn_blocking(move || ssh_read_text_file_sync(target, root, path))
-        .await
-        .map_err(|e| format!("ssh task join failed: {e:?}"))?
+pub async fn ssh_read_text_file(
+    app: AppHandle,
+    target: String,
+    root: String,
+    path: String,
+    max_bytes: Option<u64>,
+    project_id: Option<String>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (text, root, path) = ssh_read_text_file_sync(target.clone(), root, path, max_bytes)?;
+        crate::recent_files::record(&app, project_id.as_deref(), Some(&target), &root, &path);
+        Ok(text)
+    })
+    .await
+    .map_err(|e| format!("ssh task join failed: {e:?}"))?
 }
 
-fn ssh_read_text_file_sync(target: String, root: String, path: String) -> Result<String, String> {
+fn ssh_read_text_file_sync(
+    target: String,
+    root: String,
+    path: String,
+    max_bytes: Option<u64>,
+) -> Result<(String, String, String), String> {
+    let limits = crate::file_limits::resolve(max_bytes)?;
+    let max = limits.max_text_file_bytes;
     let target = target.trim();
     if target.is_empty() {
         return Err("missing ssh target".to_string());
@@ -458,7 +558,7 @@ fn ssh_read_text_file_sync(target: String, root: String, path: String) -> Result
     let (root, path) = ensure_within_root(&root, &path)?;
     ensure_not_root(&root, &path, "read")?;
 
-    let limit = MAX_TEXT_FILE_BYTES + 1;
+    let limit = max + 1;
     let script = format!(
         r#"set -e; file="$1"; [ -f "$file" ] || {{ echo "not a file" >&2; exit 1; }}; if command -v head >/dev/null 2>&1; then head -c {limit} "$file"; else dd if="$file" bs=1 count={limit}; fi"#
     );
@@ -471,28 +571,45 @@ fn ssh_read_text_file_sync(target: String, root: String, path: String) -> Result
     }
 
     let bytes = output.stdout;
-    if bytes.len() > MAX_TEXT_FILE_BYTES {
-        return Err(format!(
-            "file too large (>{MAX_TEXT_FILE_BYTES} bytes); open smaller files only"
-        ));
+    if bytes.len() as u64 > max {
+        return Err(format!("file too large (>{max} bytes); open smaller files only"));
     }
-    if bytes[..bytes.len().min(BINARY_CHECK_BYTES)]
-        .iter()
-        .any(|b| *b == 0)
-    {
-        return Err("binary files are not supported".to_string());
-    }
-    String::from_utf8(bytes).map_err(|_| "file is not valid UTF-8".to_string())
+    let text = crate::file_limits::decode_text(bytes, &limits)?;
+    Ok((text, root, path))
 }
 
 #[tauri::command]
-pub async fn ssh_write_text_file(target: String, root: String, path: String, content: String) -> Result<(), String> {
-    tauri::async_runtime::spawn_blocking(move || ssh_write_text_file_sync(target, root, path, content))
-        .await
+pub async fn ssh_write_text_file(
+    app: AppHandle,
+    target: String,
+    root: String,
+    path: String,
+    content: String,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let audit_target = format!("{}:{}", target.trim(), path.trim());
+        let request = crate::ssh_retry::RemoteOpRequest {
+            kind: crate::ssh_retry::RemoteOpKind::Write,
+            target: target.trim().to_string(),
+            root: root.clone(),
+            path: path.clone(),
+            local_path: None,
+            content: crate::ssh_retry::queue_offline().then(|| content.clone()),
+        };
+        let result = ssh_write_text_file_sync(target, root, path, content);
+        crate::audit::record_result(&app, "ssh.write", &audit_target, &result);
+        crate::ssh_retry::settle(&app, request, result)
+    })
+    .await
         .map_err(|e| format!("ssh task join failed: {e:?}"))?
 }
 
-fn ssh_write_text_file_sync(target: String, root: String, path: String, content: String) -> Result<(), String> {
+pub(crate) fn ssh_write_text_file_sync(
+    target: String,
+    root: String,
+    path: String,
+    content: String,
+) -> Result<(), String> {
     let target = target.trim();
     if target.is_empty() {
         return Err("missing ssh target".to_string());
@@ -513,9 +630,20 @@ fn ssh_write_text_file_sync(target: String, root: String, path: String, content:
 }
 
 #[tauri::command]
-pub async fn ssh_rename_fs_entry(target: String, root: String, path: String, new_name: String) -> Result<String, String> {
-    tauri::async_runtime::spawn_blocking(move || ssh_rename_fs_entry_sync(target, root, path, new_name))
-        .await
+pub async fn ssh_rename_fs_entry(
+    app: AppHandle,
+    target: String,
+    root: String,
+    path: String,
+    new_name: String,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let audit_target = format!("{}:{}", target.trim(), path.trim());
+        let result = ssh_rename_fs_entry_sync(target, root, path, new_name);
+        crate::audit::record_result(&app, "ssh.rename", &audit_target, &result);
+        result
+    })
+    .await
         .map_err(|e| format!("ssh task join failed: {e:?}"))?
 }
 
@@ -556,9 +684,14 @@ fn ssh_rename_fs_entry_sync(target: String, root: String, path: String, new_name
 }
 
 #[tauri::command]
-pub async fn ssh_delete_fs_entry(target: String, root: String, path: String) -> Result<(), String> {
-    tauri::async_runtime::spawn_blocking(move || ssh_delete_fs_entry_sync(target, root, path))
-        .await
+pub async fn ssh_delete_fs_entry(app: AppHandle, target: String, root: String, path: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let audit_target = format!("{}:{}", target.trim(), path.trim());
+        let result = ssh_delete_fs_entry_sync(target, root, path);
+        crate::audit::record_result(&app, "ssh.delete", &audit_target, &result);
+        result
+    })
+    .await
         .map_err(|e| format!("ssh task join failed: {e:?}"))?
 }
 
@@ -580,18 +713,406 @@ fn ssh_delete_fs_entry_sync(target: String, root: String, path: String) -> Resul
     Ok(())
 }
 
-fn run_scp(scp_flags: &[&str], ssh_args: Vec<String>, paths: &[String]) -> Result<Output, String> {
+/// One step of `ssh_batch_operations`. Paths are absolute and must stay inside `root`.
+#[derive(Deserialize, Clone)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum SshBatchOp {
+    #[serde(rename_all = "camelCase")]
+    Rename { path: String, new_name: String },
+    /// Move `path` into the existing directory `to_dir`, keeping its name.
+    #[serde(rename_all = "camelCase")]
+    Move { path: String, to_dir: String },
+    Delete { path: String },
+    Mkdir { path: String },
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SshBatchOpResult {
+    pub index: usize,
+    pub ok: bool,
+    pub error: Option<String>,
+    /// Resulting path for rename/move/mkdir.
+    pub path: Option<String>,
+}
+
+fn validate_entry_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("missing new name".to_string());
+    }
+    if name == "." || name == ".." {
+        return Err("invalid name".to_string());
+    }
+    if name.contains('/') || name.contains('\\') {
+        return Err("name must not contain path separators".to_string());
+    }
+    Ok(name)
+}
+
+fn parent_posix_path(path: &str) -> String {
+    let idx = path.rfind('/').unwrap_or(0);
+    if idx == 0 { "/".to_string() } else { path[..idx].to_string() }
+}
+
+/// (audit action, remote verb, first path, second path) for a validated op.
+fn plan_batch_op(root: &str, op: &SshBatchOp) -> Result<(&'static str, &'static str, String, String), String> {
+    match op {
+        SshBatchOp::Rename { path, new_name } => {
+            let (root, path) = ensure_within_root(root, path)?;
+            ensure_not_root(&root, &path, "rename")?;
+            let to = join_posix_path(&parent_posix_path(&path), validate_entry_name(new_name)?);
+            let (_, to) = ensure_within_root(&root, &to)?;
+            Ok(("ssh.rename", "mv", path, to))
+        }
+        SshBatchOp::Move { path, to_dir } => {
+            let (root, path) = ensure_within_root(root, path)?;
+            ensure_not_root(&root, &path, "move")?;
+            let (_, to_dir) = ensure_within_root(&root, to_dir)?;
+            if to_dir == path || to_dir.starts_with(&format!("{path}/")) {
+                return Err("cannot move a directory into itself".to_string());
+            }
+            let name = path.rsplit('/').next().unwrap_or_default();
+            Ok(("ssh.move", "mv", path.clone(), join_posix_path(&to_dir, name)))
+        }
+        SshBatchOp::Delete { path } => {
+            let (root, path) = ensure_within_root(root, path)?;
+            ensure_not_root(&root, &path, "delete")?;
+            Ok(("ssh.delete", "rm", path, String::new()))
+        }
+        SshBatchOp::Mkdir { path } => {
+            let (root, path) = ensure_within_root(root, path)?;
+            ensure_not_root(&root, &path, "create")?;
+            Ok(("ssh.mkdir", "mkdir", path, String::new()))
+        }
+    }
+}
+
+/// Parse `<index>\t<ok|error|skipped>\t<message>` lines printed by the batch script.
+fn parse_batch_output(stdout: &str, count: usize) -> Vec<(bool, Option<String>)> {
+    let mut results: Vec<(bool, Option<String>)> = vec![(false, Some("not run".to_string())); count];
+    for line in stdout.lines() {
+        let mut fields = line.splitn(3, '\t');
+        let (Some(index), Some(status)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let Some(slot) = index.parse::<usize>().ok().and_then(|i| results.get_mut(i)) else {
+            continue;
+        };
+        let message = fields.next().map(str::trim).filter(|m| !m.is_empty());
+        *slot = match status {
+            "ok" => (true, None),
+            "skipped" => (false, Some("skipped after an earlier error".to_string())),
+            _ => (false, Some(message.unwrap_or("operation failed").to_string())),
+        };
+    }
+    results
+}
+
+/// Run several file operations on a remote host in a single ssh invocation, instead
+/// of one ssh process per rename/delete. Every op is validated before anything runs;
+/// with `stop_on_error` the ops after the first failure are skipped.
+#[tauri::command]
+pub async fn ssh_batch_operations(
+    app: AppHandle,
+    target: String,
+    root: String,
+    ops: Vec<SshBatchOp>,
+    stop_on_error: Option<bool>,
+) -> Result<Vec<SshBatchOpResult>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let target = target.trim().to_string();
+        let (results, actions) = ssh_batch_operations_sync(&target, &root, &ops, stop_on_error.unwrap_or(false))?;
+        for ((action, path), result) in actions.iter().zip(&results) {
+            crate::audit::record(&app, action, &format!("{target}:{path}"), result.error.as_deref());
+        }
+        Ok(results)
+    })
+    .await
+    .map_err(|e| format!("ssh task join failed: {e:?}"))?
+}
+
+type BatchAudit = Vec<(&'static str, String)>;
+
+fn ssh_batch_operations_sync(
+    target: &str,
+    root: &str,
+    ops: &[SshBatchOp],
+    stop_on_error: bool,
+) -> Result<(Vec<SshBatchOpResult>, BatchAudit), String> {
+    if target.is_empty() {
+        return Err("missing ssh target".to_string());
+    }
+    if ops.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    if ops.len() > MAX_BATCH_OPS {
+        return Err(format!("too many operations (max {MAX_BATCH_OPS})"));
+    }
+
+    let mut args: Vec<String> = Vec::with_capacity(ops.len() * 3);
+    let mut planned: Vec<(&'static str, String, String)> = Vec::with_capacity(ops.len());
+    for (i, op) in ops.iter().enumerate() {
+        let (action, verb, from, to) = plan_batch_op(root, op).map_err(|e| format!("operation {i}: {e}"))?;
+        args.extend([verb.to_string(), from.clone(), to.clone()]);
+        planned.push((action, from, to));
+    }
+
+    // Three args per op; each op prints one `<index>\t<status>\t<message>` line.
+    let script = format!(
+        r#"i=0; halt=0; while [ $# -ge 3 ]; do op="$1"; a="$2"; b="$3"; shift 3; if [ "$halt" = 1 ]; then printf '%s\tskipped\t\n' "$i"; i=$((i+1)); continue; fi; err=""; case "$op" in mv) if [ ! -e "$a" ] && [ ! -L "$a" ]; then err="missing source"; elif [ -e "$b" ] || [ -L "$b" ]; then err="target already exists"; else err="$(mv "$a" "$b" 2>&1)" || err="${{err:-mv failed}}"; fi;; rm) err="$(rm -rf "$a" 2>&1)" || err="${{err:-rm failed}}";; mkdir) if [ -e "$a" ]; then err="already exists"; else err="$(mkdir -p "$a" 2>&1)" || err="${{err:-mkdir failed}}"; fi;; *) err="unknown operation";; esac; if [ -z "$err" ]; then printf '%s\tok\t\n' "$i"; else printf '%s\terror\t%s\n' "$i" "$(printf '%s' "$err" | tr '\t\n' '  ')"; [ {stop} = 1 ] && halt=1; fi; i=$((i+1)); done"#,
+        stop = u8::from(stop_on_error)
+    );
+    let command = build_sh_c_command(&script, Some("--"), &args);
+    let output = run_ssh(target, &[command], None)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() && !output.status.success() {
+        return Err(output_to_error("ssh failed", &output));
+    }
+
+    let mut results: Vec<SshBatchOpResult> = Vec::with_capacity(ops.len());
+    let mut audit: BatchAudit = Vec::with_capacity(ops.len());
+    for (index, ((ok, error), (action, from, to))) in parse_batch_output(&stdout, ops.len())
+        .into_iter()
+        .zip(planned)
+        .enumerate()
+    {
+        let path = match action {
+            "ssh.delete" => None,
+            "ssh.mkdir" => Some(from.clone()),
+            _ => Some(to),
+        };
+        audit.push((action, from));
+        results.push(SshBatchOpResult { index, ok, error, path });
+    }
+    Ok((results, audit))
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SshDiskUsage {
+    pub path: String,
+    pub filesystem: String,
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub used_percent: u8,
+    /// Size of `path` itself (`du -s`); None if skipped or it timed out.
+    pub path_bytes: Option<u64>,
+}
+
+/// Parse the `df:` and `du:` lines printed by the disk usage script.
+fn parse_disk_usage(path: &str, stdout: &str) -> Option<SshDiskUsage> {
+    let df = stdout.lines().find_map(|l| l.strip_prefix("df:"))?;
+    // `df -P`: Filesystem 1024-blocks Used Available Capacity Mounted-on (may contain spaces).
+    let (fields, mount_point) = split_whitespace_with_remainder(df, 5)?;
+    let kib = |s: &str| s.parse::<u64>().ok().map(|n| n.saturating_mul(1024));
+    let path_bytes = stdout
+        .lines()
+        .find_map(|l| l.strip_prefix("du:"))
+        .and_then(|l| l.split_whitespace().next())
+        .and_then(kib);
+    Some(SshDiskUsage {
+        path: path.to_string(),
+        filesystem: fields[0].to_string(),
+        mount_point: mount_point.trim().to_string(),
+        total_bytes: kib(fields[1])?,
+        used_bytes: kib(fields[2])?,
+        available_bytes: kib(fields[3])?,
+        used_percent: fields[4].trim_end_matches('%').parse().unwrap_or(0),
+        path_bytes,
+    })
+}
+
+/// Free space on the filesystem holding `path` on a remote host, plus the size of
+/// `path` itself unless `include_path_size` is false. The `du` walk is capped at 20s.
+#[tauri::command]
+pub async fn ssh_get_disk_usage(
+    target: String,
+    path: String,
+    include_path_size: Option<bool>,
+) -> Result<SshDiskUsage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        ssh_get_disk_usage_sync(target, path, include_path_size.unwrap_or(true))
+    })
+    .await
+    .map_err(|e| format!("ssh task join failed: {e:?}"))?
+}
+
+fn ssh_get_disk_usage_sync(target: String, path: String, include_path_size: bool) -> Result<SshDiskUsage, String> {
+    let target = target.trim();
+    if target.is_empty() {
+        return Err("missing ssh target".to_string());
+    }
+    let path = normalize_posix_path(&path)?;
+
+    let script = r#"p="$1"; [ -e "$p" ] || { echo "path does not exist" >&2; exit 1; }; line="$(df -Pk "$p" | tail -n 1)" || exit 1; printf 'df:%s\n' "$line"; if [ "$2" = 1 ]; then if command -v timeout >/dev/null 2>&1; then s="$(timeout 20 du -sk "$p" 2>/dev/null | tail -n 1)"; else s="$(du -sk "$p" 2>/dev/null | tail -n 1)"; fi; [ -n "$s" ] && printf 'du:%s\n' "$s"; fi; exit 0"#;
+    let flag = if include_path_size { "1" } else { "0" };
+    let command = build_sh_c_command(script, Some("--"), &[path.clone(), flag.to_string()]);
+    let output = run_ssh(target, &[command], None)?;
+    if !output.status.success() {
+        return Err(output_to_error("ssh failed", &output));
+    }
+    parse_disk_usage(&path, &String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "unexpected df output".to_string())
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SshProcessListOptions {
+    /// Case-insensitive substring matched against the command line, user and pid.
+    pub filter: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SshProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    pub user: String,
+    pub cpu_percent: f32,
+    pub mem_percent: f32,
+    pub elapsed_secs: u64,
+    pub command: String,
+}
+
+/// `ps` elapsed time: `[[dd-]hh:]mm:ss`.
+fn parse_ps_elapsed(value: &str) -> Option<u64> {
+    let (days, rest) = match value.split_once('-') {
+        Some((d, rest)) => (d.parse::<u64>().ok()?, rest),
+        None => (0, value),
+    };
+    let mut secs = 0u64;
+    for part in rest.split(':') {
+        secs = secs * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(days * 86_400 + secs)
+}
+
+/// Parse `self:<pid>` and `ps -o pid=,ppid=,user=,pcpu=,pmem=,etime=,args=` lines, leaving
+/// out the listing's own shell and `ps`.
+fn parse_ps_output(stdout: &str, filter: Option<&str>) -> Vec<SshProcessInfo> {
+    let own_pid = stdout
+        .lines()
+        .find_map(|l| l.strip_prefix("self:"))
+        .and_then(|p| p.trim().parse::<u32>().ok());
+    let filter = filter.map(|f| f.trim().to_lowercase()).filter(|f| !f.is_empty());
+
+    stdout
+        .lines()
+        .filter(|l| !l.starts_with("self:"))
+        .filter_map(|line| {
+            let (fields, command) = split_whitespace_with_remainder(line, 6)?;
+            Some(SshProcessInfo {
+                pid: fields[0].parse().ok()?,
+                ppid: fields[1].parse().ok()?,
+                user: fields[2].to_string(),
+                cpu_percent: fields[3].parse().unwrap_or(0.0),
+                mem_percent: fields[4].parse().unwrap_or(0.0),
+                elapsed_secs: parse_ps_elapsed(fields[5]).unwrap_or(0),
+                command: command.trim_end().to_string(),
+            })
+        })
+        .filter(|p| own_pid != Some(p.pid) && own_pid != Some(p.ppid))
+        .filter(|p| match filter.as_deref() {
+            Some(f) => {
+                p.command.to_lowercase().contains(f) || p.user.to_lowercase().contains(f) || p.pid.to_string() == f
+            }
+            None => true,
+        })
+        .take(MAX_PROCESSES)
+        .collect()
+}
+
+/// Processes on a remote host, optionally narrowed by `filter`, so a stuck agent or
+/// dev server can be found and stopped with `ssh_kill_process`.
+#[tauri::command]
+pub async fn ssh_list_processes(
+    target: String,
+    options: Option<SshProcessListOptions>,
+) -> Result<Vec<SshProcessInfo>, String> {
+    tauri::async_runtime::spawn_blocking(move || ssh_list_processes_sync(target, options.unwrap_or_default()))
+        .await
+        .map_err(|e| format!("ssh task join failed: {e:?}"))?
+}
+
+fn ssh_list_processes_sync(target: String, options: SshProcessListOptions) -> Result<Vec<SshProcessInfo>, String> {
+    let target = target.trim();
+    if target.is_empty() {
+        return Err("missing ssh target".to_string());
+    }
+
+    // `-axo` and `etime` work with both procps (Linux) and BSD/macOS ps.
+    let script = r#"printf 'self:%s\n' "$$"; ps -axo pid=,ppid=,user=,pcpu=,pmem=,etime=,args="#;
+    let command = build_sh_c_command(script, None, &[]);
+    let output = run_ssh(target, &[command], None)?;
+    if !output.status.success() {
+        return Err(output_to_error("ssh failed", &output));
+    }
+    Ok(parse_ps_output(
+        &String::from_utf8_lossy(&output.stdout),
+        options.filter.as_deref(),
+    ))
+}
+
+/// Send `signal` (default TERM) to a process on a remote host.
+#[tauri::command]
+pub async fn ssh_kill_process(app: AppHandle, target: String, pid: u32, signal: Option<String>) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let audit_target = format!("{}:{pid}", target.trim());
+        let result = ssh_kill_process_sync(target, pid, signal);
+        crate::audit::record_result(&app, "ssh.kill", &audit_target, &result);
+        result
+    })
+    .await
+    .map_err(|e| format!("ssh task join failed: {e:?}"))?
+}
+
+fn ssh_kill_process_sync(target: String, pid: u32, signal: Option<String>) -> Result<(), String> {
+    let target = target.trim();
+    if target.is_empty() {
+        return Err("missing ssh target".to_string());
+    }
+    if pid <= 1 {
+        return Err("invalid pid".to_string());
+    }
+    let signal = signal
+        .map(|s| s.trim().trim_start_matches("SIG").to_ascii_uppercase())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "TERM".to_string());
+    if !KILL_SIGNALS.contains(&signal.as_str()) {
+        return Err(format!("unsupported signal: {signal}"));
+    }
+
+    let script = r#"kill -s "$2" "$1""#;
+    let command = build_sh_c_command(script, Some("--"), &[pid.to_string(), signal]);
+    let output = run_ssh(target, &[command], None)?;
+    if !output.status.success() {
+        return Err(output_to_error("kill failed", &output));
+    }
+    Ok(())
+}
+
+fn run_scp(target: &str, scp_flags: &[&str], paths: &[String]) -> Result<Output, String> {
+    crate::ssh_retry::run_with_retry(|| run_scp_once(target, scp_flags, paths))
+}
+
+fn run_scp_once(target: &str, scp_flags: &[&str], paths: &[String]) -> Result<Output, String> {
+    let chain = resolve_jump_chain(target);
     let mut cmd = Command::new(program_path("scp")?);
+    crate::ssh_askpass::configure(&mut cmd, target);
     // scp flags first (like -r)
     cmd.args(scp_flags);
     // SSH options next
-    cmd.args(ssh_args);
+    cmd.args(ssh_common_args(target, &chain)?);
     // Source and destination paths last
     cmd.args(paths);
     cmd.stdin(Stdio::null());
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
-    cmd.output().map_err(|e| format!("run scp failed: {e}"))
+    let output = cmd.output().map_err(|e| format!("run scp failed: {e}"))?;
+    check_jump_hosts(target, &chain, output)
 }
 
 #[tauri::command]
@@ -630,7 +1151,7 @@ fn ssh_download_file_sync(
     // Note: No shell escaping needed - scp handles paths directly
     let source = format!("{}:{}", target, remote_path);
     let paths = vec![source, local.to_string()];
-    let output = run_scp(&["-r"], ssh_common_args()?, &paths)?;
+    let output = run_scp(target, &["-r"], &paths)?;
     if !output.status.success() {
         return Err(output_to_error("scp download failed", &output));
     }
@@ -639,19 +1160,31 @@ fn ssh_download_file_sync(
 
 #[tauri::command]
 pub async fn ssh_upload_file(
+    app: AppHandle,
     target: String,
     root: String,
     local_path: String,
     remote_path: String,
 ) -> Result<(), String> {
     tauri::async_runtime::spawn_blocking(move || {
-        ssh_upload_file_sync(target, root, local_path, remote_path)
+        let audit_target = format!("{}:{}", target.trim(), remote_path.trim());
+        let request = crate::ssh_retry::RemoteOpRequest {
+            kind: crate::ssh_retry::RemoteOpKind::Upload,
+            target: target.trim().to_string(),
+            root: root.clone(),
+            path: remote_path.clone(),
+            local_path: Some(local_path.clone()),
+            content: None,
+        };
+        let result = ssh_upload_file_sync(target, root, local_path, remote_path);
+        crate::audit::record_result(&app, "ssh.upload", &audit_target, &result);
+        crate::ssh_retry::settle(&app, request, result)
     })
     .await
     .map_err(|e| format!("ssh task join failed: {e:?}"))?
 }
 
-fn ssh_upload_file_sync(
+pub(crate) fn ssh_upload_file_sync(
     target: String,
     root: String,
     local_path: String,
@@ -676,7 +1209,7 @@ fn ssh_upload_file_sync(
     // Note: No shell escaping needed - scp handles paths directly
     let dest = format!("{}:{}", target, remote_path);
     let paths = vec![local.to_string(), dest];
-    let output = run_scp(&["-r"], ssh_common_args()?, &paths)?;
+    let output = run_scp(target, &["-r"], &paths)?;
     if !output.status.success() {
         return Err(output_to_error("scp upload failed", &output));
     }
@@ -734,10 +1267,93 @@ fn ssh_download_to_temp_sync(
     // Note: No shell escaping needed - scp handles paths directly
     let source = format!("{}:{}", target, remote_path);
     let paths = vec![source, local_path_str.clone()];
-    let output = run_scp(&["-r"], ssh_common_args()?, &paths)?;
+    let output = run_scp(target, &["-r"], &paths)?;
     if !output.status.success() {
         return Err(output_to_error("scp download failed", &output));
     }
 
     Ok(local_path_str)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        jump_failure, parse_batch_output, parse_disk_usage, parse_ps_elapsed, parse_ps_output, plan_batch_op,
+        SshBatchOp,
+    };
+    use crate::ssh::JumpHop;
+
+    #[test]
+    fn jump_failures_name_the_broken_hop() {
+        let chain = vec![
+            JumpHop {
+                spec: "edge".to_string(),
+                host: "203.0.113.7".to_string(),
+            },
+            JumpHop {
+                spec: "ops@inner".to_string(),
+                host: "10.0.1.1".to_string(),
+            },
+        ];
+        let err = jump_failure("db", &chain, "ssh: connect to host 203.0.113.7 port 22: Connection timed out\r\nConnection closed by UNKNOWN port 65535\n");
+        assert_eq!(
+            err.as_deref(),
+            Some("jump host edge failed: ssh: connect to host 203.0.113.7 port 22: Connection timed out")
+        );
+        let err = jump_failure("db", &chain, "ops@inner: Permission denied (publickey).\n").unwrap();
+        assert!(err.starts_with("jump host ops@inner failed"));
+        let err = jump_failure("db", &chain, "channel 0: open failed: connect failed: No route to host\n").unwrap();
+        assert!(err.starts_with("jump host ops@inner could not reach db"));
+        assert_eq!(jump_failure("db", &[], "Connection closed by UNKNOWN port 65535"), None);
+        assert_eq!(jump_failure("db", &chain, "deploy@db: Permission denied (publickey)."), None);
+    }
+
+    #[test]
+    fn batch_ops_are_validated_against_root() {
+        let rename = SshBatchOp::Rename {
+            path: "/srv/app/a.txt".to_string(),
+            new_name: "b.txt".to_string(),
+        };
+        let (_, verb, from, to) = plan_batch_op("/srv/app", &rename).unwrap();
+        assert_eq!((verb, from.as_str(), to.as_str()), ("mv", "/srv/app/a.txt", "/srv/app/b.txt"));
+
+        let escape = SshBatchOp::Move {
+            path: "/srv/app/a.txt".to_string(),
+            to_dir: "/srv/app/../other".to_string(),
+        };
+        assert!(plan_batch_op("/srv/app", &escape).is_err());
+        assert!(plan_batch_op("/srv/app", &SshBatchOp::Delete { path: "/srv/app".to_string() }).is_err());
+    }
+
+    #[test]
+    fn batch_output_fills_missing_results() {
+        let results = parse_batch_output("0\tok\t\n1\terror\tPermission denied\n2\tskipped\t\n", 4);
+        assert_eq!(results[0], (true, None));
+        assert_eq!(results[1], (false, Some("Permission denied".to_string())));
+        assert!(!results[2].0);
+        assert_eq!(results[3], (false, Some("not run".to_string())));
+    }
+
+    #[test]
+    fn disk_usage_parses_df_and_du_lines() {
+        let out = "df:/dev/sda1  102400  51200  51200  50% /mnt/my disk\ndu:2048\t/mnt/my disk/app\n";
+        let usage = parse_disk_usage("/mnt/my disk/app", out).unwrap();
+        assert_eq!(usage.mount_point, "/mnt/my disk");
+        assert_eq!(usage.available_bytes, 51200 * 1024);
+        assert_eq!(usage.used_percent, 50);
+        assert_eq!(usage.path_bytes, Some(2048 * 1024));
+        assert!(parse_disk_usage("/", "du:1\n").is_none());
+    }
+
+    #[test]
+    fn ps_output_skips_own_processes_and_filters() {
+        let out = "self:900\n  412     1 deploy    12.5  3.1  2-03:04:05 node /srv/app/server.js --port 3000\n  900   880 deploy     0.0  0.0       00:00 sh -c ps\n  901   900 deploy     0.0  0.0       00:00 ps -axo pid=\n";
+        let all = parse_ps_output(out, None);
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].command, "node /srv/app/server.js --port 3000");
+        assert_eq!(all[0].elapsed_secs, parse_ps_elapsed("2-03:04:05").unwrap());
+        assert_eq!(all[0].elapsed_secs, 2 * 86_400 + 3 * 3600 + 4 * 60 + 5);
+        assert!(parse_ps_output(out, Some("SERVER.JS")).len() == 1);
+        assert!(parse_ps_output(out, Some("python")).is_empty());
+    }
+}
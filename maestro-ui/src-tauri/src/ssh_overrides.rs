@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+const OVERRIDES_FILE_NAME: &str = "ssh-host-overrides-v1.json";
+
+/// Settings for one ssh host alias that take precedence over ~/.ssh/config when
+/// Maestro runs ssh, sftp or scp against it.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SshHostOverride {
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub identity_file: Option<String>,
+    pub connect_timeout_secs: Option<u32>,
+    /// ServerAliveInterval; 0 turns keepalives off.
+    pub keepalive_interval_secs: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SshHostOverridesV1 {
+    #[serde(default)]
+    hosts: HashMap<String, SshHostOverride>,
+}
+
+fn overrides_cache() -> &'static Mutex<Option<HashMap<String, SshHostOverride>>> {
+    static CACHE: OnceLock<Mutex<Option<HashMap<String, SshHostOverride>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn overrides_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, OVERRIDES_FILE_NAME)
+}
+
+fn read_overrides(app: &AppHandle) -> Result<HashMap<String, SshHostOverride>, String> {
+    let path = overrides_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str::<SshHostOverridesV1>(&raw)
+            .map(|o| o.hosts)
+            .map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+/// Load the saved overrides at startup. ssh_fs reads them without a window handle.
+pub(crate) fn init(app: &AppHandle) {
+    let hosts = read_overrides(app).unwrap_or_else(|e| {
+        eprintln!("Failed to load ssh host overrides: {e}");
+        HashMap::new()
+    });
+    if let Ok(mut cache) = overrides_cache().lock() {
+        *cache = Some(hosts);
+    }
+}
+
+pub(crate) fn all() -> HashMap<String, SshHostOverride> {
+    overrides_cache()
+        .lock()
+        .ok()
+        .and_then(|c| c.clone())
+        .unwrap_or_default()
+}
+
+fn clean_text(field: &str, value: Option<String>) -> Result<Option<String>, String> {
+    let Some(value) = value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    if value.chars().any(|c| c == '"' || c.is_control()) {
+        return Err(format!("invalid {field}: {value}"));
+    }
+    Ok(Some(value))
+}
+
+fn validate(o: SshHostOverride) -> Result<SshHostOverride, String> {
+    let user = clean_text("user", o.user)?;
+    if user.as_deref().is_some_and(|u| u.contains(char::is_whitespace) || u.contains('@')) {
+        return Err("invalid user".to_string());
+    }
+    if o.port == Some(0) {
+        return Err("invalid port: 0".to_string());
+    }
+    if o.connect_timeout_secs == Some(0) {
+        return Err("connect timeout must be at least 1 second".to_string());
+    }
+    Ok(SshHostOverride {
+        port: o.port,
+        user,
+        identity_file: clean_text("identity file", o.identity_file)?,
+        connect_timeout_secs: o.connect_timeout_secs,
+        keepalive_interval_secs: o.keepalive_interval_secs,
+    })
+}
+
+fn override_args(o: &SshHostOverride) -> Vec<String> {
+    let mut options: Vec<String> = Vec::new();
+    if let Some(port) = o.port {
+        options.push(format!("Port={port}"));
+    }
+    if let Some(user) = &o.user {
+        options.push(format!("User={user}"));
+    }
+    if let Some(identity) = &o.identity_file {
+        // ssh parses -o values like config lines, so a path with spaces needs quotes.
+        options.push(format!("IdentityFile=\"{identity}\""));
+        options.push("IdentitiesOnly=yes".to_string());
+    }
+    if let Some(timeout) = o.connect_timeout_secs {
+        options.push(format!("ConnectTimeout={timeout}"));
+    }
+    if let Some(interval) = o.keepalive_interval_secs {
+        options.push(format!("ServerAliveInterval={interval}"));
+    }
+    options.into_iter().flat_map(|opt| ["-o".to_string(), opt]).collect()
+}
+
+/// `-o` arguments for the override saved for `target`'s host alias. They must come
+/// before Maestro's defaults, since ssh keeps the first value given for an option.
+pub(crate) fn args_for_target(target: &str) -> Vec<String> {
+    let alias = crate::ssh::hop_host(target.trim());
+    overrides_cache()
+        .lock()
+        .ok()
+        .and_then(|c| c.as_ref().and_then(|hosts| hosts.get(alias)).map(override_args))
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_ssh_host_overrides(window: WebviewWindow) -> Result<HashMap<String, SshHostOverride>, String> {
+    read_overrides(window.app_handle())
+}
+
+/// Save the override for a host alias; `None` or an empty override removes it.
+#[tauri::command]
+pub fn set_ssh_host_override(
+    window: WebviewWindow,
+    alias: String,
+    overrides: Option<SshHostOverride>,
+) -> Result<(), String> {
+    let alias = alias.trim().to_string();
+    if alias.is_empty() || alias.contains(char::is_whitespace) {
+        return Err("invalid host alias".to_string());
+    }
+    let overrides = overrides.map(validate).transpose()?;
+
+    let app = window.app_handle();
+    let mut hosts = read_overrides(app)?;
+    match overrides.filter(|o| *o != SshHostOverride::default()) {
+        Some(o) => {
+            hosts.insert(alias, o);
+        }
+        None => {
+            hosts.remove(&alias);
+        }
+    }
+
+    let path = overrides_file_path(app)?;
+    crate::util::write_json_atomic(&path, &SshHostOverridesV1 { hosts: hosts.clone() })?;
+
+    *overrides_cache().lock().map_err(|_| "ssh overrides cache poisoned")? = Some(hosts);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{override_args, validate, SshHostOverride};
+
+    #[test]
+    fn overrides_become_ssh_options() {
+        let o = validate(SshHostOverride {
+            port: Some(2222),
+            user: Some(" deploy ".to_string()),
+            identity_file: Some("~/.ssh/work key".to_string()),
+            connect_timeout_secs: None,
+            keepalive_interval_secs: Some(30),
+        })
+        .unwrap();
+        assert_eq!(
+            override_args(&o),
+            [
+                "-o",
+                "Port=2222",
+                "-o",
+                "User=deploy",
+                "-o",
+                "IdentityFile=\"~/.ssh/work key\"",
+                "-o",
+                "IdentitiesOnly=yes",
+                "-o",
+                "ServerAliveInterval=30",
+            ]
+        );
+        assert!(validate(SshHostOverride {
+            user: Some("a b".to_string()),
+            ..SshHostOverride::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+use crate::assets::TextAssetInput;
+use crate::ssh_fs::{build_sh_c_command, normalize_posix_path, output_to_error, run_ssh, shell_escape_posix};
+
+/// Agent CLIs that can be installed on a remote host: (id, binary, npm package).
+const REMOTE_AGENTS: &[(&str, &str, &str)] = &[
+    ("claude", "claude", "@anthropic-ai/claude-code"),
+    ("codex", "codex", "@openai/codex"),
+    ("gemini", "gemini", "@google/gemini-cli"),
+];
+
+/// Remote file holding the pushed variables, relative to `$HOME`.
+const REMOTE_ENV_FILE: &str = ".maestro/agent-env.sh";
+
+/// Non-interactive ssh shells skip the login profile, where node version managers
+/// usually put npm and global CLIs on PATH.
+const LOAD_PROFILE: &str = r#"[ -f "$HOME/.profile" ] && . "$HOME/.profile" >/dev/null 2>&1;"#;
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteAgentSetup {
+    /// Agent ids to check: `claude`, `codex`, `gemini`.
+    #[serde(default)]
+    pub agents: Vec<String>,
+    /// Install missing agents with `npm install -g`.
+    pub install_missing: Option<bool>,
+    /// Variables written to `~/.maestro/agent-env.sh`, which shell profiles then source.
+    pub env: Option<HashMap<String, String>>,
+    /// Absolute remote directory the project assets are written under.
+    pub project_root: Option<String>,
+    pub assets: Option<Vec<TextAssetInput>>,
+    pub overwrite_assets: Option<bool>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteAgentStatus {
+    pub agent: String,
+    /// `found`, `installed`, `missing` or `failed`.
+    pub status: String,
+    pub path: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteAgentSetupReport {
+    pub agents: Vec<RemoteAgentStatus>,
+    pub env_file: Option<String>,
+    pub env_keys: Vec<String>,
+    pub assets_written: Vec<String>,
+    /// Asset and env failures; agent failures are reported per agent.
+    pub errors: Vec<String>,
+}
+
+fn valid_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+/// A relative POSIX path without `..`, absolute or empty segments.
+fn validate_remote_relative_path(input: &str) -> Result<String, String> {
+    let rel = input.trim().trim_start_matches("./");
+    if rel.is_empty() || rel.starts_with('/') || rel.contains('\\') {
+        return Err(format!("invalid relative path: {input}"));
+    }
+    if rel.split('/').any(|part| part.is_empty() || part == "..") {
+        return Err(format!("invalid relative path: {input}"));
+    }
+    Ok(rel.to_string())
+}
+
+fn env_file_contents(vars: &[(String, String)]) -> String {
+    let mut out = String::from("# Written by Maestro. Changes are overwritten on the next setup.\n");
+    for (key, value) in vars {
+        out.push_str(&format!("export {key}={}\n", shell_escape_posix(value)));
+    }
+    out
+}
+
+fn setup_agent(target: &str, agent: &str, install: bool) -> RemoteAgentStatus {
+    let failed = |error: String| RemoteAgentStatus {
+        agent: agent.to_string(),
+        status: "failed".to_string(),
+        path: None,
+        error: Some(error),
+    };
+    let Some((_, binary, package)) = REMOTE_AGENTS.iter().find(|(id, _, _)| *id == agent) else {
+        return failed(format!("unknown agent: {agent}"));
+    };
+
+    let script = format!(
+        r#"{LOAD_PROFILE} a="$1"; p="$(command -v "$a" 2>/dev/null)"; if [ -n "$p" ]; then printf 'found\t%s\n' "$p"; exit 0; fi; [ "$3" = 1 ] || {{ printf 'missing\t\n'; exit 0; }}; command -v npm >/dev/null 2>&1 || {{ echo "npm not found on remote host" >&2; exit 1; }}; npm install -g "$2" >/dev/null || exit 1; p="$(command -v "$a" 2>/dev/null)"; printf 'installed\t%s\n' "$p""#
+    );
+    let flag = if install { "1" } else { "0" };
+    let command = build_sh_c_command(&script, Some("--"), &[binary.to_string(), package.to_string(), flag.to_string()]);
+    let output = match run_ssh(target, &[command], None) {
+        Ok(output) => output,
+        Err(e) => return failed(e),
+    };
+    if !output.status.success() {
+        return failed(output_to_error("setup failed", &output));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (status, path) = stdout
+        .lines()
+        .rev()
+        .find_map(|l| l.split_once('\t'))
+        .unwrap_or(("failed", ""));
+    RemoteAgentStatus {
+        agent: agent.to_string(),
+        status: status.to_string(),
+        path: Some(path.trim().to_string()).filter(|p| !p.is_empty()),
+        error: None,
+    }
+}
+
+fn push_env(target: &str, vars: &[(String, String)]) -> Result<String, String> {
+    // Write the snippet, then make sure the usual profiles source it exactly once.
+    let script = r#"set -e; f="$HOME/$1"; mkdir -p "$(dirname "$f")"; umask 077; cat > "$f.tmp"; mv "$f.tmp" "$f"; line="[ -f \"\$HOME/$1\" ] && . \"\$HOME/$1\""; for rc in "$HOME/.profile" "$HOME/.bashrc" "$HOME/.zshrc"; do if [ "$rc" = "$HOME/.profile" ] || [ -f "$rc" ]; then grep -qF "$line" "$rc" 2>/dev/null || printf '\n%s\n' "$line" >> "$rc"; fi; done; printf '%s' "$f""#;
+    let command = build_sh_c_command(script, Some("--"), &[REMOTE_ENV_FILE.to_string()]);
+    let output = run_ssh(target, &[command], Some(env_file_contents(vars).as_bytes()))?;
+    if !output.status.success() {
+        return Err(output_to_error("env push failed", &output));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Returns the remote path when written, None when it existed and was left alone.
+fn write_asset(target: &str, path: &str, content: &str, overwrite: bool) -> Result<Option<String>, String> {
+    let script = r#"set -e; f="$1"; if [ -e "$f" ] && [ "$2" != 1 ]; then exit 0; fi; [ ! -d "$f" ] || { echo "target exists and is a directory" >&2; exit 1; }; mkdir -p "$(dirname "$f")"; cat > "$f.tmp"; mv "$f.tmp" "$f"; printf 'written'"#;
+    let flag = if overwrite { "1" } else { "0" };
+    let command = build_sh_c_command(script, Some("--"), &[path.to_string(), flag.to_string()]);
+    let output = run_ssh(target, &[command], Some(content.as_bytes()))?;
+    if !output.status.success() {
+        return Err(output_to_error(&format!("write {path} failed"), &output));
+    }
+    Ok((String::from_utf8_lossy(&output.stdout).trim() == "written").then(|| path.to_string()))
+}
+
+/// Prepare a remote host for running agents: check (and optionally install) agent CLIs,
+/// push environment variables into a profile snippet, and write project assets under
+/// `project_root`. Each part runs independently; the report says what happened.
+#[tauri::command]
+pub async fn ssh_setup_remote_agent_host(
+    app: AppHandle,
+    target: String,
+    setup: RemoteAgentSetup,
+) -> Result<RemoteAgentSetupReport, String> {
+    let target = target.trim().to_string();
+    if target.is_empty() {
+        return Err("missing ssh target".to_string());
+    }
+
+    let mut env: Vec<(String, String)> = setup
+        .env
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(k, v)| (k.trim().to_string(), v))
+        .collect();
+    if let Some((key, _)) = env.iter().find(|(k, _)| !valid_env_key(k)) {
+        return Err(format!("invalid variable name: {key}"));
+    }
+    env.sort();
+
+    let assets = setup.assets.unwrap_or_default();
+    let root = match setup.project_root.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+        Some(root) => Some(normalize_posix_path(root)?),
+        None if assets.is_empty() => None,
+        None => return Err("project root is required to apply assets".to_string()),
+    };
+    let assets: Vec<(String, String)> = assets
+        .into_iter()
+        .map(|a| Ok((validate_remote_relative_path(&a.relative_path)?, a.content)))
+        .collect::<Result<_, String>>()?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut report = RemoteAgentSetupReport::default();
+        let install = setup.install_missing.unwrap_or(false);
+        for agent in &setup.agents {
+            report.agents.push(setup_agent(&target, agent.trim(), install));
+        }
+
+        if !env.is_empty() {
+            match push_env(&target, &env) {
+                Ok(path) => {
+                    report.env_file = Some(path);
+                    report.env_keys = env.into_iter().map(|(k, _)| k).collect();
+                }
+                Err(e) => report.errors.push(e),
+            }
+        }
+
+        if let Some(root) = root {
+            let overwrite = setup.overwrite_assets.unwrap_or(false);
+            for (rel, content) in assets {
+                let path = format!("{}/{rel}", root.trim_end_matches('/'));
+                match write_asset(&target, &path, &content, overwrite) {
+                    Ok(Some(path)) => report.assets_written.push(path),
+                    Ok(None) => {}
+                    Err(e) => report.errors.push(e),
+                }
+            }
+        }
+
+        let failed = report.agents.iter().any(|a| a.status == "failed") || !report.errors.is_empty();
+        crate::audit::record(
+            &app,
+            "ssh.setup",
+            &target,
+            failed.then_some("some setup steps failed"),
+        );
+        report
+    })
+    .await
+    .map_err(|e| format!("ssh task join failed: {e:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{env_file_contents, validate_remote_relative_path};
+
+    #[test]
+    fn remote_setup_inputs_are_checked_and_quoted() {
+        assert_eq!(validate_remote_relative_path("./.claude/settings.json").unwrap(), ".claude/settings.json");
+        assert!(validate_remote_relative_path("../etc/passwd").is_err());
+        assert!(validate_remote_relative_path("/etc/passwd").is_err());
+
+        let env = env_file_contents(&[("TOKEN".to_string(), "a'b $c".to_string())]);
+        assert!(env.ends_with("export TOKEN='a'\"'\"'b $c'\n"));
+    }
+}
@@ -0,0 +1,539 @@
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::util::now_epoch_ms;
+
+const SETTINGS_FILE_NAME: &str = "ssh-retry-v1.json";
+const QUEUE_FILE_NAME: &str = "ssh-pending-ops-v1.json";
+const EVENT_REMOTE_OP_FINISHED: &str = "remote-op-finished";
+const MAX_ATTEMPTS: u32 = 10;
+const MAX_DELAY_MS: u64 = 60_000;
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+const QUEUE_RETRY_BASE_MS: u64 = 15_000;
+const MAX_QUEUE_RETRY_MS: u64 = 10 * 60 * 1000;
+/// Queued operations still failing after this long are given up.
+const MAX_PENDING_AGE_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+const MAX_PENDING_OPS: usize = 100;
+/// Larger writes fail as before instead of being kept in the queue file.
+const MAX_QUEUED_WRITE_BYTES: usize = 2 * 1024 * 1024;
+
+/// ssh couldn't reach the host, so the remote command never ran and running it again
+/// is safe whatever it does.
+const CONNECT_ERRORS: &[&str] = &[
+    "connection timed out",
+    "operation timed out",
+    "connection refused",
+    "network is unreachable",
+    "no route to host",
+    "could not resolve hostname",
+    "temporary failure in name resolution",
+    "kex_exchange_identification",
+];
+/// The connection dropped part way; the remote command may or may not have run.
+const DROPPED_ERRORS: &[&str] = &[
+    "connection reset",
+    "broken pipe",
+    "lost connection",
+    "connection closed by",
+    "timeout, server not responding",
+];
+
+fn default_max_attempts() -> u32 {
+    4
+}
+
+fn default_initial_delay_ms() -> u64 {
+    500
+}
+
+fn default_max_delay_ms() -> u64 {
+    8_000
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SshRetryPolicyV1 {
+    /// Off by default: every ssh, scp and sftp failure is reported right away.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Tries in total, the first included. Only failures to reach the host are retried.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait before the second try; doubled each time after.
+    #[serde(default = "default_initial_delay_ms")]
+    pub initial_delay_ms: u64,
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+    /// Text file writes and uploads that still fail for network reasons are kept and
+    /// retried in the background (see `list_pending_remote_ops`).
+    #[serde(default)]
+    pub queue_offline: bool,
+}
+
+impl Default for SshRetryPolicyV1 {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts: default_max_attempts(),
+            initial_delay_ms: default_initial_delay_ms(),
+            max_delay_ms: default_max_delay_ms(),
+            queue_offline: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RemoteOpKind {
+    /// `ssh_write_text_file`: replaces the whole file, so replaying it is harmless.
+    Write,
+    /// `ssh_upload_file`: scp overwrites what is there.
+    Upload,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingRemoteOpV1 {
+    pub id: String,
+    pub kind: RemoteOpKind,
+    pub target: String,
+    pub root: String,
+    /// The remote file.
+    pub path: String,
+    /// The local source of an upload.
+    pub local_path: Option<String>,
+    /// Size of the queued content of a write.
+    pub bytes: Option<u64>,
+    pub queued_at: u64,
+    /// Background retries so far.
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+    pub last_error: String,
+}
+
+/// A queue entry: the op as listed, plus a write's content.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct QueuedOp {
+    #[serde(flatten)]
+    op: PendingRemoteOpV1,
+    content: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteOpFinished {
+    pub id: String,
+    pub kind: RemoteOpKind,
+    pub target: String,
+    pub path: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// A write or upload as attempted by its command, for `settle`.
+pub(crate) struct RemoteOpRequest {
+    pub(crate) kind: RemoteOpKind,
+    pub(crate) target: String,
+    pub(crate) root: String,
+    pub(crate) path: String,
+    pub(crate) local_path: Option<String>,
+    /// A write's content; only kept when the policy queues offline writes.
+    pub(crate) content: Option<String>,
+}
+
+fn new_op_id() -> String {
+    let mut bytes = [0u8; 8];
+    OsRng.fill_bytes(&mut bytes);
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("rop-{hex}")
+}
+
+fn settings_cache() -> &'static Mutex<Option<SshRetryPolicyV1>> {
+    static CACHE: OnceLock<Mutex<Option<SshRetryPolicyV1>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// Serializes reads and rewrites of the queue file.
+fn queue_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|_| "unknown app data dir".to_string())
+}
+
+fn read_settings(app: &AppHandle) -> Result<SshRetryPolicyV1, String> {
+    let path = app_data_dir(app)?.join(SETTINGS_FILE_NAME);
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SshRetryPolicyV1::default()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn read_queue(path: &Path) -> Vec<QueuedOp> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn validate(policy: &SshRetryPolicyV1) -> Result<(), String> {
+    if policy.max_attempts == 0 || policy.max_attempts > MAX_ATTEMPTS {
+        return Err(format!("maxAttempts must be 1-{MAX_ATTEMPTS}"));
+    }
+    if policy.max_delay_ms > MAX_DELAY_MS {
+        return Err(format!("maxDelayMs must be at most {MAX_DELAY_MS}"));
+    }
+    if policy.initial_delay_ms > policy.max_delay_ms {
+        return Err("initialDelayMs must not exceed maxDelayMs".to_string());
+    }
+    Ok(())
+}
+
+fn policy() -> SshRetryPolicyV1 {
+    settings_cache().lock().ok().and_then(|c| *c).unwrap_or_default()
+}
+
+/// Whether writes should hold on to their content so they can be queued.
+pub(crate) fn queue_offline() -> bool {
+    let policy = policy();
+    policy.enabled && policy.queue_offline
+}
+
+fn matches_any(message: &str, needles: &[&str]) -> bool {
+    let message = message.to_ascii_lowercase();
+    needles.iter().any(|needle| message.contains(needle))
+}
+
+/// ssh never got to run the remote command.
+fn is_connect_error(message: &str) -> bool {
+    matches_any(message, CONNECT_ERRORS)
+}
+
+/// Any network failure, including a connection dropped part way.
+fn is_network_error(message: &str) -> bool {
+    is_connect_error(message) || matches_any(message, DROPPED_ERRORS)
+}
+
+/// Wait before try `attempt + 1`: the initial delay, doubled for each earlier retry.
+fn backoff_delay(policy: &SshRetryPolicyV1, attempt: u32) -> Duration {
+    let ms = policy
+        .initial_delay_ms
+        .saturating_mul(1 << attempt.saturating_sub(1).min(20))
+        .min(policy.max_delay_ms);
+    Duration::from_millis(ms)
+}
+
+fn queue_retry_delay_ms(attempts: u32) -> u64 {
+    QUEUE_RETRY_BASE_MS
+        .saturating_mul(1 << attempts.min(20))
+        .min(MAX_QUEUE_RETRY_MS)
+}
+
+/// Run one ssh, scp or sftp invocation, trying again per the retry policy while it
+/// fails to reach the host. Anything else is returned as is.
+pub(crate) fn run_with_retry(mut attempt: impl FnMut() -> Result<Output, String>) -> Result<Output, String> {
+    let policy = policy();
+    let mut tries = 1;
+    loop {
+        let result = attempt();
+        let unreachable = match &result {
+            Ok(output) => {
+                !output.status.success() && is_connect_error(&String::from_utf8_lossy(&output.stderr))
+            }
+            Err(e) => is_connect_error(e),
+        };
+        if !policy.enabled || !unreachable || tries >= policy.max_attempts {
+            return result;
+        }
+        std::thread::sleep(backoff_delay(&policy, tries));
+        tries += 1;
+    }
+}
+
+fn same_file(a: &PendingRemoteOpV1, kind: RemoteOpKind, target: &str, path: &str) -> bool {
+    a.kind == kind && a.target == target && a.path == path
+}
+
+fn enqueue(app: &AppHandle, request: RemoteOpRequest, error: &str) -> Result<String, String> {
+    if request.kind == RemoteOpKind::Write && request.content.is_none() {
+        return Err("write content not kept".to_string());
+    }
+    if request.content.as_ref().is_some_and(|c| c.len() > MAX_QUEUED_WRITE_BYTES) {
+        return Err("write too large to queue".to_string());
+    }
+    let path = app_data_dir(app)?.join(QUEUE_FILE_NAME);
+    let _guard = queue_lock().lock().map_err(|_| "ssh queue poisoned")?;
+    let mut queue = read_queue(&path);
+    // A newer write or upload of the same file replaces the queued one.
+    queue.retain(|q| !same_file(&q.op, request.kind, &request.target, &request.path));
+    if queue.len() >= MAX_PENDING_OPS {
+        return Err("too many pending remote operations".to_string());
+    }
+    let now = now_epoch_ms();
+    let id = new_op_id();
+    queue.push(QueuedOp {
+        op: PendingRemoteOpV1 {
+            id: id.clone(),
+            kind: request.kind,
+            target: request.target,
+            root: request.root,
+            path: request.path,
+            local_path: request.local_path,
+            bytes: request.content.as_ref().map(|c| c.len() as u64),
+            queued_at: now,
+            attempts: 0,
+            next_attempt_at: now + queue_retry_delay_ms(0),
+            last_error: error.to_string(),
+        },
+        content: request.content,
+    });
+    crate::util::write_json_atomic(&path, &queue)?;
+    Ok(id)
+}
+
+/// Drop queued ops for a file that has since been written directly, so a stale
+/// replay can't overwrite it.
+fn supersede(app: &AppHandle, kind: RemoteOpKind, target: &str, path: &str) {
+    let Ok(file) = app_data_dir(app).map(|dir| dir.join(QUEUE_FILE_NAME)) else {
+        return;
+    };
+    if !file.exists() {
+        return;
+    }
+    let Ok(_guard) = queue_lock().lock() else {
+        return;
+    };
+    let mut queue = read_queue(&file);
+    let before = queue.len();
+    queue.retain(|q| !same_file(&q.op, kind, target, path));
+    if queue.len() != before {
+        if let Err(e) = crate::util::write_json_atomic(&file, &queue) {
+            eprintln!("Failed to update pending remote ops: {e}");
+        }
+    }
+}
+
+/// Called by a write or upload command with its result: on success, queued ops for
+/// the same file are dropped; on a network failure with offline queueing on, the op
+/// is queued and the error says so.
+pub(crate) fn settle(
+    app: &AppHandle,
+    request: RemoteOpRequest,
+    result: Result<(), String>,
+) -> Result<(), String> {
+    match result {
+        Ok(()) => {
+            supersede(app, request.kind, &request.target, &request.path);
+            Ok(())
+        }
+        Err(e) if queue_offline() && is_network_error(&e) => match enqueue(app, request, &e) {
+            Ok(id) => Err(format!("{e} (queued for retry as {id})")),
+            Err(queue_error) => {
+                eprintln!("Failed to queue remote operation: {queue_error}");
+                Err(e)
+            }
+        },
+        Err(e) => Err(e),
+    }
+}
+
+fn replay(app: &AppHandle, queued: &QueuedOp) -> Result<(), String> {
+    let op = &queued.op;
+    let audit_target = format!("{}:{}", op.target, op.path);
+    let (action, result) = match op.kind {
+        RemoteOpKind::Write => (
+            "ssh.write",
+            crate::ssh_fs::ssh_write_text_file_sync(
+                op.target.clone(),
+                op.root.clone(),
+                op.path.clone(),
+                queued.content.clone().unwrap_or_default(),
+            ),
+        ),
+        RemoteOpKind::Upload => (
+            "ssh.upload",
+            crate::ssh_fs::ssh_upload_file_sync(
+                op.target.clone(),
+                op.root.clone(),
+                op.local_path.clone().unwrap_or_default(),
+                op.path.clone(),
+            ),
+        ),
+    };
+    crate::audit::record_result(app, action, &audit_target, &result);
+    result
+}
+
+fn finished(app: &AppHandle, op: &PendingRemoteOpV1, error: Option<String>) {
+    let _ = app.emit(
+        EVENT_REMOTE_OP_FINISHED,
+        RemoteOpFinished {
+            id: op.id.clone(),
+            kind: op.kind,
+            target: op.target.clone(),
+            path: op.path.clone(),
+            ok: error.is_none(),
+            error,
+        },
+    );
+}
+
+/// Replay the queued ops that are due, without holding the queue lock over the
+/// network, then record how each went. Ops cancelled or superseded meanwhile stay gone.
+fn retry_due(app: &AppHandle) {
+    let Ok(path) = app_data_dir(app).map(|dir| dir.join(QUEUE_FILE_NAME)) else {
+        return;
+    };
+    let now = now_epoch_ms();
+    let due: Vec<QueuedOp> = {
+        let Ok(_guard) = queue_lock().lock() else {
+            return;
+        };
+        read_queue(&path)
+            .into_iter()
+            .filter(|q| q.op.next_attempt_at <= now)
+            .collect()
+    };
+    if due.is_empty() {
+        return;
+    }
+
+    let mut outcomes: Vec<(String, Result<(), String>)> = Vec::new();
+    for queued in &due {
+        outcomes.push((queued.op.id.clone(), replay(app, queued)));
+    }
+
+    let Ok(_guard) = queue_lock().lock() else {
+        return;
+    };
+    let mut queue = read_queue(&path);
+    let now = now_epoch_ms();
+    for (id, outcome) in outcomes {
+        let Some(index) = queue.iter().position(|q| q.op.id == id) else {
+            continue;
+        };
+        let expired = now.saturating_sub(queue[index].op.queued_at) >= MAX_PENDING_AGE_MS;
+        match outcome {
+            Ok(()) => finished(app, &queue.remove(index).op, None),
+            Err(e) if is_network_error(&e) && !expired => {
+                let op = &mut queue[index].op;
+                op.attempts += 1;
+                op.next_attempt_at = now + queue_retry_delay_ms(op.attempts);
+                op.last_error = e;
+            }
+            Err(e) => finished(app, &queue.remove(index).op, Some(e)),
+        }
+    }
+    if let Err(e) = crate::util::write_json_atomic(&path, &queue) {
+        eprintln!("Failed to update pending remote ops: {e}");
+    }
+}
+
+fn start_worker(app: &AppHandle) {
+    static WORKER: Once = Once::new();
+    let app = app.clone();
+    WORKER.call_once(move || {
+        std::thread::spawn(move || loop {
+            std::thread::sleep(TICK_INTERVAL);
+            retry_due(&app);
+        });
+    });
+}
+
+/// Load the saved policy at startup and start replaying queued ops. Ops queued
+/// before queueing was turned off are still replayed.
+pub(crate) fn init(app: &AppHandle) {
+    let policy = read_settings(app)
+        .and_then(|p| validate(&p).map(|_| p))
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load ssh retry policy: {e}");
+            SshRetryPolicyV1::default()
+        });
+    if let Ok(mut cache) = settings_cache().lock() {
+        *cache = Some(policy);
+    }
+    start_worker(app);
+}
+
+#[tauri::command]
+pub fn get_ssh_retry_policy(window: WebviewWindow) -> Result<SshRetryPolicyV1, String> {
+    read_settings(window.app_handle())
+}
+
+#[tauri::command]
+pub fn set_ssh_retry_policy(window: WebviewWindow, policy: SshRetryPolicyV1) -> Result<(), String> {
+    validate(&policy)?;
+    let path = app_data_dir(window.app_handle())?.join(SETTINGS_FILE_NAME);
+    crate::util::write_json_atomic(&path, &policy)?;
+    *settings_cache().lock().map_err(|_| "ssh retry cache poisoned")? = Some(policy);
+    Ok(())
+}
+
+/// Writes and uploads waiting for the network, oldest first. `remote-op-finished`
+/// is emitted as each one completes or is given up.
+#[tauri::command]
+pub fn list_pending_remote_ops(window: WebviewWindow) -> Result<Vec<PendingRemoteOpV1>, String> {
+    let path = app_data_dir(window.app_handle())?.join(QUEUE_FILE_NAME);
+    let _guard = queue_lock().lock().map_err(|_| "ssh queue poisoned")?;
+    Ok(read_queue(&path).into_iter().map(|q| q.op).collect())
+}
+
+#[tauri::command]
+pub fn cancel_pending_remote_op(window: WebviewWindow, id: String) -> Result<(), String> {
+    let path = app_data_dir(window.app_handle())?.join(QUEUE_FILE_NAME);
+    let _guard = queue_lock().lock().map_err(|_| "ssh queue poisoned")?;
+    let mut queue = read_queue(&path);
+    let before = queue.len();
+    queue.retain(|q| q.op.id != id);
+    if queue.len() == before {
+        return Err("unknown pending operation".to_string());
+    }
+    crate::util::write_json_atomic(&path, &queue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backoff_delay, is_connect_error, is_network_error, validate, SshRetryPolicyV1};
+    use std::time::Duration;
+
+    #[test]
+    fn only_unreachable_hosts_are_safe_to_rerun() {
+        let refused = "ssh failed: ssh: connect to host example.com port 22: Connection refused";
+        assert!(is_connect_error(refused));
+        assert!(is_connect_error("ssh: Could not resolve hostname devbox: nodename nor servname"));
+        let dropped = "scp upload failed: client_loop: send disconnect: Broken pipe";
+        assert!(!is_connect_error(dropped));
+        assert!(is_network_error(dropped));
+        assert!(!is_network_error("ssh failed: not a file"));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = SshRetryPolicyV1 {
+            enabled: true,
+            initial_delay_ms: 500,
+            max_delay_ms: 3_000,
+            ..SshRetryPolicyV1::default()
+        };
+        let delays: Vec<Duration> = (1..=4).map(|attempt| backoff_delay(&policy, attempt)).collect();
+        assert_eq!(delays, [500, 1_000, 2_000, 3_000].map(Duration::from_millis));
+        assert!(validate(&policy).is_ok());
+        assert!(validate(&SshRetryPolicyV1 {
+            max_attempts: 0,
+            ..policy
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use serde::Serialize;
+use tauri::{State, WebviewWindow};
+
+use crate::activity::ActivityState;
+use crate::pty::AppState;
+use crate::tray::{FocusModeStatus, StatusTrayState};
+use crate::util::now_epoch_ms;
+
+/// Fallback for sessions activity.rs doesn't know about: an agent counts as working
+/// if its pty produced output this recently.
+const WORKING_OUTPUT_WINDOW_MS: u64 = 3000;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusAgentInfo {
+    pub id: String,
+    pub name: String,
+    pub command: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusRecordingInfo {
+    pub session_id: String,
+    pub session_name: String,
+    pub recording_id: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusProjectInfo {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusSummary {
+    pub working_agents: Vec<StatusAgentInfo>,
+    pub sessions_open: usize,
+    pub active_recordings: Vec<StatusRecordingInfo>,
+    pub current_project: Option<StatusProjectInfo>,
+    pub focus_mode: FocusModeStatus,
+}
+
+/// Everything the tray/menu bar shows, computed from the live session state
+/// rather than pushed from the frontend.
+#[tauri::command]
+pub fn get_status_summary(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    tray: State<'_, StatusTrayState>,
+) -> Result<StatusSummary, String> {
+    let mut sessions = state.session_activity()?;
+    sessions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let now = now_epoch_ms();
+    let working_agents = sessions
+        .iter()
+        .filter(|s| {
+            !s.is_shell
+                && match s.activity {
+                    Some(activity) => activity == ActivityState::Working,
+                    None => now.saturating_sub(s.last_output_at) <= WORKING_OUTPUT_WINDOW_MS,
+                }
+        })
+        .map(|s| StatusAgentInfo {
+            id: s.id.clone(),
+            name: s.name.clone(),
+            command: s.command.clone(),
+        })
+        .collect();
+
+    let active_recordings = sessions
+        .iter()
+        .filter_map(|s| {
+            Some(StatusRecordingInfo {
+                session_id: s.id.clone(),
+                session_name: s.name.clone(),
+                recording_id: s.recording_id.clone()?,
+            })
+        })
+        .collect();
+
+    let current_project = crate::persist::read_active_project(&window)
+        .map(|(id, title)| StatusProjectInfo { id, title });
+
+    Ok(StatusSummary {
+        working_agents,
+        sessions_open: sessions.len(),
+        active_recordings,
+        current_project,
+        focus_mode: tray.focus_mode(),
+    })
+}
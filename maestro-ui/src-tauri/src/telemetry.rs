@@ -0,0 +1,483 @@
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::util::now_epoch_ms;
+
+const SETTINGS_FILE_NAME: &str = "telemetry-v1.json";
+const QUEUE_FILE_NAME: &str = "telemetry-queue-v1.json";
+const SCHEMA_VERSION: u32 = 1;
+/// Where batches are POSTed; set at build time, overridable at run time. Without
+/// one, batches stay in the local queue.
+const ENDPOINT_ENV: &str = "MAESTRO_TELEMETRY_ENDPOINT";
+const BUILD_ENDPOINT: Option<&str> = option_env!("MAESTRO_TELEMETRY_ENDPOINT");
+/// Counters are cut into a batch this often.
+const BATCH_INTERVAL_MS: u64 = 15 * 60 * 1000;
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_QUEUED_BATCHES: usize = 100;
+const MAX_SUBMIT_ATTEMPTS: u32 = 10;
+const RETRY_BASE_MS: u64 = 60 * 1000;
+const MAX_RETRY_MS: u64 = 6 * 60 * 60 * 1000;
+const MAX_COUNTERS: usize = 200;
+const MAX_COUNTER_NAME_LEN: usize = 64;
+/// Agent CLIs counted by name in `session.started.<agent>`; anything else is `other`.
+const KNOWN_AGENTS: &[&str] = &[
+    "aider",
+    "amp",
+    "claude",
+    "codex",
+    "copilot",
+    "cursor-agent",
+    "gemini",
+    "goose",
+    "opencode",
+    "qwen",
+];
+/// Launchers looked through to find the agent (`npx @openai/codex`, `exec claude`).
+const LAUNCHERS: &[&str] = &["exec", "env", "npx", "bunx", "pnpx", "uvx"];
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetrySettingsV1 {
+    /// Off unless the user turns it on.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Random id created when telemetry is turned on and dropped when it's turned
+    /// off, so batches from one install can be told apart without identifying anyone.
+    #[serde(default)]
+    pub install_id: Option<String>,
+}
+
+/// One submission, POSTed as JSON to the telemetry endpoint:
+///
+/// ```json
+/// {"schema": 1, "batchId": "tb-…", "installId": "tel-…", "appVersion": "0.3.0",
+///  "os": "macos", "arch": "aarch64", "from": 1760000000000, "to": 1760000900000,
+///  "counters": {"session.started.claude": 3, "session.started.shell": 2, "feature.recording": 1}}
+/// ```
+///
+/// `from`/`to` are epoch ms. Counters are `session.started.<agent>` (a known agent
+/// CLI, `shell` or `other`) and `feature.<name>`. Nothing else is collected: no
+/// paths, commands, session names, output or hostnames.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryBatchV1 {
+    pub schema: u32,
+    /// Lets the endpoint drop a batch it already has, after a retry.
+    pub batch_id: String,
+    pub install_id: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub from: u64,
+    pub to: u64,
+    pub counters: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct QueuedBatch {
+    batch: TelemetryBatchV1,
+    #[serde(default)]
+    attempts: u32,
+    #[serde(default)]
+    next_attempt_at: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryStatus {
+    pub enabled: bool,
+    pub install_id: Option<String>,
+    /// Whether this build knows where to send batches.
+    pub endpoint_configured: bool,
+    /// Counted since the last batch was cut.
+    pub pending: BTreeMap<String, u64>,
+    /// Batches waiting to be sent, exactly as they will be sent.
+    pub queued: Vec<TelemetryBatchV1>,
+}
+
+#[derive(Default)]
+struct Counters {
+    settings: TelemetrySettingsV1,
+    since: u64,
+    counts: BTreeMap<String, u64>,
+}
+
+fn random_id(prefix: &str) -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("{prefix}-{hex}")
+}
+
+fn counters() -> &'static Mutex<Counters> {
+    static COUNTERS: OnceLock<Mutex<Counters>> = OnceLock::new();
+    COUNTERS.get_or_init(|| Mutex::new(Counters::default()))
+}
+
+/// Serializes reads and rewrites of the queue file.
+fn queue_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|_| "unknown app data dir".to_string())
+}
+
+fn endpoint() -> Option<String> {
+    std::env::var(ENDPOINT_ENV)
+        .ok()
+        .or_else(|| BUILD_ENDPOINT.map(str::to_string))
+        .map(|e| e.trim().to_string())
+        .filter(|e| {
+            // Plain http only to a local collector.
+            e.starts_with("https://") || e.starts_with("http://localhost") || e.starts_with("http://127.")
+        })
+}
+
+fn read_settings(app: &AppHandle) -> Result<TelemetrySettingsV1, String> {
+    let path = app_data_dir(app)?.join(SETTINGS_FILE_NAME);
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TelemetrySettingsV1::default()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn read_queue(path: &Path) -> Vec<QueuedBatch> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// `[a-z0-9]` then `[a-z0-9._-]`, so counter names can't carry anything identifying
+/// beyond the label itself.
+fn valid_counter_name(name: &str) -> bool {
+    name.len() <= MAX_COUNTER_NAME_LEN
+        && name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-'))
+}
+
+/// The agent a session command runs, reduced to a known CLI name, `shell` or `other`.
+fn agent_type(command: Option<&str>) -> &'static str {
+    let command = command.map(str::trim).unwrap_or_default();
+    if command.is_empty() {
+        return "shell";
+    }
+    let program = command
+        .split_whitespace()
+        .filter(|word| !word.contains('=') && !word.starts_with('-'))
+        .map(|word| word.trim_matches(|c| c == '"' || c == '\''))
+        .find(|word| !LAUNCHERS.contains(word))
+        .unwrap_or_default();
+    // `/usr/local/bin/claude`, `@openai/codex`, `@google/gemini-cli`
+    let name = program.rsplit(['/', '\\']).next().unwrap_or(program).to_ascii_lowercase();
+    let name = name.strip_suffix(".exe").unwrap_or(&name);
+    let name = name.strip_suffix("-cli").unwrap_or(name);
+    KNOWN_AGENTS
+        .iter()
+        .find(|agent| **agent == name)
+        .copied()
+        .unwrap_or("other")
+}
+
+fn retry_delay_ms(attempts: u32) -> u64 {
+    RETRY_BASE_MS
+        .saturating_mul(1 << attempts.min(20))
+        .min(MAX_RETRY_MS)
+}
+
+fn is_enabled() -> bool {
+    counters().lock().map(|c| c.settings.enabled).unwrap_or(false)
+}
+
+fn increment(name: &str) {
+    let Ok(mut counters) = counters().lock() else {
+        return;
+    };
+    if !counters.settings.enabled || !valid_counter_name(name) {
+        return;
+    }
+    if counters.counts.len() >= MAX_COUNTERS && !counters.counts.contains_key(name) {
+        return;
+    }
+    *counters.counts.entry(name.to_string()).or_default() += 1;
+}
+
+/// Count a session start (see `create_session`), by agent.
+pub(crate) fn record_session_started(command: Option<&str>) {
+    increment(&format!("session.started.{}", agent_type(command)));
+}
+
+/// Count use of a feature. A no-op unless telemetry is on.
+pub(crate) fn record_feature(feature: &str) {
+    increment(&format!("feature.{feature}"));
+}
+
+/// Move the counters into a queued batch once `BATCH_INTERVAL_MS` has passed (or
+/// right away with `force`).
+fn cut_batch(app: &AppHandle, force: bool) {
+    let now = now_epoch_ms();
+    let batch = {
+        let Ok(mut counters) = counters().lock() else {
+            return;
+        };
+        let Some(install_id) = counters.settings.install_id.clone() else {
+            return;
+        };
+        if !counters.settings.enabled || counters.counts.is_empty() {
+            return;
+        }
+        if !force && now.saturating_sub(counters.since) < BATCH_INTERVAL_MS {
+            return;
+        }
+        let from = counters.since;
+        counters.since = now;
+        TelemetryBatchV1 {
+            schema: SCHEMA_VERSION,
+            batch_id: random_id("tb"),
+            install_id,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            from,
+            to: now,
+            counters: std::mem::take(&mut counters.counts),
+        }
+    };
+    let Ok(path) = app_data_dir(app).map(|dir| dir.join(QUEUE_FILE_NAME)) else {
+        return;
+    };
+    let Ok(_guard) = queue_lock().lock() else {
+        return;
+    };
+    // Opting out in the meantime deleted the queue; don't bring it back.
+    if !is_enabled() {
+        return;
+    }
+    let mut queue = read_queue(&path);
+    queue.push(QueuedBatch {
+        batch,
+        attempts: 0,
+        next_attempt_at: 0,
+    });
+    let overflow = queue.len().saturating_sub(MAX_QUEUED_BATCHES);
+    queue.drain(..overflow);
+    if let Err(e) = crate::util::write_json_atomic(&path, &queue) {
+        eprintln!("Failed to queue telemetry batch: {e}");
+    }
+}
+
+fn post(endpoint: &str, batch: &TelemetryBatchV1) -> Result<(), String> {
+    let body = serde_json::to_vec(batch).map_err(|e| format!("serialize failed: {e}"))?;
+    let mut child = Command::new("curl")
+        .args(["-sS", "-f", "--max-time", "20", "-X", "POST"])
+        .args(["-H", "Content-Type: application/json", "--data-binary", "@-", endpoint])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("curl failed: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&body).map_err(|e| format!("curl write failed: {e}"))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("curl failed: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// Send the batches that are due, oldest first; failures wait longer each time and
+/// are given up after `MAX_SUBMIT_ATTEMPTS`. The queue lock is only held to read and
+/// update the queue, not while posting, and opting out stops before the next post.
+fn submit_due(app: &AppHandle) {
+    let Some(endpoint) = endpoint() else {
+        return;
+    };
+    let Ok(path) = app_data_dir(app).map(|dir| dir.join(QUEUE_FILE_NAME)) else {
+        return;
+    };
+    let now = now_epoch_ms();
+    let due: Vec<TelemetryBatchV1> = {
+        let Ok(_guard) = queue_lock().lock() else {
+            return;
+        };
+        read_queue(&path)
+            .into_iter()
+            .filter(|queued| queued.next_attempt_at <= now)
+            .map(|queued| queued.batch)
+            .collect()
+    };
+    let mut results = Vec::new();
+    for batch in due {
+        if !is_enabled() {
+            break;
+        }
+        results.push((batch.batch_id.clone(), post(&endpoint, &batch)));
+    }
+    if results.is_empty() {
+        return;
+    }
+
+    let Ok(_guard) = queue_lock().lock() else {
+        return;
+    };
+    if !is_enabled() {
+        return;
+    }
+    let mut queue = read_queue(&path);
+    queue.retain_mut(|queued| {
+        let Some((_, result)) = results.iter().find(|(id, _)| *id == queued.batch.batch_id) else {
+            return true;
+        };
+        let Err(e) = result else {
+            return false;
+        };
+        queued.attempts += 1;
+        if queued.attempts >= MAX_SUBMIT_ATTEMPTS {
+            eprintln!("Dropping telemetry batch {}: {e}", queued.batch.batch_id);
+            return false;
+        }
+        queued.next_attempt_at = now + retry_delay_ms(queued.attempts);
+        true
+    });
+    if let Err(e) = crate::util::write_json_atomic(&path, &queue) {
+        eprintln!("Failed to update telemetry queue: {e}");
+    }
+}
+
+fn start_worker(app: &AppHandle) {
+    static WORKER: Once = Once::new();
+    let app = app.clone();
+    WORKER.call_once(move || {
+        std::thread::spawn(move || loop {
+            std::thread::sleep(TICK_INTERVAL);
+            if is_enabled() {
+                cut_batch(&app, false);
+                submit_due(&app);
+            }
+        });
+    });
+}
+
+/// Load the opt-in at startup and start the batching worker.
+pub(crate) fn init(app: &AppHandle) {
+    let settings = read_settings(app).unwrap_or_else(|e| {
+        eprintln!("Failed to load telemetry settings: {e}");
+        TelemetrySettingsV1::default()
+    });
+    if let Ok(mut counters) = counters().lock() {
+        counters.settings = settings;
+        counters.since = now_epoch_ms();
+    }
+    start_worker(app);
+}
+
+/// What is collected and queued, for the settings page to show before and after opting in.
+#[tauri::command]
+pub fn get_telemetry_status(window: WebviewWindow) -> Result<TelemetryStatus, String> {
+    let (settings, pending) = {
+        let counters = counters().lock().map_err(|_| "telemetry poisoned")?;
+        (counters.settings.clone(), counters.counts.clone())
+    };
+    let path = app_data_dir(window.app_handle())?.join(QUEUE_FILE_NAME);
+    let queued = {
+        let _guard = queue_lock().lock().map_err(|_| "telemetry queue poisoned")?;
+        read_queue(&path).into_iter().map(|q| q.batch).collect()
+    };
+    Ok(TelemetryStatus {
+        enabled: settings.enabled,
+        install_id: settings.install_id,
+        endpoint_configured: endpoint().is_some(),
+        pending,
+        queued,
+    })
+}
+
+/// Opt in or out. Opting out deletes the install id, the counters and the queue.
+#[tauri::command]
+pub fn set_telemetry_enabled(window: WebviewWindow, enabled: bool) -> Result<TelemetryStatus, String> {
+    let app = window.app_handle();
+    let dir = app_data_dir(app)?;
+    let mut settings = read_settings(app)?;
+    settings.enabled = enabled;
+    if !enabled {
+        settings.install_id = None;
+    } else if settings.install_id.is_none() {
+        settings.install_id = Some(random_id("tel"));
+    }
+    crate::util::write_json_atomic(&dir.join(SETTINGS_FILE_NAME), &settings)?;
+
+    // Switch off before deleting the queue: the worker checks under the queue lock
+    // and won't write it, or post anything more, once it sees this.
+    {
+        let mut counters = counters().lock().map_err(|_| "telemetry poisoned")?;
+        if counters.settings.enabled != enabled {
+            counters.counts.clear();
+            counters.since = now_epoch_ms();
+        }
+        counters.settings = settings;
+    }
+    if !enabled {
+        let _guard = queue_lock().lock().map_err(|_| "telemetry queue poisoned")?;
+        match fs::remove_file(dir.join(QUEUE_FILE_NAME)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("remove queue failed: {e}")),
+        }
+    }
+    get_telemetry_status(window)
+}
+
+/// Count a frontend feature (`feature.<name>`); names are lowercase labels such as
+/// `split-view` or `theme.import`.
+#[tauri::command]
+pub fn record_feature_usage(feature: String) -> Result<(), String> {
+    let feature = feature.trim();
+    if !valid_counter_name(&format!("feature.{feature}")) || feature.starts_with(['.', '_', '-']) {
+        let max = MAX_COUNTER_NAME_LEN - "feature.".len();
+        return Err(format!("feature must be a lowercase label of at most {max} chars"));
+    }
+    record_feature(feature);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{agent_type, retry_delay_ms, valid_counter_name, MAX_RETRY_MS, RETRY_BASE_MS};
+
+    #[test]
+    fn reduces_commands_to_agent_names() {
+        assert_eq!(agent_type(None), "shell");
+        assert_eq!(agent_type(Some("  ")), "shell");
+        assert_eq!(agent_type(Some("claude --resume")), "claude");
+        assert_eq!(agent_type(Some("FOO=1 exec /usr/local/bin/codex -m o3")), "codex");
+        assert_eq!(agent_type(Some("npx -y @google/gemini-cli")), "gemini");
+        assert_eq!(agent_type(Some("python secret_project/run.py")), "other");
+    }
+
+    #[test]
+    fn counter_names_and_retry_backoff() {
+        assert!(valid_counter_name("feature.theme.import"));
+        assert!(!valid_counter_name("feature./Users/me"));
+        assert!(!valid_counter_name(".hidden"));
+        assert_eq!(retry_delay_ms(0), RETRY_BASE_MS);
+        assert_eq!(retry_delay_ms(1), RETRY_BASE_MS * 2);
+        assert_eq!(retry_delay_ms(30), MAX_RETRY_MS);
+    }
+}
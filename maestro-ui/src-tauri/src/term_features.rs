@@ -0,0 +1,122 @@
+use portable_pty::CommandBuilder;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_TERM: &str = "xterm-256color";
+const MAX_TERM_LEN: usize = 64;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorDepth {
+    /// 24-bit colour, advertised with `COLORTERM=truecolor`.
+    #[default]
+    Truecolor,
+    /// The 256-colour palette only; `COLORTERM` is left unset.
+    Ansi256,
+}
+
+/// Terminal identity for a spawned session. Every field is optional and falls back
+/// to the defaults (`xterm-256color`, truecolor, kitty keyboard on); some remote
+/// hosts and older tools misbehave with those and need e.g. `xterm` + 256 colours.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalFeaturesV1 {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub term: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colors: Option<ColorDepth>,
+    /// Answer kitty keyboard protocol queries (handled by the terminal view).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kitty_keyboard: Option<bool>,
+}
+
+/// The features a session actually runs with, returned in `SessionInfo` so the
+/// terminal view can match them.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedTerminal {
+    pub term: String,
+    pub colors: ColorDepth,
+    pub kitty_keyboard: bool,
+}
+
+impl Default for ResolvedTerminal {
+    fn default() -> Self {
+        Self {
+            term: DEFAULT_TERM.to_string(),
+            colors: ColorDepth::default(),
+            kitty_keyboard: true,
+        }
+    }
+}
+
+fn validate_term(term: &str) -> Result<(), String> {
+    if term.is_empty() || term.len() > MAX_TERM_LEN {
+        return Err(format!("TERM must be 1-{MAX_TERM_LEN} characters"));
+    }
+    // terminfo names: letters, digits and a little punctuation, never a path.
+    let valid = term
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+        && !term.starts_with(['-', '.']);
+    if !valid {
+        return Err(format!("invalid TERM value: {term}"));
+    }
+    Ok(())
+}
+
+pub(crate) fn resolve(features: Option<&TerminalFeaturesV1>) -> Result<ResolvedTerminal, String> {
+    let mut resolved = ResolvedTerminal::default();
+    let Some(features) = features else {
+        return Ok(resolved);
+    };
+    if let Some(term) = features.term.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        validate_term(term)?;
+        resolved.term = term.to_string();
+    }
+    if let Some(colors) = features.colors {
+        resolved.colors = colors;
+    }
+    if let Some(kitty) = features.kitty_keyboard {
+        resolved.kitty_keyboard = kitty;
+    }
+    Ok(resolved)
+}
+
+/// Set `TERM`/`COLORTERM` on the session command, replacing anything inherited
+/// from the app's own environment.
+pub(crate) fn apply(terminal: &ResolvedTerminal, cmd: &mut CommandBuilder) {
+    cmd.env("TERM", &terminal.term);
+    match terminal.colors {
+        ColorDepth::Truecolor => cmd.env("COLORTERM", "truecolor"),
+        ColorDepth::Ansi256 => cmd.env_remove("COLORTERM"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve, ColorDepth, TerminalFeaturesV1};
+
+    #[test]
+    fn resolves_and_validates_overrides() {
+        assert_eq!(resolve(None).unwrap().term, "xterm-256color");
+
+        let legacy = TerminalFeaturesV1 {
+            term: Some(" xterm ".to_string()),
+            colors: Some(ColorDepth::Ansi256),
+            kitty_keyboard: Some(false),
+        };
+        let resolved = resolve(Some(&legacy)).unwrap();
+        assert_eq!(
+            (resolved.term.as_str(), resolved.colors, resolved.kitty_keyboard),
+            ("xterm", ColorDepth::Ansi256, false)
+        );
+
+        for bad in ["../../bin/sh", "xterm 256", "-x", &"x".repeat(65)] {
+            let features = TerminalFeaturesV1 {
+                term: Some(bad.to_string()),
+                ..Default::default()
+            };
+            assert!(resolve(Some(&features)).is_err(), "{bad}");
+        }
+    }
+}
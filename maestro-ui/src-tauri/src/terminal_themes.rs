@@ -0,0 +1,649 @@
+use rand_core::{OsRng, RngCore};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::util::now_epoch_ms;
+
+const THEMES_FILE_NAME: &str = "terminal-themes-v1.json";
+pub(crate) const EVENT_TERMINAL_THEMES_CHANGED: &str = "terminal-themes-changed";
+const MAX_THEME_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_LABEL_CHARS: usize = 120;
+const MAX_IMPORTED_THEMES: usize = 200;
+
+/// The 16 ANSI colour names in palette order, as used by the terminal view.
+const ANSI_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "brightBlack",
+    "brightRed",
+    "brightGreen",
+    "brightYellow",
+    "brightBlue",
+    "brightMagenta",
+    "brightCyan",
+    "brightWhite",
+];
+
+/// Colours in the terminal view's theme shape. A missing value means "use the
+/// automatic one" (the background, for instance, follows the app light/dark mode).
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalColors {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreground: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor_accent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection_background: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection_foreground: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub black: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub red: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub green: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yellow: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub magenta: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cyan: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub white: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bright_black: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bright_red: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bright_green: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bright_yellow: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bright_blue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bright_magenta: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bright_cyan: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bright_white: Option<String>,
+}
+
+impl TerminalColors {
+    fn slot(&mut self, name: &str) -> Option<&mut Option<String>> {
+        Some(match name {
+            "background" => &mut self.background,
+            "foreground" => &mut self.foreground,
+            "cursor" => &mut self.cursor,
+            "cursorAccent" => &mut self.cursor_accent,
+            "selectionBackground" => &mut self.selection_background,
+            "selectionForeground" => &mut self.selection_foreground,
+            "black" => &mut self.black,
+            "red" => &mut self.red,
+            "green" => &mut self.green,
+            "yellow" => &mut self.yellow,
+            "blue" => &mut self.blue,
+            "magenta" => &mut self.magenta,
+            "cyan" => &mut self.cyan,
+            "white" => &mut self.white,
+            "brightBlack" => &mut self.bright_black,
+            "brightRed" => &mut self.bright_red,
+            "brightGreen" => &mut self.bright_green,
+            "brightYellow" => &mut self.bright_yellow,
+            "brightBlue" => &mut self.bright_blue,
+            "brightMagenta" => &mut self.bright_magenta,
+            "brightCyan" => &mut self.bright_cyan,
+            "brightWhite" => &mut self.bright_white,
+            _ => return None,
+        })
+    }
+
+    /// Set a colour from a theme file, ignoring values that aren't colours.
+    fn set(&mut self, name: &str, value: &str) {
+        if let (Some(slot), Some(color)) = (self.slot(name), normalize_hex(value)) {
+            *slot = Some(color);
+        }
+    }
+
+    fn missing_ansi(&mut self) -> Vec<&'static str> {
+        ANSI_NAMES
+            .iter()
+            .copied()
+            .filter(|name| self.slot(name).is_some_and(|slot| slot.is_none()))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalThemeV1 {
+    pub id: String,
+    pub label: String,
+    pub colors: TerminalColors,
+    /// `iterm2`, `alacritty` or `vscode`.
+    pub imported_from: String,
+    pub imported_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct TerminalThemesV1 {
+    #[serde(default)]
+    themes: Vec<TerminalThemeV1>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalTheme {
+    pub id: String,
+    pub label: String,
+    pub builtin: bool,
+    pub colors: TerminalColors,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalThemeSummary {
+    pub id: String,
+    pub label: String,
+    pub builtin: bool,
+    /// For swatches in the theme picker.
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+}
+
+/// Built-in presets, the same palettes the settings screen has always offered.
+fn builtin_themes() -> Vec<TerminalTheme> {
+    let preset = |id: &str, label: &str, pairs: &[(&str, &str)]| {
+        let mut colors = TerminalColors::default();
+        for (name, value) in pairs {
+            if let Some(slot) = colors.slot(name) {
+                *slot = Some(value.to_string());
+            }
+        }
+        TerminalTheme {
+            id: id.to_string(),
+            label: label.to_string(),
+            builtin: true,
+            colors,
+        }
+    };
+    vec![
+        preset(
+            "warm-atelier",
+            "Warm Atelier (Default)",
+            &[
+                ("foreground", "#D9D2C4"),
+                ("cursor", "#E0A45A"),
+                ("selectionBackground", "rgba(224,164,90,0.22)"),
+                ("selectionForeground", "#F3EEE2"),
+                ("black", "#322D24"),
+                ("red", "#CB7059"),
+                ("green", "#74B083"),
+                ("yellow", "#D2A24C"),
+                ("blue", "#6E9BC4"),
+                ("magenta", "#B98BC0"),
+                ("cyan", "#6FB2A8"),
+                ("white", "#CFC8BA"),
+                ("brightBlack", "#6B6453"),
+                ("brightRed", "#DC8B73"),
+                ("brightGreen", "#8FC79C"),
+                ("brightYellow", "#E6B968"),
+                ("brightBlue", "#88B0D6"),
+                ("brightMagenta", "#CCA0D2"),
+                ("brightCyan", "#86C4BA"),
+                ("brightWhite", "#EFE9DB"),
+            ],
+        ),
+        preset(
+            "classic-dark",
+            "Classic Dark",
+            &[
+                ("background", "#1A1A1A"),
+                ("foreground", "#F0F0F0"),
+                ("cursor", "#FFFFFF"),
+                ("cursorAccent", "#1A1A1A"),
+                ("selectionBackground", "rgba(255,255,255,0.2)"),
+                ("selectionForeground", "#FFFFFF"),
+                ("black", "#2E2E2E"),
+                ("red", "#E06C75"),
+                ("green", "#98C379"),
+                ("yellow", "#E5C07B"),
+                ("blue", "#61AFEF"),
+                ("magenta", "#C678DD"),
+                ("cyan", "#56B6C2"),
+                ("white", "#DCDFE4"),
+                ("brightBlack", "#636D83"),
+                ("brightRed", "#F16079"),
+                ("brightGreen", "#A8D89C"),
+                ("brightYellow", "#F0CC8A"),
+                ("brightBlue", "#74BFFF"),
+                ("brightMagenta", "#D48CF0"),
+                ("brightCyan", "#6CC7D2"),
+                ("brightWhite", "#FFFFFF"),
+            ],
+        ),
+        preset(
+            "light",
+            "Light Terminal",
+            &[
+                ("background", "#FAF6F0"),
+                ("foreground", "#2C2620"),
+                ("cursor", "#7A5C2A"),
+                ("cursorAccent", "#FAF6F0"),
+                ("selectionBackground", "rgba(122,92,42,0.18)"),
+                ("selectionForeground", "#2C2620"),
+                ("black", "#3C3530"),
+                ("red", "#8B2D20"),
+                ("green", "#2D6B3A"),
+                ("yellow", "#7A5C2A"),
+                ("blue", "#2A5080"),
+                ("magenta", "#6A3878"),
+                ("cyan", "#2A6060"),
+                ("white", "#BFBAB2"),
+                ("brightBlack", "#7A7268"),
+                ("brightRed", "#B04030"),
+                ("brightGreen", "#3A8A4A"),
+                ("brightYellow", "#9A7838"),
+                ("brightBlue", "#3A6A9A"),
+                ("brightMagenta", "#864898"),
+                ("brightCyan", "#3A7A7A"),
+                ("brightWhite", "#F5F0E8"),
+            ],
+        ),
+    ]
+}
+
+/// `#rgb`, `#rrggbb`, `#rrggbbaa` or Alacritty's `0xrrggbb`, as upper-case `#RRGGBB[AA]`.
+fn normalize_hex(value: &str) -> Option<String> {
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    let hex = value.strip_prefix('#').or_else(|| value.strip_prefix("0x"))?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        3 => Some(format!("#{}", hex.chars().flat_map(|c| [c, c]).collect::<String>()).to_uppercase()),
+        6 | 8 => Some(format!("#{}", hex.to_uppercase())),
+        _ => None,
+    }
+}
+
+/// iTerm2 `.itermcolors`: a plist of colour dicts with 0-1 float components.
+fn parse_iterm2(raw: &str) -> Result<TerminalColors, String> {
+    static ENTRY: OnceLock<Regex> = OnceLock::new();
+    static COMPONENT: OnceLock<Regex> = OnceLock::new();
+    let entry = ENTRY.get_or_init(|| Regex::new(r"(?s)<key>([^<]+)</key>\s*<dict>(.*?)</dict>").unwrap());
+    let component = COMPONENT.get_or_init(|| {
+        Regex::new(r"<key>(Red|Green|Blue) Component</key>\s*<(?:real|integer)>([^<]+)<").unwrap()
+    });
+
+    let mut colors = TerminalColors::default();
+    for caps in entry.captures_iter(raw) {
+        let key = caps[1].trim();
+        let name = match key {
+            "Foreground Color" => "foreground".to_string(),
+            "Background Color" => "background".to_string(),
+            "Cursor Color" => "cursor".to_string(),
+            "Cursor Text Color" => "cursorAccent".to_string(),
+            "Selection Color" => "selectionBackground".to_string(),
+            "Selected Text Color" => "selectionForeground".to_string(),
+            _ => match key
+                .strip_prefix("Ansi ")
+                .and_then(|k| k.strip_suffix(" Color"))
+                .and_then(|n| n.parse::<usize>().ok())
+                .and_then(|n| ANSI_NAMES.get(n))
+            {
+                Some(name) => name.to_string(),
+                None => continue,
+            },
+        };
+        let mut rgb = [None; 3];
+        for comp in component.captures_iter(&caps[2]) {
+            let index = match &comp[1] {
+                "Red" => 0,
+                "Green" => 1,
+                _ => 2,
+            };
+            rgb[index] = comp[2].trim().parse::<f64>().ok();
+        }
+        if let [Some(r), Some(g), Some(b)] = rgb {
+            let byte = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+            colors.set(&name, &format!("#{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b)));
+        }
+    }
+    Ok(colors)
+}
+
+/// Alacritty themes, TOML (`[colors.normal]` / `black = '#...'`) or the older YAML
+/// (`colors:` / `  normal:` / `    black: '0x...'`). Only the colour tables are read.
+fn parse_alacritty(raw: &str) -> Result<TerminalColors, String> {
+    let mut values: HashMap<String, String> = HashMap::new();
+    let mut section = String::new();
+    let mut yaml_path: Vec<(usize, String)> = Vec::new();
+    for line in raw.lines() {
+        let trimmed = line.split(" #").next().unwrap_or_default().trim_end();
+        let content = trimmed.trim_start();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = content.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+            section = header.trim().to_string();
+            continue;
+        }
+        if let Some((key, value)) = content.split_once('=') {
+            values.insert(format!("{section}.{}", key.trim()), value.trim().to_string());
+            continue;
+        }
+        let Some((key, value)) = content.split_once(':') else {
+            continue;
+        };
+        let indent = trimmed.len() - content.len();
+        while yaml_path.last().is_some_and(|(i, _)| *i >= indent) {
+            yaml_path.pop();
+        }
+        let value = value.trim();
+        if value.is_empty() {
+            yaml_path.push((indent, key.trim().to_string()));
+        } else {
+            let mut path: Vec<&str> = yaml_path.iter().map(|(_, k)| k.as_str()).collect();
+            path.push(key.trim());
+            values.insert(path.join("."), value.to_string());
+        }
+    }
+
+    let mut colors = TerminalColors::default();
+    for (path, value) in &values {
+        let path = path.strip_prefix("colors.").unwrap_or(path);
+        let name = match path.split_once('.') {
+            Some(("primary", "background")) => "background".to_string(),
+            Some(("primary", "foreground")) => "foreground".to_string(),
+            Some(("cursor", "cursor")) => "cursor".to_string(),
+            Some(("cursor", "text")) => "cursorAccent".to_string(),
+            Some(("selection", "background")) => "selectionBackground".to_string(),
+            Some(("selection", "text")) => "selectionForeground".to_string(),
+            Some(("normal", color)) => color.to_string(),
+            Some(("bright", color)) => {
+                let mut chars = color.chars();
+                match chars.next() {
+                    Some(first) => format!("bright{}{}", first.to_ascii_uppercase(), chars.as_str()),
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+        colors.set(&name, value);
+    }
+    Ok(colors)
+}
+
+/// VS Code colour themes (`colors`) or settings (`workbench.colorCustomizations`),
+/// using their `terminal.*` entries. Whole-line `//` comments are allowed.
+fn parse_vscode(raw: &str) -> Result<(TerminalColors, Option<String>), String> {
+    let cleaned: String = raw
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let json: serde_json::Value = serde_json::from_str(&cleaned).map_err(|e| format!("parse failed: {e}"))?;
+    let entries = json
+        .get("colors")
+        .or_else(|| json.get("workbench.colorCustomizations"))
+        .and_then(|c| c.as_object())
+        .ok_or("no colors found in theme")?;
+
+    let mut colors = TerminalColors::default();
+    for (key, value) in entries {
+        let Some(value) = value.as_str() else {
+            continue;
+        };
+        let name = match key.as_str() {
+            "terminal.background" => "background".to_string(),
+            "terminal.foreground" => "foreground".to_string(),
+            "terminalCursor.foreground" => "cursor".to_string(),
+            "terminalCursor.background" => "cursorAccent".to_string(),
+            "terminal.selectionBackground" => "selectionBackground".to_string(),
+            "terminal.selectionForeground" => "selectionForeground".to_string(),
+            other => match other.strip_prefix("terminal.ansi") {
+                Some(color) => {
+                    let mut chars = color.chars();
+                    match chars.next() {
+                        Some(first) => format!("{}{}", first.to_ascii_lowercase(), chars.as_str()),
+                        None => continue,
+                    }
+                }
+                None => continue,
+            },
+        };
+        colors.set(&name, value);
+    }
+    let name = json.get("name").and_then(|n| n.as_str()).map(str::to_string);
+    Ok((colors, name))
+}
+
+/// Colours, source format and the name the file gives the theme, if any.
+type ParsedTheme = (TerminalColors, &'static str, Option<String>);
+
+/// Parse a theme file by extension, falling back to sniffing the contents.
+fn parse_theme_file(path: &Path, raw: &str) -> Result<ParsedTheme, String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let start = raw.trim_start();
+    let format = match ext.as_str() {
+        "itermcolors" => "iterm2",
+        "json" | "jsonc" => "vscode",
+        "toml" | "yml" | "yaml" => "alacritty",
+        _ if start.starts_with("<?xml") || start.starts_with("<plist") => "iterm2",
+        _ if start.starts_with('{') => "vscode",
+        _ => "alacritty",
+    };
+    let (mut colors, name) = match format {
+        "iterm2" => (parse_iterm2(raw)?, None),
+        "vscode" => parse_vscode(raw)?,
+        _ => (parse_alacritty(raw)?, None),
+    };
+    let missing = colors.missing_ansi();
+    if !missing.is_empty() {
+        return Err(format!("theme is missing colors: {}", missing.join(", ")));
+    }
+    Ok((colors, format, name))
+}
+
+fn new_theme_id() -> String {
+    let mut bytes = [0u8; 8];
+    OsRng.fill_bytes(&mut bytes);
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("theme-{hex}")
+}
+
+fn themes_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, THEMES_FILE_NAME)
+}
+
+fn read_themes(app: &AppHandle) -> Result<Vec<TerminalThemeV1>, String> {
+    let path = themes_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str::<TerminalThemesV1>(&raw)
+            .map(|t| t.themes)
+            .map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn write_themes(app: &AppHandle, themes: Vec<TerminalThemeV1>) -> Result<(), String> {
+    let path = themes_file_path(app)?;
+    crate::util::write_json_atomic(&path, &TerminalThemesV1 { themes })?;
+    // Every window's theme picker reads the same list.
+    let _ = app.emit(EVENT_TERMINAL_THEMES_CHANGED, ());
+    Ok(())
+}
+
+fn imported_theme(theme: TerminalThemeV1) -> TerminalTheme {
+    TerminalTheme {
+        id: theme.id,
+        label: theme.label,
+        builtin: false,
+        colors: theme.colors,
+    }
+}
+
+/// Built-in presets first, then imported themes in the order they were added.
+#[tauri::command]
+pub fn list_terminal_themes(window: WebviewWindow) -> Result<Vec<TerminalThemeSummary>, String> {
+    let imported = read_themes(window.app_handle())?.into_iter().map(imported_theme);
+    Ok(builtin_themes()
+        .into_iter()
+        .chain(imported)
+        .map(|theme| TerminalThemeSummary {
+            id: theme.id,
+            label: theme.label,
+            builtin: theme.builtin,
+            background: theme.colors.background,
+            foreground: theme.colors.foreground,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn get_terminal_theme(window: WebviewWindow, id: String) -> Result<TerminalTheme, String> {
+    if let Some(theme) = builtin_themes().into_iter().find(|t| t.id == id) {
+        return Ok(theme);
+    }
+    read_themes(window.app_handle())?
+        .into_iter()
+        .find(|t| t.id == id)
+        .map(imported_theme)
+        .ok_or_else(|| format!("unknown theme: {id}"))
+}
+
+/// Import an iTerm2 (`.itermcolors`), Alacritty (`.toml`/`.yml`) or VS Code
+/// (`.json`) theme. All 16 ANSI colours are required; the label defaults to the
+/// theme's name or the file name.
+#[tauri::command]
+pub fn import_terminal_theme(
+    window: WebviewWindow,
+    path: String,
+    label: Option<String>,
+) -> Result<TerminalTheme, String> {
+    let path = PathBuf::from(path.trim());
+    let meta = fs::metadata(&path).map_err(|e| format!("read failed: {e}"))?;
+    if meta.len() > MAX_THEME_FILE_BYTES {
+        return Err("theme file is too large".to_string());
+    }
+    let raw = fs::read_to_string(&path).map_err(|e| format!("read failed: {e}"))?;
+    let (colors, format, name) = parse_theme_file(&path, &raw)?;
+
+    let label = label
+        .or(name)
+        .or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| "Imported theme".to_string());
+    if label.chars().count() > MAX_LABEL_CHARS {
+        return Err(format!("label is longer than {MAX_LABEL_CHARS} characters"));
+    }
+
+    let app = window.app_handle();
+    let mut themes = read_themes(app)?;
+    if themes.len() >= MAX_IMPORTED_THEMES {
+        return Err(format!("at most {MAX_IMPORTED_THEMES} themes can be imported"));
+    }
+    let theme = TerminalThemeV1 {
+        id: new_theme_id(),
+        label,
+        colors,
+        imported_from: format.to_string(),
+        imported_at: now_epoch_ms(),
+    };
+    themes.push(theme.clone());
+    write_themes(app, themes)?;
+    Ok(imported_theme(theme))
+}
+
+#[tauri::command]
+pub fn delete_terminal_theme(window: WebviewWindow, id: String) -> Result<(), String> {
+    let app = window.app_handle();
+    let mut themes = read_themes(app)?;
+    let before = themes.len();
+    themes.retain(|t| t.id != id);
+    if themes.len() == before {
+        return Err(format!("unknown theme: {id}"));
+    }
+    write_themes(app, themes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_alacritty, parse_iterm2, parse_theme_file, parse_vscode, ANSI_NAMES};
+    use std::path::Path;
+
+    #[test]
+    fn parses_theme_formats() {
+        let iterm = r#"<plist><dict>
+            <key>Ansi 1 Color</key>
+            <dict>
+                <key>Blue Component</key><real>0.0</real>
+                <key>Green Component</key><real>0.5</real>
+                <key>Red Component</key><real>1</real>
+            </dict>
+            <key>Background Color</key>
+            <dict><key>Red Component</key><real>0</real><key>Green Component</key><real>0</real>
+            <key>Blue Component</key><real>0</real></dict>
+        </dict></plist>"#;
+        let colors = parse_iterm2(iterm).unwrap();
+        assert_eq!(colors.red.as_deref(), Some("#FF8000"));
+        assert_eq!(colors.background.as_deref(), Some("#000000"));
+
+        let toml = "[colors.primary]\nbackground = '#1d1f21'\n\n[colors.bright]\nblack = \"0x666666\" # grey";
+        let colors = parse_alacritty(toml).unwrap();
+        assert_eq!(colors.background.as_deref(), Some("#1D1F21"));
+        assert_eq!(colors.bright_black.as_deref(), Some("#666666"));
+
+        let yaml = "colors:\n  primary:\n    foreground: '#c5c8c6'\n  normal:\n    red: '#cc6666'\n";
+        let colors = parse_alacritty(yaml).unwrap();
+        assert_eq!(colors.foreground.as_deref(), Some("#C5C8C6"));
+        assert_eq!(colors.red.as_deref(), Some("#CC6666"));
+
+        let vscode = "{\n// comment\n\"name\": \"Night\",\n\"colors\": {\"terminal.ansiBrightBlue\": \"#abc\"}}";
+        let (colors, name) = parse_vscode(vscode).unwrap();
+        assert_eq!(colors.bright_blue.as_deref(), Some("#AABBCC"));
+        assert_eq!(name.as_deref(), Some("Night"));
+    }
+
+    #[test]
+    fn rejects_themes_without_a_full_palette() {
+        let mut toml = String::from("[colors.normal]\n");
+        for name in &ANSI_NAMES[..8] {
+            toml.push_str(&format!("{name} = '#101010'\n"));
+        }
+        let err = parse_theme_file(Path::new("half.toml"), &toml).unwrap_err();
+        assert!(err.contains("brightBlack"), "{err}");
+
+        toml.push_str("[colors.bright]\n");
+        for name in &ANSI_NAMES[..8] {
+            toml.push_str(&format!("{name} = '#202020'\n"));
+        }
+        let (colors, format, _) = parse_theme_file(Path::new("full.toml"), &toml).unwrap();
+        assert_eq!((format, colors.bright_white.as_deref()), ("alacritty", Some("#202020")));
+    }
+}
@@ -0,0 +1,210 @@
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub(crate) const EVENT_AGENT_THROTTLED: &str = "agent-throttled";
+
+/// Don't re-announce the same session more often than this; CLIs tend to
+/// repeat the error on every retry.
+const REPEAT_SUPPRESS: Duration = Duration::from_secs(30);
+const TAIL_CHARS: usize = 512;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentProvider {
+    Claude,
+    Codex,
+    Gemini,
+    Unknown,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentThrottled {
+    pub id: String,
+    pub provider: AgentProvider,
+    /// The output line that matched, with escape codes removed.
+    pub message: String,
+    pub retry_after_secs: Option<u64>,
+    pub detected_at: u64,
+}
+
+struct Pattern {
+    provider: AgentProvider,
+    re: Regex,
+}
+
+fn patterns() -> &'static Vec<Pattern> {
+    static PATTERNS: OnceLock<Vec<Pattern>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (AgentProvider::Claude, r"(?i)claude ai usage limit reached"),
+            (AgentProvider::Claude, r"(?i)\b\d+-hour limit reached"),
+            (AgentProvider::Claude, r"(?i)rate_limit_error|overloaded_error"),
+            (AgentProvider::Codex, r"(?i)you've hit your usage limit"),
+            (AgentProvider::Codex, r"(?i)exceeded retry limit, last status: 429"),
+            (AgentProvider::Gemini, r"RESOURCE_EXHAUSTED"),
+            (AgentProvider::Unknown, r"(?i)rate limit (reached|exceeded)"),
+            (AgentProvider::Unknown, r"(?i)quota exceeded"),
+            (AgentProvider::Unknown, r"(?i)\b429\b.{0,40}too many requests|too many requests.{0,40}\b429\b"),
+            (AgentProvider::Unknown, r"(?i)api error: 429"),
+        ]
+        .into_iter()
+        .filter_map(|(provider, pattern)| Regex::new(pattern).ok().map(|re| Pattern { provider, re }))
+        .collect()
+    })
+}
+
+fn ansi_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\x1b\[[0-9;?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(\x07|\x1b\\)|\x1b[@-Z\\-_]")
+            .expect("valid ansi regex")
+    })
+}
+
+/// Drop terminal escape sequences (CSI, OSC, single-char escapes) from output.
+pub(crate) fn strip_ansi(data: &str) -> std::borrow::Cow<'_, str> {
+    ansi_re().replace_all(data, "")
+}
+
+fn now_epoch_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Seconds to wait, from "try again in 20s", "retry after 2m", "Retry-After: 30"
+/// or Claude's `usage limit reached|<epoch seconds>` suffix.
+fn parse_retry_after(text: &str) -> Option<u64> {
+    static RELATIVE: OnceLock<Regex> = OnceLock::new();
+    static EPOCH: OnceLock<Regex> = OnceLock::new();
+    let relative = RELATIVE.get_or_init(|| {
+        Regex::new(
+            r"(?i)(?:try again in|retry in|retry after|retry-after:?)\s*(\d+(?:\.\d+)?)\s*(ms|milliseconds?|s|sec|seconds?|m|min|minutes?|h|hours?)?\b",
+        )
+        .expect("valid retry regex")
+    });
+    let epoch = EPOCH.get_or_init(|| Regex::new(r"limit reached\|(\d{10})").expect("valid epoch regex"));
+
+    if let Some(c) = epoch.captures(text) {
+        let at: u64 = c[1].parse().ok()?;
+        return Some(at.saturating_sub(now_epoch_secs()));
+    }
+    let c = relative.captures(text)?;
+    let value: f64 = c[1].parse().ok()?;
+    let unit = c.get(2).map(|m| m.as_str().to_ascii_lowercase()).unwrap_or_default();
+    let secs = if unit.starts_with("ms") || unit.starts_with("milli") {
+        value / 1000.0
+    } else if unit.starts_with('h') {
+        value * 3600.0
+    } else if unit.starts_with('m') {
+        value * 60.0
+    } else {
+        value
+    };
+    Some(secs.ceil() as u64)
+}
+
+fn provider_from_command(command: &str) -> AgentProvider {
+    let lower = command.to_ascii_lowercase();
+    if lower.contains("claude") {
+        AgentProvider::Claude
+    } else if lower.contains("codex") {
+        AgentProvider::Codex
+    } else if lower.contains("gemini") {
+        AgentProvider::Gemini
+    } else {
+        AgentProvider::Unknown
+    }
+}
+
+/// Watches one session's output stream for rate-limit / quota errors.
+pub(crate) struct ThrottleDetector {
+    id: String,
+    provider: AgentProvider,
+    tail: String,
+    last_emit: Option<Instant>,
+}
+
+impl ThrottleDetector {
+    pub(crate) fn new(id: String, command: &str) -> Self {
+        Self {
+            id,
+            provider: provider_from_command(command),
+            tail: String::new(),
+            last_emit: None,
+        }
+    }
+
+    /// Feed a chunk of pty output; returns an event when a throttle message
+    /// completes a line.
+    pub(crate) fn feed(&mut self, data: &str) -> Option<AgentThrottled> {
+        self.tail.push_str(&strip_ansi(data));
+        let mut found: Option<AgentThrottled> = None;
+        while let Some(pos) = self.tail.find(['\n', '\r']) {
+            let line: String = self.tail.drain(..=pos).collect();
+            if found.is_none() {
+                found = self.check_line(line.trim());
+            }
+        }
+        if self.tail.chars().count() > TAIL_CHARS {
+            let skip = self.tail.chars().count() - TAIL_CHARS;
+            self.tail = self.tail.chars().skip(skip).collect();
+        }
+
+        let event = found?;
+        if matches!(self.last_emit, Some(at) if at.elapsed() < REPEAT_SUPPRESS) {
+            return None;
+        }
+        self.last_emit = Some(Instant::now());
+        Some(event)
+    }
+
+    fn check_line(&self, line: &str) -> Option<AgentThrottled> {
+        if line.is_empty() {
+            return None;
+        }
+        let pattern = patterns().iter().find(|p| p.re.is_match(line))?;
+        let provider = if self.provider != AgentProvider::Unknown {
+            self.provider
+        } else {
+            pattern.provider
+        };
+        Some(AgentThrottled {
+            id: self.id.clone(),
+            provider,
+            message: line.chars().take(300).collect(),
+            retry_after_secs: parse_retry_after(line),
+            detected_at: now_epoch_secs() * 1000,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_retry_after, AgentProvider, ThrottleDetector};
+
+    #[test]
+    fn detects_rate_limit_across_chunks_with_retry_after() {
+        let mut d = ThrottleDetector::new("1".to_string(), "/bin/bash -c codex");
+        assert!(d.feed("\x1b[31mRate limit reached for gpt-5. Please try ").is_none());
+        let event = d.feed("again in 20s.\r\n").expect("throttle event");
+        assert_eq!(event.provider, AgentProvider::Codex);
+        assert_eq!(event.retry_after_secs, Some(20));
+        assert!(!event.message.contains('\x1b'));
+
+        // Repeats are suppressed.
+        assert!(d.feed("Rate limit reached. Please try again in 20s.\n").is_none());
+    }
+
+    #[test]
+    fn ignores_normal_output_and_parses_units() {
+        let mut d = ThrottleDetector::new("1".to_string(), "claude");
+        assert!(d.feed("Compiling 429 crates\n").is_none());
+        assert_eq!(parse_retry_after("retry after 2m"), Some(120));
+        assert_eq!(parse_retry_after("Retry-After: 1500ms"), Some(2));
+    }
+}
@@ -0,0 +1,23 @@
+/// Persistent session backends `create_session` accepts as `backend`.
+const BACKENDS: &[&str] = &["tmux", "zellij"];
+
+pub(crate) fn validate_backend(backend: &str) -> Result<(), String> {
+    if BACKENDS.contains(&backend) {
+        return Ok(());
+    }
+    Err(format!("unknown persistence backend: {backend}"))
+}
+
+/// The shell command that attaches to `session` on the default tmux server, creating
+/// it to run `command` (or the default shell) if it isn't running. A running session
+/// keeps whatever it was started with. Closing the Maestro session only detaches.
+pub(crate) fn attach_command(session: &str, command: Option<&str>) -> Result<String, String> {
+    if crate::ssh_fs::find_program_in_path("tmux").is_none() {
+        return Err("tmux is not installed".to_string());
+    }
+    let session = crate::ssh_fs::shell_escape_posix(session);
+    Ok(match command.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(command) => format!("exec tmux new-session -A -s {session} {}", crate::ssh_fs::shell_escape_posix(command)),
+        None => format!("exec tmux new-session -A -s {session}"),
+    })
+}
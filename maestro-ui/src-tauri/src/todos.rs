@@ -0,0 +1,286 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+const MAX_SCANNED_FILES: usize = 20_000;
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const BINARY_CHECK_BYTES: usize = 8 * 1024;
+const DEFAULT_MAX_RESULTS: usize = 500;
+/// Blame is one `git blame` per file, so only the first files with markers get it.
+const MAX_BLAMED_FILES: usize = 200;
+const MAX_TEXT_CHARS: usize = 300;
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "coverage"];
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoEntry {
+    /// Relative to the scanned root.
+    pub path: String,
+    /// 1-based.
+    pub line: usize,
+    /// `TODO`, `FIXME` or `HACK`.
+    pub marker: String,
+    /// Text after the marker.
+    pub text: String,
+    /// Owner or ticket in parentheses, as in `TODO(alice): ...`.
+    pub tag: Option<String>,
+    /// Who last changed the line, from git blame; `None` outside git or for
+    /// uncommitted lines.
+    pub author: Option<String>,
+    pub authored_at: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoScan {
+    pub entries: Vec<TodoEntry>,
+    pub files_scanned: usize,
+    /// Stopped at the result or file limit.
+    pub truncated: bool,
+    /// Files came from git, so .gitignore was honoured.
+    pub git: bool,
+}
+
+fn marker_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b(TODO|FIXME|HACK)\b(?:\(([^)]*)\))?:?\s*(.*)").expect("valid todo regex"))
+}
+
+/// Markers on one line of source; the first marker wins.
+fn parse_marker(line: &str) -> Option<(String, Option<String>, String)> {
+    let caps = marker_regex().captures(line)?;
+    let tag = caps.get(2).map(|m| m.as_str().trim().to_string()).filter(|t| !t.is_empty());
+    let text: String = caps[3]
+        .trim()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim()
+        .chars()
+        .take(MAX_TEXT_CHARS)
+        .collect();
+    Some((caps[1].to_string(), tag, text))
+}
+
+/// Tracked and untracked-but-not-ignored files, when `root` is in a git work tree.
+fn git_files(root: &Path) -> Option<Vec<String>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["ls-files", "-z", "--cached", "--others", "--exclude-standard"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let mut files: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|f| !f.is_empty())
+        .map(str::to_string)
+        .collect();
+    files.dedup();
+    Some(files)
+}
+
+/// Outside git: walk the tree, skipping hidden and build directories like
+/// `list_project_files`.
+fn walk_files(root: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            if files.len() >= MAX_SCANNED_FILES {
+                return files;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if let Ok(rel) = path.strip_prefix(root) {
+                files.push(rel.to_string_lossy().to_string());
+            }
+        }
+    }
+    files
+}
+
+fn scan_file(root: &Path, rel: &str, out: &mut Vec<TodoEntry>, max_results: usize) {
+    let path = root.join(rel);
+    if fs::metadata(&path).map(|m| !m.is_file() || m.len() > MAX_FILE_BYTES).unwrap_or(true) {
+        return;
+    }
+    let Ok(bytes) = fs::read(&path) else {
+        return;
+    };
+    if bytes[..bytes.len().min(BINARY_CHECK_BYTES)].contains(&0) {
+        return;
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    for (i, line) in text.lines().enumerate() {
+        if out.len() >= max_results {
+            return;
+        }
+        if let Some((marker, tag, text)) = parse_marker(line) {
+            out.push(TodoEntry {
+                path: rel.to_string(),
+                line: i + 1,
+                marker,
+                text,
+                tag,
+                author: None,
+                authored_at: None,
+            });
+        }
+    }
+}
+
+/// Author and time (epoch ms) per final line number from `git blame --porcelain`.
+/// Commit details appear only on a commit's first hunk, so they're remembered by sha.
+fn parse_blame(porcelain: &str) -> HashMap<usize, (String, u64)> {
+    let mut commits: HashMap<String, (Option<String>, Option<u64>)> = HashMap::new();
+    let mut line_commits: Vec<(usize, String)> = Vec::new();
+    let mut current: Option<String> = None;
+    for line in porcelain.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        let mut parts = line.split(' ');
+        let first = parts.next().unwrap_or_default();
+        if first.len() == 40 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+            if let Some(final_line) = parts.nth(1).and_then(|n| n.parse::<usize>().ok()) {
+                line_commits.push((final_line, first.to_string()));
+                commits.entry(first.to_string()).or_insert((None, None));
+                current = Some(first.to_string());
+            }
+            continue;
+        }
+        let Some(sha) = &current else {
+            continue;
+        };
+        let entry = commits.entry(sha.clone()).or_insert((None, None));
+        if let Some(author) = line.strip_prefix("author ") {
+            entry.0 = Some(author.to_string());
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            entry.1 = time.trim().parse::<u64>().ok().map(|t| t * 1000);
+        }
+    }
+
+    line_commits
+        .into_iter()
+        .filter(|(_, sha)| !sha.chars().all(|c| c == '0'))
+        .filter_map(|(line, sha)| match commits.get(&sha) {
+            Some((Some(author), Some(at))) => Some((line, (author.clone(), *at))),
+            _ => None,
+        })
+        .collect()
+}
+
+fn blame_entries(root: &Path, entries: &mut [TodoEntry]) {
+    let mut by_file: Vec<(String, Vec<usize>)> = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        match by_file.iter_mut().find(|(path, _)| *path == entry.path) {
+            Some((_, indexes)) => indexes.push(i),
+            None => by_file.push((entry.path.clone(), vec![i])),
+        }
+    }
+    for (path, indexes) in by_file.into_iter().take(MAX_BLAMED_FILES) {
+        let Ok(output) = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(["blame", "--porcelain", "--", &path])
+            .output()
+        else {
+            return;
+        };
+        if !output.status.success() {
+            continue;
+        }
+        let lines = parse_blame(&String::from_utf8_lossy(&output.stdout));
+        for i in indexes {
+            if let Some((author, at)) = lines.get(&entries[i].line) {
+                entries[i].author = Some(author.clone());
+                entries[i].authored_at = Some(*at);
+            }
+        }
+    }
+}
+
+fn scan(root: &Path, max_results: usize, blame: bool) -> TodoScan {
+    let (files, git) = match git_files(root) {
+        Some(files) => (files, true),
+        None => (walk_files(root), false),
+    };
+    let mut truncated = files.len() > MAX_SCANNED_FILES;
+    let mut entries = Vec::new();
+    let mut files_scanned = 0;
+    for rel in files.iter().take(MAX_SCANNED_FILES) {
+        if entries.len() >= max_results {
+            truncated = true;
+            break;
+        }
+        scan_file(root, rel, &mut entries, max_results);
+        files_scanned += 1;
+    }
+    if git && blame {
+        blame_entries(root, &mut entries);
+    }
+    TodoScan {
+        entries,
+        files_scanned,
+        truncated,
+        git,
+    }
+}
+
+/// TODO/FIXME/HACK comments under `root`, in file order. Inside a git work tree the
+/// file list comes from git (so ignored files are skipped) and each entry carries
+/// the line's blame author unless `blame` is false. At most `max_results` entries
+/// (default 500) from 20,000 files; files over 1 MiB and binaries are skipped.
+#[tauri::command]
+pub async fn scan_todos(root: String, max_results: Option<usize>, blame: Option<bool>) -> Result<TodoScan, String> {
+    let root = PathBuf::from(root.trim());
+    if !root.is_absolute() || !root.is_dir() {
+        return Err("root must be an absolute directory".to_string());
+    }
+    let max_results = max_results.unwrap_or(DEFAULT_MAX_RESULTS).clamp(1, 5000);
+    tauri::async_runtime::spawn_blocking(move || scan(&root, max_results, blame.unwrap_or(true)))
+        .await
+        .map_err(|e| format!("scan todos task join failed: {e:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_blame, parse_marker};
+
+    #[test]
+    fn parses_markers_and_blame() {
+        assert_eq!(
+            parse_marker("    // TODO(alice): handle retries */"),
+            Some(("TODO".to_string(), Some("alice".to_string()), "handle retries".to_string()))
+        );
+        assert_eq!(
+            parse_marker("# FIXME leaks on error"),
+            Some(("FIXME".to_string(), None, "leaks on error".to_string()))
+        );
+        assert_eq!(parse_marker("let todos = TODOS;"), None);
+
+        let sha_a = "a".repeat(40);
+        let sha_b = "b".repeat(40);
+        let zero = "0".repeat(40);
+        let porcelain = format!(
+            "{sha_a} 1 1 2\nauthor Alice\nauthor-time 1700000000\nsummary x\n\tline1\n{sha_a} 2 2\n\tline2\n\
+             {sha_b} 5 3 1\nauthor Bob\nauthor-time 1700000100\n\tline3\n\
+             {zero} 4 4 1\nauthor Not Committed Yet\nauthor-time 1700000200\n\tline4\n"
+        );
+        let lines = parse_blame(&porcelain);
+        assert_eq!(lines[&2], ("Alice".to_string(), 1_700_000_000_000));
+        assert_eq!(lines[&3].0, "Bob");
+        assert!(!lines.contains_key(&4));
+    }
+}
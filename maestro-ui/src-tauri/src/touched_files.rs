@@ -0,0 +1,301 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::State;
+
+use crate::pty::AppState;
+
+/// Tools whose file argument means the file was changed, not just looked at.
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit", "apply_patch"];
+/// Lines in a Codex `apply_patch` body that name a file.
+const PATCH_HEADERS: &[&str] = &["*** Add File: ", "*** Update File: ", "*** Delete File: ", "*** Move to: "];
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TouchedFile {
+    pub path: String,
+    /// Tools that used the file, in first-use order.
+    pub tools: Vec<String>,
+    /// Changed by at least one tool call (rather than only read).
+    pub edited: bool,
+    pub touches: u32,
+    /// Log timestamp of the latest tool call on the file.
+    pub last_touched_at: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTouchedFiles {
+    pub id: String,
+    pub cwd: Option<String>,
+    /// Most recently touched first.
+    pub files: Vec<TouchedFile>,
+    /// Agent logs the files were read from.
+    pub logs: Vec<String>,
+}
+
+/// Touches collected from one log so far; later calls only read what was appended.
+struct LogScan {
+    offset: u64,
+    files: HashMap<String, TouchedFile>,
+}
+
+fn scans() -> &'static Mutex<HashMap<PathBuf, LogScan>> {
+    static SCANS: OnceLock<Mutex<HashMap<PathBuf, LogScan>>> = OnceLock::new();
+    SCANS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn patch_paths(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            PATCH_HEADERS
+                .iter()
+                .find_map(|h| line.strip_prefix(h))
+                .map(|p| p.trim().to_string())
+        })
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// (tool, path, timestamp) for each file a Claude or Codex log line's tool calls used.
+fn touches_from_line(line: &str) -> Vec<(String, String, Option<String>)> {
+    let Ok(entry) = serde_json::from_str::<Value>(line) else {
+        return Vec::new();
+    };
+    let timestamp = entry.get("timestamp").and_then(|v| v.as_str()).map(str::to_string);
+    let mut out = Vec::new();
+
+    // Claude: assistant messages carry tool_use blocks.
+    if entry.get("type").and_then(|v| v.as_str()) == Some("assistant") {
+        let blocks = entry.pointer("/message/content").and_then(|v| v.as_array());
+        for block in blocks.into_iter().flatten() {
+            if block.get("type").and_then(|v| v.as_str()) != Some("tool_use") {
+                continue;
+            }
+            let tool = block.get("name").and_then(|v| v.as_str()).unwrap_or("tool");
+            let input = block.get("input");
+            let path = ["file_path", "notebook_path"]
+                .iter()
+                .find_map(|k| input.and_then(|i| i.get(*k)).and_then(|v| v.as_str()));
+            if let Some(path) = path {
+                out.push((tool.to_string(), path.to_string(), timestamp.clone()));
+            }
+        }
+        return out;
+    }
+
+    // Codex: patches arrive as function or custom tool calls, possibly wrapped in a
+    // `response_item`.
+    let item = match entry.get("type").and_then(|v| v.as_str()) {
+        Some("response_item") => entry.get("payload").unwrap_or(&Value::Null),
+        _ => &entry,
+    };
+    if !matches!(
+        item.get("type").and_then(|v| v.as_str()),
+        Some("function_call") | Some("custom_tool_call")
+    ) {
+        return out;
+    }
+    let text = [item.get("input"), item.get("arguments")]
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    // Patches passed inside JSON arguments keep their newlines escaped.
+    let text = text.replace("\\n", "\n");
+    for path in patch_paths(&text) {
+        out.push(("apply_patch".to_string(), path, timestamp.clone()));
+    }
+    out
+}
+
+fn record_touch(files: &mut HashMap<String, TouchedFile>, base: &Path, tool: String, path: &str, at: Option<String>) {
+    let path = base.join(path).to_string_lossy().to_string();
+    let file = files.entry(path.clone()).or_insert_with(|| TouchedFile {
+        path,
+        tools: Vec::new(),
+        edited: false,
+        touches: 0,
+        last_touched_at: None,
+    });
+    file.edited |= EDIT_TOOLS.contains(&tool.as_str());
+    if !file.tools.contains(&tool) {
+        file.tools.push(tool);
+    }
+    file.touches += 1;
+    if at.is_some() && at >= file.last_touched_at {
+        file.last_touched_at = at;
+    }
+}
+
+/// Bring the cached scan of `log` up to date, reading complete lines past the last
+/// offset. Relative paths are resolved against `base` (the agent's cwd).
+fn scan_log(log: &Path, base: &Path) -> Result<HashMap<String, TouchedFile>, String> {
+    let mut scans = scans().lock().map_err(|_| "touched files cache poisoned")?;
+    let len = fs::metadata(log).map_err(|e| format!("metadata failed: {e}"))?.len();
+    let scan = scans.entry(log.to_path_buf()).or_insert_with(|| LogScan {
+        offset: 0,
+        files: HashMap::new(),
+    });
+    if len < scan.offset {
+        // Rewritten or truncated; start over.
+        scan.offset = 0;
+        scan.files.clear();
+    }
+    if len > scan.offset {
+        let mut file = fs::File::open(log).map_err(|e| format!("open failed: {e}"))?;
+        file.seek(SeekFrom::Start(scan.offset))
+            .map_err(|e| format!("seek failed: {e}"))?;
+        let mut reader = BufReader::new(file);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let n = reader.read_until(b'\n', &mut buf).map_err(|e| format!("read failed: {e}"))?;
+            if n == 0 || buf.last() != Some(&b'\n') {
+                break;
+            }
+            scan.offset += n as u64;
+            let line = String::from_utf8_lossy(&buf);
+            // Cheap pre-filter: most lines are messages and tool results.
+            if !line.contains("tool_use") && !line.contains("_call") {
+                continue;
+            }
+            for (tool, path, at) in touches_from_line(&line) {
+                record_touch(&mut scan.files, base, tool, &path, at);
+            }
+        }
+    }
+    Ok(scan.files.clone())
+}
+
+fn modified_since(path: &Path, since: u64) -> bool {
+    fs::metadata(path)
+        .map(|m| crate::agent_logs::modified_ms(&m) >= since)
+        .unwrap_or(false)
+}
+
+/// Claude and Codex logs belonging to a session, with the cwd each agent ran in.
+/// Logs tagged with the session's `MAESTRO_SESSION_ID` are used when there are any;
+/// otherwise every log for its directories written since the session started.
+fn session_logs(cwds: &[String], maestro_session_id: Option<&str>, started_at: u64) -> Vec<(PathBuf, String)> {
+    let mut candidates: Vec<(PathBuf, String, Option<String>)> = Vec::new();
+    for cwd in cwds {
+        if let Ok(dir) = crate::claude_logs::claude_project_dir(cwd) {
+            for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) == Some("jsonl") && modified_since(&path, started_at) {
+                    let tag = crate::claude_logs::extract_maestro_session_id(&path);
+                    candidates.push((path, cwd.clone(), tag));
+                }
+            }
+        }
+        for log in crate::codex_logs::list_codex_session_logs(cwd.clone()).unwrap_or_default() {
+            if log.modified_at < started_at {
+                continue;
+            }
+            if let Ok(path) = crate::codex_logs::resolve_codex_log_for_cwd(cwd, &log.relative_path) {
+                candidates.push((path, cwd.clone(), log.maestro_session_id));
+            }
+        }
+    }
+
+    let tagged: Vec<(PathBuf, String)> = candidates
+        .iter()
+        .filter(|(_, _, tag)| maestro_session_id.is_some() && tag.as_deref() == maestro_session_id)
+        .map(|(path, cwd, _)| (path.clone(), cwd.clone()))
+        .collect();
+    if !tagged.is_empty() {
+        return tagged;
+    }
+    candidates.into_iter().map(|(path, cwd, _)| (path, cwd)).collect()
+}
+
+fn merge_files(into: &mut HashMap<String, TouchedFile>, from: HashMap<String, TouchedFile>) {
+    for (path, file) in from {
+        match into.get_mut(&path) {
+            Some(existing) => {
+                existing.edited |= file.edited;
+                existing.touches += file.touches;
+                for tool in file.tools {
+                    if !existing.tools.contains(&tool) {
+                        existing.tools.push(tool);
+                    }
+                }
+                if file.last_touched_at > existing.last_touched_at {
+                    existing.last_touched_at = file.last_touched_at;
+                }
+            }
+            None => {
+                into.insert(path, file);
+            }
+        }
+    }
+}
+
+/// Files the agent in a session has read or changed, from the tool calls in its
+/// Claude/Codex logs. Logs are found through the session's cwd (as last reported,
+/// and where it started) and its `MAESTRO_SESSION_ID`; repeated calls only read
+/// what the logs gained since.
+#[tauri::command]
+pub async fn get_session_touched_files(state: State<'_, AppState>, id: String) -> Result<SessionTouchedFiles, String> {
+    let context = state.log_context(&id).ok_or("unknown session")?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut cwds: Vec<String> = Vec::new();
+        for cwd in [context.cwd.clone(), context.start_cwd].into_iter().flatten() {
+            if !cwds.contains(&cwd) {
+                cwds.push(cwd);
+            }
+        }
+
+        let logs = session_logs(&cwds, context.maestro_session_id.as_deref(), context.started_at);
+        let mut files: HashMap<String, TouchedFile> = HashMap::new();
+        for (log, cwd) in &logs {
+            match scan_log(log, Path::new(cwd)) {
+                Ok(found) => merge_files(&mut files, found),
+                Err(e) => eprintln!("Failed to scan agent log {}: {e}", log.display()),
+            }
+        }
+
+        let mut files: Vec<TouchedFile> = files.into_values().collect();
+        files.sort_by(|a, b| b.last_touched_at.cmp(&a.last_touched_at).then_with(|| a.path.cmp(&b.path)));
+        Ok(SessionTouchedFiles {
+            id,
+            cwd: context.cwd.or_else(|| cwds.first().cloned()),
+            files,
+            logs: logs.into_iter().map(|(log, _)| log.to_string_lossy().to_string()).collect(),
+        })
+    })
+    .await
+    .map_err(|e| format!("touched files task join failed: {e:?}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{record_touch, touches_from_line};
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    #[test]
+    fn collects_claude_and_codex_tool_paths() {
+        let claude = r#"{"type":"assistant","timestamp":"2026-01-02T03:04:05Z","message":{"content":[{"type":"tool_use","name":"Read","input":{"file_path":"/repo/src/a.rs"}},{"type":"tool_use","name":"Bash","input":{"command":"ls"}},{"type":"tool_use","name":"Edit","input":{"file_path":"/repo/src/a.rs"}}]}}"#;
+        let codex = r#"{"type":"response_item","timestamp":"2026-01-02T03:05:00Z","payload":{"type":"custom_tool_call","name":"apply_patch","input":"*** Begin Patch\n*** Update File: src/b.rs\n@@\n-x\n+y\n*** Add File: docs/new.md\n+hi\n*** End Patch"}}"#;
+
+        let mut files = HashMap::new();
+        for line in [claude, codex] {
+            for (tool, path, at) in touches_from_line(line) {
+                record_touch(&mut files, Path::new("/repo"), tool, &path, at);
+            }
+        }
+        let a = &files["/repo/src/a.rs"];
+        assert_eq!((a.tools.clone(), a.edited, a.touches), (vec!["Read".to_string(), "Edit".to_string()], true, 2));
+        assert!(files["/repo/src/b.rs"].edited);
+        assert_eq!(files["/repo/docs/new.md"].last_touched_at.as_deref(), Some("2026-01-02T03:05:00Z"));
+        assert_eq!(files.len(), 3);
+    }
+}
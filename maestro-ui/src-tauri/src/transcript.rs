@@ -0,0 +1,417 @@
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::fs;
+use std::io::{Read, Write};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{Manager, State, WebviewWindow};
+
+use crate::pty::AppState;
+
+const TRANSCRIPTS_DIR_NAME: &str = "transcripts";
+const SEGMENT_EXT: &str = "gz";
+/// Raw bytes buffered before a gzip member is appended to the current segment.
+const FLUSH_BYTES: usize = 32 * 1024;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+/// Raw bytes per segment file; a new segment starts once this is exceeded.
+const SEGMENT_BYTES: u64 = 256 * 1024;
+/// Compressed bytes kept per session; the oldest segments are dropped past this.
+const MAX_TRANSCRIPT_BYTES: u64 = 8 * 1024 * 1024;
+/// Transcripts not written to for this long are deleted.
+const MAX_TRANSCRIPT_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Compressed bytes kept across all transcripts; the least recently written go first.
+const MAX_TOTAL_TRANSCRIPT_BYTES: u64 = 512 * 1024 * 1024;
+/// How often opening a transcript also prunes the others.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Upper bound on raw bytes returned by a single `get_session_transcript` call.
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTranscript {
+    pub key: String,
+    pub data: String,
+    /// Raw byte offset of the first returned byte.
+    pub from: u64,
+    /// Raw byte offset just past the last returned byte.
+    pub to: u64,
+    /// Oldest offset still on disk (earlier output was dropped by the cap).
+    pub start: u64,
+    pub total: u64,
+}
+
+fn transcripts_root(window: &WebviewWindow) -> Result<PathBuf, String> {
+    crate::util::app_data_file(window.app_handle(), TRANSCRIPTS_DIR_NAME)
+}
+
+pub(crate) fn transcript_key(persist_id: Option<&str>, id: &str, started_at: u64) -> String {
+    match persist_id.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(persist_id) => crate::recording::sanitize_recording_id(persist_id),
+        None => format!("session-{id}-{started_at}"),
+    }
+}
+
+type PendingBuffer = Arc<Mutex<Vec<u8>>>;
+
+/// Output of live sessions not yet flushed to disk, by transcript key.
+fn live_pending() -> &'static Mutex<HashMap<String, PendingBuffer>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, PendingBuffer>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn segment_path(dir: &Path, start: u64) -> PathBuf {
+    dir.join(format!("{start:016}.{SEGMENT_EXT}"))
+}
+
+/// Segments in a transcript dir as (raw start offset, path), oldest first.
+fn list_segments(dir: &Path) -> Vec<(u64, PathBuf)> {
+    let mut out: Vec<(u64, PathBuf)> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some(SEGMENT_EXT) {
+                    return None;
+                }
+                let start = path.file_stem()?.to_str()?.parse::<u64>().ok()?;
+                Some((start, path))
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    out.sort_by_key(|(start, _)| *start);
+    out
+}
+
+fn read_segment(path: &Path) -> Result<Vec<u8>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("open failed: {e}"))?;
+    let mut out = Vec::new();
+    // A member cut short by a crash still yields everything before it.
+    let _ = MultiGzDecoder::new(file).read_to_end(&mut out);
+    Ok(out)
+}
+
+/// Delete transcripts last written before `now - MAX_TRANSCRIPT_AGE`, then the least
+/// recently written until all of them fit in `MAX_TOTAL_TRANSCRIPT_BYTES`. Transcripts
+/// of live sessions (`live` keys) are kept. Returns the keys removed.
+fn prune_transcripts(root: &Path, live: &[String], now: SystemTime) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    // (key, last written, compressed size)
+    let mut transcripts: Vec<(String, SystemTime, u64)> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| {
+            let key = entry.file_name().to_str()?.to_string();
+            let (mut written, mut size) = (SystemTime::UNIX_EPOCH, 0);
+            for file in fs::read_dir(entry.path()).ok()?.flatten() {
+                let Ok(meta) = file.metadata() else {
+                    continue;
+                };
+                size += meta.len();
+                written = written.max(meta.modified().unwrap_or(SystemTime::UNIX_EPOCH));
+            }
+            Some((key, written, size))
+        })
+        .collect();
+    transcripts.sort_by_key(|(_, written, _)| *written);
+
+    let mut total: u64 = transcripts.iter().map(|(_, _, size)| size).sum();
+    let mut removed = Vec::new();
+    for (key, written, size) in transcripts {
+        if live.contains(&key) {
+            continue;
+        }
+        let expired = now.duration_since(written).is_ok_and(|age| age > MAX_TRANSCRIPT_AGE);
+        if !expired && total <= MAX_TOTAL_TRANSCRIPT_BYTES {
+            continue;
+        }
+        if fs::remove_dir_all(root.join(&key)).is_ok() {
+            total -= size;
+            removed.push(key);
+        }
+    }
+    removed
+}
+
+/// Prune the transcripts dir in the background, at most every `PRUNE_INTERVAL`.
+fn prune_transcripts_soon(root: PathBuf) {
+    static LAST_PRUNE: Mutex<Option<Instant>> = Mutex::new(None);
+    {
+        let Ok(mut last) = LAST_PRUNE.lock() else {
+            return;
+        };
+        if last.is_some_and(|at| at.elapsed() < PRUNE_INTERVAL) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+    std::thread::spawn(move || {
+        let live: Vec<String> = live_pending()
+            .lock()
+            .map(|live| live.keys().cloned().collect())
+            .unwrap_or_default();
+        prune_transcripts(&root, &live, SystemTime::now());
+    });
+}
+
+/// Appends pty output to gzip segments under `transcripts/<key>/`. Owned by the
+/// session's reader thread; buffered output is flushed on size, age, or drop.
+pub(crate) struct TranscriptWriter {
+    key: String,
+    dir: PathBuf,
+    segment_start: u64,
+    total: u64,
+    /// Shared through `live_pending` so readers also see unflushed output.
+    pending: PendingBuffer,
+    last_flush: Instant,
+}
+
+impl TranscriptWriter {
+    /// Opens (or resumes) the transcript for `key`. Returns None if the dir can't be created.
+    pub(crate) fn open(window: &WebviewWindow, key: &str) -> Option<Self> {
+        let root = transcripts_root(window).ok()?;
+        let dir = root.join(key);
+        fs::create_dir_all(&dir).ok()?;
+
+        let (segment_start, total) = match list_segments(&dir).pop() {
+            Some((start, path)) => {
+                let len = read_segment(&path).map(|data| data.len() as u64).unwrap_or(0);
+                (start, start + len)
+            }
+            None => (0, 0),
+        };
+
+        let pending = PendingBuffer::default();
+        if let Ok(mut live) = live_pending().lock() {
+            live.insert(key.to_string(), pending.clone());
+        }
+        prune_transcripts_soon(root);
+        Some(Self {
+            key: key.to_string(),
+            dir,
+            segment_start,
+            total,
+            pending,
+            last_flush: Instant::now(),
+        })
+    }
+
+    pub(crate) fn feed(&mut self, data: &[u8]) {
+        let len = match self.pending.lock() {
+            Ok(mut pending) => {
+                pending.extend_from_slice(data);
+                pending.len()
+            }
+            Err(_) => return,
+        };
+        if len >= FLUSH_BYTES || self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        self.last_flush = Instant::now();
+        let Ok(mut pending) = self.pending.lock() else {
+            return;
+        };
+        if pending.is_empty() {
+            return;
+        }
+        if self.total - self.segment_start >= SEGMENT_BYTES {
+            self.segment_start = self.total;
+        }
+
+        let path = segment_path(&self.dir, self.segment_start);
+        let file = match fs::OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => file,
+            Err(_) => return,
+        };
+        let mut encoder = GzEncoder::new(file, Compression::fast());
+        if encoder.write_all(&pending).is_err() || encoder.finish().is_err() {
+            return;
+        }
+        self.total += pending.len() as u64;
+        pending.clear();
+        drop(pending);
+        self.enforce_cap();
+    }
+
+    fn enforce_cap(&self) {
+        let segments = list_segments(&self.dir);
+        let sizes: Vec<u64> = segments
+            .iter()
+            .map(|(_, path)| fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+            .collect();
+        let mut used: u64 = sizes.iter().sum();
+        for ((start, path), size) in segments.iter().zip(sizes) {
+            if used <= MAX_TRANSCRIPT_BYTES || *start == self.segment_start {
+                break;
+            }
+            if fs::remove_file(path).is_ok() {
+                used -= size;
+            }
+        }
+    }
+}
+
+impl Drop for TranscriptWriter {
+    fn drop(&mut self) {
+        self.flush();
+        if let Ok(mut live) = live_pending().lock() {
+            if live.get(&self.key).is_some_and(|p| Arc::ptr_eq(p, &self.pending)) {
+                live.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Reads raw output `[from, to)` from a session's on-disk transcript. `id` may be a live
+/// session id or a transcript key (persist id), so transcripts can be read after restart.
+/// Defaults to the last 1 MiB; larger ranges are truncated from the end.
+#[tauri::command]
+pub async fn get_session_transcript(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    id: String,
+    from: Option<u64>,
+    to: Option<u64>,
+) -> Result<SessionTranscript, String> {
+    let key = state
+        .transcript_key(&id)
+        .unwrap_or_else(|| crate::recording::sanitize_recording_id(&id));
+    let dir = transcripts_root(&window)?.join(&key);
+
+    tauri::async_runtime::spawn_blocking(move || read_transcript(&dir, key, from, to))
+        .await
+        .map_err(|e| format!("read transcript task join failed: {e:?}"))?
+}
+
+/// The most recent output (up to 1 MiB) in the transcript stored under `key`,
+/// including output a live session hasn't flushed to disk yet.
+pub(crate) fn read_transcript_tail(window: &WebviewWindow, key: &str) -> Option<String> {
+    let dir = transcripts_root(window).ok()?.join(key);
+    let mut data: Vec<u8> = read_transcript(&dir, key.to_string(), None, None)
+        .map(|t| t.data.into_bytes())
+        .unwrap_or_default();
+    let pending = live_pending().lock().ok()?.get(key).cloned();
+    if let Some(pending) = pending {
+        data.extend_from_slice(&pending.lock().ok()?);
+    }
+    if data.is_empty() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&data).to_string())
+}
+
+fn read_transcript(dir: &Path, key: String, from: Option<u64>, to: Option<u64>) -> Result<SessionTranscript, String> {
+    let segments = list_segments(dir);
+    let Some((last_start, last_path)) = segments.last() else {
+        return Err("no transcript for session".to_string());
+    };
+    let last_data = read_segment(last_path)?;
+    let start = segments[0].0;
+    let total = last_start + last_data.len() as u64;
+
+    let to = to.unwrap_or(total).clamp(start, total);
+    let from = match from {
+        Some(from) => from.clamp(start, to),
+        None => to.saturating_sub(MAX_READ_BYTES).max(start),
+    };
+    let to = to.min(from + MAX_READ_BYTES);
+
+    let mut data: Vec<u8> = Vec::new();
+    for (i, (seg_start, path)) in segments.iter().enumerate() {
+        let seg_end = segments.get(i + 1).map(|(next, _)| *next).unwrap_or(total);
+        if seg_end <= from || *seg_start >= to {
+            continue;
+        }
+        let bytes = if path == last_path {
+            last_data.clone()
+        } else {
+            read_segment(path)?
+        };
+        let lo = from.saturating_sub(*seg_start) as usize;
+        let hi = ((to - seg_start) as usize).min(bytes.len());
+        if lo < hi {
+            data.extend_from_slice(&bytes[lo..hi]);
+        }
+    }
+
+    Ok(SessionTranscript {
+        key,
+        data: String::from_utf8_lossy(&data).to_string(),
+        from,
+        to,
+        start,
+        total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prune_transcripts, read_transcript, segment_path, MAX_TRANSCRIPT_AGE};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::fs;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime};
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("maestro-transcript-test-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_segment(dir: &Path, start: u64, data: &[u8]) {
+        let file = fs::File::create(segment_path(dir, start)).unwrap();
+        let mut encoder = GzEncoder::new(file, Compression::fast());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap();
+    }
+
+    #[test]
+    fn reads_ranges_across_segments() {
+        let dir = test_dir("ranges");
+        // The segment at 0 was dropped by the cap.
+        write_segment(&dir, 4, b"hello ");
+        write_segment(&dir, 10, b"world");
+        let read = |from, to| {
+            let t = read_transcript(&dir, "k".to_string(), from, to).unwrap();
+            (t.data, t.from, t.to)
+        };
+        assert_eq!(read(None, None), ("hello world".to_string(), 4, 15));
+        assert_eq!(read(Some(7), Some(12)), ("lo wo".to_string(), 7, 12));
+        assert_eq!(read(Some(0), Some(6)), ("he".to_string(), 4, 6));
+        assert_eq!(read(Some(10), None), ("world".to_string(), 10, 15));
+        assert_eq!(read(Some(20), Some(30)), (String::new(), 15, 15));
+        let t = read_transcript(&dir, "k".to_string(), None, None).unwrap();
+        assert_eq!((t.start, t.total), (4, 15));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(read_transcript(&dir, "k".to_string(), None, None).is_err());
+    }
+
+    #[test]
+    fn prunes_old_transcripts_but_not_live_ones() {
+        let root = test_dir("prune");
+        for key in ["old", "live", "recent"] {
+            fs::create_dir_all(root.join(key)).unwrap();
+            write_segment(&root.join(key), 0, key.as_bytes());
+        }
+        let later = SystemTime::now() + MAX_TRANSCRIPT_AGE + Duration::from_secs(60);
+        let recent = fs::File::options().append(true).open(segment_path(&root.join("recent"), 0)).unwrap();
+        recent.set_modified(later).unwrap();
+
+        let removed = prune_transcripts(&root, &["live".to_string()], later);
+        assert_eq!(removed, ["old"]);
+        assert!(root.join("live").is_dir() && root.join("recent").is_dir());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,9 +1,20 @@
+use std::fs;
+use std::path::PathBuf;
 use std::sync::Mutex;
-use tauri::menu::{MenuBuilder, MenuEvent, MenuItem, MenuItemBuilder};
+use std::time::Duration;
+use tauri::menu::{Menu, MenuBuilder, MenuEvent, MenuItem, MenuItemBuilder, Submenu, SubmenuBuilder};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
 use tauri::{include_image, AppHandle, Emitter, Manager, State};
 
+use crate::activity::ActivityState;
+use crate::pty::AppState;
+use crate::util::now_epoch_ms;
+
 const RECENT_LIMIT: usize = 10;
+/// Menu position of the first "Start <agent>" entry: open, new terminal,
+/// separator, recent header, recent items, separator.
+const AGENT_ITEMS_POSITION: usize = 5 + RECENT_LIMIT;
+const TRAY_AGENTS_FILE_NAME: &str = "tray-agents-v1.json";
 
 pub struct StatusTrayState {
     tray: Option<TrayIcon>,
@@ -14,10 +25,57 @@ pub struct StatusTrayState {
     project_item: Option<MenuItem<tauri::Wry>>,
     session_item: Option<MenuItem<tauri::Wry>>,
     recording_item: Option<MenuItem<tauri::Wry>>,
+    menu: Option<Menu<tauri::Wry>>,
+    /// "Start <agent>" entries and the effect id each one launches.
+    agent_items: Mutex<Vec<(MenuItem<tauri::Wry>, Option<String>)>>,
+    /// Count currently drawn onto the tray icon (Windows/Linux only).
+    #[cfg_attr(target_os = "macos", allow(dead_code))]
+    badge_count: Mutex<u32>,
+    focus_menu: Option<Submenu<tauri::Wry>>,
+    focus_off_item: Option<MenuItem<tauri::Wry>>,
+    /// Do Not Disturb end time (epoch ms); `u64::MAX` until turned off.
+    focus_until: Mutex<Option<u64>>,
+    /// Counts last shown, so the badge can be restored when Do Not Disturb ends
+    /// and the ticker only touches the menu when something changed.
+    last_counts: Mutex<TrayCounts>,
+}
+
+/// How often the status lines are recomputed from the session registry.
+const STATUS_TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Default, PartialEq)]
+struct TrayCounts {
+    /// Agent (non-shell) sessions classified as working by activity.rs.
+    working: u32,
+    sessions_open: u32,
+    recording: u32,
+}
+
+impl TrayCounts {
+    fn from_registry(state: &AppState) -> Self {
+        let sessions = state.session_activity().unwrap_or_default();
+        Self {
+            working: sessions
+                .iter()
+                .filter(|s| !s.is_shell && s.activity == Some(ActivityState::Working))
+                .count() as u32,
+            sessions_open: sessions.len() as u32,
+            recording: sessions.iter().filter(|s| s.recording_id.is_some()).count() as u32,
+        }
+    }
 }
 
 const TRAY_ICON: tauri::image::Image<'_> = include_image!("./icons/tray.png");
 const EVENT_TRAY_MENU: &str = "tray-menu";
+const EVENT_FOCUS_MODE: &str = "focus-mode-changed";
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusModeStatus {
+    pub enabled: bool,
+    /// Epoch ms when focus mode ends; `None` while enabled means until turned off.
+    pub until: Option<u64>,
+}
 
 #[derive(Clone)]
 struct TrayRecentTarget {
@@ -33,6 +91,65 @@ pub struct TrayRecentSessionInput {
     pub persist_id: String,
 }
 
+/// An agent profile offered as a "Start <label>" tray entry.
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayAgentV1 {
+    pub effect_id: String,
+    pub label: String,
+}
+
+fn default_tray_agents() -> Vec<TrayAgentV1> {
+    ["codex", "claude", "gemini"]
+        .into_iter()
+        .map(|id| TrayAgentV1 {
+            effect_id: id.to_string(),
+            label: format!("Start {id}"),
+        })
+        .collect()
+}
+
+fn tray_agents_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, TRAY_AGENTS_FILE_NAME)
+}
+
+fn load_tray_agents(app: &AppHandle) -> Vec<TrayAgentV1> {
+    tray_agents_file_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_else(default_tray_agents)
+}
+
+fn save_tray_agents(app: &AppHandle, agents: &[TrayAgentV1]) -> Result<(), String> {
+    let path = tray_agents_file_path(app)?;
+    crate::util::write_json_atomic(&path, agents)
+}
+
+/// Menu items for the agent entries; a disabled placeholder when there are none.
+fn build_agent_items(
+    app: &AppHandle,
+    agents: &[TrayAgentV1],
+) -> Result<Vec<(MenuItem<tauri::Wry>, Option<String>)>, String> {
+    if agents.is_empty() {
+        let item = MenuItemBuilder::with_id("tray-agent-none", "No agent profiles")
+            .enabled(false)
+            .build(app)
+            .map_err(|e| e.to_string())?;
+        return Ok(vec![(item, None)]);
+    }
+    agents
+        .iter()
+        .enumerate()
+        .map(|(i, agent)| {
+            let item = MenuItemBuilder::with_id(format!("tray-agent-{i}"), agent.label.clone())
+                .build(app)
+                .map_err(|e| e.to_string())?;
+            Ok((item, Some(agent.effect_id.clone())))
+        })
+        .collect()
+}
+
 #[derive(serde::Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct TrayMenuEventPayload {
@@ -88,37 +205,29 @@ fn on_menu_event(app: &AppHandle, event: MenuEvent) {
                 },
             );
         }
-        "tray-start-codex" => {
-            show_main_window(app);
-            let _ = app.emit(
-                EVENT_TRAY_MENU,
-                TrayMenuEventPayload {
-                    id: "start-agent".to_string(),
-                    effect_id: Some("codex".to_string()),
-                    project_id: None,
-                    persist_id: None,
-                },
-            );
-        }
-        "tray-start-claude" => {
-            show_main_window(app);
-            let _ = app.emit(
-                EVENT_TRAY_MENU,
-                TrayMenuEventPayload {
-                    id: "start-agent".to_string(),
-                    effect_id: Some("claude".to_string()),
-                    project_id: None,
-                    persist_id: None,
-                },
-            );
-        }
-        "tray-start-gemini" => {
+        id if id.starts_with("tray-agent-") => {
+            let index = id
+                .strip_prefix("tray-agent-")
+                .and_then(|raw| raw.parse::<usize>().ok());
+            let Some(index) = index else {
+                return;
+            };
+
+            let state = app.state::<StatusTrayState>();
+            let effect_id = match state.agent_items.lock() {
+                Ok(items) => items.get(index).and_then(|(_, effect_id)| effect_id.clone()),
+                Err(_) => None,
+            };
+            let Some(effect_id) = effect_id else {
+                return;
+            };
+
             show_main_window(app);
             let _ = app.emit(
                 EVENT_TRAY_MENU,
                 TrayMenuEventPayload {
                     id: "start-agent".to_string(),
-                    effect_id: Some("gemini".to_string()),
+                    effect_id: Some(effect_id),
                     project_id: None,
                     persist_id: None,
                 },
@@ -152,11 +261,28 @@ fn on_menu_event(app: &AppHandle, event: MenuEvent) {
                 },
             );
         }
+        "tray-focus-30" => enable_focus_from_tray(app, Some(30)),
+        "tray-focus-60" => enable_focus_from_tray(app, Some(60)),
+        "tray-focus-120" => enable_focus_from_tray(app, Some(120)),
+        "tray-focus-on" => enable_focus_from_tray(app, None),
+        "tray-focus-off" => {
+            let state = app.state::<StatusTrayState>();
+            if let Err(e) = state.set_focus_mode(app, false, None) {
+                eprintln!("focus mode update failed: {e}");
+            }
+        }
         "tray-quit" => app.exit(0),
         _ => {}
     }
 }
 
+fn enable_focus_from_tray(app: &AppHandle, minutes: Option<u64>) {
+    let state = app.state::<StatusTrayState>();
+    if let Err(e) = state.set_focus_mode(app, true, minutes) {
+        eprintln!("focus mode update failed: {e}");
+    }
+}
+
 impl StatusTrayState {
     pub fn disabled() -> Self {
         Self {
@@ -168,7 +294,112 @@ impl StatusTrayState {
             project_item: None,
             session_item: None,
             recording_item: None,
+            menu: None,
+            agent_items: Mutex::new(Vec::new()),
+            badge_count: Mutex::new(0),
+            focus_menu: None,
+            focus_off_item: None,
+            focus_until: Mutex::new(None),
+            last_counts: Mutex::new(TrayCounts::default()),
+        }
+    }
+
+    /// Current focus mode, clearing it if the chosen duration has elapsed.
+    pub fn focus_mode(&self) -> FocusModeStatus {
+        let mut until = match self.focus_until.lock() {
+            Ok(until) => until,
+            Err(_) => {
+                return FocusModeStatus {
+                    enabled: false,
+                    until: None,
+                }
+            }
+        };
+        if matches!(*until, Some(end) if end <= now_epoch_ms()) {
+            *until = None;
+        }
+        FocusModeStatus {
+            enabled: until.is_some(),
+            until: until.filter(|end| *end != u64::MAX),
+        }
+    }
+
+    /// Turn Do Not Disturb on (for `minutes`, or until turned off) or off.
+    /// While on, the tray shows no working count; the frontend is told via
+    /// `focus-mode-changed` so it can hold back notifications and attention
+    /// requests.
+    fn set_focus_mode(
+        &self,
+        app: &AppHandle,
+        enabled: bool,
+        minutes: Option<u64>,
+    ) -> Result<FocusModeStatus, String> {
+        let end = match (enabled, minutes) {
+            (false, _) => None,
+            (true, Some(0)) => return Err("minutes must be greater than 0".to_string()),
+            (true, Some(m)) => Some(now_epoch_ms().saturating_add(m.saturating_mul(60_000))),
+            (true, None) => Some(u64::MAX),
+        };
+        *self.focus_until.lock().map_err(|_| "state poisoned")? = end;
+
+        if let (Some(end), Some(m)) = (end, minutes) {
+            // Restore the badge and notify the frontend once the duration ends,
+            // unless focus mode has been changed in the meantime.
+            let app = app.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_secs(m.saturating_mul(60)));
+                let state = app.state::<StatusTrayState>();
+                let still_current = state
+                    .focus_until
+                    .lock()
+                    .map(|until| *until == Some(end))
+                    .unwrap_or(false);
+                if still_current {
+                    if let Err(e) = state.set_focus_mode(&app, false, None) {
+                        eprintln!("focus mode update failed: {e}");
+                    }
+                }
+            });
+        }
+
+        let status = self.focus_mode();
+        if let Some(focus_menu) = &self.focus_menu {
+            let label = if status.enabled {
+                "Do Not Disturb (on)"
+            } else {
+                "Do Not Disturb"
+            };
+            focus_menu.set_text(label).map_err(|e| e.to_string())?;
+        }
+        if let Some(focus_off_item) = &self.focus_off_item {
+            focus_off_item
+                .set_enabled(status.enabled)
+                .map_err(|e| e.to_string())?;
         }
+
+        let counts = *self.last_counts.lock().map_err(|_| "state poisoned")?;
+        self.update_tray_count(counts.working, counts.sessions_open)?;
+
+        let _ = app.emit(EVENT_FOCUS_MODE, status.clone());
+        Ok(status)
+    }
+
+    /// Replace the "Start <agent>" entries in place.
+    fn set_agents(&self, app: &AppHandle, agents: &[TrayAgentV1]) -> Result<(), String> {
+        let Some(menu) = &self.menu else {
+            return Ok(());
+        };
+        let mut items = self.agent_items.lock().map_err(|_| "state poisoned")?;
+        for (item, _) in items.iter() {
+            menu.remove(item).map_err(|e| e.to_string())?;
+        }
+        let next = build_agent_items(app, agents)?;
+        for (offset, (item, _)) in next.iter().enumerate() {
+            menu.insert(item, AGENT_ITEMS_POSITION + offset)
+                .map_err(|e| e.to_string())?;
+        }
+        *items = next;
+        Ok(())
     }
 
     fn set_recent_sessions(&self, sessions: Vec<TrayRecentSessionInput>) -> Result<(), String> {
@@ -205,14 +436,8 @@ impl StatusTrayState {
         Ok(())
     }
 
-    fn set_status(
-        &self,
-        working_count: u32,
-        sessions_open: u32,
-        active_project: Option<String>,
-        active_session: Option<String>,
-        recording_count: u32,
-    ) -> Result<(), String> {
+    /// The active project/session lines, which only the frontend knows.
+    fn set_labels(&self, active_project: Option<String>, active_session: Option<String>) -> Result<(), String> {
         if let Some(project_item) = &self.project_item {
             let label = active_project
                 .as_deref()
@@ -234,30 +459,60 @@ impl StatusTrayState {
                 .set_text(format!("Session: {label}"))
                 .map_err(|e| e.to_string())?;
         }
+        Ok(())
+    }
 
+    fn set_counts(&self, counts: TrayCounts) -> Result<(), String> {
         if let Some(sessions_item) = &self.sessions_item {
             sessions_item
-                .set_text(format!("Sessions open: {sessions_open}"))
+                .set_text(format!("Sessions open: {}", counts.sessions_open))
                 .map_err(|e| e.to_string())?;
         }
 
         if let Some(recording_item) = &self.recording_item {
             recording_item
-                .set_text(format!("Recordings active: {recording_count}"))
+                .set_text(format!("Recordings active: {}", counts.recording))
                 .map_err(|e| e.to_string())?;
         }
 
         if let Some(working_item) = &self.working_item {
             working_item
-                .set_text(format!("Agents working: {working_count}"))
+                .set_text(format!("Agents working: {}", counts.working))
                 .map_err(|e| e.to_string())?;
         }
 
+        *self.last_counts.lock().map_err(|_| "state poisoned")? = counts;
+        self.update_tray_count(counts.working, counts.sessions_open)
+    }
+
+    /// Recount from the session registry and update the menu if anything changed.
+    fn refresh_counts(&self, state: &AppState) -> Result<(), String> {
+        let counts = TrayCounts::from_registry(state);
+        if *self.last_counts.lock().map_err(|_| "state poisoned")? == counts {
+            return Ok(());
+        }
+        self.set_counts(counts)
+    }
+
+    /// Update only the working count, as classified by activity.rs, without waiting
+    /// for the next tick.
+    pub(crate) fn set_working_count(&self, working_count: u32) -> Result<(), String> {
+        let mut counts = *self.last_counts.lock().map_err(|_| "state poisoned")?;
+        counts.working = working_count;
+        self.set_counts(counts)
+    }
+
+    /// Reflect the working count in the tray title/badge and tooltip, hiding
+    /// it while Do Not Disturb is on.
+    fn update_tray_count(&self, working_count: u32, sessions_open: u32) -> Result<(), String> {
+        let focus = self.focus_mode().enabled;
+        let working_count = if focus { 0 } else { working_count };
+
         let Some(tray) = &self.tray else {
             return Ok(());
         };
 
-        #[cfg(not(windows))]
+        #[cfg(target_os = "macos")]
         {
             // `None` is a no-op in Tauri, so it won't clear an existing title.
             // Use an empty string to explicitly remove the count when idle.
@@ -269,7 +524,27 @@ impl StatusTrayState {
             let _ = tray.set_title(title);
         }
 
-        let tooltip = if working_count == 0 {
+        // Tray titles aren't shown on Windows and most Linux panels, so draw
+        // the count onto the icon instead. Only re-render when it changes.
+        #[cfg(not(target_os = "macos"))]
+        {
+            let mut badge_count = self.badge_count.lock().map_err(|_| "state poisoned")?;
+            if *badge_count != working_count {
+                let icon = if working_count == 0 {
+                    TRAY_ICON
+                } else {
+                    let mut rgba = TRAY_ICON.rgba().to_vec();
+                    draw_count_badge(&mut rgba, TRAY_ICON.width(), TRAY_ICON.height(), working_count);
+                    tauri::image::Image::new_owned(rgba, TRAY_ICON.width(), TRAY_ICON.height())
+                };
+                let _ = tray.set_icon(Some(icon));
+                *badge_count = working_count;
+            }
+        }
+
+        let tooltip = if focus {
+            format!("Agent Maestro — Do Not Disturb • {sessions_open} sessions open")
+        } else if working_count == 0 {
             format!("Agent Maestro — {sessions_open} sessions open")
         } else {
             format!(
@@ -282,6 +557,88 @@ impl StatusTrayState {
     }
 }
 
+/// 3x5 bitmap glyphs for the count badge: digits 0-9, then `+`.
+/// Each row is 3 bits, most significant bit leftmost.
+#[cfg(not(target_os = "macos"))]
+const BADGE_GLYPHS: [[u8; 5]; 11] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b000, 0b010, 0b111, 0b010, 0b000],
+];
+#[cfg(not(target_os = "macos"))]
+const BADGE_PLUS: usize = 10;
+
+/// Draw a red circle with `count` in white over the bottom-right of an RGBA
+/// image. Counts above 9 render as `9+`.
+#[cfg(not(target_os = "macos"))]
+fn draw_count_badge(rgba: &mut [u8], width: u32, height: u32, count: u32) {
+    let (w, h) = (width as i32, height as i32);
+    if w == 0 || h == 0 || rgba.len() < (w * h * 4) as usize {
+        return;
+    }
+
+    let glyphs: Vec<usize> = if count > 9 {
+        vec![9, BADGE_PLUS]
+    } else {
+        vec![count as usize]
+    };
+
+    let mut put = |x: i32, y: i32, color: [u8; 4]| {
+        if x >= 0 && y >= 0 && x < w && y < h {
+            let i = ((y * w + x) * 4) as usize;
+            rgba[i..i + 4].copy_from_slice(&color);
+        }
+    };
+
+    let diameter = (w.min(h) * 5 / 8).max(7);
+    let radius = diameter as f32 / 2.0;
+    let (cx, cy) = (w as f32 - radius, h as f32 - radius);
+    for y in (h - diameter).max(0)..h {
+        for x in (w - diameter).max(0)..w {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                put(x, y, [0xe5, 0x39, 0x35, 0xff]);
+            }
+        }
+    }
+
+    // Scale the 3x5 glyphs to fill roughly the middle of the circle, with a
+    // one-cell gap between glyphs.
+    let cells_wide = glyphs.len() as i32 * 4 - 1;
+    let scale = ((diameter * 2 / 3) / cells_wide.max(5)).max(1);
+    let text_w = cells_wide * scale;
+    let text_h = 5 * scale;
+    let left = (cx - text_w as f32 / 2.0).round() as i32;
+    let top = (cy - text_h as f32 / 2.0).round() as i32;
+    for (n, glyph) in glyphs.iter().enumerate() {
+        let gx = left + n as i32 * 4 * scale;
+        for (row, bits) in BADGE_GLYPHS[*glyph].iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for sy in 0..scale {
+                    for sx in 0..scale {
+                        put(
+                            gx + col * scale + sx,
+                            top + row as i32 * scale + sy,
+                            [0xff, 0xff, 0xff, 0xff],
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub fn build_status_tray(app: &AppHandle) -> Result<StatusTrayState, String> {
     let open_item = MenuItemBuilder::with_id("tray-open", "Open Agent Maestro")
         .build(app)
@@ -303,15 +660,7 @@ pub fn build_status_tray(app: &AppHandle) -> Result<StatusTrayState, String> {
         recent_items.push(item);
     }
 
-    let start_codex_item = MenuItemBuilder::with_id("tray-start-codex", "Start codex")
-        .build(app)
-        .map_err(|e| e.to_string())?;
-    let start_claude_item = MenuItemBuilder::with_id("tray-start-claude", "Start claude")
-        .build(app)
-        .map_err(|e| e.to_string())?;
-    let start_gemini_item = MenuItemBuilder::with_id("tray-start-gemini", "Start gemini")
-        .build(app)
-        .map_err(|e| e.to_string())?;
+    let agent_items = build_agent_items(app, &load_tray_agents(app))?;
 
     let project_item = MenuItemBuilder::with_id("tray-project", "Project: —")
         .enabled(false)
@@ -333,6 +682,31 @@ pub fn build_status_tray(app: &AppHandle) -> Result<StatusTrayState, String> {
         .enabled(false)
         .build(app)
         .map_err(|e| e.to_string())?;
+    let focus_30_item = MenuItemBuilder::with_id("tray-focus-30", "For 30 minutes")
+        .build(app)
+        .map_err(|e| e.to_string())?;
+    let focus_60_item = MenuItemBuilder::with_id("tray-focus-60", "For 1 hour")
+        .build(app)
+        .map_err(|e| e.to_string())?;
+    let focus_120_item = MenuItemBuilder::with_id("tray-focus-120", "For 2 hours")
+        .build(app)
+        .map_err(|e| e.to_string())?;
+    let focus_on_item = MenuItemBuilder::with_id("tray-focus-on", "Until turned off")
+        .build(app)
+        .map_err(|e| e.to_string())?;
+    let focus_off_item = MenuItemBuilder::with_id("tray-focus-off", "Turn off")
+        .enabled(false)
+        .build(app)
+        .map_err(|e| e.to_string())?;
+    let focus_menu = SubmenuBuilder::with_id(app, "tray-focus", "Do Not Disturb")
+        .item(&focus_30_item)
+        .item(&focus_60_item)
+        .item(&focus_120_item)
+        .item(&focus_on_item)
+        .separator()
+        .item(&focus_off_item)
+        .build()
+        .map_err(|e| e.to_string())?;
     let quit_item = MenuItemBuilder::with_id("tray-quit", "Quit")
         .build(app)
         .map_err(|e| e.to_string())?;
@@ -347,11 +721,12 @@ pub fn build_status_tray(app: &AppHandle) -> Result<StatusTrayState, String> {
         menu_builder = menu_builder.item(item);
     }
 
+    menu_builder = menu_builder.separator();
+    for (item, _) in &agent_items {
+        menu_builder = menu_builder.item(item);
+    }
+
     let menu = menu_builder
-        .separator()
-        .item(&start_codex_item)
-        .item(&start_claude_item)
-        .item(&start_gemini_item)
         .separator()
         .item(&project_item)
         .item(&session_item)
@@ -359,6 +734,8 @@ pub fn build_status_tray(app: &AppHandle) -> Result<StatusTrayState, String> {
         .item(&recording_item)
         .item(&working_item)
         .separator()
+        .item(&focus_menu)
+        .separator()
         .item(&quit_item)
         .build()
         .map_err(|e| e.to_string())?;
@@ -387,30 +764,58 @@ pub fn build_status_tray(app: &AppHandle) -> Result<StatusTrayState, String> {
         project_item: Some(project_item),
         session_item: Some(session_item),
         recording_item: Some(recording_item),
+        menu: Some(menu),
+        agent_items: Mutex::new(agent_items),
+        badge_count: Mutex::new(0),
+        focus_menu: Some(focus_menu),
+        focus_off_item: Some(focus_off_item),
+        focus_until: Mutex::new(None),
+        last_counts: Mutex::new(TrayCounts::default()),
     })
 }
 
+/// Keep the tray's counts in step with the session registry, whatever the frontend
+/// is doing (it may be hidden or throttled).
+pub(crate) fn start_status_ticker(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(STATUS_TICK_INTERVAL);
+        let Some(tray) = app.try_state::<StatusTrayState>() else {
+            continue;
+        };
+        if let Err(e) = tray.refresh_counts(&app.state::<AppState>()) {
+            eprintln!("tray status update failed: {e}");
+        }
+    });
+}
+
+/// `count` is accepted for API compatibility but ignored; the counts come from the
+/// session registry.
 #[tauri::command]
-pub fn set_tray_agent_count(state: State<'_, StatusTrayState>, count: u32) -> Result<(), String> {
-    state.set_status(count, 0, None, None, 0)
+pub fn set_tray_agent_count(
+    state: State<'_, StatusTrayState>,
+    app_state: State<'_, AppState>,
+    count: u32,
+) -> Result<(), String> {
+    let _ = count;
+    state.refresh_counts(&app_state)
 }
 
+/// Set the active project/session lines. The counts are accepted for API
+/// compatibility but ignored: they're computed from the session registry.
 #[tauri::command]
 pub fn set_tray_status(
     state: State<'_, StatusTrayState>,
+    app_state: State<'_, AppState>,
     working_count: u32,
     sessions_open: u32,
     active_project: Option<String>,
     active_session: Option<String>,
     recording_count: u32,
 ) -> Result<(), String> {
-    state.set_status(
-        working_count,
-        sessions_open,
-        active_project,
-        active_session,
-        recording_count,
-    )
+    let _ = (working_count, sessions_open, recording_count);
+    state.set_labels(active_project, active_session)?;
+    state.refresh_counts(&app_state)
 }
 
 #[tauri::command]
@@ -420,3 +825,47 @@ pub fn set_tray_recent_sessions(
 ) -> Result<(), String> {
     state.set_recent_sessions(sessions)
 }
+
+/// Rebuild the tray's "Start <agent>" entries. Pass the current agent profiles to
+/// replace the saved list, or nothing to reload it from disk.
+#[tauri::command]
+pub fn refresh_tray_agents(
+    app: AppHandle,
+    state: State<'_, StatusTrayState>,
+    agents: Option<Vec<TrayAgentV1>>,
+) -> Result<Vec<TrayAgentV1>, String> {
+    let agents = match agents {
+        Some(agents) => {
+            let agents: Vec<TrayAgentV1> = agents
+                .into_iter()
+                .map(|a| TrayAgentV1 {
+                    effect_id: a.effect_id.trim().to_string(),
+                    label: a.label.trim().to_string(),
+                })
+                .filter(|a| !a.effect_id.is_empty() && !a.label.is_empty())
+                .collect();
+            save_tray_agents(&app, &agents)?;
+            agents
+        }
+        None => load_tray_agents(&app),
+    };
+    state.set_agents(&app, &agents)?;
+    Ok(agents)
+}
+
+#[tauri::command]
+pub fn get_focus_mode(state: State<'_, StatusTrayState>) -> FocusModeStatus {
+    state.focus_mode()
+}
+
+/// Enable Do Not Disturb for `minutes` (or until turned off when omitted), or
+/// disable it.
+#[tauri::command]
+pub fn set_focus_mode(
+    app: AppHandle,
+    state: State<'_, StatusTrayState>,
+    enabled: bool,
+    minutes: Option<u64>,
+) -> Result<FocusModeStatus, String> {
+    state.set_focus_mode(&app, enabled, minutes)
+}
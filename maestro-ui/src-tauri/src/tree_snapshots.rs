@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{Manager, WebviewWindow};
+
+use crate::util::now_epoch_ms;
+
+const SNAPSHOTS_DIR_NAME: &str = "tree-snapshots";
+/// Snapshots kept on disk; the oldest are removed past this.
+const MAX_SNAPSHOTS: usize = 100;
+const MAX_FILES: usize = 200_000;
+/// Dependency and build output directories, which would drown the report. Unlike
+/// `list_project_files`, other dot-entries are kept: `.env` edits matter in review.
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target", "dist", "build", "coverage"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FileStamp {
+    size: u64,
+    modified_ms: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TreeSnapshotV1 {
+    schema_version: u32,
+    id: String,
+    root: String,
+    created_at: u64,
+    label: Option<String>,
+    truncated: bool,
+    /// Relative path (with `/` separators) to size and mtime.
+    files: BTreeMap<String, FileStamp>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeSnapshotInfo {
+    pub id: String,
+    pub root: String,
+    pub created_at: u64,
+    pub label: Option<String>,
+    pub file_count: usize,
+    /// The tree had more than 200k files; later ones are missing from the snapshot.
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeDiffEntry {
+    pub path: String,
+    pub size_before: Option<u64>,
+    pub size_after: Option<u64>,
+    pub size_delta: i64,
+}
+
+#[derive(Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeDiff {
+    pub root: String,
+    pub before_id: String,
+    /// None when compared against the live tree.
+    pub after_id: Option<String>,
+    pub added: Vec<TreeDiffEntry>,
+    pub removed: Vec<TreeDiffEntry>,
+    pub modified: Vec<TreeDiffEntry>,
+    pub size_delta: i64,
+}
+
+fn snapshots_root(window: &WebviewWindow) -> Result<PathBuf, String> {
+    crate::util::app_data_file(window.app_handle(), SNAPSHOTS_DIR_NAME)
+}
+
+fn snapshot_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", crate::recording::sanitize_recording_id(id)))
+}
+
+/// Size and mtime of every file under `root`, skipping `SKIPPED_DIRS` and not
+/// following symlinks. Returns (files, truncated).
+fn scan_tree(root: &Path) -> Result<(BTreeMap<String, FileStamp>, bool), String> {
+    let mut files = BTreeMap::new();
+    let mut dirs_to_visit = vec![root.to_path_buf()];
+    while let Some(dir) = dirs_to_visit.pop() {
+        let read_dir = match fs::read_dir(&dir) {
+            Ok(rd) => rd,
+            Err(e) if dir == root => return Err(format!("read dir failed: {e}")),
+            Err(_) => continue,
+        };
+        for entry in read_dir.flatten() {
+            let Ok(meta) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            if meta.is_dir() {
+                if !SKIPPED_DIRS.contains(&name.as_str()) {
+                    dirs_to_visit.push(entry.path());
+                }
+                continue;
+            }
+            if files.len() >= MAX_FILES {
+                return Ok((files, true));
+            }
+            let Ok(rel) = entry.path().strip_prefix(root).map(|p| p.to_path_buf()) else {
+                continue;
+            };
+            let rel = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            let modified_ms = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            files.insert(
+                rel,
+                FileStamp {
+                    size: meta.len(),
+                    modified_ms,
+                },
+            );
+        }
+    }
+    Ok((files, false))
+}
+
+/// A file counts as modified when its size or mtime changed; contents aren't hashed.
+fn diff_files(
+    before: &BTreeMap<String, FileStamp>,
+    after: &BTreeMap<String, FileStamp>,
+) -> (Vec<TreeDiffEntry>, Vec<TreeDiffEntry>, Vec<TreeDiffEntry>) {
+    let entry = |path: &str, before: Option<&FileStamp>, after: Option<&FileStamp>| TreeDiffEntry {
+        path: path.to_string(),
+        size_before: before.map(|f| f.size),
+        size_after: after.map(|f| f.size),
+        size_delta: after.map(|f| f.size as i64).unwrap_or(0) - before.map(|f| f.size as i64).unwrap_or(0),
+    };
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let mut modified = Vec::new();
+    for (path, old) in before {
+        match after.get(path) {
+            None => removed.push(entry(path, Some(old), None)),
+            Some(new) if new != old => modified.push(entry(path, Some(old), Some(new))),
+            Some(_) => {}
+        }
+    }
+    for (path, new) in after {
+        if !before.contains_key(path) {
+            added.push(entry(path, None, Some(new)));
+        }
+    }
+    (added, removed, modified)
+}
+
+fn read_snapshot(dir: &Path, id: &str) -> Result<TreeSnapshotV1, String> {
+    let raw = fs::read_to_string(snapshot_path(dir, id)).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("unknown snapshot: {id}"),
+        _ => format!("read failed: {e}"),
+    })?;
+    serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}"))
+}
+
+fn prune_snapshots(dir: &Path) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(SystemTime, PathBuf)> = read_dir
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    if files.len() <= MAX_SNAPSHOTS {
+        return;
+    }
+    files.sort();
+    for (_, path) in &files[..files.len() - MAX_SNAPSHOTS] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn info(snapshot: &TreeSnapshotV1) -> TreeSnapshotInfo {
+    TreeSnapshotInfo {
+        id: snapshot.id.clone(),
+        root: snapshot.root.clone(),
+        created_at: snapshot.created_at,
+        label: snapshot.label.clone(),
+        file_count: snapshot.files.len(),
+        truncated: snapshot.truncated,
+    }
+}
+
+/// Record the size and mtime of every file under `root`, e.g. before an agent run,
+/// for a later `diff_tree_snapshots`.
+#[tauri::command]
+pub async fn create_tree_snapshot(
+    window: WebviewWindow,
+    root: String,
+    label: Option<String>,
+) -> Result<TreeSnapshotInfo, String> {
+    let root = PathBuf::from(root.trim());
+    if !root.is_absolute() || !root.is_dir() {
+        return Err("root must be an absolute directory".to_string());
+    }
+    let dir = snapshots_root(&window)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = fs::canonicalize(&root).map_err(|e| format!("canonicalize failed: {e}"))?;
+        let (files, truncated) = scan_tree(&root)?;
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        let created_at = now_epoch_ms();
+        let snapshot = TreeSnapshotV1 {
+            schema_version: 1,
+            id: format!("snap-{created_at}-{}", NEXT.fetch_add(1, Ordering::Relaxed)),
+            root: root.to_string_lossy().to_string(),
+            created_at,
+            label: label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()),
+            truncated,
+            files,
+        };
+
+        let json = serde_json::to_vec(&snapshot).map_err(|e| format!("serialize failed: {e}"))?;
+        crate::util::write_atomic(&snapshot_path(&dir, &snapshot.id), &json)?;
+        prune_snapshots(&dir);
+        Ok(info(&snapshot))
+    })
+    .await
+    .map_err(|e| format!("snapshot task join failed: {e:?}"))?
+}
+
+/// What changed between two snapshots of the same root, or between a snapshot and
+/// the live tree when `after_id` is omitted: the "what did this agent run change"
+/// report. Entries are sorted by path.
+#[tauri::command]
+pub async fn diff_tree_snapshots(
+    window: WebviewWindow,
+    before_id: String,
+    after_id: Option<String>,
+) -> Result<TreeDiff, String> {
+    let dir = snapshots_root(&window)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let before = read_snapshot(&dir, &before_id)?;
+        let after_files = match after_id.as_deref() {
+            Some(after_id) => {
+                let after = read_snapshot(&dir, after_id)?;
+                if after.root != before.root {
+                    return Err("snapshots are of different directories".to_string());
+                }
+                after.files
+            }
+            None => scan_tree(Path::new(&before.root))?.0,
+        };
+        let (added, removed, modified) = diff_files(&before.files, &after_files);
+        let size_delta = added
+            .iter()
+            .chain(&removed)
+            .chain(&modified)
+            .map(|e| e.size_delta)
+            .sum();
+        Ok(TreeDiff {
+            root: before.root,
+            before_id: before.id,
+            after_id,
+            added,
+            removed,
+            modified,
+            size_delta,
+        })
+    })
+    .await
+    .map_err(|e| format!("snapshot task join failed: {e:?}"))?
+}
+
+/// Saved snapshots, newest first, optionally only those of `root`.
+#[tauri::command]
+pub async fn list_tree_snapshots(window: WebviewWindow, root: Option<String>) -> Result<Vec<TreeSnapshotInfo>, String> {
+    let dir = snapshots_root(&window)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = root.map(|r| r.trim().to_string());
+        let mut out: Vec<TreeSnapshotInfo> = fs::read_dir(&dir)
+            .map(|rd| rd.flatten().collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|e| {
+                let raw = fs::read_to_string(e.path()).ok()?;
+                serde_json::from_str::<TreeSnapshotV1>(&raw).ok()
+            })
+            .filter(|s| root.as_deref().map(|r| s.root == r).unwrap_or(true))
+            .map(|s| info(&s))
+            .collect();
+        out.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(out)
+    })
+    .await
+    .map_err(|e| format!("snapshot task join failed: {e:?}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_files, FileStamp};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn diff_reports_added_removed_and_modified() {
+        let stamp = |size, modified_ms| FileStamp { size, modified_ms };
+        let before: BTreeMap<String, FileStamp> = [
+            ("a.rs".to_string(), stamp(10, 1)),
+            ("b.rs".to_string(), stamp(20, 1)),
+            ("c.rs".to_string(), stamp(30, 1)),
+        ]
+        .into();
+        let after: BTreeMap<String, FileStamp> = [
+            ("a.rs".to_string(), stamp(10, 1)),
+            ("b.rs".to_string(), stamp(25, 2)),
+            ("d.rs".to_string(), stamp(5, 2)),
+        ]
+        .into();
+
+        let (added, removed, modified) = diff_files(&before, &after);
+        assert_eq!(added.len(), 1);
+        assert_eq!((added[0].path.as_str(), added[0].size_delta), ("d.rs", 5));
+        assert_eq!((removed[0].path.as_str(), removed[0].size_after, removed[0].size_delta), ("c.rs", None, -30));
+        assert_eq!((modified[0].path.as_str(), modified[0].size_delta), ("b.rs", 5));
+    }
+}
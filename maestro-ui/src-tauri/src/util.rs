@@ -0,0 +1,64 @@
+use serde::Serialize;
+use std::ffi::OsString;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+
+pub(crate) fn now_epoch_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// `name` inside the app data dir (a file or a subdirectory).
+pub(crate) fn app_data_file<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|_| "unknown app data dir".to_string())?;
+    Ok(dir.join(name))
+}
+
+/// Replace `path` with `contents` through a sibling temp file and a rename, so a
+/// crash mid-write never leaves a truncated file behind. Creates the parent dir.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let dir = path.parent().ok_or("invalid file path")?;
+    fs::create_dir_all(dir).map_err(|e| format!("create dir failed: {e}"))?;
+    let mut tmp = OsString::from(path.as_os_str());
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut file = fs::File::create(&tmp).map_err(|e| format!("write temp failed: {e}"))?;
+    file.write_all(contents)
+        .map_err(|e| format!("write temp failed: {e}"))?;
+    file.sync_all().ok();
+    drop(file);
+    fs::rename(&tmp, path).map_err(|e| format!("rename failed: {e}"))
+}
+
+/// `write_atomic` for a value serialized as pretty JSON.
+pub(crate) fn write_json_atomic<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| format!("serialize failed: {e}"))?;
+    write_atomic(path, json.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_atomic, write_json_atomic};
+    use std::fs;
+
+    #[test]
+    fn atomic_writes_replace_the_file_and_leave_no_temp() {
+        let dir = std::env::temp_dir().join(format!("maestro-util-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("nested").join("settings-v1.json");
+
+        write_json_atomic(&path, &serde_json::json!({ "a": 1 })).unwrap();
+        write_atomic(&path, b"{\"a\":2}").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\":2}");
+        assert!(!dir.join("nested").join("settings-v1.json.tmp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
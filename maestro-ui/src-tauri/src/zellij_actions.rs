@@ -0,0 +1,169 @@
+#[cfg(target_family = "unix")]
+use std::process::Command;
+use tauri::WebviewWindow;
+
+/// Longest tab or pane name passed to zellij.
+const MAX_NAME_CHARS: usize = 64;
+
+fn clean_name(name: Option<String>) -> Result<Option<String>, String> {
+    let Some(name) = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()) else {
+        return Ok(None);
+    };
+    if name.chars().count() > MAX_NAME_CHARS || name.chars().any(char::is_control) {
+        return Err(format!("name must be at most {MAX_NAME_CHARS} printable chars"));
+    }
+    Ok(Some(name))
+}
+
+/// Arguments after `zellij --session <name>` for each action.
+fn new_tab_args(name: Option<&str>, cwd: Option<&str>) -> Vec<String> {
+    let mut args = vec!["action".to_string(), "new-tab".to_string()];
+    if let Some(name) = name {
+        args.extend(["--name".to_string(), name.to_string()]);
+    }
+    if let Some(cwd) = cwd {
+        args.extend(["--cwd".to_string(), cwd.to_string()]);
+    }
+    args
+}
+
+fn run_args(command: &str, name: Option<&str>, cwd: Option<&str>, floating: bool) -> Vec<String> {
+    let mut args = vec!["run".to_string()];
+    if let Some(name) = name {
+        args.extend(["--name".to_string(), name.to_string()]);
+    }
+    if let Some(cwd) = cwd {
+        args.extend(["--cwd".to_string(), cwd.to_string()]);
+    }
+    if floating {
+        args.push("--floating".to_string());
+    }
+    // Through the shell, so the command line means what it would typed into a pane.
+    args.extend(["--".to_string(), "sh".to_string(), "-c".to_string(), command.to_string()]);
+    args
+}
+
+fn write_chars_args(chars: &str) -> Vec<String> {
+    vec!["action".to_string(), "write-chars".to_string(), chars.to_string()]
+}
+
+/// The running zellij session `persist_id` names (see `persistent_sessions`).
+async fn zellij_session(window: &WebviewWindow, persist_id: &str) -> Result<String, String> {
+    let session = crate::persistent_sessions::find_session_async(window, persist_id).await?;
+    if session.backend != "zellij" {
+        return Err("not a zellij session".to_string());
+    }
+    if session.exited {
+        return Err("session has exited; resurrect it first".to_string());
+    }
+    Ok(session.session_name)
+}
+
+async fn run_zellij(session: String, args: Vec<String>) -> Result<(), String> {
+    #[cfg(target_family = "unix")]
+    {
+        tauri::async_runtime::spawn_blocking(move || {
+            let zellij = crate::ssh_fs::find_program_in_path("zellij").ok_or("zellij is not installed")?;
+            let output = Command::new(zellij)
+                .args(["--session", &session])
+                .args(&args)
+                .output()
+                .map_err(|e| format!("zellij failed: {e}"))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("zellij failed: {}", stderr.trim()));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("zellij task join failed: {e:?}"))?
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        let _ = (session, args);
+        Err("zellij is only supported on unix".to_string())
+    }
+}
+
+/// Open a tab in a persistent zellij session (`zellij action new-tab`).
+#[tauri::command]
+pub async fn zellij_new_tab(
+    window: WebviewWindow,
+    persist_id: String,
+    name: Option<String>,
+    cwd: Option<String>,
+) -> Result<(), String> {
+    let name = clean_name(name)?;
+    let session = zellij_session(&window, &persist_id).await?;
+    let args = new_tab_args(name.as_deref(), cwd.as_deref().map(str::trim).filter(|c| !c.is_empty()));
+    run_zellij(session, args).await?;
+    crate::telemetry::record_feature("zellij-action");
+    Ok(())
+}
+
+/// Run a command in a new pane of a persistent zellij session (`zellij run`).
+/// `automated` commands are checked against the command policy first.
+#[tauri::command]
+pub async fn zellij_run(
+    window: WebviewWindow,
+    persist_id: String,
+    command: String,
+    name: Option<String>,
+    cwd: Option<String>,
+    floating: Option<bool>,
+    automated: Option<bool>,
+) -> Result<(), String> {
+    let command = command.trim().to_string();
+    if command.is_empty() {
+        return Err("missing command".to_string());
+    }
+    if automated.unwrap_or(false) {
+        crate::command_policy::check_automation_command(&window, &command)?;
+    }
+    let name = clean_name(name)?;
+    let session = zellij_session(&window, &persist_id).await?;
+    let cwd = cwd.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    let args = run_args(&command, name.as_deref(), cwd, floating.unwrap_or(false));
+    run_zellij(session, args).await?;
+    crate::telemetry::record_feature("zellij-action");
+    Ok(())
+}
+
+/// Type into the focused pane of a persistent zellij session (`zellij action
+/// write-chars`). For `automated` writes, each command line completed by a newline is
+/// checked against the command policy first.
+#[tauri::command]
+pub async fn zellij_write_chars(
+    window: WebviewWindow,
+    persist_id: String,
+    chars: String,
+    automated: Option<bool>,
+) -> Result<(), String> {
+    if chars.is_empty() {
+        return Ok(());
+    }
+    if automated.unwrap_or(false) {
+        crate::command_policy::preload(&window)?;
+        crate::command_policy::screen_input(&mut String::new(), &chars)?;
+    }
+    let session = zellij_session(&window, &persist_id).await?;
+    run_zellij(session, write_chars_args(&chars)).await?;
+    crate::telemetry::record_feature("zellij-action");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clean_name, new_tab_args, run_args};
+
+    #[test]
+    fn builds_zellij_action_args() {
+        assert_eq!(new_tab_args(Some("build"), None), ["action", "new-tab", "--name", "build"]);
+        assert_eq!(
+            run_args("cargo test && echo ok", None, Some("/src"), true),
+            ["run", "--cwd", "/src", "--floating", "--", "sh", "-c", "cargo test && echo ok"]
+        );
+        assert_eq!(clean_name(Some("  ".to_string())), Ok(None));
+        assert!(clean_name(Some("a\nb".to_string())).is_err());
+    }
+}
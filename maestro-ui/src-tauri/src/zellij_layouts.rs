@@ -0,0 +1,468 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(target_family = "unix")]
+use std::process::Command;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+const LAYOUT_DIR_NAME: &str = "zellij-layouts";
+const SERIALIZATION_FILE_NAME: &str = "zellij-serialization-v1.json";
+const MAX_SCROLLBACK_LINES_TO_SERIALIZE: u32 = 100_000;
+/// `list-sessions --short`, which tells which sessions this zellij can attach to,
+/// arrived in 0.39.
+const MIN_ZELLIJ_VERSION: (u32, u32, u32) = (0, 39, 0);
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleZellijSession {
+    pub name: String,
+    /// zellij's socket subdirectory for the version that started it, e.g. `0.38.2`.
+    pub socket_version: String,
+    pub socket: String,
+}
+
+#[derive(Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ZellijInfo {
+    /// The `zellij` on the login PATH; Maestro doesn't bundle one.
+    pub path: Option<String>,
+    /// e.g. `0.41.2`.
+    pub version: Option<String>,
+    pub minimum_version: String,
+    /// Layouts can be launched with this zellij.
+    pub supported: bool,
+    /// Running sessions this zellij can't attach to, because a zellij with another
+    /// client protocol started them (typically before an upgrade). They have to be
+    /// quit from that version, or their server killed.
+    pub stale_sessions: Vec<StaleZellijSession>,
+}
+
+fn default_session_serialization() -> bool {
+    true
+}
+
+/// How zellij sessions started by Maestro save themselves to disk. A serialized
+/// session whose server is gone (after a reboot, say) shows as exited and comes back
+/// on attach; see `resurrect_persistent_session`. Passed as `zellij options` when a
+/// session starts, so the user's own zellij config is left alone.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ZellijSerializationV1 {
+    /// zellij's own default is on.
+    #[serde(default = "default_session_serialization")]
+    pub session_serialization: bool,
+    /// Also save what each pane was showing.
+    #[serde(default)]
+    pub serialize_pane_viewport: bool,
+    /// Scrollback lines saved along with the viewport; `None` saves the viewport only.
+    #[serde(default)]
+    pub scrollback_lines_to_serialize: Option<u32>,
+}
+
+impl Default for ZellijSerializationV1 {
+    fn default() -> Self {
+        Self {
+            session_serialization: default_session_serialization(),
+            serialize_pane_viewport: false,
+            scrollback_lines_to_serialize: None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLayoutInfo {
+    pub name: String,
+    pub description: String,
+    /// Pane names, in the order they appear.
+    pub panes: Vec<String>,
+}
+
+struct LayoutTemplate {
+    name: &'static str,
+    description: &'static str,
+    panes: &'static [&'static str],
+}
+
+/// The layout of a persistent session with the zellij backend when none is picked.
+pub(crate) const DEFAULT_LAYOUT: &str = "single";
+
+const LAYOUTS: &[LayoutTemplate] = &[
+    LayoutTemplate {
+        name: DEFAULT_LAYOUT,
+        description: "Just the agent, in one pane",
+        panes: &["agent"],
+    },
+    LayoutTemplate {
+        name: "agent+logs",
+        description: "The agent on top, a shell for logs below it",
+        panes: &["agent", "logs"],
+    },
+    LayoutTemplate {
+        name: "3-pane review",
+        description: "The agent on the left, the working tree diff and a shell on the right",
+        panes: &["agent", "diff", "shell"],
+    },
+];
+
+fn template(name: &str) -> Result<&'static LayoutTemplate, String> {
+    let name = name.trim();
+    LAYOUTS
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("unknown layout: {name}"))
+}
+
+/// Fail early on a layout name `prepare` wouldn't accept.
+pub(crate) fn validate(name: &str) -> Result<(), String> {
+    template(name).map(|_| ())
+}
+
+fn kdl_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The agent's pane: `command` run through `sh -c`, or the default shell.
+fn agent_pane(indent: &str, attrs: &str, command: Option<&str>) -> String {
+    match command.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(command) => format!(
+            "{indent}pane name=\"agent\" {attrs}focus=true command=\"/bin/sh\" {{\n\
+             {indent}    args \"-c\" {}\n\
+             {indent}}}\n",
+            kdl_string(command)
+        ),
+        None => format!("{indent}pane name=\"agent\" {attrs}focus=true\n"),
+    }
+}
+
+/// KDL for the layout `name`, with `command` (the session's own command) in the
+/// agent pane. Tabs keep zellij's tab and status bars.
+fn render_layout(name: &str, command: Option<&str>) -> Result<String, String> {
+    let template = template(name)?;
+    let body = match template.name {
+        DEFAULT_LAYOUT => agent_pane("        ", "", command),
+        "agent+logs" => format!(
+            "        pane split_direction=\"horizontal\" {{\n\
+             {}\
+             \x20           pane name=\"logs\"\n\
+             \x20       }}\n",
+            agent_pane("            ", "size=\"70%\" ", command)
+        ),
+        "3-pane review" => format!(
+            "        pane split_direction=\"vertical\" {{\n\
+             {}\
+             \x20           pane split_direction=\"horizontal\" {{\n\
+             \x20               pane name=\"diff\" command=\"git\" {{\n\
+             \x20                   args \"diff\" \"--stat\"\n\
+             \x20               }}\n\
+             \x20               pane name=\"shell\"\n\
+             \x20           }}\n\
+             \x20       }}\n",
+            agent_pane("            ", "size=\"60%\" ", command)
+        ),
+        other => return Err(format!("unknown layout: {other}")),
+    };
+    Ok(format!(
+        "// Generated by Maestro for the \"{}\" layout; rewritten on each launch.\n\
+         layout {{\n\
+         \x20   default_tab_template {{\n\
+         \x20       pane size=1 borderless=true {{\n\
+         \x20           plugin location=\"zellij:tab-bar\"\n\
+         \x20       }}\n\
+         \x20       children\n\
+         \x20       pane size=2 borderless=true {{\n\
+         \x20           plugin location=\"zellij:status-bar\"\n\
+         \x20       }}\n\
+         \x20   }}\n\
+         \x20   tab name={} {{\n\
+         {body}\
+         \x20   }}\n\
+         }}\n",
+        template.name,
+        kdl_string(template.name),
+    ))
+}
+
+fn serialization_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::util::app_data_file(app, SERIALIZATION_FILE_NAME)
+}
+
+fn read_serialization(app: &AppHandle) -> Result<ZellijSerializationV1, String> {
+    let path = serialization_file_path(app)?;
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("parse failed: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ZellijSerializationV1::default()),
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn validate_serialization(settings: &ZellijSerializationV1) -> Result<(), String> {
+    if settings
+        .scrollback_lines_to_serialize
+        .is_some_and(|lines| lines > MAX_SCROLLBACK_LINES_TO_SERIALIZE)
+    {
+        return Err(format!(
+            "scrollbackLinesToSerialize must be at most {MAX_SCROLLBACK_LINES_TO_SERIALIZE}"
+        ));
+    }
+    Ok(())
+}
+
+/// The `zellij options` overrides for a new session.
+fn serialization_args(settings: &ZellijSerializationV1) -> String {
+    let mut args = format!(
+        "options --session-serialization {} --serialize-pane-viewport {}",
+        settings.session_serialization, settings.serialize_pane_viewport
+    );
+    if let Some(lines) = settings.scrollback_lines_to_serialize {
+        args.push_str(&format!(" --scrollback-lines-to-serialize {lines}"));
+    }
+    args
+}
+
+fn parse_version(output: &str) -> Option<(u32, u32, u32)> {
+    let version = output
+        .split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))?;
+    let mut parts = version.split(|c: char| !c.is_ascii_digit()).map(str::parse::<u32>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next()?.ok()?;
+    Some((major, minor, parts.next().and_then(Result::ok).unwrap_or(0)))
+}
+
+fn format_version((major, minor, patch): (u32, u32, u32)) -> String {
+    format!("{major}.{minor}.{patch}")
+}
+
+fn socket_label(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// Live `sockets` the current zellij didn't list as `reachable`, or that sit in another
+/// version's socket dir than the ones it did list.
+fn stale_sessions(sockets: &[PathBuf], reachable: &[String]) -> Vec<StaleZellijSession> {
+    let version_of = |path: &Path| path.parent().map(socket_label).unwrap_or_default();
+    let current: Vec<String> = sockets
+        .iter()
+        .filter(|path| reachable.contains(&socket_label(path)))
+        .map(|path| version_of(path))
+        .collect();
+    sockets
+        .iter()
+        .filter(|path| {
+            !reachable.contains(&socket_label(path))
+                || (!current.is_empty() && !current.contains(&version_of(path)))
+        })
+        .map(|path| StaleZellijSession {
+            name: socket_label(path),
+            socket_version: version_of(path),
+            socket: path.to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+#[cfg(target_family = "unix")]
+pub(crate) fn inspect() -> ZellijInfo {
+    let mut info = ZellijInfo {
+        minimum_version: format_version(MIN_ZELLIJ_VERSION),
+        ..ZellijInfo::default()
+    };
+    let Some(zellij) = crate::ssh_fs::find_program_in_path("zellij") else {
+        return info;
+    };
+    info.path = Some(zellij.to_string_lossy().to_string());
+    let version = Command::new(&zellij)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|out| parse_version(&String::from_utf8_lossy(&out.stdout)));
+    let Some(version) = version else {
+        return info;
+    };
+    info.version = Some(format_version(version));
+    info.supported = version >= MIN_ZELLIJ_VERSION;
+    if !info.supported {
+        return info;
+    }
+    // Exits non-zero when there are no sessions; the list is empty then.
+    let Ok(listed) = Command::new(&zellij).args(["list-sessions", "--short"]).output() else {
+        return info;
+    };
+    let reachable: Vec<String> = String::from_utf8_lossy(&listed.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    info.stale_sessions = stale_sessions(&crate::external_sessions::live_zellij_sockets(), &reachable);
+    info
+}
+
+#[cfg(not(target_family = "unix"))]
+pub(crate) fn inspect() -> ZellijInfo {
+    ZellijInfo {
+        minimum_version: format_version(MIN_ZELLIJ_VERSION),
+        ..ZellijInfo::default()
+    }
+}
+
+/// Write the layout to `zellij-layouts/<session>.kdl` in the app config dir and return
+/// the shell command that attaches to `session` if it is already running, or starts
+/// it with the layout. A running session keeps the panes it has.
+pub(crate) fn prepare(
+    app: &AppHandle,
+    layout: &str,
+    session: &str,
+    command: Option<&str>,
+) -> Result<String, String> {
+    let info = inspect();
+    match info.version.as_deref() {
+        None if info.path.is_none() => {
+            return Err("zellij is not installed (layouts run in a zellij session)".to_string())
+        }
+        None => return Err("zellij --version failed".to_string()),
+        Some(version) if !info.supported => {
+            let minimum = &info.minimum_version;
+            return Err(format!("zellij {version} is too old for layouts; {minimum} or newer is needed"));
+        }
+        Some(_) => {}
+    }
+    if let Some(stale) = info.stale_sessions.iter().find(|s| s.name == session) {
+        return Err(format!(
+            "zellij session {session} was started by another zellij version ({}) and can't be attached; \
+             quit it from that version or kill its server",
+            stale.socket_version
+        ));
+    }
+    let kdl = render_layout(layout, command)?;
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("config dir failed: {e}"))?
+        .join(LAYOUT_DIR_NAME);
+    let file: PathBuf = dir.join(format!("{session}.kdl"));
+    crate::util::write_atomic(&file, kdl.as_bytes())?;
+
+    let serialization = read_serialization(app).unwrap_or_else(|e| {
+        eprintln!("Failed to load zellij serialization settings: {e}");
+        ZellijSerializationV1::default()
+    });
+    let session = crate::ssh_fs::shell_escape_posix(session);
+    Ok(format!(
+        "if zellij list-sessions --short 2>/dev/null | grep -qxF {session}; \
+         then exec zellij attach {session}; \
+         else exec zellij --session {session} --layout {} {}; fi",
+        crate::ssh_fs::shell_escape_posix(&file.to_string_lossy()),
+        serialization_args(&serialization)
+    ))
+}
+
+/// The installed zellij's version and whether layouts can use it, plus running sessions
+/// left by a different zellij version, which would otherwise fail to attach.
+#[tauri::command]
+pub async fn get_zellij_info() -> Result<ZellijInfo, String> {
+    tauri::async_runtime::spawn_blocking(inspect)
+        .await
+        .map_err(|e| format!("zellij info task join failed: {e:?}"))
+}
+
+#[tauri::command]
+pub fn get_zellij_serialization(window: WebviewWindow) -> Result<ZellijSerializationV1, String> {
+    read_serialization(window.app_handle())
+}
+
+/// Applies to zellij sessions started afterwards; running ones keep their settings.
+#[tauri::command]
+pub fn set_zellij_serialization(
+    window: WebviewWindow,
+    settings: ZellijSerializationV1,
+) -> Result<(), String> {
+    validate_serialization(&settings)?;
+    let path = serialization_file_path(window.app_handle())?;
+    crate::util::write_json_atomic(&path, &settings)
+}
+
+/// Layouts `create_session` accepts as `layout`.
+#[tauri::command]
+pub fn list_session_layouts() -> Vec<SessionLayoutInfo> {
+    LAYOUTS
+        .iter()
+        .map(|t| SessionLayoutInfo {
+            name: t.name.to_string(),
+            description: t.description.to_string(),
+            panes: t.panes.iter().map(|p| p.to_string()).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_version, render_layout, serialization_args, stale_sessions, ZellijSerializationV1};
+    use std::path::PathBuf;
+
+    #[test]
+    fn renders_agent_command_into_layout() {
+        let kdl = render_layout("Agent+Logs", Some("claude --resume \"x\"")).unwrap();
+        assert!(kdl.contains("pane name=\"agent\" size=\"70%\" focus=true command=\"/bin/sh\" {"));
+        assert!(kdl.contains("args \"-c\" \"claude --resume \\\"x\\\"\""));
+        assert!(kdl.contains("pane name=\"logs\""));
+        assert_eq!(kdl.matches('{').count(), kdl.matches('}').count());
+
+        let review = render_layout("3-pane review", None).unwrap();
+        assert!(review.contains("pane name=\"agent\" size=\"60%\" focus=true\n"));
+        assert!(review.contains("args \"diff\" \"--stat\""));
+        assert_eq!(review.matches('{').count(), review.matches('}').count());
+        assert!(render_layout("grid", None).is_err());
+
+        let single = render_layout("single", Some("npm run dev")).unwrap();
+        assert!(single.contains("        pane name=\"agent\" focus=true command=\"/bin/sh\" {\n"));
+        assert_eq!(single.matches('{').count(), single.matches('}').count());
+    }
+
+    #[test]
+    fn serialization_settings_become_zellij_options() {
+        assert_eq!(
+            serialization_args(&ZellijSerializationV1::default()),
+            "options --session-serialization true --serialize-pane-viewport false"
+        );
+        let settings = ZellijSerializationV1 {
+            serialize_pane_viewport: true,
+            scrollback_lines_to_serialize: Some(500),
+            ..ZellijSerializationV1::default()
+        };
+        assert!(serialization_args(&settings)
+            .ends_with("--serialize-pane-viewport true --scrollback-lines-to-serialize 500"));
+    }
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(parse_version("zellij 0.41.2\n"), Some((0, 41, 2)));
+        assert_eq!(parse_version("zellij 0.40.0-rc1"), Some((0, 40, 0)));
+        assert_eq!(parse_version("zellij"), None);
+    }
+
+    #[test]
+    fn sessions_outside_the_current_socket_dir_are_stale() {
+        let sockets: Vec<PathBuf> = ["/run/zellij/contract_version_1/work", "/run/zellij/0.38.2/old"]
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        let stale = stale_sessions(&sockets, &["work".to_string()]);
+        assert_eq!(stale.len(), 1);
+        assert_eq!((stale[0].name.as_str(), stale[0].socket_version.as_str()), ("old", "0.38.2"));
+        // Nothing reachable: every live socket belongs to some other zellij.
+        assert_eq!(stale_sessions(&sockets, &[]).len(), 2);
+    }
+}
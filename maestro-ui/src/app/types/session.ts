@@ -3,6 +3,8 @@ export type TerminalSessionInfo = {
   name: string;
   command: string;
   cwd?: string | null;
+  foregroundPid?: number | null;
+  foregroundCommand?: string | null;
 };
 
 export type TerminalSession = TerminalSessionInfo & {
@@ -120,6 +120,16 @@ export type SshHostEntry = {
   hostName?: string | null;
   user?: string | null;
   port?: number | null;
+  proxyJump?: string | null;
+  hasOverrides?: boolean;
+};
+
+export type SshHostOverride = {
+  port?: number | null;
+  user?: string | null;
+  identityFile?: string | null;
+  connectTimeoutSecs?: number | null;
+  keepaliveIntervalSecs?: number | null;
 };
 
 export type SshForwardType = "local" | "remote" | "dynamic";
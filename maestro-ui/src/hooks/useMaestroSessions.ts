@@ -55,13 +55,15 @@ export function useMaestroSessions({
 
             try {
                 const info = await invoke<TerminalSessionInfo>("create_session", {
-                    name: sessionInfo.name,
-                    command: sessionInfo.command,
-                    cwd: sessionInfo.cwd,
-                    cols: 200,
-                    rows: 50,
-                    env_vars: sessionInfo.envVars,
-                    persistent: false,
+                    options: {
+                        name: sessionInfo.name,
+                        command: sessionInfo.command,
+                        cwd: sessionInfo.cwd,
+                        cols: 200,
+                        rows: 50,
+                        envVars: sessionInfo.envVars,
+                        persistent: false,
+                    },
                 });
 
                 const newSession: TerminalSession = {
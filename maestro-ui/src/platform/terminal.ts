@@ -8,12 +8,14 @@ import type { TerminalSessionInfo } from '../app/types/session';
 export const tauriTerminal: TerminalTransport = {
   createSession(opts: CreateSessionOpts): Promise<TerminalSessionInfo> {
     return invoke<TerminalSessionInfo>('create_session', {
-      name: opts.name,
-      command: opts.command,
-      cwd: opts.cwd,
-      envVars: opts.envVars,
-      persistent: opts.persistent,
-      persistId: opts.persistId,
+      options: {
+        name: opts.name,
+        command: opts.command,
+        cwd: opts.cwd,
+        envVars: opts.envVars,
+        persistent: opts.persistent,
+        persistId: opts.persistId,
+      },
     });
   },
 